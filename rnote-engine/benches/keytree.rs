@@ -0,0 +1,117 @@
+//! Benchmarks the spatial index of the strokes against the linear scan over all strokes it replaced.
+//! Run with `cargo bench --bench keytree`
+
+extern crate nalgebra as na;
+extern crate parry2d_f64 as p2d;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p2d::bounding_volume::{BoundingVolume, AABB};
+use rand::{Rng, SeedableRng};
+use rnote_engine::strokesstate::keytree::KeyTree;
+use slotmap::SlotMap;
+
+slotmap::new_key_type! {
+    struct BenchKey;
+}
+
+const N_STROKES: usize = 50_000;
+const SHEET_WIDTH: f64 = 1000.0;
+/// 200 A4 pages at 96 DPI
+const SHEET_HEIGHT: f64 = 200.0 * 1123.0;
+
+/// Generates strokes with random bounds, spread over the entire sheet
+fn gen_strokes() -> SlotMap<BenchKey, AABB> {
+    let mut rng = rand_pcg::Pcg64::seed_from_u64(0);
+    let mut strokes = SlotMap::with_key();
+
+    for _ in 0..N_STROKES {
+        let mins = na::point![
+            rng.gen_range(0.0..SHEET_WIDTH),
+            rng.gen_range(0.0..SHEET_HEIGHT)
+        ];
+        let extents = na::vector![rng.gen_range(2.0..200.0), rng.gen_range(2.0..100.0)];
+
+        strokes.insert(AABB::new(mins, mins + extents));
+    }
+
+    strokes
+}
+
+fn linear_scan(strokes: &SlotMap<BenchKey, AABB>, bounds: AABB) -> Vec<BenchKey> {
+    strokes
+        .iter()
+        .filter(|(_key, stroke_bounds)| stroke_bounds.intersects(&bounds))
+        .map(|(key, _stroke_bounds)| key)
+        .collect::<Vec<BenchKey>>()
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let strokes = gen_strokes();
+    let mut key_tree = KeyTree::new();
+    key_tree.rebuild_from(strokes.iter().map(|(key, bounds)| (key, *bounds)));
+
+    let viewport = AABB::new(
+        na::point![0.0, SHEET_HEIGHT / 2.0],
+        na::point![SHEET_WIDTH, SHEET_HEIGHT / 2.0 + 800.0],
+    );
+    let eraser = AABB::new(
+        na::point![SHEET_WIDTH / 2.0, SHEET_HEIGHT / 2.0],
+        na::point![SHEET_WIDTH / 2.0 + 20.0, SHEET_HEIGHT / 2.0 + 20.0],
+    );
+
+    let mut group = c.benchmark_group("viewport query");
+    group.bench_function("linear scan", |b| {
+        b.iter(|| linear_scan(black_box(&strokes), black_box(viewport)))
+    });
+    group.bench_function("key tree", |b| {
+        b.iter(|| key_tree.keys_intersecting_bounds(black_box(viewport)))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("eraser query");
+    group.bench_function("linear scan", |b| {
+        b.iter(|| linear_scan(black_box(&strokes), black_box(eraser)))
+    });
+    group.bench_function("key tree", |b| {
+        b.iter(|| key_tree.keys_intersecting_bounds(black_box(eraser)))
+    });
+    group.finish();
+}
+
+fn bench_updates(c: &mut Criterion) {
+    let strokes = gen_strokes();
+    let keys = strokes.keys().collect::<Vec<BenchKey>>();
+
+    let mut group = c.benchmark_group("key tree updates");
+    group.bench_function("rebuild", |b| {
+        let mut key_tree = KeyTree::new();
+        b.iter(|| key_tree.rebuild_from(strokes.iter().map(|(key, bounds)| (key, *bounds))))
+    });
+    group.bench_function("update geometry of one stroke", |b| {
+        let mut key_tree = KeyTree::new();
+        key_tree.rebuild_from(strokes.iter().map(|(key, bounds)| (key, *bounds)));
+
+        let mut i = 0;
+        b.iter(|| {
+            let key = keys[i % keys.len()];
+            let offset = if (i / keys.len()) % 2 == 0 {
+                10.0
+            } else {
+                -10.0
+            };
+            let bounds = strokes[key];
+            key_tree.insert(
+                key,
+                AABB::new(
+                    bounds.mins + na::vector![offset, offset],
+                    bounds.maxs + na::vector![offset, offset],
+                ),
+            );
+            i += 1;
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries, bench_updates);
+criterion_main!(benches);
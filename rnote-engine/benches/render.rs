@@ -0,0 +1,230 @@
+//! Benchmarks rendering strokes through svg text parsed by resvg against drawing their paths directly with tiny-skia.
+//! Run with `cargo bench --bench render`
+
+extern crate nalgebra as na;
+extern crate parry2d_f64 as p2d;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p2d::bounding_volume::{BoundingVolume, AABB};
+use rnote_engine::compose::drawpath::{self, DrawPath};
+use svg::node::element::path;
+
+/// The number of segments of the generated strokes
+const N_SEGMENTS: usize = 500;
+const ZOOM: f64 = 1.5;
+const MARKER_WIDTH: f64 = 6.0;
+const BRUSH_WIDTH: f64 = 4.0;
+
+fn color() -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(0.1, 0.2, 0.6, 0.8).unwrap()
+}
+
+/// A wavy line over the sheet, the points the strokes are generated from
+fn gen_points() -> Vec<na::Vector2<f64>> {
+    (0..=N_SEGMENTS)
+        .map(|i| {
+            let t = i as f64 / N_SEGMENTS as f64;
+            na::vector![
+                20.0 + t * 960.0,
+                500.0 + (t * 40.0).sin() * 300.0 * (t * 3.0).cos()
+            ]
+        })
+        .collect()
+}
+
+fn segment_bounds(start: na::Vector2<f64>, end: na::Vector2<f64>, width: f64) -> AABB {
+    let mut bounds = AABB::new_invalid();
+    bounds.take_point(na::Point2::from(start));
+    bounds.take_point(na::Point2::from(end));
+    bounds.loosened(width)
+}
+
+/// Stroked cubic segments, like the marker generates them
+fn gen_marker_paths(points: &[na::Vector2<f64>]) -> Vec<DrawPath> {
+    points
+        .windows(2)
+        .map(|segment| {
+            let (start, end) = (segment[0], segment[1]);
+            let cp1 = start + (end - start) / 3.0 + na::vector![0.0, 4.0];
+            let cp2 = start + (end - start) * 2.0 / 3.0 - na::vector![0.0, 4.0];
+
+            let commands = vec![
+                path::Command::Move(
+                    path::Position::Absolute,
+                    path::Parameters::from(vec![start[0] as f32, start[1] as f32]),
+                ),
+                path::Command::CubicCurve(
+                    path::Position::Absolute,
+                    path::Parameters::from(vec![
+                        cp1[0] as f32,
+                        cp1[1] as f32,
+                        cp2[0] as f32,
+                        cp2[1] as f32,
+                        end[0] as f32,
+                        end[1] as f32,
+                    ]),
+                ),
+            ];
+
+            DrawPath::new_stroked(
+                commands,
+                color(),
+                MARKER_WIDTH,
+                segment_bounds(start, end, MARKER_WIDTH),
+            )
+        })
+        .collect()
+}
+
+/// Filled segments with variable width and round ends, like the solid brush generates them
+fn gen_brush_paths(points: &[na::Vector2<f64>]) -> Vec<DrawPath> {
+    points
+        .windows(2)
+        .enumerate()
+        .map(|(i, segment)| {
+            let (start, end) = (segment[0], segment[1]);
+            let start_offset = BRUSH_WIDTH * (1.0 + (i as f64 * 0.1).sin() * 0.5) / 2.0;
+            let end_offset = BRUSH_WIDTH * (1.0 + ((i + 1) as f64 * 0.1).sin() * 0.5) / 2.0;
+
+            let direction = (end - start).normalize();
+            let normal = na::vector![-direction[1], direction[0]];
+
+            let point = |p: na::Vector2<f64>| vec![p[0] as f32, p[1] as f32];
+            let arc = |radius: f64, to: na::Vector2<f64>| {
+                path::Command::EllipticalArc(
+                    path::Position::Absolute,
+                    path::Parameters::from(vec![
+                        radius as f32,
+                        radius as f32,
+                        0.0,
+                        0.0,
+                        0.0,
+                        to[0] as f32,
+                        to[1] as f32,
+                    ]),
+                )
+            };
+
+            let commands = vec![
+                path::Command::Move(
+                    path::Position::Absolute,
+                    path::Parameters::from(point(start + normal * start_offset)),
+                ),
+                path::Command::Line(
+                    path::Position::Absolute,
+                    path::Parameters::from(point(end + normal * end_offset)),
+                ),
+                arc(end_offset, end - normal * end_offset),
+                path::Command::Line(
+                    path::Position::Absolute,
+                    path::Parameters::from(point(start - normal * start_offset)),
+                ),
+                arc(start_offset, start + normal * start_offset),
+                path::Command::Close,
+            ];
+
+            DrawPath::new_filled(
+                commands,
+                color(),
+                segment_bounds(start, end, start_offset.max(end_offset)),
+            )
+        })
+        .collect()
+}
+
+fn bounds_of(paths: &[DrawPath]) -> AABB {
+    paths.iter().fold(AABB::new_invalid(), |bounds, path| {
+        bounds.merged(&path.bounds)
+    })
+}
+
+fn to_css_color(color: Option<tiny_skia::Color>) -> String {
+    color.map_or(String::from("none"), |color| {
+        format!(
+            "rgb({:03},{:03},{:03},{:.3})",
+            (color.red() * 255.0) as i32,
+            (color.green() * 255.0) as i32,
+            (color.blue() * 255.0) as i32,
+            color.alpha()
+        )
+    })
+}
+
+/// What rendering through svg does: generates the svg text of the paths, then parses and renders it with resvg
+fn render_svg(paths: &[DrawPath], zoom: f64, bounds: AABB, usvg_options: &usvg::Options) {
+    let svg_data = paths
+        .iter()
+        .map(|drawpath| {
+            svg::node::element::Path::new()
+                .set("fill", to_css_color(drawpath.fill))
+                .set(
+                    "stroke",
+                    to_css_color(drawpath.stroke.map(|stroke| stroke.color)),
+                )
+                .set(
+                    "stroke-width",
+                    drawpath.stroke.map_or(0.0, |stroke| stroke.width),
+                )
+                .set("stroke-linejoin", "round")
+                .set("stroke-linecap", "round")
+                .set("d", path::Data::from(drawpath.commands.clone()))
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let svg_data = format!(
+        r#"<svg x="{x:.3}" y="{y:.3}" width="{w:.3}" height="{h:.3}" viewBox="{x:.3} {y:.3} {w:.3} {h:.3}" xmlns="http://www.w3.org/2000/svg">{data}</svg>"#,
+        x = bounds.mins[0],
+        y = bounds.mins[1],
+        w = bounds.extents()[0],
+        h = bounds.extents()[1],
+        data = svg_data
+    );
+
+    let width_scaled = ((bounds.extents()[0]) * zoom).round() as u32;
+    let height_scaled = ((bounds.extents()[1]) * zoom).round() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width_scaled, height_scaled).unwrap();
+
+    let rtree = usvg::Tree::from_data(svg_data.as_bytes(), &usvg_options.to_ref()).unwrap();
+    resvg::render(&rtree, usvg::FitTo::Zoom(zoom as f32), pixmap.as_mut()).unwrap();
+
+    black_box(pixmap);
+}
+
+fn bench_render(c: &mut Criterion) {
+    let points = gen_points();
+    let usvg_options = usvg::Options::default();
+
+    for (name, paths) in [
+        ("render marker stroke", gen_marker_paths(&points)),
+        ("render brush stroke", gen_brush_paths(&points)),
+    ] {
+        let bounds = bounds_of(&paths);
+
+        let mut group = c.benchmark_group(name);
+        group.bench_function("svg + resvg", |b| {
+            b.iter(|| render_svg(black_box(&paths), ZOOM, bounds, &usvg_options))
+        });
+        group.bench_function("direct paths", |b| {
+            b.iter(|| drawpath::render_paths(black_box(&paths), ZOOM, bounds).unwrap())
+        });
+        group.finish();
+    }
+
+    // Appending the newest segment while drawing only renders the last path
+    let paths = gen_brush_paths(&points);
+    let last = &paths[paths.len() - 1..];
+    let bounds = bounds_of(last);
+
+    let mut group = c.benchmark_group("render newest segment");
+    group.bench_function("svg + resvg", |b| {
+        b.iter(|| render_svg(black_box(last), ZOOM, bounds, &usvg_options))
+    });
+    group.bench_function("direct paths", |b| {
+        b.iter(|| drawpath::render_paths(black_box(last), ZOOM, bounds).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_render);
+criterion_main!(benches);
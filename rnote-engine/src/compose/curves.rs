@@ -36,6 +36,11 @@ impl StrokeBehaviour for Line {
         self.start = mid - half_vec.component_mul(&scale);
         self.end = mid + half_vec.component_mul(&scale);
     }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: nalgebra::Point2<f64>) {
+        self.start = geometry::flip_pos(self.start, horizontal, vertical, center);
+        self.end = geometry::flip_pos(self.end, horizontal, vertical, center);
+    }
 }
 
 impl Line {
@@ -0,0 +1,374 @@
+//! Paths with their paint, which are drawn directly onto a tiny-skia pixmap. This skips generating svg text and parsing it again,
+//! which is what the svg export needs, but is wasted work when the strokes are only rendered.
+//! This module only depends on external crates, so that it can be included in the benchmarks.
+
+use p2d::bounding_volume::AABB;
+use svg::node::element::path;
+
+/// The stroke of a path. Joins and caps are always round
+#[derive(Debug, Clone, Copy)]
+pub struct PathStroke {
+    pub color: tiny_skia::Color,
+    pub width: f64,
+}
+
+/// A path in the coordinate space of the sheet, together with its paint
+#[derive(Debug, Clone)]
+pub struct DrawPath {
+    /// the same commands the svg path data is generated from
+    pub commands: Vec<path::Command>,
+    /// filled with the nonzero fill rule
+    pub fill: Option<tiny_skia::Color>,
+    pub stroke: Option<PathStroke>,
+    /// the area the path is drawn into, including the stroke width
+    pub bounds: AABB,
+}
+
+impl DrawPath {
+    pub fn new_filled(commands: Vec<path::Command>, color: tiny_skia::Color, bounds: AABB) -> Self {
+        Self {
+            commands,
+            fill: Some(color),
+            stroke: None,
+            bounds,
+        }
+    }
+
+    pub fn new_stroked(
+        commands: Vec<path::Command>,
+        color: tiny_skia::Color,
+        width: f64,
+        bounds: AABB,
+    ) -> Self {
+        Self {
+            commands,
+            fill: None,
+            stroke: Some(PathStroke { color, width }),
+            bounds,
+        }
+    }
+}
+
+/// Renders the paths into a new pixmap, covering the bounds at the zoom. Bounds are in the coordinate space of the sheet
+pub fn render_paths(
+    paths: &[DrawPath],
+    zoom: f64,
+    bounds: AABB,
+) -> Result<tiny_skia::Pixmap, anyhow::Error> {
+    let width_scaled = ((bounds.extents()[0]) * zoom).round() as u32;
+    let height_scaled = ((bounds.extents()[1]) * zoom).round() as u32;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width_scaled, height_scaled).ok_or_else(|| {
+        anyhow::anyhow!(
+            "tiny_skia::Pixmap::new() with dimensions ({}, {}) failed in render_paths()",
+            width_scaled,
+            height_scaled
+        )
+    })?;
+
+    let transform = tiny_skia::Transform::from_row(
+        zoom as f32,
+        0.0,
+        0.0,
+        zoom as f32,
+        (-bounds.mins[0] * zoom) as f32,
+        (-bounds.mins[1] * zoom) as f32,
+    );
+
+    for drawpath in paths {
+        draw_path(&mut pixmap.as_mut(), drawpath, transform);
+    }
+
+    Ok(pixmap)
+}
+
+/// Draws the path onto the pixmap. Empty paths are skipped
+pub fn draw_path(
+    pixmap: &mut tiny_skia::PixmapMut,
+    drawpath: &DrawPath,
+    transform: tiny_skia::Transform,
+) {
+    let skia_path = if let Some(skia_path) = to_skia_path(&drawpath.commands) {
+        skia_path
+    } else {
+        return;
+    };
+
+    if let Some(fill) = drawpath.fill {
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color(fill);
+        paint.anti_alias = true;
+
+        pixmap.fill_path(
+            &skia_path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            transform,
+            None,
+        );
+    }
+
+    if let Some(stroke) = drawpath.stroke {
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color(stroke.color);
+        paint.anti_alias = true;
+
+        let skia_stroke = tiny_skia::Stroke {
+            width: stroke.width as f32,
+            line_cap: tiny_skia::LineCap::Round,
+            line_join: tiny_skia::LineJoin::Round,
+            ..tiny_skia::Stroke::default()
+        };
+
+        pixmap.stroke_path(&skia_path, &paint, &skia_stroke, transform, None);
+    }
+}
+
+/// Converts the svg path commands to a tiny-skia path. Elliptical arcs are approximated with cubic beziers.
+/// Returns None if the commands don't produce a path with an area
+pub fn to_skia_path(commands: &[path::Command]) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    // The current point and the start of the current subpath, for relative commands and closing
+    let mut current = (0.0_f32, 0.0_f32);
+    let mut subpath_start = (0.0_f32, 0.0_f32);
+    // The last control point, for the smooth curves
+    let mut last_cubic_cp: Option<(f32, f32)> = None;
+    let mut last_quad_cp: Option<(f32, f32)> = None;
+
+    for command in commands {
+        match command {
+            path::Command::Move(position, params) => {
+                for (i, p) in params.chunks_exact(2).enumerate() {
+                    let to = absolute(position, current, (p[0], p[1]));
+                    // Following pairs are implicit line commands
+                    if i == 0 {
+                        builder.move_to(to.0, to.1);
+                        subpath_start = to;
+                    } else {
+                        builder.line_to(to.0, to.1);
+                    }
+                    current = to;
+                }
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+            path::Command::Line(position, params) => {
+                for p in params.chunks_exact(2) {
+                    let to = absolute(position, current, (p[0], p[1]));
+                    builder.line_to(to.0, to.1);
+                    current = to;
+                }
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+            path::Command::HorizontalLine(position, params) => {
+                for &x in params.iter() {
+                    let x = match position {
+                        path::Position::Absolute => x,
+                        path::Position::Relative => current.0 + x,
+                    };
+                    builder.line_to(x, current.1);
+                    current = (x, current.1);
+                }
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+            path::Command::VerticalLine(position, params) => {
+                for &y in params.iter() {
+                    let y = match position {
+                        path::Position::Absolute => y,
+                        path::Position::Relative => current.1 + y,
+                    };
+                    builder.line_to(current.0, y);
+                    current = (current.0, y);
+                }
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+            path::Command::QuadraticCurve(position, params) => {
+                for p in params.chunks_exact(4) {
+                    let cp = absolute(position, current, (p[0], p[1]));
+                    let to = absolute(position, current, (p[2], p[3]));
+                    builder.quad_to(cp.0, cp.1, to.0, to.1);
+                    last_quad_cp = Some(cp);
+                    current = to;
+                }
+                last_cubic_cp = None;
+            }
+            path::Command::SmoothQuadraticCurve(position, params) => {
+                for p in params.chunks_exact(2) {
+                    let cp = reflect(last_quad_cp, current);
+                    let to = absolute(position, current, (p[0], p[1]));
+                    builder.quad_to(cp.0, cp.1, to.0, to.1);
+                    last_quad_cp = Some(cp);
+                    current = to;
+                }
+                last_cubic_cp = None;
+            }
+            path::Command::CubicCurve(position, params) => {
+                for p in params.chunks_exact(6) {
+                    let cp1 = absolute(position, current, (p[0], p[1]));
+                    let cp2 = absolute(position, current, (p[2], p[3]));
+                    let to = absolute(position, current, (p[4], p[5]));
+                    builder.cubic_to(cp1.0, cp1.1, cp2.0, cp2.1, to.0, to.1);
+                    last_cubic_cp = Some(cp2);
+                    current = to;
+                }
+                last_quad_cp = None;
+            }
+            path::Command::SmoothCubicCurve(position, params) => {
+                for p in params.chunks_exact(4) {
+                    let cp1 = reflect(last_cubic_cp, current);
+                    let cp2 = absolute(position, current, (p[0], p[1]));
+                    let to = absolute(position, current, (p[2], p[3]));
+                    builder.cubic_to(cp1.0, cp1.1, cp2.0, cp2.1, to.0, to.1);
+                    last_cubic_cp = Some(cp2);
+                    current = to;
+                }
+                last_quad_cp = None;
+            }
+            path::Command::EllipticalArc(position, params) => {
+                for p in params.chunks_exact(7) {
+                    let to = absolute(position, current, (p[5], p[6]));
+                    arc_to(
+                        &mut builder,
+                        current,
+                        (p[0], p[1]),
+                        p[2],
+                        p[3] != 0.0,
+                        p[4] != 0.0,
+                        to,
+                    );
+                    current = to;
+                }
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+            path::Command::Close => {
+                builder.close();
+                current = subpath_start;
+                last_cubic_cp = None;
+                last_quad_cp = None;
+            }
+        }
+    }
+
+    builder.finish()
+}
+
+fn absolute(position: &path::Position, current: (f32, f32), point: (f32, f32)) -> (f32, f32) {
+    match position {
+        path::Position::Absolute => point,
+        path::Position::Relative => (current.0 + point.0, current.1 + point.1),
+    }
+}
+
+/// Reflects the last control point on the current point. Is the current point if there is no last control point
+fn reflect(last_cp: Option<(f32, f32)>, current: (f32, f32)) -> (f32, f32) {
+    match last_cp {
+        Some(cp) => (2.0 * current.0 - cp.0, 2.0 * current.1 - cp.1),
+        None => current,
+    }
+}
+
+/// Appends an svg elliptical arc as cubic beziers, at most one per quarter of the ellipse.
+/// The center parametrization is calculated as described in the SVG spec, appendix B.2.4
+fn arc_to(
+    builder: &mut tiny_skia::PathBuilder,
+    from: (f32, f32),
+    radii: (f32, f32),
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: (f32, f32),
+) {
+    let (x1, y1) = (f64::from(from.0), f64::from(from.1));
+    let (x2, y2) = (f64::from(to.0), f64::from(to.1));
+    let (mut rx, mut ry) = (f64::from(radii.0).abs(), f64::from(radii.1).abs());
+
+    if x1 == x2 && y1 == y2 {
+        return;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        builder.line_to(to.0, to.1);
+        return;
+    }
+
+    let phi = f64::from(x_axis_rotation).to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    let dx2 = (x1 - x2) / 2.0;
+    let dy2 = (y1 - y2) / 2.0;
+    let x1p = cos_phi * dx2 + sin_phi * dy2;
+    let y1p = -sin_phi * dx2 + cos_phi * dy2;
+
+    // Scale up the radii if they are too small to reach the end point
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (num / den).max(0.0).sqrt();
+
+    let cxp = coef * rx * y1p / ry;
+    let cyp = -coef * ry * x1p / rx;
+    let cx = cos_phi * cxp - sin_phi * cyp + (x1 + x2) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (y1 + y2) / 2.0;
+
+    let angle = |u: (f64, f64), v: (f64, f64)| (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1);
+
+    let start_vec = ((x1p - cxp) / rx, (y1p - cyp) / ry);
+    let end_vec = ((-x1p - cxp) / rx, (-y1p - cyp) / ry);
+    let theta1 = angle((1.0, 0.0), start_vec);
+    let mut delta_theta = angle(start_vec, end_vec);
+
+    if !sweep && delta_theta > 0.0 {
+        delta_theta -= 2.0 * std::f64::consts::PI;
+    } else if sweep && delta_theta < 0.0 {
+        delta_theta += 2.0 * std::f64::consts::PI;
+    }
+
+    let n_segments = (delta_theta.abs() / std::f64::consts::FRAC_PI_2)
+        .ceil()
+        .max(1.0) as usize;
+    let segment_theta = delta_theta / n_segments as f64;
+    let k = 4.0 / 3.0 * (segment_theta / 4.0).tan();
+
+    let point = |theta: f64| {
+        let (sin_t, cos_t) = theta.sin_cos();
+        (
+            cx + rx * cos_t * cos_phi - ry * sin_t * sin_phi,
+            cy + rx * cos_t * sin_phi + ry * sin_t * cos_phi,
+        )
+    };
+    let derivative = |theta: f64| {
+        let (sin_t, cos_t) = theta.sin_cos();
+        (
+            -rx * sin_t * cos_phi - ry * cos_t * sin_phi,
+            -rx * sin_t * sin_phi + ry * cos_t * cos_phi,
+        )
+    };
+
+    for i in 0..n_segments {
+        let t1 = theta1 + i as f64 * segment_theta;
+        let t2 = t1 + segment_theta;
+
+        let (p1, d1) = (point(t1), derivative(t1));
+        let (p2, d2) = (point(t2), derivative(t2));
+        // Ending exactly on the end point avoids gaps from rounding errors
+        let p2 = if i == n_segments - 1 { (x2, y2) } else { p2 };
+
+        builder.cubic_to(
+            (p1.0 + k * d1.0) as f32,
+            (p1.1 + k * d1.1) as f32,
+            (p2.0 - k * d2.0) as f32,
+            (p2.1 - k * d2.1) as f32,
+            p2.0 as f32,
+            p2.1 as f32,
+        );
+    }
+}
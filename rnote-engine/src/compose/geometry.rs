@@ -1,5 +1,5 @@
 use geo::line_string;
-use gtk4::graphene;
+use gsk::graphene;
 use p2d::bounding_volume::AABB;
 use p2d::query::PointQuery;
 
@@ -172,6 +172,24 @@ pub fn vec2_mins_maxs(
     }
 }
 
+/// The scale which mirrors at the vertical axis when flipping horizontally, and at the horizontal axis when flipping vertically
+pub fn flip_scale(horizontal: bool, vertical: bool) -> na::Vector2<f64> {
+    na::vector![
+        if horizontal { -1.0 } else { 1.0 },
+        if vertical { -1.0 } else { 1.0 }
+    ]
+}
+
+/// Flips the position at the axes through the center
+pub fn flip_pos(
+    pos: na::Vector2<f64>,
+    horizontal: bool,
+    vertical: bool,
+    center: na::Point2<f64>,
+) -> na::Vector2<f64> {
+    (pos - center.coords).component_mul(&flip_scale(horizontal, vertical)) + center.coords
+}
+
 pub fn aabb_new_zero() -> AABB {
     AABB::new(na::point![0.0, 0.0], na::point![0.0, 0.0])
 }
@@ -229,6 +247,15 @@ pub fn aabb_translate(aabb: AABB, offset: na::Vector2<f64>) -> AABB {
     )
 }
 
+/// The index of the page, stacked vertically with the given page height, which contains the center of the aabb
+pub fn page_of_aabb(aabb: AABB, page_height: f64) -> u32 {
+    if page_height <= 0.0 {
+        return 0;
+    }
+
+    (aabb.center()[1] / page_height).floor().max(0.0) as u32
+}
+
 /// Shrinks the aabb to the nearest integer of its vertices
 pub fn aabb_floor(aabb: AABB) -> AABB {
     AABB::new(
@@ -1,6 +1,7 @@
 use p2d::bounding_volume::AABB;
 
 pub mod curves;
+pub mod drawpath;
 pub mod geometry;
 pub mod rough;
 pub mod shapes;
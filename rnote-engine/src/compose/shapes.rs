@@ -46,6 +46,11 @@ impl StrokeBehaviour for Rectangle {
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.transform.append_scale_mut(scale);
     }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>) {
+        self.transform
+            .append_flip_wrt_point_mut(horizontal, vertical, center);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +85,11 @@ impl StrokeBehaviour for Ellipse {
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.transform.append_scale_mut(scale);
     }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>) {
+        self.transform
+            .append_flip_wrt_point_mut(horizontal, vertical, center);
+    }
 }
 
 impl Ellipse {
@@ -507,3 +507,75 @@ pub fn compose_ellipse(ellipse: shapes::Ellipse, shaper: &Shaper) -> element::El
         .set("fill", fill)
         .into()
 }
+
+/// The path of the rectangle outline, with the transform applied
+pub fn compose_rectangle_commands(rectangle: &shapes::Rectangle) -> Vec<path::Command> {
+    let half_extents = rectangle.cuboid.half_extents;
+    let corners = [
+        na::point![-half_extents[0], -half_extents[1]],
+        na::point![half_extents[0], -half_extents[1]],
+        na::point![half_extents[0], half_extents[1]],
+        na::point![-half_extents[0], half_extents[1]],
+    ]
+    .map(|corner| rectangle.transform.transform * corner);
+
+    vec![
+        path::Command::Move(
+            path::Position::Absolute,
+            path::Parameters::from((corners[0][0], corners[0][1])),
+        ),
+        path::Command::Line(
+            path::Position::Absolute,
+            path::Parameters::from((
+                (corners[1][0], corners[1][1]),
+                (corners[2][0], corners[2][1]),
+                (corners[3][0], corners[3][1]),
+            )),
+        ),
+        path::Command::Close,
+    ]
+}
+
+/// The path of the ellipse outline as one cubic bezier per quarter, with the transform applied.
+/// The transform is affine, so transforming the control points transforms the curves exactly
+pub fn compose_ellipse_commands(ellipse: &shapes::Ellipse) -> Vec<path::Command> {
+    // The distance of the control points for approximating a quarter circle
+    const K: f64 = 0.552_284_749_831;
+    let radii = ellipse.radii;
+    let transform = |point: na::Point2<f64>| ellipse.transform.transform * point;
+
+    let quarter_points = [
+        na::point![radii[0], 0.0],
+        na::point![0.0, radii[1]],
+        na::point![-radii[0], 0.0],
+        na::point![0.0, -radii[1]],
+    ];
+    // The tangents at the quarter points, scaled by the control point distance
+    let quarter_tangents = [
+        na::vector![0.0, K * radii[1]],
+        na::vector![-K * radii[0], 0.0],
+        na::vector![0.0, -K * radii[1]],
+        na::vector![K * radii[0], 0.0],
+    ];
+
+    let start = transform(quarter_points[0]);
+    let mut commands = vec![path::Command::Move(
+        path::Position::Absolute,
+        path::Parameters::from((start[0], start[1])),
+    )];
+
+    for i in 0..4 {
+        let j = (i + 1) % 4;
+        let cp1 = transform(quarter_points[i] + quarter_tangents[i]);
+        let cp2 = transform(quarter_points[j] - quarter_tangents[j]);
+        let end = transform(quarter_points[j]);
+
+        commands.push(path::Command::CubicCurve(
+            path::Position::Absolute,
+            path::Parameters::from(((cp1[0], cp1[1]), (cp2[0], cp2[1]), (end[0], end[1]))),
+        ));
+    }
+    commands.push(path::Command::Close);
+
+    commands
+}
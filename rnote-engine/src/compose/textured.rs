@@ -4,7 +4,6 @@ use crate::utils;
 
 use super::curves;
 
-use gtk4::glib;
 use rand::SeedableRng;
 use rand_distr::{Distribution, Uniform};
 use serde::{Deserialize, Serialize};
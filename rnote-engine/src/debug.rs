@@ -0,0 +1,137 @@
+//! Module for visual debugging
+
+use gsk::graphene;
+use p2d::bounding_volume::AABB;
+
+use crate::compose::geometry;
+use crate::render::NodeSnapshot;
+use crate::utils;
+
+pub const COLOR_POS: utils::Color = utils::Color {
+    r: 1.0,
+    g: 0.0,
+    b: 0.0,
+    a: 1.0,
+};
+pub const COLOR_POS_ALT: utils::Color = utils::Color {
+    r: 1.0,
+    g: 1.0,
+    b: 0.0,
+    a: 1.0,
+};
+pub const COLOR_STROKE_HITBOX: utils::Color = utils::Color {
+    r: 0.0,
+    g: 0.8,
+    b: 0.2,
+    a: 0.5,
+};
+pub const COLOR_STROKE_BOUNDS: utils::Color = utils::Color {
+    r: 0.0,
+    g: 0.8,
+    b: 0.8,
+    a: 1.0,
+};
+pub const COLOR_STROKE_REGENERATE_FLAG: utils::Color = utils::Color {
+    r: 0.9,
+    g: 0.0,
+    b: 0.8,
+    a: 0.3,
+};
+pub const COLOR_SELECTOR_BOUNDS: utils::Color = utils::Color {
+    r: 1.0,
+    g: 0.0,
+    b: 0.8,
+    a: 1.0,
+};
+pub const COLOR_SHEET_BOUNDS: utils::Color = utils::Color {
+    r: 0.8,
+    g: 0.0,
+    b: 0.8,
+    a: 1.0,
+};
+
+pub fn draw_bounds(bounds: AABB, color: utils::Color, zoom: f64, snapshot: &NodeSnapshot) {
+    let bounds = graphene::Rect::new(
+        bounds.mins[0] as f32,
+        bounds.mins[1] as f32,
+        (bounds.extents()[0]) as f32,
+        (bounds.extents()[1]) as f32,
+    );
+
+    let border_width = 1.5;
+    let rounded_rect = gsk::RoundedRect::new(
+        bounds.scale(zoom as f32, zoom as f32),
+        graphene::Size::zero(),
+        graphene::Size::zero(),
+        graphene::Size::zero(),
+        graphene::Size::zero(),
+    );
+
+    snapshot.append_border(
+        &rounded_rect,
+        &[border_width, border_width, border_width, border_width],
+        &[
+            color.to_gdk(),
+            color.to_gdk(),
+            color.to_gdk(),
+            color.to_gdk(),
+        ],
+    )
+}
+
+pub fn draw_pos(pos: na::Vector2<f64>, color: utils::Color, zoom: f64, snapshot: &NodeSnapshot) {
+    snapshot.append_color(
+        &color.to_gdk(),
+        &graphene::Rect::new(
+            (zoom * pos[0] - 1.0) as f32,
+            (zoom * pos[1] - 1.0) as f32,
+            2.0,
+            2.0,
+        ),
+    );
+}
+
+/// Draws the text with its top left corner at the position
+pub fn draw_text(
+    text: &str,
+    pos: na::Vector2<f64>,
+    color: utils::Color,
+    zoom: f64,
+    snapshot: &NodeSnapshot,
+) {
+    const FONT_SIZE: f64 = 14.0;
+    const PADDING: f64 = 6.0;
+    // A rough estimate, the text is clipped to these bounds
+    let width = text.len() as f64 * FONT_SIZE * 0.7 + 2.0 * PADDING;
+    let height = FONT_SIZE + 2.0 * PADDING;
+
+    let cx = snapshot.append_cairo(&graphene::Rect::new(
+        (zoom * pos[0]) as f32,
+        (zoom * pos[1]) as f32,
+        width as f32,
+        height as f32,
+    ));
+    cx.select_font_face(
+        "monospace",
+        cairo::FontSlant::Normal,
+        cairo::FontWeight::Normal,
+    );
+    cx.set_font_size(FONT_SIZE);
+    cx.set_source_rgba(
+        f64::from(color.r),
+        f64::from(color.g),
+        f64::from(color.b),
+        f64::from(color.a),
+    );
+    cx.move_to(zoom * pos[0] + PADDING, zoom * pos[1] + PADDING + FONT_SIZE);
+    if let Err(e) = cx.show_text(text) {
+        log::error!("cx.show_text() failed in draw_text() with Err {}", e);
+    }
+}
+
+pub fn draw_fill(rect: AABB, color: utils::Color, zoom: f64, snapshot: &NodeSnapshot) {
+    snapshot.append_color(
+        &color.to_gdk(),
+        &geometry::aabb_to_graphene_rect(geometry::aabb_scale(rect, zoom)),
+    );
+}
@@ -1,3 +1,4 @@
+use crate::compose::drawpath::DrawPath;
 use crate::compose::geometry;
 use crate::render;
 
@@ -30,6 +31,11 @@ pub trait DrawBehaviour {
     }
     /// generates the svg elements, without the xml header or the svg root.
     fn gen_svgs(&self, offset: na::Vector2<f64>) -> Result<Vec<render::Svg>, anyhow::Error>;
+    /// generates the paths which are drawn directly when rendering, skipping the svgs. The svgs are still used for exporting.
+    /// Returns None if the stroke can only be rendered from its svgs
+    fn gen_paths(&self, _offset: na::Vector2<f64>) -> Option<Vec<DrawPath>> {
+        None
+    }
     /// generates the image for this stroke
     fn gen_image(
         &self,
@@ -37,6 +43,9 @@ pub trait DrawBehaviour {
         renderer: &render::Renderer,
     ) -> Result<render::Image, anyhow::Error> {
         let offset = na::vector![0.0, 0.0];
+        if let Some(paths) = self.gen_paths(offset) {
+            return renderer.gen_image_from_paths(zoom, &paths, self.bounds());
+        }
         let svgs = self.gen_svgs(offset)?;
 
         renderer.gen_image(zoom, &svgs, self.bounds())
@@ -0,0 +1,28 @@
+use p2d::bounding_volume::AABB;
+use std::collections::VecDeque;
+
+use crate::strokes::strokestyle::InputData;
+
+pub const INPUT_OVERSHOOT: f64 = 30.0;
+
+/// Map Stylus input to the position on a sheet
+pub fn map_inputdata(
+    zoom: f64,
+    data_entries: &mut VecDeque<InputData>,
+    mapped_offset: na::Vector2<f64>,
+) {
+    *data_entries = data_entries
+        .iter()
+        .map(|inputdata| {
+            InputData::new(
+                inputdata.pos().scale(1.0 / zoom) + mapped_offset,
+                inputdata.pressure(),
+            )
+        })
+        .collect();
+}
+
+/// Filter inputdata to sheet bounds
+pub fn filter_mapped_inputdata(filter_bounds: AABB, data_entries: &mut VecDeque<InputData>) {
+    data_entries.retain(|data| filter_bounds.contains_local_point(&na::Point2::from(data.pos())));
+}
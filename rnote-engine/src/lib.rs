@@ -0,0 +1,21 @@
+#![warn(missing_debug_implementations)]
+#![allow(dead_code)]
+
+//! The document and rendering engine of Rnote. It holds the sheet with its strokes, generates their renderings,
+//! (de)serializes the sheet and implements the pens. It does not depend on any widgets, the ui drives it with the input
+//! and draws what it renders.
+
+pub mod compose;
+pub mod debug;
+pub mod drawbehaviour;
+pub mod input;
+pub mod pdf;
+pub mod pens;
+pub mod render;
+pub mod sheet;
+pub mod strokes;
+pub mod strokesstate;
+pub mod utils;
+
+extern crate nalgebra as na;
+extern crate parry2d_f64 as p2d;
@@ -0,0 +1,247 @@
+use std::cell::RefCell;
+use std::ops::Range;
+use std::sync::{Arc, Weak};
+
+use p2d::bounding_volume::{BoundingVolume, AABB};
+use serde::{Deserialize, Serialize};
+
+use crate::compose::shapes;
+
+/// How the imported pdf pages are laid out on the sheet
+#[derive(Debug, Clone, Copy)]
+pub enum PdfImportLayout {
+    /// The pages are placed below each other, starting at the position. They are scaled to the page width, or have their intrinsic size if it is None
+    Continuous {
+        pos: na::Vector2<f64>,
+        page_width: Option<f64>,
+    },
+    /// Each pdf page is placed on its own sheet page, scaled to fit into the format. Starts at the sheet page with the index
+    SheetPages {
+        first_page: u32,
+        page_size: na::Vector2<f64>,
+    },
+}
+
+impl PdfImportLayout {
+    /// The spacing between the pages in a continuous layout
+    pub const CONTINUOUS_PAGE_SPACING: f64 = 16.0;
+
+    /// The bounds of the imported page, where i is its index counted from the first imported page
+    pub fn page_bounds(&self, i: u32, intrinsic_size: na::Vector2<f64>) -> AABB {
+        match self {
+            Self::Continuous { pos, page_width } => {
+                let zoom = page_width
+                    .map(|page_width| page_width / intrinsic_size[0])
+                    .unwrap_or(1.0);
+                let size = intrinsic_size * zoom;
+
+                let mins = na::point![
+                    pos[0],
+                    pos[1] + f64::from(i) * (size[1] + Self::CONTINUOUS_PAGE_SPACING)
+                ];
+                AABB::new(mins, mins + size)
+            }
+            Self::SheetPages {
+                first_page,
+                page_size,
+            } => {
+                let zoom = (page_size[0] / intrinsic_size[0]).min(page_size[1] / intrinsic_size[1]);
+                let size = intrinsic_size * zoom;
+
+                // Centered on the sheet page
+                let mins = na::point![
+                    (page_size[0] - size[0]) * 0.5,
+                    f64::from(first_page + i) * page_size[1] + (page_size[1] - size[1]) * 0.5
+                ];
+                AABB::new(mins, mins + size)
+            }
+        }
+    }
+}
+
+/// Preferences for importing a pdf
+#[derive(Debug, Clone)]
+pub struct PdfImportPrefs {
+    /// The range of the imported pages, starting at 0. All pages are imported when None
+    pub page_range: Option<Range<u32>>,
+    pub layout: PdfImportLayout,
+    /// The resolution in dots per inch, when the pages are imported as bitmap images
+    pub bitmap_dpi: f64,
+}
+
+impl Default for PdfImportPrefs {
+    fn default() -> Self {
+        Self {
+            page_range: None,
+            layout: PdfImportLayout::Continuous {
+                pos: na::vector![0.0, 0.0],
+                page_width: None,
+            },
+            bitmap_dpi: Self::BITMAP_DPI_DEFAULT,
+        }
+    }
+}
+
+impl PdfImportPrefs {
+    pub const BITMAP_DPI_DEFAULT: f64 = 96.0;
+    /// The resolution of the pdf coordinate space, in which the intrinsic page sizes are
+    pub const PDF_DPI: f64 = 72.0;
+
+    /// The indices of the pages to import, clamped to the pages of the document
+    pub fn pages(&self, n_pages: u32) -> Range<u32> {
+        match self.page_range.as_ref() {
+            Some(page_range) => page_range.start.min(n_pages)..page_range.end.min(n_pages),
+            None => 0..n_pages,
+        }
+    }
+}
+
+/// The number of pages of the pdf
+pub fn pdf_n_pages(bytes: &[u8]) -> Result<u32, anyhow::Error> {
+    let doc = poppler::Document::from_data(bytes, None)?;
+
+    Ok(u32::try_from(doc.n_pages()).unwrap_or(0))
+}
+
+/// The bounds the imported pages will cover on the sheet. None if no pages are imported
+pub fn pdf_import_bounds(
+    bytes: &[u8],
+    prefs: &PdfImportPrefs,
+) -> Result<Option<AABB>, anyhow::Error> {
+    let doc = poppler::Document::from_data(bytes, None)?;
+    let n_pages = u32::try_from(doc.n_pages()).unwrap_or(0);
+
+    Ok(prefs
+        .pages(n_pages)
+        .enumerate()
+        .filter_map(|(i, page_i)| {
+            let intrinsic_size = doc.page(page_i as i32)?.size();
+
+            Some(
+                prefs
+                    .layout
+                    .page_bounds(i as u32, na::vector![intrinsic_size.0, intrinsic_size.1]),
+            )
+        })
+        .reduce(|acc, bounds| acc.merged(&bounds)))
+}
+
+thread_local! {
+    /// The parsed pdf documents by their data. Poppler documents can't be shared between threads, so they are cached for each thread
+    static PARSED_DOCUMENTS: RefCell<Vec<(Weak<Vec<u8>>, poppler::Document)>> = RefCell::new(Vec::new());
+}
+
+/// The parsed pdf document of the data. It is parsed once in each thread, and cached as long as the data is alive
+pub fn parsed_document(data: &Arc<Vec<u8>>) -> Result<poppler::Document, anyhow::Error> {
+    PARSED_DOCUMENTS.with(|documents| {
+        let mut documents = documents.borrow_mut();
+        documents.retain(|(other, _)| other.strong_count() > 0);
+
+        if let Some((_, doc)) = documents
+            .iter()
+            .find(|(other, _)| other.as_ptr() == Arc::as_ptr(data))
+        {
+            return Ok(doc.clone());
+        }
+
+        let doc = poppler::Document::from_data(data, None)?;
+        documents.push((Arc::downgrade(data), doc.clone()));
+
+        Ok(doc)
+    })
+}
+
+/// A imported pdf document. It is stored once and referenced by the strokes which were imported from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "pdf_document")]
+pub struct PdfDocument {
+    #[serde(rename = "data_base64", with = "crate::sheet::attachments::pdf_bytes")]
+    pub data: Arc<Vec<u8>>,
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self {
+            data: Arc::new(Vec::new()),
+        }
+    }
+}
+
+impl PdfDocument {
+    pub fn new(bytes: &[u8]) -> Self {
+        Self {
+            data: Arc::new(bytes.to_vec()),
+        }
+    }
+}
+
+/// The page of a stored pdf document a stroke was imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename = "pdf_page_origin")]
+pub struct PdfPageOrigin {
+    /// The key of the document in the stored pdf documents
+    #[serde(rename = "document")]
+    pub document: u32,
+    /// The page index in the document, starting at 0
+    #[serde(rename = "page")]
+    pub page: u32,
+}
+
+impl Default for PdfPageOrigin {
+    fn default() -> Self {
+        Self {
+            document: 0,
+            page: 0,
+        }
+    }
+}
+
+/// The transform from the coordinate space of a pdf page (in points, with the origin at the top left) into the sheet, when the page is drawn into the rectangle
+pub fn page_to_sheet_transform(
+    rectangle: &shapes::Rectangle,
+    page_size: na::Vector2<f64>,
+) -> na::Matrix3<f64> {
+    let half_extents = rectangle.cuboid.half_extents;
+
+    rectangle.transform.transform.to_homogeneous()
+        * na::Matrix3::new(
+            2.0 * half_extents[0] / page_size[0],
+            0.0,
+            -half_extents[0],
+            0.0,
+            2.0 * half_extents[1] / page_size[1],
+            -half_extents[1],
+            0.0,
+            0.0,
+            1.0,
+        )
+}
+
+/// The bounds on the sheet of a area on a pdf page, with the page_to_sheet transform of the page
+pub fn page_area_to_sheet(
+    page_to_sheet: &na::Matrix3<f64>,
+    mins: na::Point2<f64>,
+    maxs: na::Point2<f64>,
+) -> AABB {
+    let corners = [
+        mins,
+        na::point![maxs[0], mins[1]],
+        maxs,
+        na::point![mins[0], maxs[1]],
+    ]
+    .map(|corner| page_to_sheet.transform_point(&corner));
+
+    let mut bounds = AABB::new(corners[0], corners[0]);
+    for corner in &corners[1..] {
+        bounds.take_point(*corner);
+    }
+    bounds
+}
+
+/// Text on imported pdf pages, selected with the selector
+#[derive(Debug, Clone)]
+pub struct PdfTextSelection {
+    pub text: String,
+    /// The selected areas on the pages, in the coordinate space of the sheet
+    pub bounds: Vec<AABB>,
+}
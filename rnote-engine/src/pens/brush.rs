@@ -1,16 +1,16 @@
 use std::collections::VecDeque;
 
 use crate::compose::textured;
+use crate::sheet::Sheet;
 use crate::strokes::brushstroke::BrushStroke;
-use crate::strokes::strokestyle::{Element, StrokeStyle};
+use crate::strokes::strokestyle::{Element, InputData, StrokeStyle};
 use crate::strokesstate::StrokeKey;
 use crate::{input, utils};
 
-use gtk4::prelude::*;
 use p2d::bounding_volume::AABB;
 use serde::{Deserialize, Serialize};
 
-use super::penbehaviour::PenBehaviour;
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename = "brushstyle")]
@@ -62,19 +62,17 @@ impl Default for Brush {
 impl PenBehaviour for Brush {
     fn begin(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_stroke = None;
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().motion_cursor()));
 
         let filter_bounds = AABB::new(
             na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
             na::point![
-                (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                (sheet.height()) as f64 + input::INPUT_OVERSHOOT
             ],
         );
         input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
@@ -83,67 +81,73 @@ impl PenBehaviour for Brush {
             let element = Element::new(inputdata);
             let brushstroke = StrokeStyle::BrushStroke(BrushStroke::new(element, self.clone()));
 
-            self.current_stroke = Some(
-                appwindow
-                    .canvas()
-                    .sheet()
-                    .strokes_state()
-                    .borrow_mut()
-                    .insert_stroke(brushstroke),
-            );
+            let current_stroke = sheet
+                .strokes_state()
+                .borrow_mut()
+                .insert_stroke(brushstroke);
+            sheet
+                .strokes_state()
+                .borrow_mut()
+                .begin_live_stroke(current_stroke);
+
+            self.current_stroke = Some(current_stroke);
         }
+
+        SurfaceFlags::default()
     }
 
     fn motion(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke_key) = self.current_stroke {
             let filter_bounds = AABB::new(
                 na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
                 na::point![
-                    (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                    (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                    (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                    (sheet.height()) as f64 + input::INPUT_OVERSHOOT
                 ],
             );
             input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
 
             for inputdata in data_entries {
-                appwindow
-                    .canvas()
-                    .sheet()
+                sheet
                     .strokes_state()
                     .borrow_mut()
                     .add_to_stroke(current_stroke_key, Element::new(inputdata));
             }
         }
+
+        SurfaceFlags::default()
     }
 
     fn end(
         &mut self,
-        _data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().cursor()));
-
+        _data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke) = self.current_stroke.take() {
-            appwindow
-                .canvas()
-                .sheet()
+            // The live stroke is drawn until the full quality rendering is regenerated
+            sheet
+                .strokes_state()
+                .borrow_mut()
+                .end_live_stroke(current_stroke);
+
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .update_geometry_for_stroke(current_stroke);
 
-            appwindow
-                .canvas()
-                .sheet()
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .regenerate_rendering_for_stroke_threaded(current_stroke);
         }
+
+        SurfaceFlags::default()
     }
 }
 
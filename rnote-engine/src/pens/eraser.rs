@@ -1,14 +1,15 @@
 use std::collections::VecDeque;
 
 use crate::compose::geometry;
+use crate::render::NodeSnapshot;
+use crate::sheet::Sheet;
 use crate::strokes::strokestyle::InputData;
-use crate::ui::appwindow::RnoteAppWindow;
 use crate::utils;
 
-use gtk4::{gdk, graphene, gsk, prelude::*, Snapshot};
+use gsk::graphene;
 use p2d::bounding_volume::AABB;
 
-use super::penbehaviour::PenBehaviour;
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
 
 #[derive(Clone, Debug)]
 pub struct Eraser {
@@ -26,35 +27,45 @@ impl Default for Eraser {
 }
 
 impl PenBehaviour for Eraser {
-    fn begin(&mut self, mut data_entries: VecDeque<InputData>, appwindow: &RnoteAppWindow) {
-        appwindow
-            .canvas()
-            .set_cursor(gdk::Cursor::from_name("none", None).as_ref());
-
+    fn begin(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        _sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_input = data_entries.pop_back();
+
+        SurfaceFlags::default()
     }
 
-    fn motion(&mut self, mut data_entries: VecDeque<InputData>, appwindow: &RnoteAppWindow) {
+    fn motion(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_input = data_entries.pop_back();
 
-        appwindow
-            .canvas()
-            .sheet()
+        sheet
             .strokes_state()
             .borrow_mut()
-            .trash_colliding_strokes(self, Some(appwindow.canvas().viewport_in_sheet_coords()));
+            .trash_colliding_strokes(self, Some(viewport));
 
-        if appwindow.canvas().sheet().resize_endless() {
-            appwindow.canvas().update_background_rendernode(false);
+        SurfaceFlags {
+            sheet_resized: sheet.resize_endless(),
+            ..SurfaceFlags::default()
         }
     }
 
-    fn end(&mut self, _data_entries: VecDeque<InputData>, appwindow: &RnoteAppWindow) {
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().cursor()));
-
+    fn end(
+        &mut self,
+        _data_entries: VecDeque<InputData>,
+        _sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_input = None;
+
+        SurfaceFlags::default()
     }
 
     fn draw(
@@ -62,7 +73,7 @@ impl PenBehaviour for Eraser {
         _sheet_bounds: AABB,
         _renderer: &crate::render::Renderer,
         zoom: f64,
-        snapshot: &Snapshot,
+        snapshot: &NodeSnapshot,
     ) -> Result<(), anyhow::Error> {
         if let Some(bounds) = self.gen_bounds(zoom) {
             let border_color = Self::OUTLINE_COLOR.to_gdk();
@@ -1,14 +1,14 @@
-use gtk4::prelude::*;
 use p2d::bounding_volume::AABB;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
+use crate::sheet::Sheet;
 use crate::strokes::markerstroke::MarkerStroke;
-use crate::strokes::strokestyle::{Element, StrokeStyle};
+use crate::strokes::strokestyle::{Element, InputData, StrokeStyle};
 use crate::strokesstate::StrokeKey;
 use crate::{input, utils};
 
-use super::penbehaviour::PenBehaviour;
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default, rename = "marker")]
@@ -34,19 +34,17 @@ impl Default for Marker {
 impl PenBehaviour for Marker {
     fn begin(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_stroke = None;
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().motion_cursor()));
 
         let filter_bounds = AABB::new(
             na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
             na::point![
-                (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                (sheet.height()) as f64 + input::INPUT_OVERSHOOT
             ],
         );
         input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
@@ -55,67 +53,73 @@ impl PenBehaviour for Marker {
             let element = Element::new(inputdata);
             let markerstroke = StrokeStyle::MarkerStroke(MarkerStroke::new(element, self.clone()));
 
-            self.current_stroke = Some(
-                appwindow
-                    .canvas()
-                    .sheet()
-                    .strokes_state()
-                    .borrow_mut()
-                    .insert_stroke(markerstroke),
-            );
+            let current_stroke = sheet
+                .strokes_state()
+                .borrow_mut()
+                .insert_stroke(markerstroke);
+            sheet
+                .strokes_state()
+                .borrow_mut()
+                .begin_live_stroke(current_stroke);
+
+            self.current_stroke = Some(current_stroke);
         }
+
+        SurfaceFlags::default()
     }
 
     fn motion(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke_key) = self.current_stroke {
             let filter_bounds = AABB::new(
                 na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
                 na::point![
-                    (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                    (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                    (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                    (sheet.height()) as f64 + input::INPUT_OVERSHOOT
                 ],
             );
             input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
 
             for inputdata in data_entries {
-                appwindow
-                    .canvas()
-                    .sheet()
+                sheet
                     .strokes_state()
                     .borrow_mut()
                     .add_to_stroke(current_stroke_key, Element::new(inputdata));
             }
         }
+
+        SurfaceFlags::default()
     }
 
     fn end(
         &mut self,
-        _data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().cursor()));
-
+        _data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke) = self.current_stroke.take() {
-            appwindow
-                .canvas()
-                .sheet()
+            // The live stroke is drawn until the full quality rendering is regenerated
+            sheet
+                .strokes_state()
+                .borrow_mut()
+                .end_live_stroke(current_stroke);
+
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .update_geometry_for_stroke(current_stroke);
 
-            appwindow
-                .canvas()
-                .sheet()
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .regenerate_rendering_for_stroke_threaded(current_stroke);
         }
+
+        SurfaceFlags::default()
     }
 }
 
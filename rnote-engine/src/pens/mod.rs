@@ -0,0 +1,180 @@
+pub mod brush;
+pub mod eraser;
+pub mod marker;
+pub mod penbehaviour;
+pub mod selector;
+pub mod shaper;
+pub mod tools;
+
+use std::collections::VecDeque;
+
+use crate::render::Renderer;
+use crate::sheet::Sheet;
+use crate::strokes::strokestyle::InputData;
+
+use self::penbehaviour::{PenBehaviour, SurfaceFlags};
+use self::tools::Tools;
+use self::{brush::Brush, eraser::Eraser, marker::Marker, selector::Selector, shaper::Shaper};
+
+use crate::render::NodeSnapshot;
+use p2d::bounding_volume::AABB;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum PenStyle {
+    Marker,
+    Brush,
+    Shaper,
+    Eraser,
+    Selector,
+    Tools,
+    Unknown,
+}
+
+impl Default for PenStyle {
+    fn default() -> Self {
+        Self::Marker
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Pens {
+    pub shown: bool,
+    pub current_pen: PenStyle,
+
+    pub marker: Marker,
+    pub brush: Brush,
+    pub shaper: Shaper,
+    pub eraser: Eraser,
+    pub selector: Selector,
+    pub tools: Tools,
+}
+
+impl PenBehaviour for Pens {
+    fn begin(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags {
+        self.set_shown(true);
+        self.constrain_to_guides(&mut data_entries);
+
+        match self.current_pen() {
+            PenStyle::Marker => self.marker.begin(data_entries, sheet, viewport),
+            PenStyle::Brush => self.brush.begin(data_entries, sheet, viewport),
+            PenStyle::Shaper => self.shaper.begin(data_entries, sheet, viewport),
+            PenStyle::Eraser => self.eraser.begin(data_entries, sheet, viewport),
+            PenStyle::Selector => self.selector.begin(data_entries, sheet, viewport),
+            PenStyle::Tools => self.tools.begin(data_entries, sheet, viewport),
+            PenStyle::Unknown => SurfaceFlags::default(),
+        }
+    }
+
+    fn motion(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags {
+        self.constrain_to_guides(&mut data_entries);
+
+        match self.current_pen() {
+            PenStyle::Marker => self.marker.motion(data_entries, sheet, viewport),
+            PenStyle::Brush => self.brush.motion(data_entries, sheet, viewport),
+            PenStyle::Shaper => self.shaper.motion(data_entries, sheet, viewport),
+            PenStyle::Eraser => self.eraser.motion(data_entries, sheet, viewport),
+            PenStyle::Selector => self.selector.motion(data_entries, sheet, viewport),
+            PenStyle::Tools => self.tools.motion(data_entries, sheet, viewport),
+            PenStyle::Unknown => SurfaceFlags::default(),
+        }
+    }
+
+    fn end(
+        &mut self,
+        data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags {
+        let surface_flags = match self.current_pen() {
+            PenStyle::Marker => self.marker.end(data_entries, sheet, viewport),
+            PenStyle::Brush => self.brush.end(data_entries, sheet, viewport),
+            PenStyle::Shaper => self.shaper.end(data_entries, sheet, viewport),
+            PenStyle::Eraser => self.eraser.end(data_entries, sheet, viewport),
+            PenStyle::Selector => self.selector.end(data_entries, sheet, viewport),
+            PenStyle::Tools => self.tools.end(data_entries, sheet, viewport),
+            PenStyle::Unknown => SurfaceFlags::default(),
+        };
+
+        self.set_shown(false);
+
+        surface_flags
+    }
+
+    fn draw(
+        &self,
+        sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        if self.shown {
+            match self.current_pen {
+                PenStyle::Marker => {
+                    self.marker.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Brush => {
+                    self.brush.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Shaper => {
+                    self.shaper.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Eraser => {
+                    self.eraser.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Selector => {
+                    self.selector.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Tools => {
+                    self.tools.draw(sheet_bounds, renderer, zoom, snapshot)?;
+                }
+                PenStyle::Unknown => {}
+            }
+        }
+
+        // The ruler and protractor stay visible as guides while drawing with the other pens
+        if !(self.shown && self.current_pen == PenStyle::Tools) {
+            self.tools
+                .draw_guides(sheet_bounds, renderer, zoom, snapshot)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Pens {
+    pub fn shown(&self) -> bool {
+        self.shown
+    }
+
+    pub fn set_shown(&mut self, shown: bool) {
+        self.shown = shown;
+    }
+
+    pub fn current_pen(&self) -> PenStyle {
+        self.current_pen
+    }
+
+    pub fn set_current_pen(&mut self, current_pen: PenStyle) {
+        self.current_pen = current_pen;
+    }
+
+    /// Constrains the input data of the marker and brush to the ruler and protractor
+    fn constrain_to_guides(&self, data_entries: &mut VecDeque<InputData>) {
+        match self.current_pen {
+            PenStyle::Marker | PenStyle::Brush => {
+                self.tools.constrain_inputdata(data_entries);
+            }
+            _ => {}
+        }
+    }
+}
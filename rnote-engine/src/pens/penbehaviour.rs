@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+
+use crate::render::NodeSnapshot;
+use p2d::bounding_volume::AABB;
+
+use crate::render::Renderer;
+use crate::sheet::Sheet;
+use crate::strokes::strokestyle::InputData;
+
+/// Tells the ui what needs to be updated after a pen handled input
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SurfaceFlags {
+    /// The sheet was resized, so the background needs to be regenerated
+    pub sheet_resized: bool,
+    /// The selection was changed
+    pub selection_changed: bool,
+}
+
+impl SurfaceFlags {
+    /// Merges the flags of a following operation
+    pub fn merge_with_other(&mut self, other: Self) {
+        self.sheet_resized |= other.sheet_resized;
+        self.selection_changed |= other.selection_changed;
+    }
+}
+
+pub trait PenBehaviour {
+    fn begin(
+        &mut self,
+        data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags;
+    fn motion(
+        &mut self,
+        data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags;
+    fn end(
+        &mut self,
+        data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags;
+    fn draw(
+        &self,
+        _sheet_bounds: AABB,
+        _renderer: &Renderer,
+        _zoom: f64,
+        _snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
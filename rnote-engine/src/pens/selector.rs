@@ -1,16 +1,17 @@
 use std::collections::VecDeque;
 
+use crate::pdf::PdfTextSelection;
+use crate::render::NodeSnapshot;
+use crate::sheet::Sheet;
 use crate::strokes::strokestyle::InputData;
-use crate::ui::appwindow::RnoteAppWindow;
 use crate::{compose, render, utils};
 
 use anyhow::Context;
-use gtk4::{gdk, prelude::*, Snapshot};
 use p2d::bounding_volume::{BoundingVolume, AABB};
 use serde::{Deserialize, Serialize};
 use svg::node::element;
 
-use super::penbehaviour::PenBehaviour;
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum SelectorStyle {
@@ -22,6 +23,9 @@ pub enum SelectorStyle {
 pub struct Selector {
     style: SelectorStyle,
     pub path: Vec<InputData>,
+    /// The text of imported pdf pages which was selected by the last selection. Taken by the ui
+    #[serde(skip)]
+    pub text_selection: Option<PdfTextSelection>,
 }
 
 impl Default for Selector {
@@ -29,24 +33,34 @@ impl Default for Selector {
         Self {
             style: SelectorStyle::Polygon,
             path: vec![],
+            text_selection: None,
         }
     }
 }
 
 impl PenBehaviour for Selector {
-    fn begin(&mut self, mut data_entries: VecDeque<InputData>, appwindow: &RnoteAppWindow) {
-        appwindow
-            .canvas()
-            .set_cursor(gdk::Cursor::from_name("cell", None).as_ref());
-
+    fn begin(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        _sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.path.clear();
+        self.text_selection = None;
 
         if let Some(inputdata) = data_entries.pop_back() {
             self.path.push(inputdata);
         }
+
+        SurfaceFlags::default()
     }
 
-    fn motion(&mut self, mut data_entries: VecDeque<InputData>, _appwindow: &RnoteAppWindow) {
+    fn motion(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        _sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(inputdata) = data_entries.pop_back() {
             match self.style {
                 SelectorStyle::Polygon => {
@@ -60,24 +74,35 @@ impl PenBehaviour for Selector {
                 }
             }
         }
-    }
 
-    fn end(&mut self, _data_entries: VecDeque<InputData>, appwindow: &RnoteAppWindow) {
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().cursor()));
+        SurfaceFlags::default()
+    }
 
-        appwindow
-            .canvas()
-            .sheet()
+    fn end(
+        &mut self,
+        _data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        viewport: AABB,
+    ) -> SurfaceFlags {
+        sheet
             .strokes_state()
             .borrow_mut()
-            .update_selection_for_selector(
-                self,
-                Some(appwindow.canvas().viewport_in_sheet_coords()),
-            );
+            .update_selection_for_selector(self, Some(viewport));
+
+        // Selects the text of imported pdf pages when no strokes are selected
+        self.text_selection = if sheet.strokes_state().borrow().selection_len() == 0 {
+            self.gen_bounds()
+                .and_then(|bounds| sheet.strokes_state().borrow().select_pdf_text(bounds))
+        } else {
+            None
+        };
 
         self.path.clear();
+
+        SurfaceFlags {
+            selection_changed: true,
+            ..SurfaceFlags::default()
+        }
     }
 
     fn draw(
@@ -85,7 +110,7 @@ impl PenBehaviour for Selector {
         _sheet_bounds: AABB,
         renderer: &render::Renderer,
         zoom: f64,
-        snapshot: &Snapshot,
+        snapshot: &NodeSnapshot,
     ) -> Result<(), anyhow::Error> {
         if let Some(bounds) = self.gen_bounds() {
             let mut data = element::path::Data::new();
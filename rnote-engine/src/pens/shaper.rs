@@ -1,15 +1,15 @@
-use gtk4::prelude::*;
 use p2d::bounding_volume::AABB;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 use crate::compose::rough::roughoptions;
+use crate::sheet::Sheet;
 use crate::strokes::shapestroke::ShapeStroke;
-use crate::strokes::strokestyle::{Element, StrokeStyle};
+use crate::strokes::strokestyle::{Element, InputData, StrokeStyle};
 use crate::strokesstate::StrokeKey;
 use crate::{input, utils};
 
-use super::penbehaviour::PenBehaviour;
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename = "shapestyle")]
@@ -83,19 +83,17 @@ impl Default for Shaper {
 impl PenBehaviour for Shaper {
     fn begin(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         self.current_stroke = None;
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().motion_cursor()));
 
         let filter_bounds = AABB::new(
             na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
             na::point![
-                (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                (sheet.height()) as f64 + input::INPUT_OVERSHOOT
             ],
         );
         input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
@@ -105,66 +103,62 @@ impl PenBehaviour for Shaper {
             let shapestroke = StrokeStyle::ShapeStroke(ShapeStroke::new(element, self.clone()));
 
             self.current_stroke = Some(
-                appwindow
-                    .canvas()
-                    .sheet()
+                sheet
                     .strokes_state()
                     .borrow_mut()
                     .insert_stroke(shapestroke),
             );
         }
+
+        SurfaceFlags::default()
     }
 
     fn motion(
         &mut self,
-        mut data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke_key) = self.current_stroke {
             let filter_bounds = AABB::new(
                 na::point![-input::INPUT_OVERSHOOT, -input::INPUT_OVERSHOOT],
                 na::point![
-                    (appwindow.canvas().sheet().width()) as f64 + input::INPUT_OVERSHOOT,
-                    (appwindow.canvas().sheet().height()) as f64 + input::INPUT_OVERSHOOT
+                    (sheet.width()) as f64 + input::INPUT_OVERSHOOT,
+                    (sheet.height()) as f64 + input::INPUT_OVERSHOOT
                 ],
             );
             input::filter_mapped_inputdata(filter_bounds, &mut data_entries);
 
             for inputdata in data_entries {
-                appwindow
-                    .canvas()
-                    .sheet()
+                sheet
                     .strokes_state()
                     .borrow_mut()
                     .add_to_stroke(current_stroke_key, Element::new(inputdata));
             }
         }
+
+        SurfaceFlags::default()
     }
 
     fn end(
         &mut self,
-        _data_entries: VecDeque<crate::strokes::strokestyle::InputData>,
-        appwindow: &crate::ui::appwindow::RnoteAppWindow,
-    ) {
-        appwindow
-            .canvas()
-            .set_cursor(Some(&appwindow.canvas().cursor()));
-
+        _data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
         if let Some(current_stroke) = self.current_stroke.take() {
-            appwindow
-                .canvas()
-                .sheet()
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .update_geometry_for_stroke(current_stroke);
 
-            appwindow
-                .canvas()
-                .sheet()
+            sheet
                 .strokes_state()
                 .borrow_mut()
                 .regenerate_rendering_for_stroke_threaded(current_stroke);
         }
+
+        SurfaceFlags::default()
     }
 }
 
@@ -0,0 +1,905 @@
+use std::collections::VecDeque;
+
+use crate::compose::geometry;
+use crate::render::{NodeSnapshot, Renderer};
+use crate::sheet::Sheet;
+use crate::strokes::strokestyle::InputData;
+use crate::strokesstate::StrokeKey;
+use crate::{compose, render, utils};
+
+use anyhow::Context;
+use p2d::bounding_volume::{BoundingVolume, AABB};
+
+use super::penbehaviour::{PenBehaviour, SurfaceFlags};
+
+#[derive(Clone, Debug)]
+pub enum ToolStyle {
+    ExpandSheet,
+    DragProximity,
+    Ruler,
+    Protractor,
+}
+
+impl Default for ToolStyle {
+    fn default() -> Self {
+        Self::ExpandSheet
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExpandSheetTool {
+    y_start_pos: f64,
+    y_current_pos: f64,
+    strokes_below: Vec<StrokeKey>,
+}
+
+impl Default for ExpandSheetTool {
+    fn default() -> Self {
+        Self {
+            y_start_pos: 0.0,
+            y_current_pos: 0.0,
+            strokes_below: vec![],
+        }
+    }
+}
+
+impl ExpandSheetTool {
+    pub const Y_OFFSET_THRESHOLD: f64 = 2.0;
+    pub const FILL_COLOR: utils::Color = utils::Color {
+        r: 0.8,
+        g: 0.9,
+        b: 1.0,
+        a: 0.2,
+    };
+    pub const THRESHOLD_LINE_COLOR: utils::Color = utils::Color {
+        r: 0.5,
+        g: 0.7,
+        b: 0.7,
+        a: 1.0,
+    };
+    pub const THRESHOLD_LINE_STROKE_WIDTH: f64 = 10.0;
+    pub const OFFSET_LINE_COLOR: utils::Color = utils::Color {
+        r: 0.0,
+        g: 0.7,
+        b: 1.0,
+        a: 1.0,
+    };
+    pub const OFFSET_LINE_STROKE_WIDTH: f64 = 2.0;
+
+    pub fn draw(
+        &self,
+        sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        let x = sheet_bounds.mins[0];
+        let y = self.y_start_pos;
+        let width = sheet_bounds.extents()[0];
+        let height = self.y_current_pos - self.y_start_pos;
+        let bounds =
+            geometry::aabb_new_positive(na::point![x, y], na::point![x + width, y + height]);
+
+        let bounds_rect = svg::node::element::Rectangle::new()
+            .set("x", bounds.mins[0])
+            .set("y", bounds.mins[1])
+            .set("width", bounds.extents()[0])
+            .set("height", bounds.extents()[1])
+            .set("stroke", "none")
+            .set("fill", Self::FILL_COLOR.to_css_color())
+            .set("stroke-linejoin", "miter")
+            .set("stroke-linecap", "butt");
+
+        let threshold_line = svg::node::element::Line::new()
+            .set("x1", x)
+            .set("y1", y)
+            .set("x2", x + width)
+            .set("y2", y)
+            .set("stroke", Self::THRESHOLD_LINE_COLOR.to_css_color())
+            .set("stroke-width", Self::THRESHOLD_LINE_STROKE_WIDTH)
+            .set("stroke-dasharray", "16 12")
+            .set("stroke-linecap", "butt");
+
+        let offset_line = svg::node::element::Line::new()
+            .set("x1", x)
+            .set("y1", y + height)
+            .set("x2", x + width)
+            .set("y2", y + height)
+            .set("stroke", Self::OFFSET_LINE_COLOR.to_css_color())
+            .set("stroke-width", Self::OFFSET_LINE_STROKE_WIDTH)
+            .set("stroke-linecap", "butt");
+
+        let group = svg::node::element::Group::new()
+            .add(bounds_rect)
+            .add(threshold_line)
+            .add(offset_line);
+
+        let svg_data = compose::node_to_string(&group)?;
+        let svg = render::Svg { svg_data, bounds };
+
+        let image = renderer.gen_image(zoom, &[svg], bounds)?;
+        let rendernode =
+            render::image_to_rendernode(&image, zoom).context("ExpandSheetTool draw() failed")?;
+        snapshot.append_node(&rendernode);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DragProximityTool {
+    pub pos: na::Vector2<f64>,
+    pub offset: na::Vector2<f64>,
+    pub radius: f64,
+}
+
+impl Default for DragProximityTool {
+    fn default() -> Self {
+        Self {
+            pos: na::Vector2::zeros(),
+            offset: na::Vector2::zeros(),
+            radius: Self::RADIUS_DEFAULT,
+        }
+    }
+}
+
+impl DragProximityTool {
+    pub const OFFSET_MAGN_THRESHOLD: f64 = 4.0;
+    pub const OUTLINE_COLOR: utils::Color = utils::Color {
+        r: 0.5,
+        g: 0.7,
+        b: 0.7,
+        a: 1.0,
+    };
+    pub const OUTLINE_WIDTH: f64 = 1.0;
+    pub const FILL_COLOR: utils::Color = utils::Color {
+        r: 0.8,
+        g: 0.8,
+        b: 0.8,
+        a: 0.2,
+    };
+    pub const RADIUS_DEFAULT: f64 = 60.0;
+
+    pub fn draw(
+        &self,
+        _sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        let cx = self.pos[0] + self.offset[0];
+        let cy = self.pos[1] + self.offset[1];
+        let r = self.radius;
+        let mut draw_bounds = geometry::aabb_new_positive(
+            na::point![cx - r - Self::OUTLINE_WIDTH, cy - r - Self::OUTLINE_WIDTH],
+            na::point![cx + r + Self::OUTLINE_WIDTH, cy + r + Self::OUTLINE_WIDTH],
+        );
+        draw_bounds.take_point(na::Point2::from(self.pos.add_scalar(-Self::OUTLINE_WIDTH)));
+        draw_bounds.take_point(na::Point2::from(self.pos.add_scalar(Self::OUTLINE_WIDTH)));
+
+        let mut group = svg::node::element::Group::new();
+
+        let n_circles = 7;
+        for i in (0..n_circles).rev() {
+            let r = r * (f64::from(i) / f64::from(n_circles));
+
+            let outline_circle = svg::node::element::Circle::new()
+                .set("cx", cx)
+                .set("cy", cy)
+                .set("r", r)
+                .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                .set("stroke-width", Self::OUTLINE_WIDTH)
+                .set("fill", Self::FILL_COLOR.to_css_color());
+
+            group = group.add(outline_circle);
+        }
+
+        let svg_data = compose::node_to_string(&group)?;
+        let svg = render::Svg {
+            svg_data,
+            bounds: draw_bounds,
+        };
+
+        let image = renderer.gen_image(zoom, &[svg], draw_bounds)?;
+        let rendernode =
+            render::image_to_rendernode(&image, zoom).context("DrawProximityTool draw() failed")?;
+        snapshot.append_node(&rendernode);
+
+        Ok(())
+    }
+}
+
+/// The state of a drag on a guide tool ( ruler, protractor ) which is currently in progress
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuideDrag {
+    None,
+    Translate { last_pos: na::Vector2<f64> },
+    Rotate,
+}
+
+impl Default for GuideDrag {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RulerTool {
+    pub visible: bool,
+    /// the center of the ruler
+    pub pos: na::Vector2<f64>,
+    /// the rotation angle in radians
+    pub angle: f64,
+    pub length: f64,
+    pub width: f64,
+    pub drag: GuideDrag,
+}
+
+impl Default for RulerTool {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            pos: na::Vector2::zeros(),
+            angle: 0.0,
+            length: Self::LENGTH_DEFAULT,
+            width: Self::WIDTH_DEFAULT,
+            drag: GuideDrag::default(),
+        }
+    }
+}
+
+impl RulerTool {
+    pub const LENGTH_DEFAULT: f64 = 600.0;
+    pub const WIDTH_DEFAULT: f64 = 60.0;
+    pub const SNAP_DISTANCE: f64 = 20.0;
+    pub const TICK_SPACING: f64 = 10.0;
+    pub const HANDLE_RADIUS: f64 = 14.0;
+    pub const FILL_COLOR: utils::Color = utils::Color {
+        r: 0.8,
+        g: 0.9,
+        b: 1.0,
+        a: 0.4,
+    };
+    pub const OUTLINE_COLOR: utils::Color = utils::Color {
+        r: 0.0,
+        g: 0.4,
+        b: 0.7,
+        a: 1.0,
+    };
+    pub const OUTLINE_WIDTH: f64 = 1.0;
+    pub const HANDLE_COLOR: utils::Color = utils::Color {
+        r: 0.0,
+        g: 0.7,
+        b: 1.0,
+        a: 0.6,
+    };
+
+    /// the unit vector along the ruler edges
+    pub fn direction(&self) -> na::Vector2<f64> {
+        na::vector![self.angle.cos(), self.angle.sin()]
+    }
+
+    /// the unit vector perpendicular to the ruler edges
+    pub fn normal(&self) -> na::Vector2<f64> {
+        na::vector![-self.angle.sin(), self.angle.cos()]
+    }
+
+    /// the position of the rotation handle, near the end of the ruler
+    pub fn handle_pos(&self) -> na::Vector2<f64> {
+        self.pos + self.direction() * (self.length * 0.5 - Self::HANDLE_RADIUS * 2.0)
+    }
+
+    pub fn corners(&self) -> [na::Vector2<f64>; 4] {
+        let half_length = self.direction() * self.length * 0.5;
+        let half_width = self.normal() * self.width * 0.5;
+
+        [
+            self.pos - half_length - half_width,
+            self.pos + half_length - half_width,
+            self.pos + half_length + half_width,
+            self.pos - half_length + half_width,
+        ]
+    }
+
+    pub fn contains(&self, pos: na::Vector2<f64>) -> bool {
+        let rel = pos - self.pos;
+
+        rel.dot(&self.direction()).abs() <= self.length * 0.5
+            && rel.dot(&self.normal()).abs() <= self.width * 0.5
+    }
+
+    /// Begins rotating when the position is on the handle, translating when it is on the tool.
+    /// Presses which miss the tool begin no drag
+    pub fn begin_drag(&mut self, pos: na::Vector2<f64>) {
+        self.drag = if (pos - self.handle_pos()).magnitude() <= Self::HANDLE_RADIUS {
+            GuideDrag::Rotate
+        } else if self.contains(pos) {
+            GuideDrag::Translate { last_pos: pos }
+        } else {
+            GuideDrag::None
+        };
+    }
+
+    pub fn update_drag(&mut self, pos: na::Vector2<f64>) {
+        match self.drag {
+            GuideDrag::None => {}
+            GuideDrag::Translate { last_pos } => {
+                self.pos += pos - last_pos;
+                self.drag = GuideDrag::Translate { last_pos: pos };
+            }
+            GuideDrag::Rotate => {
+                let rel = pos - self.pos;
+                if rel.magnitude() > Self::HANDLE_RADIUS {
+                    self.angle = rel[1].atan2(rel[0]);
+                }
+            }
+        }
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag = GuideDrag::None;
+    }
+
+    /// Returns the position projected onto the nearest ruler edge, if it is within the snapping distance
+    pub fn snap_pos(&self, pos: na::Vector2<f64>) -> Option<na::Vector2<f64>> {
+        if !self.visible {
+            return None;
+        }
+        let direction = self.direction();
+        let normal = self.normal();
+
+        [1.0, -1.0]
+            .iter()
+            .filter_map(|&side| {
+                let edge_origin = self.pos + normal * side * self.width * 0.5;
+                let rel = pos - edge_origin;
+                let along = rel.dot(&direction);
+                let dist = rel.dot(&normal).abs();
+
+                if along.abs() <= self.length * 0.5 && dist < Self::SNAP_DISTANCE {
+                    Some((dist, edge_origin + direction * along))
+                } else {
+                    None
+                }
+            })
+            .min_by(|first, second| {
+                first
+                    .0
+                    .partial_cmp(&second.0)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(_, snapped)| snapped)
+    }
+
+    pub fn bounds(&self) -> AABB {
+        let mut bounds = AABB::new_invalid();
+        for corner in self.corners().iter() {
+            bounds.take_point(na::Point2::from(*corner));
+        }
+        bounds.loosened(Self::OUTLINE_WIDTH)
+    }
+
+    pub fn draw(
+        &self,
+        _sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        let bounds = self.bounds();
+        let half_length = self.length * 0.5;
+        let half_width = self.width * 0.5;
+
+        let mut group = svg::node::element::Group::new().set(
+            "transform",
+            format!(
+                "translate({} {}) rotate({})",
+                self.pos[0],
+                self.pos[1],
+                self.angle.to_degrees()
+            ),
+        );
+
+        group = group.add(
+            svg::node::element::Rectangle::new()
+                .set("x", -half_length)
+                .set("y", -half_width)
+                .set("width", self.length)
+                .set("height", self.width)
+                .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                .set("stroke-width", Self::OUTLINE_WIDTH)
+                .set("fill", Self::FILL_COLOR.to_css_color()),
+        );
+
+        // Ticks on both edges, longer every fifth and tenth tick
+        let n_ticks = (self.length / Self::TICK_SPACING).floor() as i32;
+        for i in 0..=n_ticks {
+            let x = -half_length + f64::from(i) * Self::TICK_SPACING;
+            let tick_length = if i % 10 == 0 {
+                half_width * 0.5
+            } else if i % 5 == 0 {
+                half_width * 0.35
+            } else {
+                half_width * 0.2
+            };
+
+            for (y_edge, y_tick) in [
+                (-half_width, -half_width + tick_length),
+                (half_width, half_width - tick_length),
+            ] {
+                group = group.add(
+                    svg::node::element::Line::new()
+                        .set("x1", x)
+                        .set("y1", y_edge)
+                        .set("x2", x)
+                        .set("y2", y_tick)
+                        .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                        .set("stroke-width", Self::OUTLINE_WIDTH),
+                );
+            }
+        }
+
+        group = group.add(
+            svg::node::element::Circle::new()
+                .set("cx", half_length - Self::HANDLE_RADIUS * 2.0)
+                .set("cy", 0.0)
+                .set("r", Self::HANDLE_RADIUS)
+                .set("stroke", "none")
+                .set("fill", Self::HANDLE_COLOR.to_css_color()),
+        );
+
+        let svg_data = compose::node_to_string(&group)?;
+        let svg = render::Svg { svg_data, bounds };
+
+        let image = renderer.gen_image(zoom, &[svg], bounds)?;
+        let rendernode =
+            render::image_to_rendernode(&image, zoom).context("RulerTool draw() failed")?;
+        snapshot.append_node(&rendernode);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProtractorTool {
+    pub visible: bool,
+    /// the center of the protractor baseline
+    pub pos: na::Vector2<f64>,
+    /// the rotation angle in radians
+    pub angle: f64,
+    pub radius: f64,
+    pub drag: GuideDrag,
+}
+
+impl Default for ProtractorTool {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            pos: na::Vector2::zeros(),
+            angle: 0.0,
+            radius: Self::RADIUS_DEFAULT,
+            drag: GuideDrag::default(),
+        }
+    }
+}
+
+impl ProtractorTool {
+    pub const RADIUS_DEFAULT: f64 = 200.0;
+    pub const SNAP_DISTANCE: f64 = 20.0;
+    /// The angle step of the radial lines the protractor snaps to
+    pub const SNAP_ANGLE_STEP: f64 = std::f64::consts::PI / 12.0;
+    pub const HANDLE_RADIUS: f64 = 14.0;
+    pub const LABEL_FONT_SIZE: f64 = 12.0;
+    pub const FILL_COLOR: utils::Color = RulerTool::FILL_COLOR;
+    pub const OUTLINE_COLOR: utils::Color = RulerTool::OUTLINE_COLOR;
+    pub const OUTLINE_WIDTH: f64 = 1.0;
+    pub const HANDLE_COLOR: utils::Color = RulerTool::HANDLE_COLOR;
+
+    /// transforms a position in sheet coordinates into the local coordinates of the protractor,
+    /// where the baseline lies on the x axis and the arc spans the negative y half
+    fn to_local(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(-self.angle) * (pos - self.pos)
+    }
+
+    fn to_sheet(&self, local: na::Vector2<f64>) -> na::Vector2<f64> {
+        na::Rotation2::new(self.angle) * local + self.pos
+    }
+
+    /// the position of the rotation handle, at the top of the arc
+    pub fn handle_pos(&self) -> na::Vector2<f64> {
+        self.to_sheet(na::vector![0.0, -self.radius + Self::HANDLE_RADIUS * 2.0])
+    }
+
+    /// whether the position is on the half disc of the protractor
+    pub fn contains(&self, pos: na::Vector2<f64>) -> bool {
+        let local = self.to_local(pos);
+
+        local[1] <= 0.0 && local.magnitude() <= self.radius
+    }
+
+    /// Begins rotating when the position is on the handle, translating when it is on the tool.
+    /// Presses which miss the tool begin no drag
+    pub fn begin_drag(&mut self, pos: na::Vector2<f64>) {
+        self.drag = if (pos - self.handle_pos()).magnitude() <= Self::HANDLE_RADIUS {
+            GuideDrag::Rotate
+        } else if self.contains(pos) {
+            GuideDrag::Translate { last_pos: pos }
+        } else {
+            GuideDrag::None
+        };
+    }
+
+    pub fn update_drag(&mut self, pos: na::Vector2<f64>) {
+        match self.drag {
+            GuideDrag::None => {}
+            GuideDrag::Translate { last_pos } => {
+                self.pos += pos - last_pos;
+                self.drag = GuideDrag::Translate { last_pos: pos };
+            }
+            GuideDrag::Rotate => {
+                let rel = pos - self.pos;
+                if rel.magnitude() > Self::HANDLE_RADIUS {
+                    self.angle = rel[0].atan2(-rel[1]);
+                }
+            }
+        }
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag = GuideDrag::None;
+    }
+
+    /// Returns the position snapped to the baseline, the arc or the nearest radial line of the protractor,
+    /// if it is within the snapping distance
+    pub fn snap_pos(&self, pos: na::Vector2<f64>) -> Option<na::Vector2<f64>> {
+        if !self.visible {
+            return None;
+        }
+        let local = self.to_local(pos);
+        let dist_center = local.magnitude();
+
+        // Baseline
+        if local[1].abs() < Self::SNAP_DISTANCE && local[0].abs() <= self.radius {
+            return Some(self.to_sheet(na::vector![local[0], 0.0]));
+        }
+        if local[1] > 0.0 || dist_center > self.radius + Self::SNAP_DISTANCE {
+            return None;
+        }
+        // Arc
+        if (dist_center - self.radius).abs() < Self::SNAP_DISTANCE {
+            return Some(self.to_sheet(local * (self.radius / dist_center)));
+        }
+        // Radial lines
+        let angle = (-local[1]).atan2(local[0]);
+        let snapped_angle = (angle / Self::SNAP_ANGLE_STEP).round() * Self::SNAP_ANGLE_STEP;
+        let radial_dir = na::vector![snapped_angle.cos(), -snapped_angle.sin()];
+        let dist_radial = (local[0] * radial_dir[1] - local[1] * radial_dir[0]).abs();
+
+        if dist_radial < Self::SNAP_DISTANCE {
+            Some(self.to_sheet(radial_dir * local.dot(&radial_dir)))
+        } else {
+            None
+        }
+    }
+
+    pub fn bounds(&self) -> AABB {
+        let mut bounds = AABB::new_invalid();
+        bounds.take_point(na::Point2::from(self.pos));
+        for i in 0..=12 {
+            let angle = f64::from(i) * std::f64::consts::PI / 12.0;
+            bounds.take_point(na::Point2::from(self.to_sheet(na::vector![
+                self.radius * angle.cos(),
+                -self.radius * angle.sin()
+            ])));
+        }
+        bounds.loosened(Self::OUTLINE_WIDTH + self.radius * 0.05)
+    }
+
+    pub fn draw(
+        &self,
+        _sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        let bounds = self.bounds();
+        let r = self.radius;
+
+        let mut group = svg::node::element::Group::new().set(
+            "transform",
+            format!(
+                "translate({} {}) rotate({})",
+                self.pos[0],
+                self.pos[1],
+                self.angle.to_degrees()
+            ),
+        );
+
+        group = group.add(
+            svg::node::element::Path::new()
+                .set("d", format!("M {} 0 A {} {} 0 0 1 {} 0 Z", -r, r, r, r))
+                .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                .set("stroke-width", Self::OUTLINE_WIDTH)
+                .set("fill", Self::FILL_COLOR.to_css_color()),
+        );
+
+        // A tick every 5 degrees, labeled every 30 degrees
+        for deg in (0..=180).step_by(5) {
+            let angle = f64::from(deg).to_radians();
+            let dir = na::vector![angle.cos(), -angle.sin()];
+            let tick_length = if deg % 30 == 0 {
+                r * 0.12
+            } else if deg % 10 == 0 {
+                r * 0.08
+            } else {
+                r * 0.05
+            };
+            let outer = dir * r;
+            let inner = dir * (r - tick_length);
+
+            group = group.add(
+                svg::node::element::Line::new()
+                    .set("x1", outer[0])
+                    .set("y1", outer[1])
+                    .set("x2", inner[0])
+                    .set("y2", inner[1])
+                    .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                    .set("stroke-width", Self::OUTLINE_WIDTH),
+            );
+
+            if deg % 30 == 0 {
+                let label_pos = dir * (r - tick_length - Self::LABEL_FONT_SIZE);
+                group = group.add(
+                    svg::node::element::Text::new()
+                        .set("x", label_pos[0])
+                        .set("y", label_pos[1])
+                        .set("font-size", Self::LABEL_FONT_SIZE)
+                        .set("font-family", "sans-serif")
+                        .set("text-anchor", "middle")
+                        .set("dominant-baseline", "middle")
+                        .set("fill", Self::OUTLINE_COLOR.to_css_color())
+                        .add(svg::node::Text::new(deg.to_string())),
+                );
+            }
+        }
+
+        // The center mark
+        group = group.add(
+            svg::node::element::Line::new()
+                .set("x1", 0.0)
+                .set("y1", 0.0)
+                .set("x2", 0.0)
+                .set("y2", -r * 0.1)
+                .set("stroke", Self::OUTLINE_COLOR.to_css_color())
+                .set("stroke-width", Self::OUTLINE_WIDTH),
+        );
+
+        group = group.add(
+            svg::node::element::Circle::new()
+                .set("cx", 0.0)
+                .set("cy", -r + Self::HANDLE_RADIUS * 2.0)
+                .set("r", Self::HANDLE_RADIUS)
+                .set("stroke", "none")
+                .set("fill", Self::HANDLE_COLOR.to_css_color()),
+        );
+
+        let svg_data = compose::node_to_string(&group)?;
+        let svg = render::Svg { svg_data, bounds };
+
+        let image = renderer.gen_image(zoom, &[svg], bounds)?;
+        let rendernode =
+            render::image_to_rendernode(&image, zoom).context("ProtractorTool draw() failed")?;
+        snapshot.append_node(&rendernode);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tools {
+    current_style: ToolStyle,
+    pub expand_sheet_tool: ExpandSheetTool,
+    pub drag_proximity_tool: DragProximityTool,
+    pub ruler_tool: RulerTool,
+    pub protractor_tool: ProtractorTool,
+}
+
+impl PenBehaviour for Tools {
+    fn begin(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
+        if let Some(inputdata) = data_entries.pop_back() {
+            match &mut self.current_style {
+                ToolStyle::ExpandSheet => {
+                    self.expand_sheet_tool.y_start_pos = inputdata.pos()[1];
+                    self.expand_sheet_tool.y_current_pos = inputdata.pos()[1];
+
+                    self.expand_sheet_tool.strokes_below = sheet
+                        .strokes_state()
+                        .borrow_mut()
+                        .strokes_below_y_pos(self.expand_sheet_tool.y_current_pos);
+                }
+                ToolStyle::DragProximity => {
+                    self.drag_proximity_tool.pos = inputdata.pos();
+                    self.drag_proximity_tool.offset = na::Vector2::zeros();
+                }
+                ToolStyle::Ruler => {
+                    if !self.ruler_tool.visible {
+                        self.ruler_tool.pos = inputdata.pos();
+                        self.ruler_tool.visible = true;
+                    }
+                    self.ruler_tool.begin_drag(inputdata.pos());
+                }
+                ToolStyle::Protractor => {
+                    if !self.protractor_tool.visible {
+                        self.protractor_tool.pos = inputdata.pos();
+                        self.protractor_tool.visible = true;
+                    }
+                    self.protractor_tool.begin_drag(inputdata.pos());
+                }
+            }
+        }
+
+        SurfaceFlags::default()
+    }
+
+    fn motion(
+        &mut self,
+        mut data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
+        if let Some(inputdata) = data_entries.pop_back() {
+            match &mut self.current_style {
+                ToolStyle::ExpandSheet => {
+                    let y_offset = inputdata.pos()[1] - self.expand_sheet_tool.y_current_pos;
+
+                    if y_offset.abs() > ExpandSheetTool::Y_OFFSET_THRESHOLD {
+                        sheet.strokes_state().borrow_mut().translate_strokes(
+                            &self.expand_sheet_tool.strokes_below,
+                            na::vector![0.0, y_offset],
+                        );
+
+                        self.expand_sheet_tool.y_current_pos = inputdata.pos()[1];
+                    }
+                }
+                ToolStyle::DragProximity => {
+                    self.drag_proximity_tool.offset =
+                        inputdata.pos() - self.drag_proximity_tool.pos;
+
+                    if self.drag_proximity_tool.offset.magnitude()
+                        > DragProximityTool::OFFSET_MAGN_THRESHOLD
+                    {
+                        sheet
+                            .strokes_state()
+                            .borrow_mut()
+                            .drag_strokes_proximity(&self.drag_proximity_tool);
+
+                        self.drag_proximity_tool.pos = inputdata.pos();
+                        self.drag_proximity_tool.offset = na::Vector2::zeros();
+                    }
+                }
+                ToolStyle::Ruler => {
+                    self.ruler_tool.update_drag(inputdata.pos());
+                }
+                ToolStyle::Protractor => {
+                    self.protractor_tool.update_drag(inputdata.pos());
+                }
+            }
+        }
+
+        SurfaceFlags::default()
+    }
+
+    fn end(
+        &mut self,
+        _data_entries: VecDeque<InputData>,
+        sheet: &Sheet,
+        _viewport: AABB,
+    ) -> SurfaceFlags {
+        match &mut self.current_style {
+            ToolStyle::ExpandSheet => {
+                self.expand_sheet_tool.y_start_pos = 0.0;
+                self.expand_sheet_tool.y_current_pos = 0.0;
+            }
+            ToolStyle::DragProximity => {
+                self.drag_proximity_tool.pos = na::Vector2::zeros();
+                self.drag_proximity_tool.offset = na::Vector2::zeros();
+            }
+            ToolStyle::Ruler => {
+                self.ruler_tool.end_drag();
+            }
+            ToolStyle::Protractor => {
+                self.protractor_tool.end_drag();
+            }
+        }
+
+        SurfaceFlags {
+            sheet_resized: sheet.resize_endless(),
+            ..SurfaceFlags::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        match &self.current_style {
+            ToolStyle::ExpandSheet => {
+                self.expand_sheet_tool
+                    .draw(sheet_bounds, renderer, zoom, snapshot)?;
+            }
+            ToolStyle::DragProximity => {
+                self.drag_proximity_tool
+                    .draw(sheet_bounds, renderer, zoom, snapshot)?;
+            }
+            ToolStyle::Ruler | ToolStyle::Protractor => {}
+        }
+
+        self.draw_guides(sheet_bounds, renderer, zoom, snapshot)?;
+
+        Ok(())
+    }
+}
+
+impl Tools {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn style(&self) -> ToolStyle {
+        self.current_style.clone()
+    }
+
+    pub fn set_style(&mut self, style: ToolStyle) {
+        self.current_style = style;
+    }
+
+    pub fn guides_visible(&self) -> bool {
+        self.ruler_tool.visible || self.protractor_tool.visible
+    }
+
+    pub fn hide_guides(&mut self) {
+        self.ruler_tool.visible = false;
+        self.protractor_tool.visible = false;
+    }
+
+    /// Constrains the positions of the input data to the visible ruler or protractor, if they are within their snapping distance
+    pub fn constrain_inputdata(&self, data_entries: &mut VecDeque<InputData>) {
+        for inputdata in data_entries.iter_mut() {
+            if let Some(snapped) = self
+                .ruler_tool
+                .snap_pos(inputdata.pos())
+                .or_else(|| self.protractor_tool.snap_pos(inputdata.pos()))
+            {
+                inputdata.set_pos(snapped);
+            }
+        }
+    }
+
+    /// Draws the ruler and protractor if they are visible. They stay on the sheet while drawing with the other pens.
+    pub fn draw_guides(
+        &self,
+        sheet_bounds: AABB,
+        renderer: &Renderer,
+        zoom: f64,
+        snapshot: &NodeSnapshot,
+    ) -> Result<(), anyhow::Error> {
+        if self.ruler_tool.visible {
+            self.ruler_tool
+                .draw(sheet_bounds, renderer, zoom, snapshot)?;
+        }
+        if self.protractor_tool.visible {
+            self.protractor_tool
+                .draw(sheet_bounds, renderer, zoom, snapshot)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,704 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+
+use anyhow::Context;
+use gdk::{gio, prelude::*};
+use gsk::{graphene, prelude::*};
+use image::ImageEncoder;
+use p2d::bounding_volume::{BoundingVolume, AABB};
+
+use crate::compose::drawpath::{self, DrawPath};
+use crate::compose::{self, geometry};
+
+#[derive(Debug, Clone)]
+pub enum RendererBackend {
+    Librsvg,
+    Resvg,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub data: Vec<u8>,
+    /// bounds in the coordinate space of the sheet
+    pub bounds: AABB,
+    /// width of the data
+    pub data_width: i32,
+    /// height of the data
+    pub data_height: i32,
+    /// the memory format
+    pub memory_format: gdk::MemoryFormat,
+}
+
+/// The formats bitmap images can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitmapExportFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl BitmapExportFormat {
+    pub const JPEG_QUALITY: u8 = 90;
+
+    /// Looks up the format from the file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+impl Image {
+    /// Encodes the image into the export format. Jpeg has no alpha channel, so the image is drawn on a white background
+    pub fn encode(&self, format: BitmapExportFormat) -> Result<Vec<u8>, anyhow::Error> {
+        let (r_i, b_i) = match self.memory_format {
+            gdk::MemoryFormat::R8g8b8a8Premultiplied => (0, 2),
+            gdk::MemoryFormat::B8g8r8a8Premultiplied => (2, 0),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "encode() failed, unsupported memory format {:?}",
+                    self.memory_format
+                ))
+            }
+        };
+        let width = self.data_width as u32;
+        let height = self.data_height as u32;
+        let mut bytes = Vec::<u8>::new();
+
+        match format {
+            BitmapExportFormat::Jpeg => {
+                let rgb_data = self
+                    .data
+                    .chunks_exact(4)
+                    .flat_map(|pixel| {
+                        let background = 255 - pixel[3];
+                        [
+                            pixel[r_i].saturating_add(background),
+                            pixel[1].saturating_add(background),
+                            pixel[b_i].saturating_add(background),
+                        ]
+                    })
+                    .collect::<Vec<u8>>();
+
+                image::codecs::jpeg::JpegEncoder::new_with_quality(
+                    &mut bytes,
+                    BitmapExportFormat::JPEG_QUALITY,
+                )
+                .encode(&rgb_data, width, height, image::ColorType::Rgb8)?;
+            }
+            BitmapExportFormat::Png | BitmapExportFormat::Webp => {
+                let rgba_data = self
+                    .data
+                    .chunks_exact(4)
+                    .flat_map(|pixel| {
+                        let unpremultiply = |value: u8| {
+                            if pixel[3] == 0 {
+                                0
+                            } else {
+                                ((u16::from(value) * 255 + u16::from(pixel[3]) / 2)
+                                    / u16::from(pixel[3]))
+                                .min(255) as u8
+                            }
+                        };
+                        [
+                            unpremultiply(pixel[r_i]),
+                            unpremultiply(pixel[1]),
+                            unpremultiply(pixel[b_i]),
+                            pixel[3],
+                        ]
+                    })
+                    .collect::<Vec<u8>>();
+
+                if format == BitmapExportFormat::Png {
+                    image::codecs::png::PngEncoder::new(&mut bytes).write_image(
+                        &rgba_data,
+                        width,
+                        height,
+                        image::ColorType::Rgba8,
+                    )?;
+                } else {
+                    image::codecs::webp::WebPEncoder::new_lossless(&mut bytes).encode(
+                        &rgba_data,
+                        width,
+                        height,
+                        image::ColorType::Rgba8,
+                    )?;
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Svg {
+    pub svg_data: String,
+    pub bounds: AABB,
+}
+
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub backend: RendererBackend,
+    pub usvg_options: usvg::Options,
+    pub usvg_xml_options: usvg::XmlOptions,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        let mut usvg_options = usvg::Options::default();
+        usvg_options.fontdb.load_system_fonts();
+
+        let usvg_xml_options = usvg::XmlOptions {
+            id_prefix: None,
+            writer_opts: xmlwriter::Options {
+                use_single_quote: false,
+                indent: xmlwriter::Indent::None,
+                attributes_indent: xmlwriter::Indent::None,
+            },
+        };
+
+        Self {
+            backend: RendererBackend::Librsvg,
+            usvg_options,
+            usvg_xml_options,
+        }
+    }
+}
+
+impl Renderer {
+    /// generates images from SVGs. bounds are in coordinate space of the sheet, (not zoomed)
+    /// expects the svgs to be raw svg tags, no svg root or xml header needed
+    pub fn gen_image(&self, zoom: f64, svgs: &[Svg], bounds: AABB) -> Result<Image, anyhow::Error> {
+        if svgs.is_empty() {
+            return Err(anyhow::Error::msg("gen_image() failed, no svg's in slice."));
+        }
+        if bounds.extents()[0] <= 0.0 || bounds.extents()[1] <= 0.0 {
+            return Err(anyhow::Error::msg(
+                "gen_image() failed, bounds extents are <= 0.0",
+            ));
+        }
+        /*         match self.backend {
+            RendererBackend::Librsvg => self.gen_image_librsvg(zoom, svgs, bounds),
+            RendererBackend::Resvg => self.gen_image_resvg(zoom, svgs, bounds),
+        } */
+        self.gen_image_resvg(zoom, svgs, bounds)
+    }
+
+    /// generates images by drawing the paths directly, without going through svg. bounds are in coordinate space of the sheet, (not zoomed)
+    pub fn gen_image_from_paths(
+        &self,
+        zoom: f64,
+        paths: &[DrawPath],
+        bounds: AABB,
+    ) -> Result<Image, anyhow::Error> {
+        if paths.is_empty() {
+            return Err(anyhow::Error::msg(
+                "gen_image_from_paths() failed, no paths in slice.",
+            ));
+        }
+        if bounds.extents()[0] <= 0.0 || bounds.extents()[1] <= 0.0 {
+            return Err(anyhow::Error::msg(
+                "gen_image_from_paths() failed, bounds extents are <= 0.0",
+            ));
+        }
+
+        let pixmap = drawpath::render_paths(paths, zoom, bounds)?;
+
+        Ok(Image {
+            data_width: pixmap.width() as i32,
+            data_height: pixmap.height() as i32,
+            data: pixmap.take(),
+            bounds,
+            memory_format: gdk::MemoryFormat::R8g8b8a8Premultiplied,
+        })
+    }
+    /*
+    fn gen_image_librsvg(
+        &self,
+        zoom: f64,
+        svgs: &[Svg],
+        bounds: AABB,
+    ) -> Result<Image, anyhow::Error> {
+        let width_scaled = ((bounds.extents()[0]) * zoom).round() as i32;
+        let height_scaled = ((bounds.extents()[1]) * zoom).round() as i32;
+
+        let mut surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width_scaled, height_scaled)
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "create ImageSurface with dimensions ({}, {}) failed, {}",
+                        width_scaled,
+                        height_scaled,
+                        e
+                    )
+                })?;
+
+        let mut svg_data = svgs
+            .iter()
+            .map(|svg| svg.svg_data.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        svg_data = compose::wrap_svg_root(svg_data.as_str(), Some(bounds), Some(bounds), true);
+
+        // Context in new scope, else accessing the surface data fails with a borrow error
+        {
+            let cx = cairo::Context::new(&surface).context("new cairo::Context failed")?;
+
+            let stream =
+                gio::MemoryInputStream::from_bytes(&glib::Bytes::from(svg_data.as_bytes()));
+
+            let handle = librsvg::Loader::new()
+                .read_stream::<gio::MemoryInputStream, gio::File, gio::Cancellable>(
+                    &stream, None, None,
+                )
+                .context("read stream to librsvg Loader failed")?;
+            let renderer = librsvg::CairoRenderer::new(&handle);
+            renderer
+                .render_document(
+                    &cx,
+                    &cairo::Rectangle {
+                        x: 0.0,
+                        y: 0.0,
+                        width: f64::from(width_scaled),
+                        height: f64::from(height_scaled),
+                    },
+                )
+                .map_err(|e| {
+                    anyhow::Error::msg(format!(
+                        "librsvg render_document() failed in gen_image_librsvg() with Err {}",
+                        e
+                    ))
+                })?;
+        }
+        // Surface needs to be flushed before accessing its data
+        surface.flush();
+
+        let data = surface
+            .data()
+            .map_err(|e| {
+                anyhow::Error::msg(format!(
+                    "accessing imagesurface data failed in gen_image_librsvg() with Err {}",
+                    e
+                ))
+            })?
+            .to_vec();
+
+        Ok(Image {
+            data,
+            bounds,
+            data_width: width_scaled,
+            data_height: height_scaled,
+            memory_format: gdk::MemoryFormat::B8g8r8a8Premultiplied,
+        })
+    } */
+
+    fn gen_image_resvg(
+        &self,
+        zoom: f64,
+        svgs: &[Svg],
+        bounds: AABB,
+    ) -> Result<Image, anyhow::Error> {
+        let width_scaled = ((bounds.extents()[0]) * zoom).round() as i32;
+        let height_scaled = ((bounds.extents()[1]) * zoom).round() as i32;
+
+        let mut svg_data = svgs
+            .iter()
+            .map(|svg| svg.svg_data.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        svg_data = compose::wrap_svg_root(svg_data.as_str(), Some(bounds), Some(bounds), true);
+
+        let mut pixmap = tiny_skia::Pixmap::new(width_scaled as u32, height_scaled as u32)
+            .ok_or_else(|| {
+                anyhow::Error::msg("tiny_skia::Pixmap::new() failed in gen_image_resvg()")
+            })?;
+
+        let rtree = usvg::Tree::from_data(svg_data.as_bytes(), &self.usvg_options.to_ref())?;
+
+        resvg::render(&rtree, usvg::FitTo::Zoom(zoom as f32), pixmap.as_mut())
+            .ok_or_else(|| anyhow::Error::msg("resvg::render failed in gen_image_resvg."))?;
+
+        let data = pixmap.data().to_vec();
+
+        Ok(Image {
+            data,
+            bounds,
+            data_width: width_scaled,
+            data_height: height_scaled,
+            memory_format: gdk::MemoryFormat::R8g8b8a8Premultiplied,
+        })
+    }
+}
+
+pub fn default_rendernode() -> gsk::RenderNode {
+    let bounds = graphene::Rect::new(0.0, 0.0, 0.0, 0.0);
+    gsk::CairoNode::new(&bounds).upcast()
+}
+
+/// A layer of the node snapshot, which wraps the nodes appended to it when it is popped
+#[derive(Debug)]
+enum NodeLayer {
+    Clip(graphene::Rect),
+    Blur(f64),
+    Opacity(f64),
+    /// Added by scale(), closed together with the layer it was added in
+    Transform(gsk::Transform),
+}
+
+/// Records render nodes, with an api like the gtk snapshot. The engine draws into it, so it does not depend on gtk.
+/// The ui then appends the recorded node to the snapshot of its widget
+#[derive(Debug, Default)]
+pub struct NodeSnapshot {
+    nodes: RefCell<Vec<gsk::RenderNode>>,
+    layers: RefCell<Vec<(NodeLayer, Vec<gsk::RenderNode>)>>,
+}
+
+impl NodeSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append_node(&self, node: &gsk::RenderNode) {
+        if let Some((_, nodes)) = self.layers.borrow_mut().last_mut() {
+            nodes.push(node.clone());
+        } else {
+            self.nodes.borrow_mut().push(node.clone());
+        }
+    }
+
+    pub fn append_color(&self, color: &gdk::RGBA, bounds: &graphene::Rect) {
+        self.append_node(&gsk::ColorNode::new(color, bounds).upcast());
+    }
+
+    pub fn append_texture(&self, texture: &impl IsA<gdk::Texture>, bounds: &graphene::Rect) {
+        self.append_node(&gsk::TextureNode::new(texture, bounds).upcast());
+    }
+
+    pub fn append_border(
+        &self,
+        outline: &gsk::RoundedRect,
+        border_width: &[f32; 4],
+        border_color: &[gdk::RGBA; 4],
+    ) {
+        self.append_node(&gsk::BorderNode::new(outline, border_width, border_color).upcast());
+    }
+
+    /// Appends a cairo node with the bounds and returns its context to draw on
+    pub fn append_cairo(&self, bounds: &graphene::Rect) -> cairo::Context {
+        let caironode = gsk::CairoNode::new(bounds);
+        let cx = caironode.draw_context();
+        self.append_node(&caironode.upcast());
+
+        cx
+    }
+
+    pub fn push_clip(&self, bounds: &graphene::Rect) {
+        self.push_layer(NodeLayer::Clip(bounds.to_owned()));
+    }
+
+    pub fn push_blur(&self, radius: f64) {
+        self.push_layer(NodeLayer::Blur(radius));
+    }
+
+    pub fn push_opacity(&self, opacity: f64) {
+        self.push_layer(NodeLayer::Opacity(opacity));
+    }
+
+    /// Scales the nodes appended after it, until the current layer is popped
+    pub fn scale(&self, factor_x: f32, factor_y: f32) {
+        self.push_layer(NodeLayer::Transform(
+            gsk::Transform::new().scale(factor_x, factor_y),
+        ));
+    }
+
+    /// Pops the last pushed clip, blur or opacity layer
+    pub fn pop(&self) {
+        while let Some(layer) = self.pop_layer() {
+            if !matches!(layer, NodeLayer::Transform(_)) {
+                return;
+            }
+        }
+        log::warn!("pop() called on NodeSnapshot without a pushed layer");
+    }
+
+    /// Returns the recorded node and clears the snapshot. Layers which were not popped are closed
+    pub fn to_node(&self) -> gsk::RenderNode {
+        while self.pop_layer().is_some() {}
+
+        gsk::ContainerNode::new(&self.nodes.take()).upcast()
+    }
+
+    fn push_layer(&self, layer: NodeLayer) {
+        self.layers.borrow_mut().push((layer, vec![]));
+    }
+
+    /// Wraps the nodes of the last layer and appends them to the layer below
+    fn pop_layer(&self) -> Option<NodeLayer> {
+        let (layer, nodes) = self.layers.borrow_mut().pop()?;
+        let child = gsk::ContainerNode::new(&nodes);
+
+        let node = match &layer {
+            NodeLayer::Clip(bounds) => gsk::ClipNode::new(&child, bounds).upcast(),
+            NodeLayer::Blur(radius) => gsk::BlurNode::new(&child, *radius as f32).upcast(),
+            NodeLayer::Opacity(opacity) => gsk::OpacityNode::new(&child, *opacity as f32).upcast(),
+            NodeLayer::Transform(transform) => gsk::TransformNode::new(&child, transform).upcast(),
+        };
+        self.append_node(&node);
+
+        Some(layer)
+    }
+}
+
+/// Draws the paths onto the cairo context, in the coordinate space of the context
+pub fn draw_paths_cairo(cx: &cairo::Context, paths: &[DrawPath]) -> Result<(), anyhow::Error> {
+    for drawpath in paths {
+        let skia_path = match drawpath::to_skia_path(&drawpath.commands) {
+            Some(skia_path) => skia_path,
+            None => continue,
+        };
+
+        cx.new_path();
+        let mut last = tiny_skia::Point::zero();
+        for segment in skia_path.segments() {
+            match segment {
+                tiny_skia::PathSegment::MoveTo(p) => {
+                    cx.move_to(f64::from(p.x), f64::from(p.y));
+                    last = p;
+                }
+                tiny_skia::PathSegment::LineTo(p) => {
+                    cx.line_to(f64::from(p.x), f64::from(p.y));
+                    last = p;
+                }
+                // Cairo has no quadratic beziers, so they are elevated to cubic ones
+                tiny_skia::PathSegment::QuadTo(cp, p) => {
+                    cx.curve_to(
+                        f64::from(last.x + (cp.x - last.x) * 2.0 / 3.0),
+                        f64::from(last.y + (cp.y - last.y) * 2.0 / 3.0),
+                        f64::from(p.x + (cp.x - p.x) * 2.0 / 3.0),
+                        f64::from(p.y + (cp.y - p.y) * 2.0 / 3.0),
+                        f64::from(p.x),
+                        f64::from(p.y),
+                    );
+                    last = p;
+                }
+                tiny_skia::PathSegment::CubicTo(cp1, cp2, p) => {
+                    cx.curve_to(
+                        f64::from(cp1.x),
+                        f64::from(cp1.y),
+                        f64::from(cp2.x),
+                        f64::from(cp2.y),
+                        f64::from(p.x),
+                        f64::from(p.y),
+                    );
+                    last = p;
+                }
+                tiny_skia::PathSegment::Close => {
+                    cx.close_path();
+                }
+            }
+        }
+
+        if let Some(fill) = drawpath.fill {
+            cx.set_fill_rule(cairo::FillRule::Winding);
+            cx.set_source_rgba(
+                f64::from(fill.red()),
+                f64::from(fill.green()),
+                f64::from(fill.blue()),
+                f64::from(fill.alpha()),
+            );
+            cx.fill_preserve()?;
+        }
+        if let Some(stroke) = drawpath.stroke {
+            cx.set_source_rgba(
+                f64::from(stroke.color.red()),
+                f64::from(stroke.color.green()),
+                f64::from(stroke.color.blue()),
+                f64::from(stroke.color.alpha()),
+            );
+            cx.set_line_width(stroke.width);
+            cx.set_line_cap(cairo::LineCap::Round);
+            cx.set_line_join(cairo::LineJoin::Round);
+            cx.stroke_preserve()?;
+        }
+        cx.new_path();
+    }
+
+    Ok(())
+}
+
+/// Records the paths into a rendernode. The paths are in the coordinate space of the sheet, the node is scaled by the zoom
+pub fn paths_to_rendernode(
+    paths: &[DrawPath],
+    zoom: f64,
+) -> Result<gsk::RenderNode, anyhow::Error> {
+    let mut bounds = AABB::new_invalid();
+    paths
+        .iter()
+        .for_each(|drawpath| bounds.merge(&drawpath.bounds));
+    if paths.is_empty() || bounds.extents()[0] <= 0.0 || bounds.extents()[1] <= 0.0 {
+        return Ok(default_rendernode());
+    }
+
+    let snapshot = NodeSnapshot::new();
+    let cx = snapshot.append_cairo(&geometry::aabb_to_graphene_rect(geometry::aabb_scale(
+        bounds, zoom,
+    )));
+    cx.scale(zoom, zoom);
+    draw_paths_cairo(&cx, paths)?;
+    // The context has to be dropped before the node is created, else the drawing is not finished
+    drop(cx);
+
+    Ok(snapshot.to_node())
+}
+
+pub fn image_to_memtexture(image: &Image) -> Result<gdk::MemoryTexture, anyhow::Error> {
+    if image.data_width <= 0 || image.data_height <= 0 || image.data.is_empty() {
+        return Err(anyhow::anyhow!(
+            "image_to_memtexture() failed, invalid image"
+        ));
+    }
+    let bytes = image.data.deref();
+
+    Ok(gdk::MemoryTexture::new(
+        image.data_width,
+        image.data_height,
+        image.memory_format,
+        &glib::Bytes::from(bytes),
+        (image.data_width * 4) as usize,
+    ))
+}
+
+/// Downloads the texture into an image, with its bounds at the origin and the size of the texture
+pub fn texture_to_image(texture: &gdk::Texture) -> Result<Image, anyhow::Error> {
+    let (width, height) = (texture.width(), texture.height());
+    if width <= 0 || height <= 0 {
+        return Err(anyhow::anyhow!(
+            "texture_to_image() failed, invalid texture size"
+        ));
+    }
+    let stride = width as usize * 4;
+    let mut data = vec![0; stride * height as usize];
+
+    // Downloaded in the cairo image surface format
+    texture.download(&mut data, stride);
+
+    Ok(Image {
+        data,
+        bounds: AABB::new(
+            na::point![0.0, 0.0],
+            na::point![f64::from(width), f64::from(height)],
+        ),
+        data_width: width,
+        data_height: height,
+        memory_format: gdk::MemoryFormat::B8g8r8a8Premultiplied,
+    })
+}
+
+pub fn image_to_rendernode(image: &Image, zoom: f64) -> Result<gsk::RenderNode, anyhow::Error> {
+    let memtexture = image_to_memtexture(image)?;
+
+    let rendernode = gsk::TextureNode::new(
+        &memtexture,
+        &geometry::aabb_to_graphene_rect(geometry::aabb_scale(image.bounds, zoom)),
+    )
+    .upcast();
+    Ok(rendernode)
+}
+
+pub fn images_to_rendernode(images: &[Image], zoom: f64) -> Result<gsk::RenderNode, anyhow::Error> {
+    let snapshot = NodeSnapshot::new();
+
+    for image in images {
+        snapshot
+            .append_node(&image_to_rendernode(image, zoom).context("images_to_rendernode failed")?);
+    }
+
+    Ok(snapshot.to_node())
+}
+
+pub fn append_images_to_rendernode(
+    rendernode: &gsk::RenderNode,
+    images: &[Image],
+    zoom: f64,
+) -> Result<gsk::RenderNode, anyhow::Error> {
+    let snapshot = NodeSnapshot::new();
+
+    snapshot.append_node(rendernode);
+    for image in images {
+        snapshot.append_node(
+            &image_to_rendernode(image, zoom)
+                .context("image_to_rendernode() failed in append_images_to_rendernode()")?,
+        );
+    }
+
+    Ok(snapshot.to_node())
+}
+
+/// Renders the node into a texture with the renderer. The ui passes the renderer of the widget the texture is drawn in
+pub fn rendernode_to_texture(
+    renderer: &gsk::Renderer,
+    node: &gsk::RenderNode,
+    viewport: Option<AABB>,
+) -> Result<gdk::Texture, anyhow::Error> {
+    let viewport = viewport.map(geometry::aabb_to_graphene_rect);
+
+    Ok(renderer.render_texture(node, viewport.as_ref()))
+}
+
+/// Draws the Svgs onto the cairo context as vector graphics. The bounds are in the coordinate space of the sheet and are mapped onto the same area of the context, scaled by the zoom.
+pub fn draw_svgs_to_cairo_context(
+    zoom: f64,
+    svgs: &[Svg],
+    bounds: AABB,
+    cx: &cairo::Context,
+) -> Result<(), anyhow::Error> {
+    let mut svg_data = svgs
+        .iter()
+        .map(|svg| svg.svg_data.as_str())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    svg_data = compose::wrap_svg_root(svg_data.as_str(), Some(bounds), Some(bounds), true);
+
+    let stream = gio::MemoryInputStream::from_bytes(&glib::Bytes::from(svg_data.as_bytes()));
+
+    let librsvg_handle = librsvg::Loader::new()
+        .read_stream::<gio::MemoryInputStream, gio::File, gio::Cancellable>(&stream, None, None)?;
+
+    let librsvg_renderer = librsvg::CairoRenderer::new(&librsvg_handle);
+    librsvg_renderer.render_document(
+        cx,
+        &cairo::Rectangle {
+            x: bounds.mins[0] * zoom,
+            y: bounds.mins[1] * zoom,
+            width: bounds.extents()[0] * zoom,
+            height: bounds.extents()[1] * zoom,
+        },
+    )?;
+
+    Ok(())
+}
+
+/*
+fn gen_caironode_librsvg(zoom: f64, svg: &Svg) -> Result<gsk::CairoNode, anyhow::Error> {
+    if svg.bounds.extents()[0] < 0.0 || svg.bounds.extents()[1] < 0.0 {
+        return Err(anyhow::anyhow!(
+            "gen_rendernode_librsvg() failed, bounds width/ height is < 0.0"
+        ));
+    }
+
+    let caironode_bounds = geometry::aabb_scale(geometry::aabb_ceil(svg.bounds), zoom);
+
+    let new_caironode = gsk::CairoNode::new(&geometry::aabb_to_graphene_rect(caironode_bounds));
+    let cx = new_caironode
+        .draw_context()
+        .context("failed to get cairo draw_context() from new_caironode")?;
+
+    draw_svgs_to_cairo_context(zoom, &[svg.to_owned()], caironode_bounds, &cx)?;
+
+    Ok(new_caironode)
+}
+ */
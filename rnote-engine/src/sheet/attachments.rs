@@ -0,0 +1,313 @@
+//! The container format of the sheet file. It is a zip archive holding the serialized sheet as `sheet.json`
+//! and the binary data of images and pdf documents as content-addressed files in `attachments/<hash>.<ext>`.
+//! Every attachment is stored once and referenced by its name from the json.
+//!
+//! The attachments are collected and resolved while (de)serializing through the `with` modules in this file.
+//! When no container is being written or read, the data is (de)serialized inline as before (e.g. for legacy files or the clipboard).
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use anyhow::Context;
+use once_cell::sync::OnceCell;
+use sha1::{Digest, Sha1};
+
+/// The name of the serialized sheet inside the container
+const SHEET_FILE_NAME: &str = "sheet.json";
+/// The directory of the attachments inside the container
+const ATTACHMENTS_DIR: &str = "attachments/";
+/// The prefix of the strings in the json which reference an attachment
+const ATTACHMENT_REF_PREFIX: &str = "attachment:";
+
+/// The attachments, by their name `<hash>.<ext>`
+pub type Attachments = BTreeMap<String, Vec<u8>>;
+
+/// Binary data which is stored as attachment. It is shared between clones, e.g. of strokes in the clipboard,
+/// and its hash is computed once when it is first stored
+#[derive(Debug, Clone, Default)]
+pub struct AttachmentData {
+    data: Arc<Vec<u8>>,
+    hash: Arc<OnceCell<String>>,
+}
+
+impl AttachmentData {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data: Arc::new(data),
+            hash: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    /// The shared data
+    pub fn data(&self) -> Arc<Vec<u8>> {
+        Arc::clone(&self.data)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The sha1 hash of the data as hex string, which names the attachment
+    pub fn hash(&self) -> &str {
+        self.hash
+            .get_or_init(|| format!("{:x}", Sha1::digest(self.data.as_slice())))
+    }
+}
+
+enum AttachmentsContext {
+    Writing(Attachments),
+    /// The attachments are shared by all references to them, so that data which is referenced multiple times is held once in memory
+    Reading(BTreeMap<String, AttachmentData>),
+}
+
+thread_local! {
+    static ATTACHMENTS_CONTEXT: RefCell<Option<AttachmentsContext>> = RefCell::new(None);
+}
+
+/// Returns true if the bytes are a sheet container, and not a legacy gzipped json file
+pub fn is_container(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+/// Runs the serialization. All attachment data which is serialized inside it is collected and returned alongside the result
+pub fn with_attachments_writer<T>(serialize: impl FnOnce() -> T) -> (T, Attachments) {
+    ATTACHMENTS_CONTEXT.with(|context| {
+        *context.borrow_mut() = Some(AttachmentsContext::Writing(Attachments::new()))
+    });
+
+    let result = serialize();
+
+    let attachments = ATTACHMENTS_CONTEXT.with(|context| match context.borrow_mut().take() {
+        Some(AttachmentsContext::Writing(attachments)) => attachments,
+        _ => Attachments::new(),
+    });
+
+    (result, attachments)
+}
+
+/// Runs the deserialization. References to attachments which are deserialized inside it are resolved from the given attachments
+pub fn with_attachments_reader<T>(attachments: Attachments, deserialize: impl FnOnce() -> T) -> T {
+    let attachments = attachments
+        .into_iter()
+        .map(|(name, data)| (name, AttachmentData::new(data)))
+        .collect::<BTreeMap<String, AttachmentData>>();
+    ATTACHMENTS_CONTEXT
+        .with(|context| *context.borrow_mut() = Some(AttachmentsContext::Reading(attachments)));
+
+    let result = deserialize();
+
+    ATTACHMENTS_CONTEXT.with(|context| context.borrow_mut().take());
+
+    result
+}
+
+/// Writes the container from the serialized sheet and the attachments
+pub fn write_container(
+    sheet_json: &str,
+    attachments: &Attachments,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::<u8>::new()));
+    let deflated =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    // Images and pdfs are already compressed
+    let stored =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    zip.start_file(SHEET_FILE_NAME, deflated)?;
+    zip.write_all(sheet_json.as_bytes())?;
+
+    for (name, data) in attachments.iter() {
+        zip.start_file(format!("{}{}", ATTACHMENTS_DIR, name), stored)?;
+        zip.write_all(data)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Reads the serialized sheet and the attachments from the container
+pub fn read_container(bytes: &[u8]) -> Result<(String, Attachments), anyhow::Error> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+    let mut sheet_json = None;
+    let mut attachments = Attachments::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let file_name = file.name().to_string();
+
+        if file_name == SHEET_FILE_NAME {
+            let mut json = String::new();
+            file.read_to_string(&mut json)?;
+            sheet_json = Some(json);
+        } else if let Some(name) = file_name.strip_prefix(ATTACHMENTS_DIR) {
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            attachments.insert(name.to_string(), data);
+        }
+    }
+
+    let sheet_json = sheet_json
+        .with_context(|| format!("{} is missing in the sheet container", SHEET_FILE_NAME))?;
+
+    Ok((sheet_json, attachments))
+}
+
+/// Stores the data as attachment, if a container is currently being written. Returns the reference to it
+fn store_attachment(data: &[u8], ext: &str) -> Option<String> {
+    store_attachment_w_hash(data, &format!("{:x}", Sha1::digest(data)), ext)
+}
+
+/// Stores the data with its already computed hash as attachment, if a container is currently being written. Returns the reference to it
+fn store_attachment_w_hash(data: &[u8], hash: &str, ext: &str) -> Option<String> {
+    if data.is_empty() {
+        return None;
+    }
+
+    ATTACHMENTS_CONTEXT.with(|context| match context.borrow_mut().as_mut() {
+        Some(AttachmentsContext::Writing(attachments)) => {
+            let name = format!("{}.{}", hash, ext);
+            attachments
+                .entry(name.clone())
+                .or_insert_with(|| data.to_vec());
+
+            Some(format!("{}{}", ATTACHMENT_REF_PREFIX, name))
+        }
+        _ => None,
+    })
+}
+
+/// Loads the data of the attachment if the value is a reference. Returns None if it is inline data
+fn load_attachment(value: &str) -> Result<Option<AttachmentData>, anyhow::Error> {
+    let name = match value.strip_prefix(ATTACHMENT_REF_PREFIX) {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    ATTACHMENTS_CONTEXT.with(|context| match context.borrow().as_ref() {
+        Some(AttachmentsContext::Reading(attachments)) => attachments
+            .get(name)
+            .cloned()
+            .map(Some)
+            .with_context(|| format!("attachment `{}` is missing in the sheet container", name)),
+        _ => Err(anyhow::anyhow!(
+            "attachment `{}` referenced outside of a sheet container",
+            name
+        )),
+    })
+}
+
+/// The file extension of the attachment for the encoded image data, from its detected format.
+/// Imported bitmap images are png or jpeg, other formats are normalized to png on import
+fn image_ext(data: &[u8]) -> &'static str {
+    match image::guess_format(data) {
+        Ok(image::ImageFormat::Png) => "png",
+        Ok(image::ImageFormat::Jpeg) => "jpg",
+        Ok(image::ImageFormat::WebP) => "webp",
+        Ok(image::ImageFormat::Gif) => "gif",
+        Ok(image::ImageFormat::Bmp) => "bmp",
+        Ok(image::ImageFormat::Tiff) => "tiff",
+        _ => "bin",
+    }
+}
+
+/// (De)serializes image data as attachment. Inline the data is encoded as base64 string
+pub mod image_data {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::AttachmentData;
+
+    pub fn serialize<S>(data: &AttachmentData, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if data.is_empty() {
+            return serializer.serialize_str("");
+        }
+
+        match super::store_attachment_w_hash(
+            data.as_slice(),
+            data.hash(),
+            super::image_ext(data.as_slice()),
+        ) {
+            Some(reference) => serializer.serialize_str(&reference),
+            None => serializer.serialize_str(&base64::encode(data.as_slice())),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<AttachmentData, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        match super::load_attachment(&value).map_err(D::Error::custom)? {
+            Some(data) => Ok(data),
+            None => base64::decode(&value)
+                .map(AttachmentData::new)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+/// (De)serializes svg data as attachment
+pub mod svg_text {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(svg_data: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match super::store_attachment(svg_data.as_bytes(), "svg") {
+            Some(reference) => serializer.serialize_str(&reference),
+            None => serializer.serialize_str(svg_data),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        match super::load_attachment(&value).map_err(D::Error::custom)? {
+            Some(data) => String::from_utf8(data.as_slice().to_vec()).map_err(D::Error::custom),
+            None => Ok(value),
+        }
+    }
+}
+
+/// (De)serializes pdf data as attachment. Inline the data is encoded as base64 string
+pub mod pdf_bytes {
+    use std::sync::Arc;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(data: &Arc<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match super::store_attachment(data.as_slice(), "pdf") {
+            Some(reference) => serializer.serialize_str(&reference),
+            None => serializer.serialize_str(&base64::encode(data.as_slice())),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Arc<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        match super::load_attachment(&value).map_err(D::Error::custom)? {
+            Some(data) => Ok(data.data()),
+            None => base64::decode(&value)
+                .map(Arc::new)
+                .map_err(D::Error::custom),
+        }
+    }
+}
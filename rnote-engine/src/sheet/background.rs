@@ -0,0 +1,1065 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use p2d::bounding_volume::AABB;
+use serde::{Deserialize, Serialize};
+use svg::node::{self, element};
+
+use crate::compose::geometry;
+use crate::pdf::PdfImportPrefs;
+use crate::render::{NodeSnapshot, Renderer};
+use crate::strokes::bitmapimage::BitmapImage;
+use crate::strokes::vectorimage::VectorImage;
+use crate::{compose, render, utils};
+
+#[derive(
+    Debug, Eq, PartialEq, Clone, Copy, glib::Enum, Serialize, Deserialize, num_derive::FromPrimitive,
+)]
+#[repr(u32)]
+#[enum_type(name = "PatternStyle")]
+pub enum PatternStyle {
+    #[enum_value(name = "None", nick = "none")]
+    None = 0,
+    #[enum_value(name = "Lines", nick = "lines")]
+    Lines,
+    #[enum_value(name = "Grid", nick = "grid")]
+    Grid,
+    #[enum_value(name = "Dots", nick = "dots")]
+    Dots,
+    #[enum_value(name = "Isometric Grid", nick = "isometric-grid")]
+    IsometricGrid,
+    #[enum_value(name = "Hexagonal Grid", nick = "hex-grid")]
+    HexGrid,
+    #[enum_value(name = "Music Staff", nick = "music-staff")]
+    MusicStaff,
+    #[enum_value(name = "Cornell Notes", nick = "cornell")]
+    Cornell,
+    #[enum_value(name = "Left Margin", nick = "left-margin")]
+    LeftMargin,
+    #[enum_value(name = "Template", nick = "template")]
+    Template,
+}
+
+impl Default for PatternStyle {
+    fn default() -> Self {
+        Self::Dots
+    }
+}
+
+impl PatternStyle {
+    /// Wether the pattern can be rendered as repeated tiles across the entire sheet.
+    /// The others depend on the page layout and are rendered page by page.
+    pub fn is_tileable(&self) -> bool {
+        matches!(self, Self::None | Self::Lines | Self::Grid | Self::Dots)
+    }
+}
+
+pub fn gen_horizontal_line_pattern(
+    bounds: AABB,
+    spacing: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    let mut y_offset = bounds.mins[1] + spacing;
+
+    if spacing > 1.0 {
+        while y_offset <= bounds.maxs[1] {
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", bounds.mins[0])
+                    .set("y1", y_offset - line_width)
+                    .set("x2", bounds.maxs[0])
+                    .set("y2", y_offset - line_width),
+            );
+
+            y_offset += spacing
+        }
+    }
+
+    group.into()
+}
+
+pub fn gen_grid_pattern(
+    bounds: AABB,
+    row_spacing: f64,
+    column_spacing: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    if column_spacing > 1.0 && row_spacing > 1.0 {
+        let mut x_offset = bounds.mins[0] + column_spacing;
+        while x_offset <= bounds.maxs[0] {
+            // vertical lines
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", x_offset - line_width)
+                    .set("y1", bounds.mins[1])
+                    .set("x2", x_offset - line_width)
+                    .set("y2", bounds.maxs[1]),
+            );
+
+            x_offset += column_spacing
+        }
+
+        let mut y_offset = bounds.mins[1] + row_spacing;
+        while y_offset <= bounds.maxs[1] {
+            // horizontal lines
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", bounds.mins[0])
+                    .set("y1", y_offset - line_width)
+                    .set("x2", bounds.maxs[0])
+                    .set("y2", y_offset - line_width),
+            );
+
+            y_offset += row_spacing
+        }
+    }
+    group.into()
+}
+
+pub fn gen_dots_pattern(
+    bounds: AABB,
+    row_spacing: f64,
+    column_spacing: f64,
+    color: utils::Color,
+    dots_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    // Only generate pattern if spacings are sufficiently large
+    if column_spacing > 1.0 && row_spacing > 1.0 {
+        let mut x_offset = bounds.mins[0] + column_spacing;
+        while x_offset <= bounds.maxs[0] {
+            let mut y_offset = bounds.mins[1] + row_spacing;
+            while y_offset <= bounds.maxs[1] {
+                // row by row
+                group = group.add(
+                    element::Rectangle::new()
+                        .set("stroke", "none")
+                        .set("fill", color.to_css_color())
+                        .set("x", x_offset - dots_width)
+                        .set("y", y_offset - dots_width)
+                        .set("width", dots_width)
+                        .set("height", dots_width),
+                );
+
+                y_offset += row_spacing;
+            }
+
+            x_offset += column_spacing;
+        }
+    }
+
+    group.into()
+}
+
+pub fn gen_isometric_grid_pattern(
+    bounds: AABB,
+    spacing: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    if spacing > 1.0 {
+        let row_spacing = spacing * 3_f64.sqrt() * 0.5;
+        // The horizontal extent of a 60 degree line from the top to the bottom of the bounds
+        let diagonal_offset = bounds.extents()[1] / 3_f64.sqrt();
+
+        let mut y_offset = bounds.mins[1] + row_spacing;
+        while y_offset <= bounds.maxs[1] {
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", bounds.mins[0])
+                    .set("y1", y_offset)
+                    .set("x2", bounds.maxs[0])
+                    .set("y2", y_offset),
+            );
+
+            y_offset += row_spacing;
+        }
+
+        let mut x_offset = bounds.mins[0] - (diagonal_offset / spacing).ceil() * spacing;
+        while x_offset <= bounds.maxs[0] + diagonal_offset {
+            // rising and falling diagonal lines
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", x_offset)
+                    .set("y1", bounds.mins[1])
+                    .set("x2", x_offset + diagonal_offset)
+                    .set("y2", bounds.maxs[1]),
+            );
+            group = group.add(
+                element::Line::new()
+                    .set("stroke-width", line_width)
+                    .set("stroke", color.to_css_color())
+                    .set("x1", x_offset)
+                    .set("y1", bounds.mins[1])
+                    .set("x2", x_offset - diagonal_offset)
+                    .set("y2", bounds.maxs[1]),
+            );
+
+            x_offset += spacing;
+        }
+    }
+
+    group.into()
+}
+
+pub fn gen_hex_grid_pattern(
+    bounds: AABB,
+    radius: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    // Only generate pattern if the radius is sufficiently large
+    if radius > 1.0 {
+        // pointy topped hexagons, every odd row is shifted by half a hexagon width
+        let hex_width = 3_f64.sqrt() * radius;
+        let row_spacing = 1.5 * radius;
+        let mut data = element::path::Data::new();
+
+        let mut row = 0;
+        let mut y_offset = bounds.mins[1];
+        while y_offset <= bounds.maxs[1] + radius {
+            let mut x_offset = if row % 2 == 0 {
+                bounds.mins[0]
+            } else {
+                bounds.mins[0] + hex_width * 0.5
+            };
+
+            while x_offset <= bounds.maxs[0] + hex_width {
+                for i in 0..6 {
+                    let angle =
+                        std::f64::consts::PI / 3.0 * f64::from(i) - std::f64::consts::PI / 2.0;
+                    let corner = (
+                        x_offset + radius * angle.cos(),
+                        y_offset + radius * angle.sin(),
+                    );
+
+                    data = if i == 0 {
+                        data.move_to(corner)
+                    } else {
+                        data.line_to(corner)
+                    };
+                }
+                data = data.close();
+
+                x_offset += hex_width;
+            }
+
+            row += 1;
+            y_offset += row_spacing;
+        }
+
+        group = group.add(
+            element::Path::new()
+                .set("stroke-width", line_width)
+                .set("stroke", color.to_css_color())
+                .set("fill", "none")
+                .set("d", data),
+        );
+    }
+
+    group.into()
+}
+
+/// Generates music staves of five lines. Each staff is `staff_height` high and is followed by a gap of the same height
+pub fn gen_music_staff_pattern(
+    bounds: AABB,
+    staff_height: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    let mut group = element::Group::new();
+
+    if staff_height > 4.0 {
+        let line_spacing = staff_height / 4.0;
+        let x_margin = staff_height * 0.5;
+
+        let mut staff_offset = bounds.mins[1] + staff_height;
+        while staff_offset + staff_height <= bounds.maxs[1] {
+            for i in 0..5 {
+                let y = staff_offset + f64::from(i) * line_spacing;
+
+                group = group.add(
+                    element::Line::new()
+                        .set("stroke-width", line_width)
+                        .set("stroke", color.to_css_color())
+                        .set("x1", bounds.mins[0] + x_margin)
+                        .set("y1", y)
+                        .set("x2", bounds.maxs[0] - x_margin)
+                        .set("y2", y),
+                );
+            }
+
+            staff_offset += 2.0 * staff_height;
+        }
+    }
+
+    group.into()
+}
+
+/// Generates the Cornell notes layout for a single page: a title area on top,
+/// a cue column on the left, the ruled notes area and a summary area at the bottom
+pub fn gen_cornell_pattern(
+    page_bounds: AABB,
+    spacing: f64,
+    color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    const TITLE_HEIGHT_RATIO: f64 = 0.08;
+    const CUE_COLUMN_WIDTH_RATIO: f64 = 0.3;
+    const SUMMARY_HEIGHT_RATIO: f64 = 0.2;
+    const LAYOUT_LINE_WIDTH_FACTOR: f64 = 2.0;
+
+    let mut group = element::Group::new();
+
+    let title_y = page_bounds.mins[1] + page_bounds.extents()[1] * TITLE_HEIGHT_RATIO;
+    let summary_y = page_bounds.maxs[1] - page_bounds.extents()[1] * SUMMARY_HEIGHT_RATIO;
+    let cue_x = page_bounds.mins[0] + page_bounds.extents()[0] * CUE_COLUMN_WIDTH_RATIO;
+
+    // The ruled lines in the notes area
+    group = group.add(gen_horizontal_line_pattern(
+        AABB::new(
+            na::point![cue_x, title_y],
+            na::point![page_bounds.maxs[0], summary_y],
+        ),
+        spacing,
+        color,
+        line_width,
+    ));
+
+    for (x1, y1, x2, y2) in [
+        (page_bounds.mins[0], title_y, page_bounds.maxs[0], title_y),
+        (
+            page_bounds.mins[0],
+            summary_y,
+            page_bounds.maxs[0],
+            summary_y,
+        ),
+        (cue_x, title_y, cue_x, summary_y),
+    ] {
+        group = group.add(
+            element::Line::new()
+                .set("stroke-width", line_width * LAYOUT_LINE_WIDTH_FACTOR)
+                .set("stroke", color.to_css_color())
+                .set("x1", x1)
+                .set("y1", y1)
+                .set("x2", x2)
+                .set("y2", y2),
+        );
+    }
+
+    group.into()
+}
+
+/// Generates horizontal lines and a vertical margin line on the left of the page
+pub fn gen_left_margin_pattern(
+    page_bounds: AABB,
+    spacing: f64,
+    color: utils::Color,
+    margin_color: utils::Color,
+    line_width: f64,
+) -> svg::node::element::Element {
+    const MARGIN_WIDTH_RATIO: f64 = 0.12;
+
+    let mut group = element::Group::new();
+
+    group = group.add(gen_horizontal_line_pattern(
+        page_bounds,
+        spacing,
+        color,
+        line_width,
+    ));
+
+    let margin_x = page_bounds.mins[0] + page_bounds.extents()[0] * MARGIN_WIDTH_RATIO;
+    group = group.add(
+        element::Line::new()
+            .set("stroke-width", line_width)
+            .set("stroke", margin_color.to_css_color())
+            .set("x1", margin_x)
+            .set("y1", page_bounds.mins[1])
+            .set("x2", margin_x)
+            .set("y2", page_bounds.maxs[1]),
+    );
+
+    group.into()
+}
+
+/// A user supplied SVG, PDF page or bitmap image, which is scaled to fit each page it is used on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "background_template")]
+pub struct BackgroundTemplate {
+    #[serde(rename = "svg_data", with = "super::attachments::svg_text")]
+    pub svg_data: String,
+    #[serde(rename = "intrinsic_size")]
+    pub intrinsic_size: na::Vector2<f64>,
+}
+
+impl Default for BackgroundTemplate {
+    fn default() -> Self {
+        Self {
+            svg_data: String::default(),
+            intrinsic_size: na::Vector2::zeros(),
+        }
+    }
+}
+
+impl BackgroundTemplate {
+    pub fn import_from_svg_data(
+        svg_data: &str,
+        renderer: &Renderer,
+    ) -> Result<Self, anyhow::Error> {
+        let vector_image =
+            VectorImage::import_from_svg_data(svg_data, na::Vector2::zeros(), None, renderer)?;
+
+        Ok(Self {
+            svg_data: vector_image.svg_data,
+            intrinsic_size: vector_image.intrinsic_size,
+        })
+    }
+
+    /// Imports each page of the pdf as template, in the order of the pages
+    pub fn import_from_pdf_bytes(
+        bytes: &[u8],
+        renderer: &Renderer,
+    ) -> Result<Vec<Self>, anyhow::Error> {
+        let templates =
+            VectorImage::import_from_pdf_bytes(bytes, &PdfImportPrefs::default(), None, renderer)?
+                .into_iter()
+                .map(|vector_image| Self {
+                    svg_data: vector_image.svg_data,
+                    intrinsic_size: vector_image.intrinsic_size,
+                })
+                .collect::<Vec<Self>>();
+
+        if templates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "pdf has no pages to use as background template"
+            ));
+        }
+
+        Ok(templates)
+    }
+
+    /// Imports a png or jpeg bitmap as template
+    pub fn import_from_image_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        let bitmap_image = BitmapImage::import_from_image_bytes(bytes, na::Vector2::zeros())?;
+
+        let image = element::Image::new()
+            .set("x", 0.0)
+            .set("y", 0.0)
+            .set("width", bitmap_image.intrinsic_size[0])
+            .set("height", bitmap_image.intrinsic_size[1])
+            .set(
+                "href",
+                format!(
+                    "data:{mime_type};base64,{data_base64}",
+                    mime_type = &bitmap_image.format.as_mime_type(),
+                    data_base64 = &base64::encode(bitmap_image.data.as_slice())
+                ),
+            );
+
+        Ok(Self {
+            svg_data: compose::node_to_string(&image)?,
+            intrinsic_size: bitmap_image.intrinsic_size,
+        })
+    }
+
+    /// Generates the template scaled into the given page bounds
+    pub fn gen_svg_elem(&self, page_bounds: AABB) -> svg::node::element::Element {
+        element::SVG::new()
+            .set("x", page_bounds.mins[0])
+            .set("y", page_bounds.mins[1])
+            .set("width", page_bounds.extents()[0])
+            .set("height", page_bounds.extents()[1])
+            .set(
+                "viewBox",
+                format!(
+                    "{:.3} {:.3} {:.3} {:.3}",
+                    0.0, 0.0, self.intrinsic_size[0], self.intrinsic_size[1]
+                ),
+            )
+            .set("preserveAspectRatio", "xMidYMid meet")
+            .add(node::Text::new(self.svg_data.clone()))
+            .into()
+    }
+}
+
+/// The background properties of a single page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "page_background")]
+pub struct PageBackground {
+    #[serde(rename = "color")]
+    pub color: utils::Color,
+    #[serde(rename = "pattern")]
+    pub pattern: PatternStyle,
+    #[serde(rename = "pattern_size")]
+    pub pattern_size: na::Vector2<f64>,
+    #[serde(rename = "pattern_color")]
+    pub pattern_color: utils::Color,
+    /// The template of the page. Pages without one use the sheet wide template
+    #[serde(rename = "template")]
+    pub template: Option<BackgroundTemplate>,
+}
+
+impl Default for PageBackground {
+    fn default() -> Self {
+        let background = Background::default();
+
+        background.default_page_background()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "background")]
+pub struct Background {
+    #[serde(rename = "color")]
+    color: utils::Color,
+    #[serde(rename = "pattern")]
+    pattern: PatternStyle,
+    #[serde(rename = "pattern_size")]
+    pattern_size: na::Vector2<f64>,
+    #[serde(rename = "pattern_color")]
+    pattern_color: utils::Color,
+    /// The sheet wide template, used for all pages which have no template of their own
+    #[serde(rename = "template")]
+    template: Option<BackgroundTemplate>,
+    /// Pages which carry their own background, indexed by the page number starting at 0
+    #[serde(rename = "page_overrides")]
+    page_overrides: BTreeMap<u32, PageBackground>,
+    #[serde(skip)]
+    image: Option<render::Image>,
+    /// The rendered page images for each distinct page background, when the background is not tileable
+    #[serde(skip)]
+    page_images: Vec<(PageBackground, render::Image)>,
+    #[serde(skip, default = "render::default_rendernode")]
+    rendernode: gsk::RenderNode,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            color: utils::Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            pattern: PatternStyle::default(),
+            pattern_size: na::Vector2::from_element(Self::PATTERN_SIZE_DEFAULT),
+            pattern_color: utils::Color {
+                r: 0.3,
+                g: 0.7,
+                b: 1.0,
+                a: 1.0,
+            },
+            template: None,
+            page_overrides: BTreeMap::new(),
+            image: None,
+            page_images: vec![],
+            rendernode: render::default_rendernode(),
+        }
+    }
+}
+
+impl Background {
+    pub const PATTERN_SIZE_DEFAULT: f64 = 20.0;
+    pub const TILE_MAX_SIZE: f64 = 256.0;
+    pub const MARGIN_LINE_COLOR: utils::Color = utils::Color {
+        r: 0.9,
+        g: 0.3,
+        b: 0.3,
+        a: 1.0,
+    };
+
+    pub fn import_background(&mut self, background: &Self) {
+        self.color = background.color;
+        self.pattern = background.pattern;
+        self.pattern_size = background.pattern_size;
+        self.pattern_color = background.pattern_color;
+        self.template = background.template.clone();
+        self.page_overrides = background.page_overrides.clone();
+    }
+
+    pub fn color(&self) -> utils::Color {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: utils::Color) {
+        self.color = color;
+    }
+
+    pub fn pattern(&self) -> PatternStyle {
+        self.pattern
+    }
+
+    pub fn set_pattern(&mut self, pattern: PatternStyle) {
+        self.pattern = pattern;
+    }
+
+    pub fn pattern_color(&self) -> utils::Color {
+        self.pattern_color
+    }
+
+    pub fn set_pattern_color(&mut self, pattern_color: utils::Color) {
+        self.pattern_color = pattern_color;
+    }
+
+    pub fn pattern_size(&self) -> na::Vector2<f64> {
+        self.pattern_size
+    }
+
+    pub fn set_pattern_size(&mut self, pattern_size: na::Vector2<f64>) {
+        self.pattern_size = pattern_size;
+    }
+
+    pub fn template(&self) -> Option<&BackgroundTemplate> {
+        self.template.as_ref()
+    }
+
+    pub fn set_template(&mut self, template: Option<BackgroundTemplate>) {
+        self.template = template;
+    }
+
+    /// The sheet wide background, used for all pages without an override
+    pub fn default_page_background(&self) -> PageBackground {
+        PageBackground {
+            color: self.color,
+            pattern: self.pattern,
+            pattern_size: self.pattern_size,
+            pattern_color: self.pattern_color,
+            template: self.template.clone(),
+        }
+    }
+
+    pub fn set_default_page_background(&mut self, page_background: PageBackground) {
+        self.color = page_background.color;
+        self.pattern = page_background.pattern;
+        self.pattern_size = page_background.pattern_size;
+        self.pattern_color = page_background.pattern_color;
+        self.template = page_background.template;
+    }
+
+    pub fn page_overrides(&self) -> &BTreeMap<u32, PageBackground> {
+        &self.page_overrides
+    }
+
+    pub fn page_override(&self, page: u32) -> Option<PageBackground> {
+        self.page_overrides.get(&page).cloned()
+    }
+
+    /// Sets or removes ( with None ) the background override of a page
+    pub fn set_page_override(&mut self, page: u32, page_background: Option<PageBackground>) {
+        if let Some(page_background) = page_background {
+            self.page_overrides.insert(page, page_background);
+        } else {
+            self.page_overrides.remove(&page);
+        }
+    }
+
+    /// Moves the page overrides to make space for a new page at the given index
+    pub fn insert_page(&mut self, page: u32) {
+        self.page_overrides = std::mem::take(&mut self.page_overrides)
+            .into_iter()
+            .map(|(i, page_background)| {
+                if i >= page {
+                    (i + 1, page_background)
+                } else {
+                    (i, page_background)
+                }
+            })
+            .collect();
+    }
+
+    /// Removes the page override of the given page and moves the overrides of the pages below up.
+    /// Returns the removed override
+    pub fn remove_page(&mut self, page: u32) -> Option<PageBackground> {
+        let removed = self.page_overrides.remove(&page);
+
+        self.page_overrides = std::mem::take(&mut self.page_overrides)
+            .into_iter()
+            .map(|(i, page_background)| {
+                if i > page {
+                    (i - 1, page_background)
+                } else {
+                    (i, page_background)
+                }
+            })
+            .collect();
+
+        removed
+    }
+
+    /// Sets the templates as the overrides of consecutive pages, starting at the given page.
+    /// The other properties of the pages stay as they are
+    pub fn set_page_templates(&mut self, first_page: u32, templates: Vec<BackgroundTemplate>) {
+        for (page, template) in (first_page..).zip(templates) {
+            let mut page_background = self.page_background(page);
+            page_background.pattern = PatternStyle::Template;
+            page_background.template = Some(template);
+
+            self.set_page_override(page, Some(page_background));
+        }
+    }
+
+    /// The template which is drawn on the page, either its own or the sheet wide template
+    pub fn page_template(&self, page: u32) -> Option<&BackgroundTemplate> {
+        self.page_overrides
+            .get(&page)
+            .and_then(|page_background| page_background.template.as_ref())
+            .or_else(|| self.template.as_ref())
+    }
+
+    pub fn swap_pages(&mut self, first: u32, second: u32) {
+        let first_override = self.page_overrides.remove(&first);
+        let second_override = self.page_overrides.remove(&second);
+
+        self.set_page_override(first, second_override);
+        self.set_page_override(second, first_override);
+    }
+
+    /// The background of the given page, either its override or the sheet wide background
+    pub fn page_background(&self, page: u32) -> PageBackground {
+        self.page_override(page)
+            .unwrap_or_else(|| self.default_page_background())
+    }
+
+    /// Wether the background can be rendered as repeated tiles, or has to be rendered page by page
+    pub fn is_tileable(&self) -> bool {
+        self.page_overrides.is_empty() && self.pattern.is_tileable()
+    }
+
+    pub fn tile_size(&self) -> na::Vector2<f64> {
+        // Calculate tile size as multiple of pattern_size with max size TITLE_MAX_SIZE
+        let tile_factor =
+            na::Vector2::from_element(Self::TILE_MAX_SIZE).component_div(&self.pattern_size);
+
+        let tile_width = if tile_factor[0] > 1.0 {
+            tile_factor[0].floor() * self.pattern_size[0]
+        } else {
+            self.pattern_size[0]
+        };
+        let tile_height = if tile_factor[1] > 1.0 {
+            tile_factor[1].floor() * self.pattern_size[1]
+        } else {
+            self.pattern_size[1]
+        };
+        let tile_size = na::vector![tile_width, tile_height];
+
+        tile_size
+    }
+
+    /// The bounds of all pages which intersect with the given bounds, together with their page number
+    pub fn pages_in_bounds(bounds: AABB, page_size: na::Vector2<f64>) -> Vec<(u32, AABB)> {
+        let mut pages = vec![];
+
+        if page_size[1] < 1.0 {
+            return pages;
+        }
+
+        let first_page = (bounds.mins[1] / page_size[1]).floor().max(0.0) as u32;
+        let mut page = first_page;
+        while f64::from(page) * page_size[1] < bounds.maxs[1] {
+            let page_y = f64::from(page) * page_size[1];
+            pages.push((
+                page,
+                AABB::new(
+                    na::point![bounds.mins[0], page_y],
+                    na::point![bounds.mins[0] + page_size[0], page_y + page_size[1]],
+                ),
+            ));
+
+            page += 1;
+        }
+
+        pages
+    }
+
+    /// Generates the pattern of a page background, without the background color
+    pub fn gen_pattern_group(
+        &self,
+        page_background: &PageBackground,
+        bounds: AABB,
+    ) -> svg::node::element::Element {
+        let pattern_size = page_background.pattern_size;
+        let pattern_color = page_background.pattern_color;
+
+        match page_background.pattern {
+            PatternStyle::None => element::Group::new().into(),
+            PatternStyle::Lines => {
+                gen_horizontal_line_pattern(bounds, pattern_size[1], pattern_color, 1.0)
+            }
+            PatternStyle::Grid => {
+                gen_grid_pattern(bounds, pattern_size[1], pattern_size[0], pattern_color, 1.0)
+            }
+            PatternStyle::Dots => {
+                gen_dots_pattern(bounds, pattern_size[1], pattern_size[0], pattern_color, 2.0)
+            }
+            PatternStyle::IsometricGrid => {
+                gen_isometric_grid_pattern(bounds, pattern_size[0], pattern_color, 1.0)
+            }
+            PatternStyle::HexGrid => {
+                gen_hex_grid_pattern(bounds, pattern_size[0], pattern_color, 1.0)
+            }
+            PatternStyle::MusicStaff => {
+                gen_music_staff_pattern(bounds, pattern_size[1], pattern_color, 1.0)
+            }
+            PatternStyle::Cornell => {
+                gen_cornell_pattern(bounds, pattern_size[1], pattern_color, 1.0)
+            }
+            PatternStyle::LeftMargin => gen_left_margin_pattern(
+                bounds,
+                pattern_size[1],
+                pattern_color,
+                Self::MARGIN_LINE_COLOR,
+                1.0,
+            ),
+            PatternStyle::Template => {
+                if let Some(template) = page_background.template.as_ref().or(self.template.as_ref())
+                {
+                    template.gen_svg_elem(bounds)
+                } else {
+                    element::Group::new().into()
+                }
+            }
+        }
+    }
+
+    /// Generates the svg group of a single page, including its background color
+    fn gen_page_group(
+        &self,
+        page_background: &PageBackground,
+        page_bounds: AABB,
+    ) -> element::Group {
+        let color_rect = element::Rectangle::new()
+            .set("x", page_bounds.mins[0])
+            .set("y", page_bounds.mins[1])
+            .set("width", page_bounds.extents()[0])
+            .set("height", page_bounds.extents()[1])
+            .set("fill", page_background.color.to_css_color());
+
+        element::Group::new()
+            .add(color_rect)
+            .add(self.gen_pattern_group(page_background, page_bounds))
+    }
+
+    /// Generates the background svg, without xml header or svg root.
+    /// When the background is not tileable, the pattern is generated for each page of the given page size.
+    pub fn gen_svg(
+        &self,
+        bounds: AABB,
+        page_size: na::Vector2<f64>,
+    ) -> Result<render::Svg, anyhow::Error> {
+        let mut group = element::Group::new();
+
+        // background color
+        let color_rect = element::Rectangle::new()
+            .set("x", bounds.mins[0])
+            .set("y", bounds.mins[1])
+            .set("width", bounds.extents()[0])
+            .set("height", bounds.extents()[1])
+            .set("fill", self.color.to_css_color());
+        group = group.add(color_rect);
+
+        let default_page_background = self.default_page_background();
+        if self.is_tileable() {
+            group = group.add(self.gen_pattern_group(&default_page_background, bounds));
+        } else {
+            for (page, page_bounds) in Self::pages_in_bounds(bounds, page_size) {
+                let page_background = self
+                    .page_overrides
+                    .get(&page)
+                    .unwrap_or(&default_page_background);
+
+                group = group.add(self.gen_page_group(page_background, page_bounds));
+            }
+        }
+
+        let svg_data = compose::node_to_string(&group)
+            .map_err(|e| anyhow::anyhow!("node_to_string() failed for background, {}", e))?;
+
+        Ok(render::Svg { svg_data, bounds })
+    }
+
+    pub fn gen_image(
+        &self,
+        renderer: &Renderer,
+        zoom: f64,
+        bounds: AABB,
+        page_size: na::Vector2<f64>,
+    ) -> Result<render::Image, anyhow::Error> {
+        let mut svg = self.gen_svg(bounds, page_size)?;
+        svg.svg_data = compose::wrap_svg_root(svg.svg_data.as_str(), Some(bounds), None, true);
+
+        renderer.gen_image(zoom, &[svg], bounds)
+    }
+
+    /// Generates the image of a single page with the given page background
+    pub fn gen_page_image(
+        &self,
+        renderer: &Renderer,
+        zoom: f64,
+        page_background: &PageBackground,
+        page_size: na::Vector2<f64>,
+    ) -> Result<render::Image, anyhow::Error> {
+        let page_bounds = AABB::new(na::point![0.0, 0.0], na::point![page_size[0], page_size[1]]);
+
+        let svg_data = compose::node_to_string(&self.gen_page_group(page_background, page_bounds))
+            .map_err(|e| anyhow::anyhow!("node_to_string() failed for page background, {}", e))?;
+        let svg = render::Svg {
+            svg_data: compose::wrap_svg_root(svg_data.as_str(), Some(page_bounds), None, true),
+            bounds: page_bounds,
+        };
+
+        renderer.gen_image(zoom, &[svg], page_bounds)
+    }
+
+    pub fn regenerate_background(
+        &mut self,
+        renderer: &Renderer,
+        zoom: f64,
+        sheet_bounds: AABB,
+        page_size: na::Vector2<f64>,
+    ) -> Result<(), anyhow::Error> {
+        self.image = None;
+        self.page_images.clear();
+
+        if self.is_tileable() {
+            let tile_size = self.tile_size();
+            let tile_bounds =
+                AABB::new(na::point![0.0, 0.0], na::point![tile_size[0], tile_size[1]]);
+
+            self.image = Some(self.gen_image(renderer, zoom, tile_bounds, page_size)?);
+        } else {
+            // Pages with the same background share their image. All overrides are rendered,
+            // so that the rendernode can be updated without regenerating when the sheet grows.
+            let page_backgrounds = std::iter::once(self.default_page_background())
+                .chain(self.page_overrides.values().cloned())
+                .collect::<Vec<PageBackground>>();
+
+            for page_background in page_backgrounds {
+                if !self
+                    .page_images
+                    .iter()
+                    .any(|(other, _)| *other == page_background)
+                {
+                    let image = self.gen_page_image(renderer, zoom, &page_background, page_size)?;
+                    self.page_images.push((page_background, image));
+                }
+            }
+        }
+
+        self.update_rendernode(zoom, sheet_bounds, page_size)?;
+        Ok(())
+    }
+
+    pub fn gen_rendernode(
+        &mut self,
+        zoom: f64,
+        bounds: AABB,
+        page_size: na::Vector2<f64>,
+    ) -> Result<gsk::RenderNode, anyhow::Error> {
+        let snapshot = NodeSnapshot::new();
+        let tile_size = self.tile_size();
+
+        snapshot.push_clip(&geometry::aabb_to_graphene_rect(geometry::aabb_scale(
+            bounds, zoom,
+        )));
+
+        // Fill with background color just in case there is any space left between the tiles
+        snapshot.append_color(
+            &self.color.to_gdk(),
+            &geometry::aabb_to_graphene_rect(geometry::aabb_scale(bounds, zoom)),
+        );
+
+        if let Some(image) = &self.image {
+            let new_texture = render::image_to_memtexture(image)
+                .context("image_to_memtexture() failed in gen_rendernode().")?;
+            for aabb in geometry::split_aabb_extended(bounds, tile_size) {
+                snapshot.append_texture(
+                    &new_texture,
+                    &geometry::aabb_to_graphene_rect(geometry::aabb_scale(aabb, zoom)),
+                );
+            }
+        } else if !self.page_images.is_empty() {
+            let page_textures = self
+                .page_images
+                .iter()
+                .map(|(page_background, image)| {
+                    Ok((
+                        page_background.clone(),
+                        render::image_to_memtexture(image)
+                            .context("image_to_memtexture() failed in gen_rendernode().")?,
+                    ))
+                })
+                .collect::<Result<Vec<(PageBackground, gdk::MemoryTexture)>, anyhow::Error>>(
+                )?;
+
+            let default_page_background = self.default_page_background();
+            for (page, page_bounds) in Self::pages_in_bounds(bounds, page_size) {
+                let page_background = self
+                    .page_overrides
+                    .get(&page)
+                    .unwrap_or(&default_page_background);
+
+                if let Some((_, texture)) = page_textures
+                    .iter()
+                    .find(|(other, _)| other == page_background)
+                {
+                    snapshot.append_texture(
+                        texture,
+                        &geometry::aabb_to_graphene_rect(geometry::aabb_scale(page_bounds, zoom)),
+                    );
+                }
+            }
+        }
+
+        snapshot.pop();
+
+        Ok(snapshot.to_node())
+    }
+
+    pub fn update_rendernode(
+        &mut self,
+        zoom: f64,
+        sheet_bounds: AABB,
+        page_size: na::Vector2<f64>,
+    ) -> Result<(), anyhow::Error> {
+        match self.gen_rendernode(zoom, sheet_bounds, page_size) {
+            Ok(new_rendernode) => {
+                self.rendernode = new_rendernode;
+            }
+            Err(e) => {
+                log::error!(
+                    "gen_rendernode() failed in update_rendernode() of background with Err: {}",
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn draw(&self, snapshot: &NodeSnapshot) {
+        snapshot.append_node(&self.rendernode);
+    }
+}
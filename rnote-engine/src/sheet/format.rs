@@ -0,0 +1,228 @@
+use gsk::graphene;
+use p2d::bounding_volume::AABB;
+use serde::{Deserialize, Serialize};
+
+use crate::compose::geometry;
+use crate::render::NodeSnapshot;
+use crate::utils;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "format")]
+pub struct Format {
+    #[serde(rename = "width")]
+    width: i32,
+    #[serde(rename = "height")]
+    height: i32,
+    #[serde(rename = "dpi")]
+    dpi: f64,
+    #[serde(rename = "orientation")]
+    orientation: Orientation,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            width: Self::WIDTH_DEFAULT,
+            height: Self::HEIGHT_DEFAULT,
+            dpi: Self::DPI_DEFAULT,
+            orientation: Orientation::Portrait,
+        }
+    }
+}
+
+impl Format {
+    pub const WIDTH_MIN: i32 = 0;
+    pub const WIDTH_MAX: i32 = 30000;
+    pub const WIDTH_DEFAULT: i32 = 1240;
+
+    pub const HEIGHT_MIN: i32 = 0;
+    pub const HEIGHT_MAX: i32 = 30000;
+    pub const HEIGHT_DEFAULT: i32 = 1754;
+
+    pub const DPI_MIN: f64 = 1.0;
+    pub const DPI_MAX: f64 = 5000.0;
+    pub const DPI_DEFAULT: f64 = 96.0;
+
+    pub const FORMAT_BORDER_COLOR: utils::Color = utils::Color {
+        r: 0.6,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn set_width(&mut self, width: i32) {
+        self.width = width.clamp(Self::WIDTH_MIN, Self::WIDTH_MAX);
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn set_height(&mut self, height: i32) {
+        self.height = height.clamp(Self::HEIGHT_MIN, Self::HEIGHT_MAX);
+    }
+
+    pub fn dpi(&self) -> f64 {
+        self.dpi
+    }
+
+    pub fn set_dpi(&mut self, dpi: f64) {
+        self.dpi = dpi.clamp(Self::DPI_MIN, Self::DPI_MAX);
+    }
+
+    /// Width and height are independent of the orientation and should be updated when the orientation changes. Its use is mainly for printing and selecting predefined formats
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Width and height are independent of the orientation and should be updated when the orientation changes. Its use is mainly for printing and selecting predefined formats
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    pub fn draw(&self, sheet_bounds: AABB, snapshot: &NodeSnapshot, zoom: f64) {
+        let border_radius = graphene::Size::new(0.0, 0.0);
+        let border_width = 2.0;
+
+        let mut offset_y = sheet_bounds.mins[1];
+
+        snapshot.push_clip(&geometry::aabb_to_graphene_rect(geometry::aabb_scale(
+            sheet_bounds,
+            zoom,
+        )));
+
+        while offset_y < sheet_bounds.maxs[1] {
+            let border_bounds = graphene::Rect::new(
+                (sheet_bounds.mins[0] * zoom) as f32,
+                (offset_y * zoom) as f32 - border_width / 2.0,
+                (f64::from(self.width()) * zoom) as f32,
+                ((offset_y + f64::from(self.height())) * zoom) as f32 + border_width / 2.0,
+            );
+
+            let rounded_rect = gsk::RoundedRect::new(
+                border_bounds.clone(),
+                border_radius.clone(),
+                border_radius.clone(),
+                border_radius.clone(),
+                border_radius.clone(),
+            );
+            snapshot.append_border(
+                &rounded_rect,
+                &[border_width, border_width, border_width, border_width],
+                &[
+                    Self::FORMAT_BORDER_COLOR.to_gdk(),
+                    Self::FORMAT_BORDER_COLOR.to_gdk(),
+                    Self::FORMAT_BORDER_COLOR.to_gdk(),
+                    Self::FORMAT_BORDER_COLOR.to_gdk(),
+                ],
+            );
+            offset_y += f64::from(self.height());
+        }
+
+        snapshot.pop();
+    }
+}
+
+#[derive(
+    Debug, Eq, PartialEq, Clone, Copy, glib::Enum, Serialize, Deserialize, num_derive::FromPrimitive,
+)]
+#[repr(u32)]
+#[enum_type(name = "PredefinedFormats")]
+pub enum PredefinedFormat {
+    #[enum_value(name = "A6", nick = "a6")]
+    A6 = 0,
+    #[enum_value(name = "A5", nick = "a5")]
+    A5,
+    #[enum_value(name = "A4", nick = "a4")]
+    A4,
+    #[enum_value(name = "A3", nick = "a3")]
+    A3,
+    #[enum_value(name = "A2", nick = "a2")]
+    A2,
+    #[enum_value(name = "US Letter", nick = "us-letter")]
+    UsLetter,
+    #[enum_value(name = "US Legal", nick = "us-legal")]
+    UsLegal,
+    #[enum_value(name = "Custom", nick = "custom")]
+    Custom,
+}
+
+impl Default for PredefinedFormat {
+    fn default() -> Self {
+        Self::A4
+    }
+}
+
+#[derive(
+    Debug, Eq, PartialEq, Clone, Copy, glib::Enum, Serialize, Deserialize, num_derive::FromPrimitive,
+)]
+#[repr(u32)]
+#[enum_type(name = "MeasureUnits")]
+pub enum MeasureUnit {
+    #[enum_value(name = "Pixel", nick = "px")]
+    Px = 0,
+    #[enum_value(name = "Millimeter", nick = "mm")]
+    Mm,
+    #[enum_value(name = "Centimeter", nick = "cm")]
+    Cm,
+    #[enum_value(name = "Inch", nick = "in")]
+    In,
+}
+
+impl Default for MeasureUnit {
+    fn default() -> Self {
+        Self::Px
+    }
+}
+
+impl MeasureUnit {
+    pub const AMOUNT_MM_IN_INCH: f64 = 25.4;
+
+    pub fn convert_measurement(
+        value: f64,
+        value_unit: MeasureUnit,
+        value_dpi: f64,
+        desired_unit: MeasureUnit,
+        desired_dpi: f64,
+    ) -> f64 {
+        let value_in_px = match value_unit {
+            MeasureUnit::Px => value,
+            MeasureUnit::Mm => (value / Self::AMOUNT_MM_IN_INCH) * value_dpi,
+            MeasureUnit::Cm => ((value * 10.0) / Self::AMOUNT_MM_IN_INCH) * value_dpi,
+            MeasureUnit::In => value * value_dpi,
+        };
+
+        match desired_unit {
+            MeasureUnit::Px => value_in_px,
+            MeasureUnit::Mm => (value_in_px / desired_dpi) * Self::AMOUNT_MM_IN_INCH,
+            MeasureUnit::Cm => (value_in_px / desired_dpi) * Self::AMOUNT_MM_IN_INCH / 10.0,
+            MeasureUnit::In => value_in_px / desired_dpi,
+        }
+    }
+}
+
+#[derive(
+    Debug, Eq, PartialEq, Clone, Copy, glib::Enum, Serialize, Deserialize, num_derive::FromPrimitive,
+)]
+#[repr(u32)]
+#[enum_type(name = "FormatOrientation")]
+pub enum Orientation {
+    #[enum_value(name = "Portrait", nick = "portrait")]
+    Portrait = 0,
+    #[enum_value(name = "Landscape", nick = "landscape")]
+    Landscape,
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self::Portrait
+    }
+}
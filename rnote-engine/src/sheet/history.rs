@@ -0,0 +1,232 @@
+use super::pages::PageOperation;
+use super::Sheet;
+use crate::strokes::strokestyle::StyleEdit;
+use crate::strokesstate::StrokeKey;
+
+/// A operation on the sheet which is undone as a whole. Holds everything needed to undo and redo it.
+#[derive(Debug, Clone)]
+pub enum SheetOperation {
+    Page(PageOperation),
+    /// The style of the strokes was changed. Holds edits which restore the changed style fields of the strokes,
+    /// which are swapped with their current values on undo and redo
+    Restyle {
+        /// The last applied edit, to merge consecutive edits of the same fields
+        edit: StyleEdit,
+        strokes: Vec<(StrokeKey, StyleEdit)>,
+    },
+    /// The strokes were brought to the front or sent to the back. Holds the chronology values of the reordered strokes,
+    /// which are swapped with their current values on undo and redo
+    Reorder(Vec<(StrokeKey, u32)>),
+    /// The strokes were grouped or ungrouped. Holds the groups of the changed strokes,
+    /// which are swapped with their current groups on undo and redo
+    Regroup(Vec<(StrokeKey, Vec<u32>)>),
+}
+
+/// The history of sheet operations. Each operation is stored together with its place in the strokes chronology,
+/// so that strokes and sheet operations can be undone in the order they happened.
+#[derive(Debug, Clone, Default)]
+pub struct SheetHistory {
+    pub done: Vec<(u32, SheetOperation)>,
+    pub undone: Vec<(u32, SheetOperation)>,
+}
+
+impl SheetHistory {
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+/// Undo and redo
+impl Sheet {
+    pub(super) fn push_operation(&self, operation: SheetOperation) {
+        let t = self.strokes_state().borrow_mut().next_chrono_counter();
+
+        let mut history = self.history.borrow_mut();
+        history.done.push((t, operation));
+        history.undone.clear();
+    }
+
+    /// Applies the style edit to the selected strokes as one undoable operation. Returns true if any stroke was changed.
+    /// Consecutive edits of the same fields of the same strokes, e.g. while dragging a slider, are merged into one operation
+    pub fn restyle_selection(&self, edit: &StyleEdit) -> bool {
+        let strokes = self.strokes_state().borrow_mut().restyle_selection(edit);
+        if strokes.is_empty() {
+            return false;
+        }
+
+        if !self.merge_restyle(edit, &strokes) {
+            self.push_operation(SheetOperation::Restyle {
+                edit: *edit,
+                strokes,
+            });
+        }
+        true
+    }
+
+    /// Brings the selected strokes to the front as one undoable operation
+    pub fn bring_selection_to_front(&self) {
+        let strokes = self.strokes_state().borrow_mut().bring_selection_to_front();
+        if !strokes.is_empty() {
+            self.push_operation(SheetOperation::Reorder(strokes));
+        }
+    }
+
+    /// Sends the selected strokes to the back as one undoable operation
+    pub fn send_selection_to_back(&self) {
+        let strokes = self.strokes_state().borrow_mut().send_selection_to_back();
+        if !strokes.is_empty() {
+            self.push_operation(SheetOperation::Reorder(strokes));
+        }
+    }
+
+    /// Groups the selected strokes as one undoable operation
+    pub fn group_selection(&self) {
+        let strokes = self.strokes_state().borrow_mut().group_selection();
+        if !strokes.is_empty() {
+            self.push_operation(SheetOperation::Regroup(strokes));
+        }
+    }
+
+    /// Ungroups the selected strokes as one undoable operation
+    pub fn ungroup_selection(&self) {
+        let strokes = self.strokes_state().borrow_mut().ungroup_selection();
+        if !strokes.is_empty() {
+            self.push_operation(SheetOperation::Regroup(strokes));
+        }
+    }
+
+    /// Merges the restyle into the last operation, if it is a restyle of the same fields of the same strokes and nothing happened since.
+    /// The last operation then keeps the style from before both edits. Returns true if merged
+    fn merge_restyle(&self, edit: &StyleEdit, strokes: &[(StrokeKey, StyleEdit)]) -> bool {
+        let chrono_counter = self.strokes_state().borrow().chrono_counter();
+        let mut history = self.history.borrow_mut();
+
+        match history.done.last_mut() {
+            Some((
+                t,
+                SheetOperation::Restyle {
+                    edit: last_edit,
+                    strokes: last_strokes,
+                },
+            )) if *t == chrono_counter
+                && last_edit.same_fields(edit)
+                && last_strokes
+                    .iter()
+                    .map(|(key, _)| key)
+                    .eq(strokes.iter().map(|(key, _)| key)) =>
+            {
+                *last_edit = *edit;
+                history.undone.clear();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Applies the operation again, or reverts it. Returns true if the pages were changed
+    fn apply_operation(&self, operation: &mut SheetOperation, revert: bool) -> bool {
+        match operation {
+            SheetOperation::Page(operation) => {
+                if revert {
+                    self.revert_page_operation(operation);
+                } else {
+                    self.apply_page_operation(operation);
+                }
+                true
+            }
+            SheetOperation::Restyle { strokes, .. } => {
+                self.strokes_state().borrow_mut().swap_styles(strokes);
+                false
+            }
+            SheetOperation::Reorder(strokes) => {
+                self.strokes_state().borrow_mut().swap_chrono(strokes);
+                false
+            }
+            SheetOperation::Regroup(strokes) => {
+                self.strokes_state().borrow_mut().swap_groups(strokes);
+                false
+            }
+        }
+    }
+
+    /// Undoes the last stroke or sheet operation, whichever happened last.
+    /// Returns true if a page operation was undone, in which case the background needs to be regenerated.
+    /// Resizing needed after calling this
+    pub fn undo(&self) -> bool {
+        let last_stroke_t = {
+            let strokes_state = self.strokes_state().borrow();
+            strokes_state
+                .last_stroke_key()
+                .and_then(|key| strokes_state.chrono_t(key))
+        };
+        let last_operation_t = self.history.borrow().done.last().map(|(t, _)| *t);
+
+        if last_operation_t.is_some() && last_operation_t > last_stroke_t {
+            // Popped before, the history is borrowed again below
+            let operation = self.history.borrow_mut().done.pop();
+            if let Some((_, mut operation)) = operation {
+                let pages_changed = self.apply_operation(&mut operation, true);
+
+                let t = self.strokes_state().borrow_mut().next_chrono_counter();
+                self.history.borrow_mut().undone.push((t, operation));
+
+                return pages_changed;
+            }
+            false
+        } else {
+            self.strokes_state().borrow_mut().undo_last_stroke();
+            false
+        }
+    }
+
+    /// Clears the undone operations when a stroke was inserted or modified after the last undo, like pushing a new operation does.
+    /// Strokes are inserted without the sheet, so this is checked before redoing. Undone strokes which are redone keep their place in the chronology
+    fn clear_undone_after_new_strokes(&self) {
+        let last_stroke_t = {
+            let strokes_state = self.strokes_state().borrow();
+            strokes_state
+                .last_stroke_key()
+                .and_then(|key| strokes_state.chrono_t(key))
+        };
+
+        let mut history = self.history.borrow_mut();
+        if let Some(&(last_undone_t, _)) = history.undone.last() {
+            if last_stroke_t > Some(last_undone_t) {
+                history.undone.clear();
+            }
+        }
+    }
+
+    /// Redoes the last undone stroke or sheet operation, whichever was undone last.
+    /// Returns true if a page operation was redone, in which case the background needs to be regenerated.
+    /// Resizing needed after calling this
+    pub fn redo(&self) -> bool {
+        self.clear_undone_after_new_strokes();
+
+        let last_trashed_t = {
+            let strokes_state = self.strokes_state().borrow();
+            strokes_state
+                .last_trashed_key()
+                .and_then(|key| strokes_state.chrono_t(key))
+        };
+        let last_operation_t = self.history.borrow().undone.last().map(|(t, _)| *t);
+
+        if last_operation_t.is_some() && last_operation_t > last_trashed_t {
+            // Popped before, the history is borrowed again below
+            let operation = self.history.borrow_mut().undone.pop();
+            if let Some((_, mut operation)) = operation {
+                let pages_changed = self.apply_operation(&mut operation, false);
+
+                let t = self.strokes_state().borrow_mut().next_chrono_counter();
+                self.history.borrow_mut().done.push((t, operation));
+
+                return pages_changed;
+            }
+            false
+        } else {
+            self.strokes_state().borrow_mut().redo_last_stroke();
+            false
+        }
+    }
+}
@@ -1,140 +1,58 @@
+pub mod attachments;
 pub mod background;
 pub mod format;
+pub mod history;
+pub mod pages;
+pub mod preview;
 
-mod imp {
-    use std::cell::Cell;
-    use std::{cell::RefCell, rc::Rc};
-
-    use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*};
-    use once_cell::sync::Lazy;
-
-    use crate::config;
-    use crate::sheet::format;
-    use crate::strokesstate::StrokesState;
-
-    use super::{Background, Format};
-
-    #[derive(Debug)]
-    pub struct Sheet {
-        pub version: Rc<RefCell<String>>,
-        pub strokes_state: Rc<RefCell<StrokesState>>,
-        pub format: Format,
-        pub background: Rc<RefCell<Background>>,
-        pub width: Cell<i32>,
-        pub height: Cell<i32>,
-        pub padding_bottom: Cell<i32>,
-        pub endless_sheet: Cell<bool>,
-        pub format_borders: Cell<bool>,
-    }
-
-    impl Default for Sheet {
-        fn default() -> Self {
-            Self {
-                version: Rc::new(RefCell::new(String::from(config::APP_VERSION))),
-                strokes_state: Rc::new(RefCell::new(StrokesState::default())),
-                format: Format::default(),
-                background: Rc::new(RefCell::new(Background::default())),
-                width: Cell::new(Format::default().width()),
-                height: Cell::new(Format::default().height()),
-                padding_bottom: Cell::new(Format::default().height()),
-                endless_sheet: Cell::new(true),
-                format_borders: Cell::new(true),
-            }
-        }
-    }
-
-    #[glib::object_subclass]
-    impl ObjectSubclass for Sheet {
-        const NAME: &'static str = "Sheet";
-        type Type = super::Sheet;
-        type ParentType = glib::Object;
-    }
-
-    impl ObjectImpl for Sheet {
-        fn constructed(&self, obj: &Self::Type) {
-            self.format.connect_notify_local(
-                Some("dpi"),
-                clone!(@weak obj => move |format, _| {
-                    let new_width = format::MeasureUnit::convert_measurement(
-                        f64::from(format.width()),
-                        format::MeasureUnit::Px,
-                        obj.format().dpi(),
-                        format::MeasureUnit::Px,
-                        format.dpi());
-
-                    let new_height = format::MeasureUnit::convert_measurement(
-                        f64::from(format.height()),
-                        format::MeasureUnit::Px,
-                        obj.format().dpi(),
-                        format::MeasureUnit::Px,
-                        format.dpi());
-
-                    obj.set_width(new_width.round() as i32);
-                    obj.set_height(new_height.round() as i32);
-
-                    obj.resize_to_format();
-                }),
-            );
-        }
-
-        fn properties() -> &'static [glib::ParamSpec] {
-            static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
-                vec![glib::ParamSpecBoolean::new(
-                    "endless-sheet",
-                    "endless-sheet",
-                    "endless-sheet",
-                    false,
-                    glib::ParamFlags::READWRITE,
-                )]
-            });
-            PROPERTIES.as_ref()
-        }
-
-        fn set_property(
-            &self,
-            _obj: &Self::Type,
-            _id: usize,
-            value: &glib::Value,
-            pspec: &glib::ParamSpec,
-        ) {
-            match pspec.name() {
-                "endless-sheet" => {
-                    self.endless_sheet
-                        .replace(value.get::<bool>().expect("Value not of type `bool`"));
-                }
-                _ => panic!("invalid property name"),
-            }
-        }
-
-        fn property(&self, _obj: &Self::Type, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
-            match pspec.name() {
-                "endless-sheet" => self.endless_sheet.get().to_value(),
-                _ => panic!("invalid property name"),
-            }
-        }
-    }
-}
-
-use std::{cell::RefCell, rc::Rc};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 use crate::{compose, strokesstate::StrokesState, utils::FileType};
+use crate::render::NodeSnapshot;
 use crate::{render, utils};
 
+use self::history::SheetHistory;
 use self::{background::Background, format::Format};
 
-use gtk4::{gio, glib, graphene, prelude::*, subclass::prelude::*, Snapshot};
+use gdk::{gio, prelude::*};
+use gsk::graphene;
 use p2d::bounding_volume::{BoundingVolume, AABB};
 use serde::de::{self, Deserializer, Visitor};
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
-glib::wrapper! {
-    pub struct Sheet(ObjectSubclass<imp::Sheet>);
+/// The sheet with its strokes, format and background. It is shared with the ui and therefore modified through shared references
+#[derive(Debug)]
+pub struct Sheet {
+    version: RefCell<String>,
+    strokes_state: Rc<RefCell<StrokesState>>,
+    format: Cell<Format>,
+    background: Rc<RefCell<Background>>,
+    width: Cell<i32>,
+    height: Cell<i32>,
+    padding_bottom: Cell<i32>,
+    endless_sheet: Cell<bool>,
+    format_borders: Cell<bool>,
+    min_n_pages: Cell<i32>,
+    history: RefCell<SheetHistory>,
 }
 
 impl Default for Sheet {
     fn default() -> Self {
-        Self::new()
+        Self {
+            version: RefCell::new(String::from(Self::VERSION)),
+            strokes_state: Rc::new(RefCell::new(StrokesState::default())),
+            format: Cell::new(Format::default()),
+            background: Rc::new(RefCell::new(Background::default())),
+            width: Cell::new(Format::default().width()),
+            height: Cell::new(Format::default().height()),
+            padding_bottom: Cell::new(Format::default().height()),
+            endless_sheet: Cell::new(true),
+            format_borders: Cell::new(true),
+            min_n_pages: Cell::new(0),
+            history: RefCell::new(SheetHistory::default()),
+        }
     }
 }
 
@@ -143,7 +61,7 @@ impl Serialize for Sheet {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("sheet", 9)?;
+        let mut state = serializer.serialize_struct("sheet", 10)?;
         state.serialize_field("version", &*self.version())?;
         state.serialize_field("strokes_state", &*self.strokes_state().borrow())?;
         state.serialize_field("format", &self.format())?;
@@ -153,6 +71,7 @@ impl Serialize for Sheet {
         state.serialize_field("endless_sheet", &self.endless_sheet())?;
         state.serialize_field("padding_bottom", &self.padding_bottom())?;
         state.serialize_field("format_borders", &self.format_borders())?;
+        state.serialize_field("min_n_pages", &self.min_n_pages())?;
         state.end()
     }
 }
@@ -175,6 +94,7 @@ impl<'de> Deserialize<'de> for Sheet {
             padding_bottom,
             endless_sheet,
             format_borders,
+            min_n_pages,
             unknown,
         }
 
@@ -217,17 +137,20 @@ impl<'de> Deserialize<'de> for Sheet {
                 let format_borders = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(8, &self))?;
+                // Not present in sheets of older versions
+                let min_n_pages = seq.next_element()?.unwrap_or(0);
 
                 let sheet = Sheet::new();
                 sheet.set_version(version);
                 *sheet.strokes_state().borrow_mut() = strokes_state;
-                sheet.format().import_format(format);
+                sheet.import_format(format);
                 *sheet.background().borrow_mut() = background;
                 sheet.set_width(width);
                 sheet.set_height(height);
                 sheet.set_endless_sheet(endless_sheet);
                 sheet.set_padding_bottom(padding_bottom);
                 sheet.set_format_borders(format_borders);
+                sheet.set_min_n_pages(min_n_pages);
 
                 Ok(sheet)
             }
@@ -245,6 +168,7 @@ impl<'de> Deserialize<'de> for Sheet {
                 let mut padding_bottom = None;
                 let mut endless_sheet = None;
                 let mut format_borders = None;
+                let mut min_n_pages = None;
 
                 while let Some(key) = match map.next_key() {
                     Ok(key) => key,
@@ -308,6 +232,12 @@ impl<'de> Deserialize<'de> for Sheet {
                             }
                             format_borders = Some(map.next_value()?);
                         }
+                        Field::min_n_pages => {
+                            if min_n_pages.is_some() {
+                                return Err(de::Error::duplicate_field("min_n_pages"));
+                            }
+                            min_n_pages = Some(map.next_value()?);
+                        }
                         Field::unknown => {
                             // throw away the value
                             map.next_value::<serde::de::IgnoredAny>()?;
@@ -362,17 +292,20 @@ impl<'de> Deserialize<'de> for Sheet {
                     log::error!("{}", err);
                     sheet_default.format_borders()
                 });
+                // Not present in sheets of older versions
+                let min_n_pages = min_n_pages.unwrap_or_else(|| sheet_default.min_n_pages());
 
                 let sheet = Sheet::new();
                 sheet.set_version(version);
                 *sheet.strokes_state().borrow_mut() = strokes_state;
-                sheet.format().import_format(format);
+                sheet.import_format(format);
                 *sheet.background().borrow_mut() = background;
                 sheet.set_width(width);
                 sheet.set_height(height);
                 sheet.set_padding_bottom(padding_bottom);
                 sheet.set_endless_sheet(endless_sheet);
                 sheet.set_format_borders(format_borders);
+                sheet.set_min_n_pages(min_n_pages);
 
                 Ok(sheet)
             }
@@ -390,84 +323,96 @@ impl<'de> Deserialize<'de> for Sheet {
             "padding_bottom",
             "endless_sheet",
             "format_borders",
+            "min_n_pages",
         ];
         deserializer.deserialize_struct("sheet", FIELDS, SheetVisitor)
     }
 }
 
 impl Sheet {
+    /// The version the sheet is saved with
+    pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
     pub fn new() -> Self {
-        let sheet: Sheet = glib::Object::new(&[]).expect("Failed to create Sheet");
-        sheet
+        Self::default()
     }
 
     pub fn version(&self) -> String {
-        imp::Sheet::from_instance(self).version.borrow().clone()
+        self.version.borrow().clone()
     }
 
     pub fn set_version(&self, version: String) {
-        *imp::Sheet::from_instance(self).version.borrow_mut() = version;
+        *self.version.borrow_mut() = version;
     }
 
     pub fn strokes_state(&self) -> Rc<RefCell<StrokesState>> {
-        imp::Sheet::from_instance(self).strokes_state.clone()
+        self.strokes_state.clone()
     }
 
     pub fn width(&self) -> i32 {
-        imp::Sheet::from_instance(self).width.get()
+        self.width.get()
     }
 
     pub fn set_width(&self, width: i32) {
-        imp::Sheet::from_instance(self).width.set(width);
+        self.width.set(width);
     }
 
     pub fn height(&self) -> i32 {
-        imp::Sheet::from_instance(self).height.get()
+        self.height.get()
     }
 
     pub fn set_height(&self, height: i32) {
-        imp::Sheet::from_instance(self).height.set(height);
+        self.height.set(height);
     }
 
     pub fn padding_bottom(&self) -> i32 {
-        imp::Sheet::from_instance(self).padding_bottom.get()
+        self.padding_bottom.get()
     }
 
     pub fn set_padding_bottom(&self, padding_bottom: i32) {
-        imp::Sheet::from_instance(self)
-            .padding_bottom
-            .set(padding_bottom);
+        self.padding_bottom.set(padding_bottom);
     }
 
     pub fn endless_sheet(&self) -> bool {
-        let priv_ = imp::Sheet::from_instance(self);
-        priv_.endless_sheet.get()
+        self.endless_sheet.get()
     }
 
     pub fn set_endless_sheet(&self, endless_sheet: bool) {
-        let priv_ = imp::Sheet::from_instance(self);
-        priv_.endless_sheet.set(endless_sheet);
+        self.endless_sheet.set(endless_sheet);
 
         self.resize_to_format();
     }
 
     pub fn format_borders(&self) -> bool {
-        let priv_ = imp::Sheet::from_instance(self);
-        priv_.format_borders.get()
+        self.format_borders.get()
     }
 
     pub fn set_format_borders(&self, format_borders: bool) {
-        let priv_ = imp::Sheet::from_instance(self);
-        priv_.format_borders.set(format_borders);
+        self.format_borders.set(format_borders);
     }
 
     pub fn format(&self) -> Format {
-        let priv_ = imp::Sheet::from_instance(self);
-        priv_.format.clone()
+        self.format.get()
+    }
+
+    /// Imports the format. The sheet takes over the size of the format and is then resized to fit the strokes
+    pub fn import_format(&self, format: Format) {
+        self.format.set(format);
+
+        self.set_width(format.width());
+        self.set_height(format.height());
+        self.resize_to_format();
+    }
+
+    /// The size of a single page, as defined by the format
+    pub fn page_size(&self) -> na::Vector2<f64> {
+        let format = self.format();
+
+        na::vector![f64::from(format.width()), f64::from(format.height())]
     }
 
     pub fn background(&self) -> Rc<RefCell<Background>> {
-        imp::Sheet::from_instance(self).background.clone()
+        self.background.clone()
     }
 
     pub fn bounds(&self) -> AABB {
@@ -481,7 +426,8 @@ impl Sheet {
     pub fn resize_endless(&self) -> bool {
         let mut resizing_needed = false;
         if self.endless_sheet() {
-            let new_height = self.strokes_state().borrow().calc_height() + self.padding_bottom();
+            let new_height = (self.strokes_state().borrow().calc_height() + self.padding_bottom())
+                .max(self.min_n_pages() * self.format().height());
 
             if new_height != self.height() {
                 resizing_needed = true;
@@ -494,15 +440,15 @@ impl Sheet {
 
     /// Called when sheet should resize to fit all strokes. Resizing needed after calling this
     pub fn resize_to_format(&self) {
-        let priv_ = imp::Sheet::from_instance(self);
         if self.endless_sheet() {
             self.resize_endless();
         } else {
             // +1 because then 'fraction'.ceil() is at least 1
             let new_height = self.strokes_state().borrow().calc_height() + 1;
             self.set_height(
-                (new_height as f64 / priv_.format.height() as f64).ceil() as i32
-                    * priv_.format.height(),
+                ((new_height as f64 / self.format().height() as f64).ceil() as i32)
+                    .max(self.min_n_pages())
+                    * self.format().height(),
             );
         }
     }
@@ -515,9 +461,7 @@ impl Sheet {
         }
     }
 
-    pub fn draw(&self, zoom: f64, snapshot: &Snapshot) {
-        let priv_ = imp::Sheet::from_instance(self);
-
+    pub fn draw(&self, zoom: f64, snapshot: &NodeSnapshot) {
         let sheet_bounds_scaled = graphene::Rect::new(
             0.0,
             0.0,
@@ -526,7 +470,7 @@ impl Sheet {
         );
 
         snapshot.push_clip(&sheet_bounds_scaled);
-        priv_.background.borrow().draw(snapshot);
+        self.background.borrow().draw(snapshot);
 
         if self.format_borders() {
             self.format().draw(self.bounds(), snapshot, zoom);
@@ -535,20 +479,44 @@ impl Sheet {
         snapshot.pop();
     }
 
+    /// Loads a sheet from the bytes of a saved file, without importing it into this sheet
+    pub fn load_sheet_from_bytes(bytes: &[u8]) -> Result<Sheet, anyhow::Error> {
+        Self::deserialize_from_bytes(bytes)
+    }
+
+    /// Deserializes the bytes of a saved sheet file. Can deserialize into other types than the sheet, which read only parts of it
+    pub(crate) fn deserialize_from_bytes<T>(bytes: &[u8]) -> Result<T, anyhow::Error>
+    where
+        T: de::DeserializeOwned,
+    {
+        if attachments::is_container(bytes) {
+            let (sheet_json, sheet_attachments) = attachments::read_container(bytes)?;
+
+            Ok(attachments::with_attachments_reader(sheet_attachments, || {
+                serde_json::from_str(&sheet_json)
+            })?)
+        } else {
+            // Legacy sheet files are a single gzipped json file with the data embedded
+            let decompressed_bytes = utils::decompress_from_gzip(bytes)?;
+            Ok(serde_json::from_str(&String::from_utf8(decompressed_bytes)?)?)
+        }
+    }
+
     pub fn open_sheet_from_bytes(&self, bytes: glib::Bytes) -> Result<(), anyhow::Error> {
-        let decompressed_bytes = utils::decompress_from_gzip(&bytes)?;
-        let sheet: Sheet = serde_json::from_str(&String::from_utf8(decompressed_bytes)?)?;
+        let sheet = Self::load_sheet_from_bytes(&bytes)?;
 
         self.strokes_state()
             .borrow_mut()
             .import_state(&*sheet.strokes_state().borrow());
-        self.format().import_format(sheet.format());
+        self.import_format(sheet.format());
         self.background()
             .borrow_mut()
             .import_background(&*sheet.background().borrow());
         self.set_width(sheet.width());
         self.set_height(sheet.height());
         self.set_padding_bottom(sheet.padding_bottom());
+        self.reset_pages();
+        self.set_min_n_pages(sheet.min_n_pages());
         self.set_endless_sheet(sheet.endless_sheet());
 
         Ok(())
@@ -557,12 +525,12 @@ impl Sheet {
     pub fn save_sheet_to_file(&self, file: &gio::File) -> Result<(), anyhow::Error> {
         match FileType::lookup_file_type(file) {
             FileType::RnoteFile => {
-                let json_output = serde_json::to_string(self)?;
-                if let Some(file_name) = file.basename() {
-                    let compressed_bytes = utils::compress_to_gzip(
-                        json_output.as_bytes(),
-                        &file_name.to_string_lossy(),
-                    )?;
+                let (json_output, sheet_attachments) =
+                    attachments::with_attachments_writer(|| serde_json::to_string(self));
+                let json_output = json_output?;
+                if file.basename().is_some() {
+                    let container_bytes =
+                        attachments::write_container(&json_output, &sheet_attachments)?;
 
                     file.replace_async(
                         None,
@@ -582,7 +550,7 @@ impl Sheet {
                                 }
                             };
 
-                            if let Err(e) = output_stream.write(&compressed_bytes, None::<&gio::Cancellable>) {
+                            if let Err(e) = output_stream.write(&container_bytes, None::<&gio::Cancellable>) {
                                 log::error!(
                         "output_stream().write() failed in save_sheet_to_file() with Err {}",
                         e
@@ -614,7 +582,7 @@ impl Sheet {
         svgs.push(
             self.background()
                 .borrow()
-                .gen_svg(sheet_bounds.loosened(1.0))?,
+                .gen_svg(sheet_bounds.loosened(1.0), self.page_size())?,
         );
 
         svgs.append(&mut self.strokes_state().borrow().gen_svgs_for_strokes()?);
@@ -675,4 +643,44 @@ impl Sheet {
 
         Ok(())
     }
+
+    /// Exports the imported pdf pages together with the strokes on top of them as a pdf
+    pub fn export_sheet_as_annotated_pdf(&self, file: gio::File) -> Result<(), anyhow::Error> {
+        let pdf_data = self.strokes_state().borrow().gen_annotated_pdf()?;
+
+        file.replace_async(
+            None,
+            false,
+            gio::FileCreateFlags::REPLACE_DESTINATION,
+            glib::PRIORITY_HIGH_IDLE,
+            None::<&gio::Cancellable>,
+            move |result| {
+                let output_stream = match result {
+                    Ok(output_stream) => output_stream,
+                    Err(e) => {
+                        log::error!(
+                            "replace_async() failed in export_sheet_as_annotated_pdf() with Err {}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                if let Err(e) = output_stream.write(&pdf_data, None::<&gio::Cancellable>) {
+                    log::error!(
+                        "output_stream().write() failed in export_sheet_as_annotated_pdf() with Err {}",
+                        e
+                    );
+                };
+                if let Err(e) = output_stream.close(None::<&gio::Cancellable>) {
+                    log::error!(
+                        "output_stream().close() failed in export_sheet_as_annotated_pdf() with Err {}",
+                        e
+                    );
+                };
+            },
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,361 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use super::background::{BackgroundTemplate, PageBackground};
+use super::history::SheetOperation;
+use super::Sheet;
+use crate::render;
+use crate::strokesstate::StrokeKey;
+
+use p2d::bounding_volume::{BoundingVolume, AABB};
+
+/// A operation on the pages of the sheet. Holds everything needed to undo and redo it.
+#[derive(Debug, Clone)]
+pub enum PageOperation {
+    /// Blank pages were inserted at the index
+    Insert { page: u32, n_pages: u32 },
+    /// The page at the index was deleted
+    Delete {
+        page: u32,
+        /// All strokes which were on the page, including already trashed ones
+        strokes: Vec<StrokeKey>,
+        /// The strokes which were trashed by the deletion
+        trashed: Vec<StrokeKey>,
+        background: Option<PageBackground>,
+    },
+    /// The page at the index was duplicated, the copy was inserted below it
+    Duplicate {
+        page: u32,
+        copies: Vec<StrokeKey>,
+        background: Option<PageBackground>,
+    },
+    /// The page at the index was swapped with the page below
+    Swap { page: u32 },
+}
+
+/// Page operations
+impl Sheet {
+    pub fn min_n_pages(&self) -> i32 {
+        self.min_n_pages.get()
+    }
+
+    /// The sheet is never resized to fewer pages than this, so that blank pages are kept
+    pub fn set_min_n_pages(&self, min_n_pages: i32) {
+        self.min_n_pages.set(min_n_pages.max(0));
+    }
+
+    /// Resets the minimum page count and the history, e.g. when the strokes were cleared.
+    pub fn reset_pages(&self) {
+        self.set_min_n_pages(0);
+        self.history.borrow_mut().clear();
+    }
+
+    fn page_height(&self) -> f64 {
+        f64::from(self.format().height())
+    }
+
+    /// Moves all strokes on and below the page down by one page, except the excluded ones
+    fn insert_page_space(&self, page: u32, exclude: &[StrokeKey]) {
+        let page_height = self.page_height();
+        let n_pages = self.calc_n_pages().max(self.min_n_pages());
+
+        {
+            let mut strokes_state = self.strokes_state().borrow_mut();
+            let strokes = strokes_state
+                .strokes_from_page(page, page_height)
+                .into_iter()
+                .filter(|key| !exclude.contains(key))
+                .collect::<Vec<StrokeKey>>();
+
+            strokes_state.translate_strokes(&strokes, na::vector![0.0, page_height]);
+        }
+        self.background().borrow_mut().insert_page(page);
+
+        self.set_min_n_pages(n_pages + 1);
+    }
+
+    /// Moves all strokes below the page up by one page, except the excluded ones. Returns the page override of the removed page.
+    fn remove_page_space(&self, page: u32, exclude: &[StrokeKey]) -> Option<PageBackground> {
+        let page_height = self.page_height();
+        let n_pages = self.calc_n_pages().max(self.min_n_pages());
+
+        {
+            let mut strokes_state = self.strokes_state().borrow_mut();
+            let strokes = strokes_state
+                .strokes_from_page(page + 1, page_height)
+                .into_iter()
+                .filter(|key| !exclude.contains(key))
+                .collect::<Vec<StrokeKey>>();
+
+            strokes_state.translate_strokes(&strokes, na::vector![0.0, -page_height]);
+        }
+
+        self.set_min_n_pages(n_pages - 1);
+        self.background().borrow_mut().remove_page(page)
+    }
+
+    /// Swaps the contents of the page with the page below
+    fn swap_page_contents(&self, page: u32) {
+        let page_height = self.page_height();
+
+        {
+            let mut strokes_state = self.strokes_state().borrow_mut();
+            let upper = strokes_state.strokes_on_page(page, page_height);
+            let lower = strokes_state.strokes_on_page(page + 1, page_height);
+
+            strokes_state.translate_strokes(&upper, na::vector![0.0, page_height]);
+            strokes_state.translate_strokes(&lower, na::vector![0.0, -page_height]);
+        }
+        self.background().borrow_mut().swap_pages(page, page + 1);
+    }
+
+    pub(super) fn apply_page_operation(&self, operation: &PageOperation) {
+        match operation {
+            PageOperation::Insert { page, n_pages } => {
+                for _ in 0..*n_pages {
+                    self.insert_page_space(*page, &[]);
+                }
+            }
+            PageOperation::Delete {
+                page,
+                strokes,
+                trashed,
+                ..
+            } => {
+                self.strokes_state()
+                    .borrow_mut()
+                    .set_trashed_keep_chrono(trashed, true);
+                self.remove_page_space(*page, strokes);
+            }
+            PageOperation::Duplicate {
+                page,
+                copies,
+                background,
+            } => {
+                self.insert_page_space(page + 1, copies);
+                self.strokes_state()
+                    .borrow_mut()
+                    .set_trashed_keep_chrono(copies, false);
+                self.background()
+                    .borrow_mut()
+                    .set_page_override(page + 1, background.clone());
+            }
+            PageOperation::Swap { page } => {
+                self.swap_page_contents(*page);
+            }
+        }
+    }
+
+    pub(super) fn revert_page_operation(&self, operation: &PageOperation) {
+        match operation {
+            PageOperation::Insert { page, n_pages } => {
+                for _ in 0..*n_pages {
+                    self.remove_page_space(*page, &[]);
+                }
+            }
+            PageOperation::Delete {
+                page,
+                strokes,
+                trashed,
+                background,
+            } => {
+                self.insert_page_space(*page, strokes);
+                self.strokes_state()
+                    .borrow_mut()
+                    .set_trashed_keep_chrono(trashed, false);
+                self.background()
+                    .borrow_mut()
+                    .set_page_override(*page, background.clone());
+            }
+            PageOperation::Duplicate { page, copies, .. } => {
+                self.strokes_state()
+                    .borrow_mut()
+                    .set_trashed_keep_chrono(copies, true);
+                self.remove_page_space(page + 1, copies);
+            }
+            PageOperation::Swap { page } => {
+                self.swap_page_contents(*page);
+            }
+        }
+    }
+
+    fn push_page_operation(&self, operation: PageOperation) {
+        self.push_operation(SheetOperation::Page(operation));
+    }
+
+    /// Inserts a blank page at the index. Resizing needed after calling this
+    pub fn insert_page(&self, page: u32) {
+        self.insert_pages(page, 1);
+    }
+
+    /// Inserts the number of blank pages at the index, undone as a whole. Resizing needed after calling this
+    pub fn insert_pages(&self, page: u32, n_pages: u32) {
+        if n_pages == 0 {
+            return;
+        }
+        let operation = PageOperation::Insert { page, n_pages };
+
+        self.apply_page_operation(&operation);
+        self.push_page_operation(operation);
+    }
+
+    /// Deletes the page at the index together with its strokes. Resizing needed after calling this
+    pub fn delete_page(&self, page: u32) {
+        let (strokes, trashed) = {
+            let strokes_state = self.strokes_state().borrow();
+            let strokes = strokes_state.strokes_on_page(page, self.page_height());
+            let trashed = strokes
+                .iter()
+                .filter(|&&key| !strokes_state.trashed(key).unwrap_or(true))
+                .copied()
+                .collect::<Vec<StrokeKey>>();
+
+            (strokes, trashed)
+        };
+        let background = self.background().borrow().page_override(page);
+
+        let operation = PageOperation::Delete {
+            page,
+            strokes,
+            trashed,
+            background,
+        };
+
+        self.apply_page_operation(&operation);
+        self.push_page_operation(operation);
+    }
+
+    /// Inserts a copy of the page at the index below it. Resizing needed after calling this
+    pub fn duplicate_page(&self, page: u32) {
+        let page_height = self.page_height();
+        let background = self.background().borrow().page_override(page);
+
+        self.insert_page_space(page + 1, &[]);
+        let copies = {
+            let mut strokes_state = self.strokes_state().borrow_mut();
+            let strokes = strokes_state.strokes_on_page(page, page_height);
+
+            strokes_state.duplicate_strokes(&strokes, na::vector![0.0, page_height])
+        };
+        self.background()
+            .borrow_mut()
+            .set_page_override(page + 1, background.clone());
+
+        self.push_page_operation(PageOperation::Duplicate {
+            page,
+            copies,
+            background,
+        });
+    }
+
+    /// Swaps the page at the index with the page above. Resizing needed after calling this
+    pub fn move_page_up(&self, page: u32) {
+        if page == 0 {
+            return;
+        }
+        let operation = PageOperation::Swap { page: page - 1 };
+
+        self.apply_page_operation(&operation);
+        self.push_page_operation(operation);
+    }
+
+    /// Swaps the page at the index with the page below. Resizing needed after calling this
+    pub fn move_page_down(&self, page: u32) {
+        if page + 1 >= self.calc_n_pages().max(self.min_n_pages()) as u32 {
+            return;
+        }
+        let operation = PageOperation::Swap { page };
+
+        self.apply_page_operation(&operation);
+        self.push_page_operation(operation);
+    }
+}
+
+/// Page thumbnails
+impl Sheet {
+    /// The bounds of the page with the given index
+    pub fn page_bounds(&self, page: u32) -> AABB {
+        let page_size = self.page_size();
+
+        AABB::new(
+            na::point![0.0, f64::from(page) * page_size[1]],
+            na::point![page_size[0], f64::from(page + 1) * page_size[1]],
+        )
+    }
+
+    /// Generates the Svgs for the page with the given index, like gen_svgs() does for the entire sheet
+    pub fn gen_page_svgs(&self, page: u32) -> Result<Vec<render::Svg>, anyhow::Error> {
+        let page_bounds = self.page_bounds(page);
+        let mut svgs = vec![];
+
+        svgs.push(
+            self.background()
+                .borrow()
+                .gen_svg(page_bounds.loosened(1.0), self.page_size())?,
+        );
+
+        svgs.append(
+            &mut self
+                .strokes_state()
+                .borrow()
+                .gen_svgs_for_bounds(page_bounds)?,
+        );
+
+        Ok(svgs)
+    }
+
+    /// Generates a hash for each page over its strokes and its background. It changes when the page needs to be rendered again.
+    pub fn gen_page_hashes(&self, n_pages: u32) -> Vec<u64> {
+        let page_size = self.page_size();
+        let background = self.background().borrow();
+        // The templates are hashed once, as they are shared by many pages
+        let template_hash = |template: Option<&BackgroundTemplate>| {
+            template.map(|template| {
+                let mut hasher = DefaultHasher::new();
+                template.svg_data.hash(&mut hasher);
+                hasher.finish()
+            })
+        };
+        let default_page_background = background.default_page_background();
+        let default_template_hash = template_hash(background.template());
+        let override_template_hashes = background
+            .page_overrides()
+            .keys()
+            .map(|&page| (page, template_hash(background.page_template(page))))
+            .collect::<BTreeMap<u32, Option<u64>>>();
+
+        self.strokes_state()
+            .borrow()
+            .gen_page_hashes(n_pages, page_size[1])
+            .into_iter()
+            .enumerate()
+            .map(|(page, strokes_hash)| {
+                let page = page as u32;
+                let page_background = background
+                    .page_overrides()
+                    .get(&page)
+                    .unwrap_or(&default_page_background);
+                let page_template_hash = override_template_hashes
+                    .get(&page)
+                    .copied()
+                    .unwrap_or(default_template_hash);
+
+                let mut hasher = DefaultHasher::new();
+                strokes_hash.hash(&mut hasher);
+                format!(
+                    "{:?} {:?} {:?} {:?} {:?} {:?}",
+                    page_background.color,
+                    page_background.pattern,
+                    page_background.pattern_size,
+                    page_background.pattern_color,
+                    page_template_hash,
+                    page_size
+                )
+                .hash(&mut hasher);
+
+                hasher.finish()
+            })
+            .collect::<Vec<u64>>()
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use p2d::bounding_volume::{BoundingVolume, AABB};
+use serde::Deserialize;
+use slotmap::{HopSlotMap, SecondaryMap};
+
+use super::{background::Background, format::Format, Sheet};
+use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::PdfDocument;
+use crate::render;
+use crate::strokes::strokestyle::StrokeStyle;
+use crate::strokesstate::chrono_comp::ChronoComponent;
+use crate::strokesstate::trash_comp::TrashComponent;
+use crate::strokesstate::StrokeKey;
+
+/// A preview of a saved sheet, for showing it in file lists
+#[derive(Debug, Clone)]
+pub struct SheetPreview {
+    /// The first page, rendered to fit into a square of the requested size
+    pub thumbnail: render::Image,
+    pub n_pages: u32,
+}
+
+/// The parts of a saved sheet which are needed for its preview. Deserializing them instead of a sheet
+/// skips setting up the strokes state with its thread pool and task channel
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename = "sheet")]
+struct PreviewSheet {
+    #[serde(rename = "strokes_state")]
+    strokes_state: PreviewStrokes,
+    #[serde(rename = "format")]
+    format: Format,
+    #[serde(rename = "background")]
+    background: Background,
+    #[serde(rename = "height")]
+    height: i32,
+}
+
+/// The strokes of a saved strokes state, with the components which decide if and in which order they are drawn
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename = "strokes_state")]
+struct PreviewStrokes {
+    #[serde(rename = "strokes")]
+    strokes: HopSlotMap<StrokeKey, StrokeStyle>,
+    #[serde(rename = "trash_components")]
+    trash_components: SecondaryMap<StrokeKey, TrashComponent>,
+    #[serde(rename = "chrono_components")]
+    chrono_components: SecondaryMap<StrokeKey, ChronoComponent>,
+    #[serde(rename = "pdf_documents")]
+    pdf_documents: BTreeMap<u32, PdfDocument>,
+}
+
+impl PreviewStrokes {
+    /// Generates the svgs of the strokes which are not trashed and intersect the bounds, in chronological order
+    fn gen_svgs_for_bounds(&mut self, bounds: AABB) -> Vec<render::Svg> {
+        let pdf_documents = &self.pdf_documents;
+        let trash_components = &self.trash_components;
+        let chrono_components = &self.chrono_components;
+
+        let mut strokes = self
+            .strokes
+            .iter_mut()
+            .filter(|(key, stroke)| {
+                !trash_components
+                    .get(*key)
+                    .map(|trash_comp| trash_comp.trashed)
+                    .unwrap_or(false)
+                    && stroke.bounds().intersects(&bounds)
+            })
+            .map(|(key, stroke)| {
+                let t = chrono_components
+                    .get(key)
+                    .map(|chrono_comp| chrono_comp.t)
+                    .unwrap_or(0);
+                (t, stroke)
+            })
+            .collect::<Vec<(u32, &mut StrokeStyle)>>();
+        strokes.sort_by_key(|(t, _)| *t);
+
+        let mut svgs = vec![];
+        for (_, stroke) in strokes {
+            // The lazily rendered pdf pages are drawn from the data of their document
+            if let StrokeStyle::BitmapImage(bitmapimage) = stroke {
+                if let Some(pdf_origin) = bitmapimage.pdf_origin {
+                    bitmapimage.pdf_data = pdf_documents
+                        .get(&pdf_origin.document)
+                        .map(|pdf_document| Arc::clone(&pdf_document.data));
+                }
+            }
+
+            match stroke.gen_svgs(na::vector![0.0, 0.0]) {
+                Ok(mut stroke_svgs) => svgs.append(&mut stroke_svgs),
+                Err(e) => {
+                    log::error!(
+                        "stroke.gen_svgs() failed in gen_svgs_for_bounds() of the sheet preview with Err {}",
+                        e
+                    );
+                }
+            }
+        }
+
+        svgs
+    }
+}
+
+/// Sheet previews
+impl Sheet {
+    /// Generates a preview from the bytes of a saved sheet file. Does not depend on any ui, so it can be called from worker threads.
+    /// Only the parts of the sheet which are drawn on the first page are deserialized, and rendered with the given renderer
+    pub fn gen_preview_from_bytes(
+        bytes: &[u8],
+        thumbnail_size: f64,
+        renderer: &render::Renderer,
+    ) -> Result<SheetPreview, anyhow::Error> {
+        let mut preview_sheet = Self::deserialize_from_bytes::<PreviewSheet>(bytes)?;
+
+        let page_size = na::vector![
+            f64::from(preview_sheet.format.width()),
+            f64::from(preview_sheet.format.height())
+        ];
+        if page_size[0] <= 0.0 || page_size[1] <= 0.0 {
+            return Err(anyhow::anyhow!(
+                "gen_preview_from_bytes() failed, page size is <= 0.0"
+            ));
+        }
+        let zoom = thumbnail_size / page_size[0].max(page_size[1]);
+        let page_bounds = AABB::new(na::point![0.0, 0.0], na::point![page_size[0], page_size[1]]);
+
+        let mut svgs = vec![preview_sheet
+            .background
+            .gen_svg(page_bounds.loosened(1.0), page_size)?];
+        svgs.append(&mut preview_sheet.strokes_state.gen_svgs_for_bounds(page_bounds));
+
+        let thumbnail = renderer.gen_image(zoom, &svgs, page_bounds)?;
+
+        Ok(SheetPreview {
+            thumbnail,
+            n_pages: (preview_sheet.height / preview_sheet.format.height()).max(1) as u32,
+        })
+    }
+}
@@ -0,0 +1,443 @@
+use std::io;
+use std::sync::Arc;
+
+use crate::compose::{geometry, shapes};
+use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::{self, PdfImportPrefs, PdfPageOrigin};
+use crate::sheet::attachments::AttachmentData;
+use crate::{compose, render};
+use anyhow::Context;
+use image::{io::Reader, GenericImageView};
+use p2d::bounding_volume::AABB;
+use serde::{Deserialize, Serialize};
+use svg::node::{self, element};
+
+use crate::strokes::strokebehaviour::StrokeBehaviour;
+
+use super::imageedits::{BitmapAdjustments, ImageEdits};
+use super::strokebehaviour;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename = "format")]
+pub enum Format {
+    #[serde(rename = "png")]
+    Png,
+    #[serde(rename = "jpeg")]
+    Jpeg,
+}
+
+impl Format {
+    pub fn as_mime_type(&self) -> String {
+        match self {
+            Format::Png => String::from("image/png"),
+            Format::Jpeg => String::from("image/jpeg"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "bitmapimage")]
+pub struct BitmapImage {
+    /// The encoded png or jpeg data. Empty for lazily rendered pdf pages
+    #[serde(rename = "data_base64", with = "crate::sheet::attachments::image_data")]
+    pub data: AttachmentData,
+    #[serde(rename = "format")]
+    pub format: Format,
+    #[serde(rename = "intrinsic_size")]
+    pub intrinsic_size: na::Vector2<f64>,
+    #[serde(rename = "rectangle")]
+    pub rectangle: shapes::Rectangle,
+    #[serde(rename = "bounds")]
+    pub bounds: AABB,
+    /// The pdf page the image was imported from
+    #[serde(rename = "pdf_origin")]
+    pub pdf_origin: Option<PdfPageOrigin>,
+    /// The data of the pdf document the page is lazily rendered from, when the image has no own data.
+    /// Not serialized, the document is stored once in the strokes state
+    #[serde(skip)]
+    pub pdf_data: Option<Arc<Vec<u8>>>,
+    /// Crop and flip
+    #[serde(rename = "edits")]
+    pub edits: ImageEdits,
+    #[serde(rename = "adjustments")]
+    pub adjustments: BitmapAdjustments,
+}
+
+impl Default for BitmapImage {
+    fn default() -> Self {
+        Self {
+            data: AttachmentData::default(),
+            format: Format::Png,
+            intrinsic_size: na::vector![0.0, 0.0],
+            rectangle: shapes::Rectangle::default(),
+            bounds: geometry::aabb_new_zero(),
+            pdf_origin: None,
+            pdf_data: None,
+            edits: ImageEdits::default(),
+            adjustments: BitmapAdjustments::default(),
+        }
+    }
+}
+
+/// The mime types of the bitmap image formats which can be imported
+pub const IMPORT_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/gif",
+    "image/bmp",
+    "image/tiff",
+];
+
+impl DrawBehaviour for BitmapImage {
+    fn bounds(&self) -> AABB {
+        self.bounds
+    }
+
+    fn set_bounds(&mut self, bounds: AABB) {
+        self.bounds = bounds;
+    }
+
+    fn gen_bounds(&self) -> Option<AABB> {
+        Some(self.rectangle.global_aabb())
+    }
+
+    fn gen_svgs(&self, offset: na::Vector2<f64>) -> Result<Vec<render::Svg>, anyhow::Error> {
+        // Lazily rendered pdf pages are embedded with the resolution of their intrinsic size
+        let (format, data_base64) = if self.is_lazy_pdf_page() {
+            (Format::Png, base64::encode(&self.gen_pdf_page_png()?))
+        } else {
+            (self.format, base64::encode(self.data.as_slice()))
+        };
+
+        let mut rectangle = self.rectangle.clone();
+        rectangle.transform.append_translation_mut(offset);
+
+        let mut image = element::Image::new()
+            .set("x", 0.0)
+            .set("y", 0.0)
+            .set("width", self.intrinsic_size[0])
+            .set("height", self.intrinsic_size[1])
+            .set("preserveAspectRatio", "none")
+            .set(
+                "href",
+                format!(
+                    "data:{mime_type};base64,{data_base64}",
+                    mime_type = &format.as_mime_type(),
+                    data_base64 = &data_base64
+                ),
+            );
+        if self.adjustments.opacity < BitmapAdjustments::OPACITY_MAX {
+            image = image.set("opacity", self.adjustments.opacity);
+        }
+
+        let content = match self.adjustments.gen_svg_filter() {
+            Some((filter_id, filter)) => element::Group::new()
+                .add(element::Definitions::new().add(node::Text::new(filter)))
+                .add(image.set("filter", format!("url(#{})", filter_id))),
+            None => element::Group::new().add(image),
+        };
+
+        let svg_root = self
+            .edits
+            .wrap_svg_content(content, self.intrinsic_size, &rectangle);
+
+        let svg_data = compose::node_to_string(&svg_root)?;
+        let svg = render::Svg {
+            bounds: geometry::aabb_translate(self.bounds, offset),
+            svg_data,
+        };
+
+        Ok(vec![svg])
+    }
+
+    fn gen_image(
+        &self,
+        zoom: f64,
+        renderer: &render::Renderer,
+    ) -> Result<render::Image, anyhow::Error> {
+        if !self.is_lazy_pdf_page() || !self.edits.is_default() || !self.adjustments.is_default() {
+            let svgs = self.gen_svgs(na::vector![0.0, 0.0])?;
+
+            return renderer.gen_image(zoom, &svgs, self.bounds());
+        }
+
+        // Lazily rendered pdf pages without edits are rendered directly at the current zoom
+        let width_scaled = (self.bounds.extents()[0] * zoom).round() as i32;
+        let height_scaled = (self.bounds.extents()[1] * zoom).round() as i32;
+
+        let mut surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width_scaled, height_scaled)
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "create ImageSurface with dimensions ({}, {}) failed, {}",
+                        width_scaled,
+                        height_scaled,
+                        e
+                    )
+                })?;
+
+        // Context in new scope, else accessing the surface data fails with a borrow error
+        {
+            let cx = cairo::Context::new(&surface).context("new cairo::Context failed")?;
+            cx.scale(zoom, zoom);
+            cx.translate(-self.bounds.mins[0], -self.bounds.mins[1]);
+
+            let transform = self.rectangle.transform.transform.to_homogeneous();
+            cx.transform(cairo::Matrix::new(
+                transform[(0, 0)],
+                transform[(1, 0)],
+                transform[(0, 1)],
+                transform[(1, 1)],
+                transform[(0, 2)],
+                transform[(1, 2)],
+            ));
+            cx.translate(
+                -self.rectangle.cuboid.half_extents[0],
+                -self.rectangle.cuboid.half_extents[1],
+            );
+
+            self.draw_pdf_page(&cx, 2.0 * self.rectangle.cuboid.half_extents)?;
+        }
+        // Surface needs to be flushed before accessing its data
+        surface.flush();
+
+        let data = surface
+            .data()
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "accessing imagesurface data failed in bitmapimage gen_image() with Err {}",
+                    e
+                )
+            })?
+            .to_vec();
+
+        Ok(render::Image {
+            data,
+            bounds: self.bounds,
+            data_width: width_scaled,
+            data_height: height_scaled,
+            memory_format: gdk::MemoryFormat::B8g8r8a8Premultiplied,
+        })
+    }
+}
+
+impl StrokeBehaviour for BitmapImage {
+    fn translate(&mut self, offset: nalgebra::Vector2<f64>) {
+        self.rectangle.translate(offset);
+        self.update_geometry();
+    }
+
+    fn rotate(&mut self, angle: f64, center: nalgebra::Point2<f64>) {
+        self.rectangle.rotate(angle, center);
+        self.update_geometry();
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.rectangle.scale(scale);
+        self.update_geometry();
+    }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>) {
+        self.rectangle.flip(horizontal, vertical, center);
+        self.update_geometry();
+    }
+}
+
+impl BitmapImage {
+    pub const OFFSET_X_DEFAULT: f64 = 32.0;
+    pub const OFFSET_Y_DEFAULT: f64 = 32.0;
+
+    pub fn import_from_image_bytes<P>(
+        to_be_read: P,
+        pos: na::Vector2<f64>,
+    ) -> Result<Self, anyhow::Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        let reader = Reader::new(io::Cursor::new(&to_be_read)).with_guessed_format()?;
+        log::debug!("BitmapImage detected format: {:?}", reader.format());
+
+        // Formats other than png and jpeg are normalized to png, so that they can be embedded in the generated svgs
+        let (format, normalize) = match reader.format() {
+            Some(image::ImageFormat::Png) => (Format::Png, false),
+            Some(image::ImageFormat::Jpeg) => (Format::Jpeg, false),
+            Some(
+                image::ImageFormat::WebP
+                | image::ImageFormat::Gif
+                | image::ImageFormat::Bmp
+                | image::ImageFormat::Tiff,
+            ) => (Format::Png, true),
+            _ => {
+                return Err(anyhow::Error::msg("unsupported format."));
+            }
+        };
+
+        let bitmap_data = reader.decode()?;
+        let data = if normalize {
+            let mut png_bytes = Vec::<u8>::new();
+            bitmap_data.write_to(
+                &mut io::Cursor::new(&mut png_bytes),
+                image::ImageOutputFormat::Png,
+            )?;
+
+            png_bytes
+        } else {
+            to_be_read.as_ref().to_vec()
+        };
+
+        let intrinsic_size = {
+            let dimensions = bitmap_data.dimensions();
+            na::vector![f64::from(dimensions.0), f64::from(dimensions.1)]
+        };
+
+        let rectangle = shapes::Rectangle {
+            cuboid: p2d::shape::Cuboid::new(intrinsic_size / 2.0),
+            transform: strokebehaviour::StrokeTransform::new_w_isometry(na::Isometry2::new(
+                pos + intrinsic_size / 2.0,
+                0.0,
+            )),
+        };
+
+        let mut bitmapimage = Self {
+            data: AttachmentData::new(data),
+            format,
+            intrinsic_size,
+            rectangle,
+            bounds: geometry::aabb_new_zero(),
+            pdf_origin: None,
+            pdf_data: None,
+            edits: ImageEdits::default(),
+            adjustments: BitmapAdjustments::default(),
+        };
+        bitmapimage.update_geometry();
+
+        Ok(bitmapimage)
+    }
+
+    /// Imports the pages of the pdf as lazily rendered pdf pages. They reference the stored pdf document, and are rendered when they are drawn.
+    pub fn import_from_pdf_document(
+        document: u32,
+        pdf_data: Arc<Vec<u8>>,
+        prefs: &PdfImportPrefs,
+    ) -> Result<Vec<Self>, anyhow::Error> {
+        let doc = pdf::parsed_document(&pdf_data)?;
+        let n_pages = u32::try_from(doc.n_pages()).unwrap_or(0);
+
+        let mut images = Vec::new();
+
+        for (i, page_i) in prefs.pages(n_pages).enumerate() {
+            if let Some(page) = doc.page(page_i as i32) {
+                let page_size = na::vector![page.size().0, page.size().1];
+                let bounds = prefs.layout.page_bounds(i as u32, page_size);
+
+                let mut image = Self {
+                    // The resolution the page is embedded with, when it is exported
+                    intrinsic_size: (page_size * prefs.bitmap_dpi / PdfImportPrefs::PDF_DPI)
+                        .map(|size| size.round()),
+                    // The image is displayed in the size of the page bounds, independent of its resolution
+                    rectangle: shapes::Rectangle {
+                        cuboid: p2d::shape::Cuboid::new(bounds.half_extents()),
+                        transform: strokebehaviour::StrokeTransform::new_w_isometry(
+                            na::Isometry2::new(bounds.center().coords, 0.0),
+                        ),
+                    },
+                    pdf_origin: Some(PdfPageOrigin {
+                        document,
+                        page: page_i,
+                    }),
+                    pdf_data: Some(Arc::clone(&pdf_data)),
+                    ..Self::default()
+                };
+                image.update_geometry();
+
+                images.push(image);
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Wether the image is a pdf page which is rendered lazily from its document
+    pub fn is_lazy_pdf_page(&self) -> bool {
+        self.data.is_empty() && self.pdf_origin.is_some()
+    }
+
+    /// Draws the pdf page with a white background and a outline, scaled into the size
+    fn draw_pdf_page(
+        &self,
+        cx: &cairo::Context,
+        size: na::Vector2<f64>,
+    ) -> Result<(), anyhow::Error> {
+        let (pdf_origin, pdf_data) = match (self.pdf_origin, self.pdf_data.as_ref()) {
+            (Some(pdf_origin), Some(pdf_data)) => (pdf_origin, pdf_data),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "draw_pdf_page() failed, the pdf document of the page is not available"
+                ))
+            }
+        };
+        let doc = pdf::parsed_document(pdf_data)?;
+        let page = doc.page(pdf_origin.page as i32).ok_or_else(|| {
+            anyhow::anyhow!(
+                "draw_pdf_page() failed, page {} not found in the pdf document",
+                pdf_origin.page
+            )
+        })?;
+        let page_size = na::vector![page.size().0, page.size().1];
+
+        cx.save()?;
+        cx.rectangle(0.0, 0.0, size[0], size[1]);
+        cx.clip();
+
+        // Set margin to white
+        cx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+        cx.paint()?;
+
+        cx.save()?;
+        cx.scale(size[0] / page_size[0], size[1] / page_size[1]);
+        page.render(cx);
+        cx.restore()?;
+
+        // Draw outline around page
+        cx.set_source_rgba(0.7, 0.5, 0.5, 1.0);
+        cx.set_line_width(1.0);
+        cx.rectangle(0.0, 0.0, size[0], size[1]);
+        cx.stroke()?;
+        cx.restore()?;
+
+        Ok(())
+    }
+
+    /// Renders the lazily rendered pdf page to png data in the resolution of the intrinsic size
+    fn gen_pdf_page_png(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let width = self.intrinsic_size[0].round() as i32;
+        let height = self.intrinsic_size[1].round() as i32;
+
+        let surface =
+            cairo::ImageSurface::create(cairo::Format::ARgb32, width, height).map_err(|e| {
+                anyhow::anyhow!(
+                    "create ImageSurface with dimensions ({}, {}) failed, {}",
+                    width,
+                    height,
+                    e
+                )
+            })?;
+
+        {
+            let cx = cairo::Context::new(&surface).context("new cairo::Context failed")?;
+            self.draw_pdf_page(&cx, self.intrinsic_size)?;
+        }
+
+        let mut png_data: Vec<u8> = Vec::new();
+        surface.write_to_png(&mut png_data)?;
+
+        Ok(png_data)
+    }
+
+    pub fn update_geometry(&mut self) {
+        if let Some(new_bounds) = self.gen_bounds() {
+            self.set_bounds(new_bounds);
+        }
+    }
+}
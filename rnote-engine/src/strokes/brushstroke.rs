@@ -1,3 +1,4 @@
+use crate::compose::drawpath::DrawPath;
 use crate::compose::geometry;
 use crate::compose::{self, curves, solid, textured};
 use crate::drawbehaviour::DrawBehaviour;
@@ -112,6 +113,23 @@ impl DrawBehaviour for BrushStroke {
             brush::BrushStyle::Experimental => self.gen_svgs_experimental(offset, svg_root),
         }
     }
+
+    fn gen_paths(&self, offset: na::Vector2<f64>) -> Option<Vec<DrawPath>> {
+        match self.brush.style() {
+            brush::BrushStyle::Solid | brush::BrushStyle::Experimental => Some(
+                self.elements
+                    .iter()
+                    .zip(self.elements.iter().skip(1))
+                    .zip(self.elements.iter().skip(2))
+                    .zip(self.elements.iter().skip(3))
+                    .filter_map(|(((first, second), third), forth)| {
+                        self.gen_path_elem((first, second, third, forth), offset)
+                    })
+                    .collect(),
+            ),
+            brush::BrushStyle::Textured => None,
+        }
+    }
 }
 
 impl StrokeBehaviour for BrushStroke {
@@ -142,6 +160,17 @@ impl StrokeBehaviour for BrushStroke {
         });
         self.update_geometry();
     }
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: nalgebra::Point2<f64>) {
+        self.elements.iter_mut().for_each(|element| {
+            element.inputdata.set_pos(geometry::flip_pos(
+                element.inputdata.pos(),
+                horizontal,
+                vertical,
+                center,
+            ));
+        });
+        self.update_geometry();
+    }
 }
 
 impl BrushStroke {
@@ -306,12 +335,12 @@ impl BrushStroke {
         }
     }
 
-    pub fn gen_svg_elem_solid(
+    /// The path commands for the elements with the solid style, together with their bounds
+    fn gen_commands_elem_solid(
         &self,
         elements: (&Element, &Element, &Element, &Element),
         offset: na::Vector2<f64>,
-        svg_root: bool,
-    ) -> Option<render::Svg> {
+    ) -> Option<(Vec<path::Command>, AABB)> {
         let mut commands = Vec::new();
 
         let start_width = elements.1.inputdata.pressure() * self.brush.width();
@@ -380,6 +409,17 @@ impl BrushStroke {
 
         bounds.loosen(start_width.max(end_width));
 
+        Some((commands, bounds))
+    }
+
+    pub fn gen_svg_elem_solid(
+        &self,
+        elements: (&Element, &Element, &Element, &Element),
+        offset: na::Vector2<f64>,
+        svg_root: bool,
+    ) -> Option<render::Svg> {
+        let (commands, bounds) = self.gen_commands_elem_solid(elements, offset)?;
+
         let path = svg::node::element::Path::new()
             .set("stroke", "none")
             //.set("stroke", self.brush.color.to_css_color())
@@ -494,13 +534,12 @@ impl BrushStroke {
         Ok(svgs)
     }
 
-    pub fn gen_svg_elem_experimental(
+    /// The path commands for the elements with the experimental style, together with their bounds
+    fn gen_commands_elem_experimental(
         &self,
-        _seed: Option<u64>,
         elements: (&Element, &Element, &Element, &Element),
         offset: na::Vector2<f64>,
-        svg_root: bool,
-    ) -> Option<render::Svg> {
+    ) -> Option<(Vec<path::Command>, AABB)> {
         let mut commands = Vec::new();
 
         let start_width = elements.1.inputdata.pressure() * self.brush.width();
@@ -572,6 +611,18 @@ impl BrushStroke {
             return None;
         }
 
+        Some((commands, bounds))
+    }
+
+    pub fn gen_svg_elem_experimental(
+        &self,
+        _seed: Option<u64>,
+        elements: (&Element, &Element, &Element, &Element),
+        offset: na::Vector2<f64>,
+        svg_root: bool,
+    ) -> Option<render::Svg> {
+        let (commands, bounds) = self.gen_commands_elem_experimental(elements, offset)?;
+
         let path = svg::node::element::Path::new()
             .set("stroke", "none")
             //.set("stroke", self.brush.color.to_css_color())
@@ -621,4 +672,102 @@ impl BrushStroke {
 
         Ok(svgs)
     }
+
+    /// Generates the path for the elements. Returns None for the textured style, which can only be rendered from its svgs
+    pub fn gen_path_elem(
+        &self,
+        elements: (&Element, &Element, &Element, &Element),
+        offset: na::Vector2<f64>,
+    ) -> Option<DrawPath> {
+        let (commands, bounds) = match self.brush.style() {
+            brush::BrushStyle::Solid => self.gen_commands_elem_solid(elements, offset)?,
+            brush::BrushStyle::Textured => return None,
+            brush::BrushStyle::Experimental => {
+                self.gen_commands_elem_experimental(elements, offset)?
+            }
+        };
+
+        Some(DrawPath::new_filled(
+            commands,
+            self.brush.color().to_tiny_skia(),
+            bounds,
+        ))
+    }
+
+    /// Generates the paths for drawing the stroke while it is drawn, for the elements starting at the index.
+    /// Includes the paths which end in the new elements, but start before them.
+    /// The textured style is too expensive to generate for every new element, so it is approximated with the solid style
+    pub fn gen_live_paths(&self, from: usize) -> Vec<DrawPath> {
+        let offset = na::vector![0.0, 0.0];
+
+        self.elements
+            .iter()
+            .zip(self.elements.iter().skip(1))
+            .zip(self.elements.iter().skip(2))
+            .zip(self.elements.iter().skip(3))
+            .skip(from.saturating_sub(3))
+            .filter_map(|(((first, second), third), forth)| {
+                let elements = (first, second, third, forth);
+
+                match self.brush.style() {
+                    brush::BrushStyle::Solid | brush::BrushStyle::Experimental => {
+                        self.gen_path_elem(elements, offset)
+                    }
+                    brush::BrushStyle::Textured => {
+                        let (commands, bounds) = self.gen_commands_elem_solid(elements, offset)?;
+
+                        Some(DrawPath::new_filled(
+                            commands,
+                            self.brush.color().to_tiny_skia(),
+                            bounds,
+                        ))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Generates the image for the last elements, for appending it to the rendering while the stroke is drawn.
+    /// Returns None if there are not enough elements yet
+    pub fn gen_image_for_last_elems(
+        &self,
+        zoom: f64,
+        renderer: &render::Renderer,
+    ) -> Result<Option<render::Image>, anyhow::Error> {
+        let elems_len = self.elements.len();
+        if elems_len < 4 {
+            return Ok(None);
+        }
+
+        let elements = (
+            &self.elements[elems_len - 4],
+            &self.elements[elems_len - 3],
+            &self.elements[elems_len - 2],
+            &self.elements[elems_len - 1],
+        );
+        let offset = na::vector![0.0, 0.0];
+
+        match self.brush.style() {
+            brush::BrushStyle::Solid | brush::BrushStyle::Experimental => {
+                match self.gen_path_elem(elements, offset) {
+                    Some(last_elems_path) => {
+                        let bounds = last_elems_path.bounds;
+                        Ok(Some(renderer.gen_image_from_paths(
+                            zoom,
+                            &[last_elems_path],
+                            bounds,
+                        )?))
+                    }
+                    None => Ok(None),
+                }
+            }
+            brush::BrushStyle::Textured => match self.gen_svg_for_elems(elements, offset, true)? {
+                Some(last_elems_svg) => {
+                    let bounds = last_elems_svg.bounds;
+                    Ok(Some(renderer.gen_image(zoom, &[last_elems_svg], bounds)?))
+                }
+                None => Ok(None),
+            },
+        }
+    }
 }
@@ -0,0 +1,238 @@
+use crate::compose::shapes;
+
+use p2d::bounding_volume::AABB;
+use serde::{Deserialize, Serialize};
+use svg::node::{self, element};
+
+/// Non-destructive edits of a image stroke. The image data itself stays untouched, the edits are applied when the svg is generated
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "image_edits")]
+pub struct ImageEdits {
+    /// The crop rectangle in the coordinate space of the intrinsic size of the image. None if the image is not cropped
+    #[serde(rename = "crop")]
+    pub crop: Option<AABB>,
+    #[serde(rename = "flip_horizontal")]
+    pub flip_horizontal: bool,
+    #[serde(rename = "flip_vertical")]
+    pub flip_vertical: bool,
+}
+
+impl Default for ImageEdits {
+    fn default() -> Self {
+        Self {
+            crop: None,
+            flip_horizontal: false,
+            flip_vertical: false,
+        }
+    }
+}
+
+impl ImageEdits {
+    /// The minimum size of the crop rectangle
+    pub const CROP_MIN: f64 = 1.0;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The crop rectangle, the full image if it is not cropped
+    pub fn crop_bounds(&self, intrinsic_size: na::Vector2<f64>) -> AABB {
+        self.crop
+            .unwrap_or_else(|| AABB::new(na::point![0.0, 0.0], na::Point2::from(intrinsic_size)))
+    }
+
+    /// The signs of the scale which flips the image
+    fn flip_scale(&self) -> na::Vector2<f64> {
+        na::vector![
+            if self.flip_horizontal { -1.0 } else { 1.0 },
+            if self.flip_vertical { -1.0 } else { 1.0 }
+        ]
+    }
+
+    /// The size of one unit of the intrinsic size in the local coordinate space of the rectangle
+    fn local_scale(
+        &self,
+        intrinsic_size: na::Vector2<f64>,
+        rectangle: &shapes::Rectangle,
+    ) -> na::Vector2<f64> {
+        (2.0 * rectangle.cuboid.half_extents)
+            .component_div(&self.crop_bounds(intrinsic_size).extents())
+    }
+
+    /// Sets the crop rectangle. The rectangle the image is drawn into is updated, so that the remaining part of the image stays in place
+    pub fn set_crop(
+        &mut self,
+        crop: AABB,
+        intrinsic_size: na::Vector2<f64>,
+        rectangle: &mut shapes::Rectangle,
+    ) {
+        let mins = na::point![
+            crop.mins[0].clamp(0.0, intrinsic_size[0] - Self::CROP_MIN),
+            crop.mins[1].clamp(0.0, intrinsic_size[1] - Self::CROP_MIN)
+        ];
+        let maxs = na::point![
+            crop.maxs[0].clamp(mins[0] + Self::CROP_MIN, intrinsic_size[0]),
+            crop.maxs[1].clamp(mins[1] + Self::CROP_MIN, intrinsic_size[1])
+        ];
+        let crop = AABB::new(mins, maxs);
+
+        let old_crop = self.crop_bounds(intrinsic_size);
+        let local_scale = self.local_scale(intrinsic_size, rectangle);
+        let local_offset = (crop.center() - old_crop.center())
+            .component_mul(&local_scale)
+            .component_mul(&self.flip_scale());
+
+        rectangle.cuboid.half_extents = crop.extents().component_mul(&local_scale) / 2.0;
+        rectangle.transform.prepend_translation_mut(local_offset);
+
+        self.crop = if crop == AABB::new(na::point![0.0, 0.0], na::Point2::from(intrinsic_size)) {
+            None
+        } else {
+            Some(crop)
+        };
+    }
+
+    /// Converts a offset in the coordinate space of the sheet to a offset in the coordinate space of the intrinsic image size
+    pub fn sheet_offset_to_intrinsic(
+        &self,
+        offset: na::Vector2<f64>,
+        intrinsic_size: na::Vector2<f64>,
+        rectangle: &shapes::Rectangle,
+    ) -> na::Vector2<f64> {
+        rectangle
+            .transform
+            .transform
+            .inverse_transform_vector(&offset)
+            .component_div(&self.local_scale(intrinsic_size, rectangle))
+            .component_mul(&self.flip_scale())
+    }
+
+    /// Wraps the content, which is in the coordinate space of the intrinsic size, into a group which crops and flips it and transforms it onto the rectangle
+    pub fn wrap_svg_content<T>(
+        &self,
+        content: T,
+        intrinsic_size: na::Vector2<f64>,
+        rectangle: &shapes::Rectangle,
+    ) -> element::Group
+    where
+        T: node::Node,
+    {
+        let crop = self.crop_bounds(intrinsic_size);
+        let flip_scale = self.flip_scale();
+
+        let svg_root = element::SVG::new()
+            .set("x", -rectangle.cuboid.half_extents[0])
+            .set("y", -rectangle.cuboid.half_extents[1])
+            .set("width", 2.0 * rectangle.cuboid.half_extents[0])
+            .set("height", 2.0 * rectangle.cuboid.half_extents[1])
+            .set(
+                "viewBox",
+                format!(
+                    "{:.3} {:.3} {:.3} {:.3}",
+                    crop.mins[0],
+                    crop.mins[1],
+                    crop.extents()[0],
+                    crop.extents()[1]
+                ),
+            )
+            .set("preserveAspectRatio", "none")
+            .add(content);
+
+        element::Group::new()
+            .set(
+                "transform",
+                format!(
+                    "{} scale({} {})",
+                    rectangle.transform.transform_as_svg_transform_attr(),
+                    flip_scale[0],
+                    flip_scale[1]
+                ),
+            )
+            .add(svg_root)
+    }
+}
+
+/// Adjustments of the colors of a bitmap image. Applied as svg filter, so the image data itself stays untouched
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "bitmap_adjustments")]
+pub struct BitmapAdjustments {
+    /// Between -1.0 and 1.0
+    #[serde(rename = "brightness")]
+    pub brightness: f64,
+    /// Between -1.0 and 1.0
+    #[serde(rename = "contrast")]
+    pub contrast: f64,
+    #[serde(rename = "greyscale")]
+    pub greyscale: bool,
+    /// Between 0.0 and 1.0
+    #[serde(rename = "opacity")]
+    pub opacity: f64,
+}
+
+impl Default for BitmapAdjustments {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 0.0,
+            greyscale: false,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl BitmapAdjustments {
+    pub const BRIGHTNESS_MIN: f64 = -1.0;
+    pub const BRIGHTNESS_MAX: f64 = 1.0;
+    pub const CONTRAST_MIN: f64 = -1.0;
+    pub const CONTRAST_MAX: f64 = 1.0;
+    pub const OPACITY_MIN: f64 = 0.0;
+    pub const OPACITY_MAX: f64 = 1.0;
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Generates the filter for brightness, contrast and greyscale with its id. None if no filter is needed
+    pub fn gen_svg_filter(&self) -> Option<(String, String)> {
+        if self.brightness == 0.0 && self.contrast == 0.0 && !self.greyscale {
+            return None;
+        }
+
+        // The id is derived from the values, so identical filters of different images are interchangeable
+        let id = format!(
+            "bitmapimage-adjustments-{:.3}-{:.3}-{}",
+            self.brightness, self.contrast, self.greyscale
+        )
+        .replace('.', "_");
+
+        let slope = 1.0 + self.contrast.clamp(Self::CONTRAST_MIN, Self::CONTRAST_MAX);
+        let intercept = 0.5 * (1.0 - slope)
+            + self
+                .brightness
+                .clamp(Self::BRIGHTNESS_MIN, Self::BRIGHTNESS_MAX);
+        let transfer_func = |channel: &str| {
+            format!(
+                r#"<feFunc{channel} type="linear" slope="{slope:.3}" intercept="{intercept:.3}"/>"#,
+                channel = channel,
+                slope = slope,
+                intercept = intercept
+            )
+        };
+        let greyscale = if self.greyscale {
+            r#"<feColorMatrix type="saturate" values="0"/>"#
+        } else {
+            ""
+        };
+
+        let filter = format!(
+            r#"<filter id="{id}" color-interpolation-filters="sRGB">{greyscale}<feComponentTransfer>{r}{g}{b}</feComponentTransfer></filter>"#,
+            id = id,
+            greyscale = greyscale,
+            r = transfer_func("R"),
+            g = transfer_func("G"),
+            b = transfer_func("B"),
+        );
+
+        Some((id, filter))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::compose::drawpath::DrawPath;
 use crate::compose::{self, curves, geometry, solid};
 use crate::{
     drawbehaviour::DrawBehaviour, pens::marker::Marker, render, strokes::strokestyle::Element,
@@ -114,6 +115,20 @@ impl DrawBehaviour for MarkerStroke {
 
         Ok(svgs)
     }
+
+    fn gen_paths(&self, offset: na::Vector2<f64>) -> Option<Vec<DrawPath>> {
+        Some(
+            self.elements
+                .iter()
+                .zip(self.elements.iter().skip(1))
+                .zip(self.elements.iter().skip(2))
+                .zip(self.elements.iter().skip(3))
+                .filter_map(|(((first, second), third), forth)| {
+                    self.gen_path_elem((first, second, third, forth), offset)
+                })
+                .collect(),
+        )
+    }
 }
 
 impl StrokeBehaviour for MarkerStroke {
@@ -144,6 +159,17 @@ impl StrokeBehaviour for MarkerStroke {
         });
         self.update_geometry();
     }
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: nalgebra::Point2<f64>) {
+        self.elements.iter_mut().for_each(|element| {
+            element.inputdata.set_pos(geometry::flip_pos(
+                element.inputdata.pos(),
+                horizontal,
+                vertical,
+                center,
+            ));
+        });
+        self.update_geometry();
+    }
 }
 
 impl MarkerStroke {
@@ -265,12 +291,12 @@ impl MarkerStroke {
         }
     }
 
-    pub fn gen_svg_elem(
+    /// The path commands for the elements, together with their bounds
+    fn gen_commands_elem(
         &self,
         elements: (&Element, &Element, &Element, &Element),
         offset: na::Vector2<f64>,
-        svg_root: bool,
-    ) -> Option<render::Svg> {
+    ) -> Option<(Vec<path::Command>, AABB)> {
         let mut commands = Vec::new();
         let marker_width = self.marker.width();
 
@@ -313,9 +339,20 @@ impl MarkerStroke {
 
         bounds.loosen(marker_width);
 
+        Some((commands, bounds))
+    }
+
+    pub fn gen_svg_elem(
+        &self,
+        elements: (&Element, &Element, &Element, &Element),
+        offset: na::Vector2<f64>,
+        svg_root: bool,
+    ) -> Option<render::Svg> {
+        let (commands, bounds) = self.gen_commands_elem(elements, offset)?;
+
         let path = svg::node::element::Path::new()
             .set("stroke", self.marker.color.to_css_color())
-            .set("stroke-width", marker_width)
+            .set("stroke-width", self.marker.width())
             .set("stroke-linejoin", "round")
             .set("stroke-linecap", "round")
             .set("fill", "none")
@@ -335,4 +372,68 @@ impl MarkerStroke {
         }
         Some(render::Svg { svg_data, bounds })
     }
+
+    pub fn gen_path_elem(
+        &self,
+        elements: (&Element, &Element, &Element, &Element),
+        offset: na::Vector2<f64>,
+    ) -> Option<DrawPath> {
+        let (commands, bounds) = self.gen_commands_elem(elements, offset)?;
+
+        Some(DrawPath::new_stroked(
+            commands,
+            self.marker.color.to_tiny_skia(),
+            self.marker.width(),
+            bounds,
+        ))
+    }
+
+    /// Generates the paths for drawing the stroke while it is drawn, for the elements starting at the index.
+    /// Includes the paths which end in the new elements, but start before them
+    pub fn gen_live_paths(&self, from: usize) -> Vec<DrawPath> {
+        let offset = na::vector![0.0, 0.0];
+
+        self.elements
+            .iter()
+            .zip(self.elements.iter().skip(1))
+            .zip(self.elements.iter().skip(2))
+            .zip(self.elements.iter().skip(3))
+            .skip(from.saturating_sub(3))
+            .filter_map(|(((first, second), third), forth)| {
+                self.gen_path_elem((first, second, third, forth), offset)
+            })
+            .collect()
+    }
+
+    /// Generates the image for the last elements, for appending it to the rendering while the stroke is drawn.
+    /// Returns None if there are not enough elements yet
+    pub fn gen_image_for_last_elems(
+        &self,
+        zoom: f64,
+        renderer: &render::Renderer,
+    ) -> Result<Option<render::Image>, anyhow::Error> {
+        let elems_len = self.elements.len();
+        if elems_len < 4 {
+            return Ok(None);
+        }
+
+        let elements = (
+            &self.elements[elems_len - 4],
+            &self.elements[elems_len - 3],
+            &self.elements[elems_len - 2],
+            &self.elements[elems_len - 1],
+        );
+
+        match self.gen_path_elem(elements, na::vector![0.0, 0.0]) {
+            Some(last_elems_path) => {
+                let bounds = last_elems_path.bounds;
+                Ok(Some(renderer.gen_image_from_paths(
+                    zoom,
+                    &[last_elems_path],
+                    bounds,
+                )?))
+            }
+            None => Ok(None),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub mod bitmapimage;
 pub mod brushstroke;
+pub mod imageedits;
 pub mod markerstroke;
 pub mod shapestroke;
 pub mod strokebehaviour;
@@ -1,4 +1,5 @@
-use crate::compose::{curves, geometry, rough, shapes};
+use crate::compose::drawpath::{DrawPath, PathStroke};
+use crate::compose::{curves, geometry, rough, shapes, solid};
 use crate::drawbehaviour::DrawBehaviour;
 use crate::pens::shaper::{self, DrawStyle};
 use crate::strokes::strokebehaviour::StrokeBehaviour;
@@ -66,6 +67,20 @@ impl StrokeBehaviour for Shape {
             }
         }
     }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: nalgebra::Point2<f64>) {
+        match self {
+            Self::Line(line) => {
+                line.flip(horizontal, vertical, center);
+            }
+            Self::Rectangle(rectangle) => {
+                rectangle.flip(horizontal, vertical, center);
+            }
+            Self::Ellipse(ellipse) => {
+                ellipse.flip(horizontal, vertical, center);
+            }
+        }
+    }
 }
 
 impl Shape {
@@ -243,6 +258,52 @@ impl DrawBehaviour for ShapeStroke {
         };
         Ok(vec![svg])
     }
+
+    fn gen_paths(&self, offset: na::Vector2<f64>) -> Option<Vec<DrawPath>> {
+        // The rough shapes are generated by the rough module as svg elements, so they are rendered from their svgs
+        if let shaper::DrawStyle::Rough = self.shaper.drawstyle() {
+            return None;
+        }
+
+        let (commands, fill) = match self.shape {
+            Shape::Line(ref line) => {
+                let line = curves::Line {
+                    start: line.start + offset,
+                    end: line.end + offset,
+                };
+
+                (solid::compose_line(line, true), None)
+            }
+            Shape::Rectangle(ref rectangle) => {
+                let mut rectangle = rectangle.clone();
+                rectangle.transform.append_translation_mut(offset);
+
+                (
+                    solid::compose_rectangle_commands(&rectangle),
+                    self.shaper.fill(),
+                )
+            }
+            Shape::Ellipse(ref ellipse) => {
+                let mut ellipse = ellipse.clone();
+                ellipse.transform.append_translation_mut(offset);
+
+                (
+                    solid::compose_ellipse_commands(&ellipse),
+                    self.shaper.fill(),
+                )
+            }
+        };
+
+        Some(vec![DrawPath {
+            commands,
+            fill: fill.map(|fill| fill.to_tiny_skia()),
+            stroke: self.shaper.color().map(|color| PathStroke {
+                color: color.to_tiny_skia(),
+                width: self.shaper.width(),
+            }),
+            bounds: geometry::aabb_translate(self.bounds, offset),
+        }])
+    }
 }
 
 impl StrokeBehaviour for ShapeStroke {
@@ -258,6 +319,10 @@ impl StrokeBehaviour for ShapeStroke {
         self.shape.scale(scale);
         self.update_geometry();
     }
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: nalgebra::Point2<f64>) {
+        self.shape.flip(horizontal, vertical, center);
+        self.update_geometry();
+    }
 }
 
 impl ShapeStroke {
@@ -1,3 +1,5 @@
+use crate::compose::geometry;
+
 use serde::{Deserialize, Serialize};
 
 /// Specifies that a type behaves as a stroke
@@ -8,6 +10,8 @@ pub trait StrokeBehaviour {
     fn rotate(&mut self, angle: f64, center: na::Point2<f64>);
     /// scales the stroke by the desired scale
     fn scale(&mut self, scale: na::Vector2<f64>);
+    /// mirrors the stroke horizontally and / or vertically at the axes through the center
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>);
 }
 
 /// To be used as state in a stroke to help implement the StrokeBehaviour trait
@@ -45,6 +49,11 @@ impl StrokeTransform {
         self.transform = na::Translation2::from(offset) * self.transform;
     }
 
+    /// Translates in the local coordinate space, before the transform is applied
+    pub fn prepend_translation_mut(&mut self, offset: na::Vector2<f64>) {
+        self.transform = self.transform * na::Translation2::from(offset);
+    }
+
     pub fn append_rotation_wrt_point_mut(&mut self, angle: f64, center: na::Point2<f64>) {
         self.transform = na::Translation2::from(-center.coords) * self.transform;
         self.transform = na::Rotation2::new(angle) * self.transform;
@@ -64,6 +73,24 @@ impl StrokeTransform {
         self.transform = na::Translation2::from(translation) * self.transform;
     }
 
+    /// Mirrors at the axes through the center
+    pub fn append_flip_wrt_point_mut(
+        &mut self,
+        horizontal: bool,
+        vertical: bool,
+        center: na::Point2<f64>,
+    ) {
+        self.transform = na::Translation2::from(-center.coords) * self.transform;
+
+        self.transform = na::try_convert(
+            na::Scale2::<f64>::from(geometry::flip_scale(horizontal, vertical)).to_homogeneous()
+                * self.transform.to_homogeneous(),
+        )
+        .unwrap();
+
+        self.transform = na::Translation2::from(center.coords) * self.transform;
+    }
+
     pub fn transform_as_svg_transform_attr(&self) -> String {
         let matrix = self.transform;
 
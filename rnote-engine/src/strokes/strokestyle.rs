@@ -0,0 +1,536 @@
+use crate::compose::drawpath::DrawPath;
+use crate::compose::shapes;
+use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::PdfPageOrigin;
+use crate::pens::brush::BrushStyle;
+use crate::pens::shaper::DrawStyle;
+use crate::{render, utils};
+
+use chrono::{TimeZone, Utc};
+use p2d::bounding_volume::AABB;
+use rand::distributions::Uniform;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::bitmapimage::BitmapImage;
+use super::brushstroke::BrushStroke;
+use super::imageedits::{BitmapAdjustments, ImageEdits};
+use super::markerstroke::MarkerStroke;
+use super::shapestroke::ShapeStroke;
+use super::strokebehaviour::StrokeBehaviour;
+use super::vectorimage::VectorImage;
+
+/// A change of the style of strokes. Only the set fields are changed, and only on the strokes which have them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StyleEdit {
+    /// The stroke color. `Some(None)` removes the color, which only shapes support
+    pub color: Option<Option<utils::Color>>,
+    pub width: Option<f64>,
+    pub brush_style: Option<BrushStyle>,
+    /// The fill of shapes. `Some(None)` removes the fill
+    pub fill: Option<Option<utils::Color>>,
+    pub drawstyle: Option<DrawStyle>,
+}
+
+impl StyleEdit {
+    /// Returns true if both edits change the same fields
+    pub fn same_fields(&self, other: &Self) -> bool {
+        self.color.is_some() == other.color.is_some()
+            && self.width.is_some() == other.width.is_some()
+            && self.brush_style.is_some() == other.brush_style.is_some()
+            && self.fill.is_some() == other.fill.is_some()
+            && self.drawstyle.is_some() == other.drawstyle.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "strokestyle")]
+pub enum StrokeStyle {
+    #[serde(rename = "markerstroke")]
+    MarkerStroke(MarkerStroke),
+    #[serde(rename = "brushstroke")]
+    BrushStroke(BrushStroke),
+    #[serde(rename = "shapestroke")]
+    ShapeStroke(ShapeStroke),
+    #[serde(rename = "vectorimage")]
+    VectorImage(VectorImage),
+    #[serde(rename = "bitmapimage")]
+    BitmapImage(BitmapImage),
+}
+
+impl DrawBehaviour for StrokeStyle {
+    fn bounds(&self) -> AABB {
+        match self {
+            Self::MarkerStroke(markerstroke) => markerstroke.bounds(),
+            Self::BrushStroke(brushstroke) => brushstroke.bounds(),
+            Self::ShapeStroke(shapestroke) => shapestroke.bounds(),
+            Self::VectorImage(vectorimage) => vectorimage.bounds(),
+            Self::BitmapImage(bitmapimage) => bitmapimage.bounds(),
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: AABB) {
+        match self {
+            Self::MarkerStroke(markerstroke) => markerstroke.set_bounds(bounds),
+            Self::BrushStroke(brushstroke) => brushstroke.set_bounds(bounds),
+            Self::ShapeStroke(shapestroke) => shapestroke.set_bounds(bounds),
+            Self::VectorImage(vectorimage) => vectorimage.set_bounds(bounds),
+            Self::BitmapImage(bitmapimage) => bitmapimage.set_bounds(bounds),
+        }
+    }
+
+    fn gen_svgs(&self, offset: na::Vector2<f64>) -> Result<Vec<render::Svg>, anyhow::Error> {
+        match self {
+            Self::MarkerStroke(markerstroke) => markerstroke.gen_svgs(offset),
+            Self::BrushStroke(brushstroke) => brushstroke.gen_svgs(offset),
+            Self::ShapeStroke(shapestroke) => shapestroke.gen_svgs(offset),
+            Self::VectorImage(vectorimage) => vectorimage.gen_svgs(offset),
+            Self::BitmapImage(bitmapimage) => bitmapimage.gen_svgs(offset),
+        }
+    }
+
+    fn gen_paths(&self, offset: na::Vector2<f64>) -> Option<Vec<DrawPath>> {
+        match self {
+            Self::MarkerStroke(markerstroke) => markerstroke.gen_paths(offset),
+            Self::BrushStroke(brushstroke) => brushstroke.gen_paths(offset),
+            Self::ShapeStroke(shapestroke) => shapestroke.gen_paths(offset),
+            Self::VectorImage(vectorimage) => vectorimage.gen_paths(offset),
+            Self::BitmapImage(bitmapimage) => bitmapimage.gen_paths(offset),
+        }
+    }
+
+    fn gen_image(
+        &self,
+        zoom: f64,
+        renderer: &render::Renderer,
+    ) -> Result<render::Image, anyhow::Error> {
+        match self {
+            Self::MarkerStroke(markerstroke) => markerstroke.gen_image(zoom, renderer),
+            Self::BrushStroke(brushstroke) => brushstroke.gen_image(zoom, renderer),
+            Self::ShapeStroke(shapestroke) => shapestroke.gen_image(zoom, renderer),
+            Self::VectorImage(vectorimage) => vectorimage.gen_image(zoom, renderer),
+            Self::BitmapImage(bitmapimage) => bitmapimage.gen_image(zoom, renderer),
+        }
+    }
+}
+
+impl StrokeBehaviour for StrokeStyle {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                markerstroke.translate(offset);
+            }
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.translate(offset);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.translate(offset);
+            }
+            Self::VectorImage(vectorimage) => {
+                vectorimage.translate(offset);
+            }
+            Self::BitmapImage(bitmapimage) => {
+                bitmapimage.translate(offset);
+            }
+        }
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                markerstroke.rotate(angle, center);
+            }
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.rotate(angle, center);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.rotate(angle, center);
+            }
+            Self::VectorImage(vectorimage) => {
+                vectorimage.rotate(angle, center);
+            }
+            Self::BitmapImage(bitmapimage) => {
+                bitmapimage.rotate(angle, center);
+            }
+        }
+    }
+
+    fn scale(&mut self, scale: nalgebra::Vector2<f64>) {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                markerstroke.scale(scale);
+            }
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.scale(scale);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.scale(scale);
+            }
+            Self::VectorImage(vectorimage) => {
+                vectorimage.scale(scale);
+            }
+            Self::BitmapImage(bitmapimage) => {
+                bitmapimage.scale(scale);
+            }
+        }
+    }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>) {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                markerstroke.flip(horizontal, vertical, center);
+            }
+            Self::BrushStroke(brushstroke) => {
+                brushstroke.flip(horizontal, vertical, center);
+            }
+            Self::ShapeStroke(shapestroke) => {
+                shapestroke.flip(horizontal, vertical, center);
+            }
+            Self::VectorImage(vectorimage) => {
+                vectorimage.flip(horizontal, vertical, center);
+            }
+            Self::BitmapImage(bitmapimage) => {
+                bitmapimage.flip(horizontal, vertical, center);
+            }
+        }
+    }
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::MarkerStroke(MarkerStroke::default())
+    }
+}
+
+impl StrokeStyle {
+    /// The pdf page the stroke was imported from, together with the rectangle it is drawn into
+    pub fn pdf_page(&self) -> Option<(PdfPageOrigin, &shapes::Rectangle)> {
+        match self {
+            Self::VectorImage(vectorimage) => vectorimage
+                .pdf_origin
+                .map(|pdf_origin| (pdf_origin, &vectorimage.rectangle)),
+            Self::BitmapImage(bitmapimage) => bitmapimage
+                .pdf_origin
+                .map(|pdf_origin| (pdf_origin, &bitmapimage.rectangle)),
+            _ => None,
+        }
+    }
+
+    /// Wether the stroke is a pdf page which is rendered lazily from its document
+    pub fn is_lazy_pdf_page(&self) -> bool {
+        match self {
+            Self::BitmapImage(bitmapimage) => bitmapimage.is_lazy_pdf_page(),
+            _ => false,
+        }
+    }
+
+    /// The number of elements, for the strokes which are made of elements
+    pub fn n_elements(&self) -> Option<usize> {
+        match self {
+            Self::MarkerStroke(markerstroke) => Some(markerstroke.elements.len()),
+            Self::BrushStroke(brushstroke) => Some(brushstroke.elements.len()),
+            _ => None,
+        }
+    }
+
+    /// The paths for drawing the elements starting at the index while the stroke is drawn. None if the stroke is not drawn live
+    pub fn gen_live_paths(&self, from: usize) -> Option<Vec<DrawPath>> {
+        match self {
+            Self::MarkerStroke(markerstroke) => Some(markerstroke.gen_live_paths(from)),
+            Self::BrushStroke(brushstroke) => Some(brushstroke.gen_live_paths(from)),
+            _ => None,
+        }
+    }
+
+    /// The edits of the stroke, if it is a image
+    pub fn image_edits(&self) -> Option<ImageEdits> {
+        match self {
+            Self::VectorImage(vectorimage) => Some(vectorimage.edits),
+            Self::BitmapImage(bitmapimage) => Some(bitmapimage.edits),
+            _ => None,
+        }
+    }
+
+    /// The crop rectangle in the coordinate space of the intrinsic size, if the stroke is a image
+    pub fn image_crop_bounds(&self) -> Option<AABB> {
+        match self {
+            Self::VectorImage(vectorimage) => {
+                Some(vectorimage.edits.crop_bounds(vectorimage.intrinsic_size))
+            }
+            Self::BitmapImage(bitmapimage) => {
+                Some(bitmapimage.edits.crop_bounds(bitmapimage.intrinsic_size))
+            }
+            _ => None,
+        }
+    }
+
+    /// The edits of the stroke together with the intrinsic size and the rectangle of the image, if it is a image
+    fn image_edits_mut(
+        &mut self,
+    ) -> Option<(&mut ImageEdits, na::Vector2<f64>, &mut shapes::Rectangle)> {
+        match self {
+            Self::VectorImage(vectorimage) => Some((
+                &mut vectorimage.edits,
+                vectorimage.intrinsic_size,
+                &mut vectorimage.rectangle,
+            )),
+            Self::BitmapImage(bitmapimage) => Some((
+                &mut bitmapimage.edits,
+                bitmapimage.intrinsic_size,
+                &mut bitmapimage.rectangle,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Crops the image to the crop rectangle in the coordinate space of its intrinsic size. Does nothing if the stroke is not a image
+    pub fn crop_image(&mut self, crop: AABB) {
+        if let Some((edits, intrinsic_size, rectangle)) = self.image_edits_mut() {
+            edits.set_crop(crop, intrinsic_size, rectangle);
+        }
+        self.update_image_geometry();
+    }
+
+    /// Flips the image in place. Does nothing if the stroke is not a image
+    pub fn flip_image(&mut self, horizontal: bool, vertical: bool) {
+        if let Some((edits, _, _)) = self.image_edits_mut() {
+            edits.flip_horizontal ^= horizontal;
+            edits.flip_vertical ^= vertical;
+        }
+    }
+
+    /// Removes the crop and the flips from the image. Does nothing if the stroke is not a image
+    pub fn reset_image_edits(&mut self) {
+        if let Some((edits, intrinsic_size, rectangle)) = self.image_edits_mut() {
+            edits.set_crop(
+                AABB::new(na::point![0.0, 0.0], na::Point2::from(intrinsic_size)),
+                intrinsic_size,
+                rectangle,
+            );
+            edits.flip_horizontal = false;
+            edits.flip_vertical = false;
+        }
+        self.update_image_geometry();
+    }
+
+    /// Converts a offset in the coordinate space of the sheet to a offset in the coordinate space of the intrinsic size of the image
+    pub fn image_offset_to_intrinsic(&self, offset: na::Vector2<f64>) -> Option<na::Vector2<f64>> {
+        match self {
+            Self::VectorImage(vectorimage) => Some(vectorimage.edits.sheet_offset_to_intrinsic(
+                offset,
+                vectorimage.intrinsic_size,
+                &vectorimage.rectangle,
+            )),
+            Self::BitmapImage(bitmapimage) => Some(bitmapimage.edits.sheet_offset_to_intrinsic(
+                offset,
+                bitmapimage.intrinsic_size,
+                &bitmapimage.rectangle,
+            )),
+            _ => None,
+        }
+    }
+
+    /// The color adjustments of the stroke, if it is a bitmap image
+    pub fn bitmap_adjustments(&self) -> Option<BitmapAdjustments> {
+        match self {
+            Self::BitmapImage(bitmapimage) => Some(bitmapimage.adjustments),
+            _ => None,
+        }
+    }
+
+    /// Sets the color adjustments. Does nothing if the stroke is not a bitmap image
+    pub fn set_bitmap_adjustments(&mut self, adjustments: BitmapAdjustments) {
+        if let Self::BitmapImage(bitmapimage) = self {
+            bitmapimage.adjustments = adjustments;
+        }
+    }
+
+    /// Applies the style edit and updates the geometry. Returns false if the stroke has no style which could be edited
+    pub fn apply_style_edit(&mut self, edit: &StyleEdit) -> bool {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                if let Some(Some(color)) = edit.color {
+                    markerstroke.marker.color = color;
+                }
+                if let Some(width) = edit.width {
+                    markerstroke.marker.set_width(width);
+                }
+                markerstroke.update_geometry();
+                true
+            }
+            Self::BrushStroke(brushstroke) => {
+                if let Some(Some(color)) = edit.color {
+                    brushstroke.brush.set_color(color);
+                }
+                if let Some(width) = edit.width {
+                    brushstroke.brush.set_width(width);
+                }
+                if let Some(brush_style) = edit.brush_style {
+                    brushstroke.brush.set_style(brush_style);
+                }
+                brushstroke.update_geometry();
+                true
+            }
+            Self::ShapeStroke(shapestroke) => {
+                if let Some(color) = edit.color {
+                    shapestroke.shaper.set_color(color);
+                }
+                if let Some(width) = edit.width {
+                    shapestroke.shaper.set_width(width);
+                }
+                if let Some(fill) = edit.fill {
+                    shapestroke.shaper.set_fill(fill);
+                }
+                if let Some(drawstyle) = edit.drawstyle {
+                    shapestroke.shaper.set_drawstyle(drawstyle);
+                }
+                shapestroke.update_geometry();
+                true
+            }
+            Self::VectorImage(_) | Self::BitmapImage(_) => false,
+        }
+    }
+
+    /// The current values of the style fields which the edit changes, as a edit which restores them. Fields the stroke does not have are not set
+    pub fn style_of_edit(&self, edit: &StyleEdit) -> StyleEdit {
+        match self {
+            Self::MarkerStroke(markerstroke) => StyleEdit {
+                color: edit.color.map(|_| Some(markerstroke.marker.color)),
+                width: edit.width.map(|_| markerstroke.marker.width()),
+                ..StyleEdit::default()
+            },
+            Self::BrushStroke(brushstroke) => StyleEdit {
+                color: edit.color.map(|_| Some(brushstroke.brush.color())),
+                width: edit.width.map(|_| brushstroke.brush.width()),
+                brush_style: edit.brush_style.map(|_| brushstroke.brush.style()),
+                ..StyleEdit::default()
+            },
+            Self::ShapeStroke(shapestroke) => StyleEdit {
+                color: edit.color.map(|_| shapestroke.shaper.color()),
+                width: edit.width.map(|_| shapestroke.shaper.width()),
+                fill: edit.fill.map(|_| shapestroke.shaper.fill()),
+                drawstyle: edit.drawstyle.map(|_| shapestroke.shaper.drawstyle()),
+                ..StyleEdit::default()
+            },
+            Self::VectorImage(_) | Self::BitmapImage(_) => StyleEdit::default(),
+        }
+    }
+
+    /// Scales the stroke width by the factor and updates the geometry. Does nothing if the stroke has no stroke width
+    pub fn scale_width(&mut self, factor: f64) {
+        match self {
+            Self::MarkerStroke(markerstroke) => {
+                let width = markerstroke.marker.width();
+                markerstroke.marker.set_width(width * factor);
+                markerstroke.update_geometry();
+            }
+            Self::BrushStroke(brushstroke) => {
+                let width = brushstroke.brush.width();
+                brushstroke.brush.set_width(width * factor);
+                brushstroke.update_geometry();
+            }
+            Self::ShapeStroke(shapestroke) => {
+                let width = shapestroke.shaper.width();
+                shapestroke.shaper.set_width(width * factor);
+                shapestroke.update_geometry();
+            }
+            Self::VectorImage(_) | Self::BitmapImage(_) => {}
+        }
+    }
+
+    fn update_image_geometry(&mut self) {
+        match self {
+            Self::VectorImage(vectorimage) => vectorimage.update_geometry(),
+            Self::BitmapImage(bitmapimage) => bitmapimage.update_geometry(),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputData {
+    pos: na::Vector2<f64>,
+    pressure: f64,
+}
+
+impl Default for InputData {
+    fn default() -> Self {
+        Self {
+            pos: na::vector![0.0, 0.0],
+            pressure: Self::PRESSURE_DEFAULT,
+        }
+    }
+}
+
+impl InputData {
+    pub const PRESSURE_DEFAULT: f64 = 0.5;
+
+    pub fn new(pos: na::Vector2<f64>, pressure: f64) -> Self {
+        let mut inputdata = Self::default();
+        inputdata.set_pos(pos);
+        inputdata.set_pressure(pressure);
+
+        inputdata
+    }
+
+    pub fn pos(&self) -> na::Vector2<f64> {
+        self.pos
+    }
+
+    pub fn set_pos(&mut self, pos: na::Vector2<f64>) {
+        self.pos = pos;
+    }
+
+    pub fn pressure(&self) -> f64 {
+        self.pressure
+    }
+
+    pub fn set_pressure(&mut self, pressure: f64) {
+        self.pressure = pressure.clamp(0.0, 1.0);
+    }
+}
+
+// Represents a single Stroke Element
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename = "element")]
+pub struct Element {
+    #[serde(rename = "inputdata")]
+    pub inputdata: InputData,
+    #[serde(rename = "timpestamp", default = "default_datetime")]
+    pub timestamp: chrono::DateTime<Utc>,
+}
+
+pub fn default_datetime() -> chrono::DateTime<Utc> {
+    Utc.ymd(2000, 1, 1).and_hms(12, 0, 0)
+}
+
+impl Element {
+    pub fn new(inputdata: InputData) -> Self {
+        let timestamp = Utc::now();
+
+        Self {
+            inputdata,
+            timestamp,
+        }
+    }
+
+    pub fn validation_data(bounds: AABB) -> Vec<Self> {
+        let mut rng = rand::thread_rng();
+        let data_entries_uniform = Uniform::from(0..=20);
+        let x_uniform = Uniform::from(bounds.mins[0]..=bounds.maxs[0]);
+        let y_uniform = Uniform::from(bounds.mins[1]..=bounds.maxs[1]);
+        let pressure_uniform = Uniform::from(0_f64..=1_f64);
+
+        let mut data_entries: Vec<Self> = Vec::new();
+
+        for _i in 0..=data_entries_uniform.sample(&mut rng) {
+            data_entries.push(Self::new(InputData::new(
+                na::vector![x_uniform.sample(&mut rng), y_uniform.sample(&mut rng)],
+                pressure_uniform.sample(&mut rng),
+            )));
+        }
+
+        data_entries
+    }
+}
@@ -1,19 +1,21 @@
 use crate::compose::{geometry, shapes};
 use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::{PdfImportPrefs, PdfPageOrigin};
 use crate::render::Renderer;
 use crate::{compose, render};
 
 use p2d::bounding_volume::AABB;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use svg::node::{self, element};
+use svg::node;
 
+use super::imageedits::ImageEdits;
 use super::strokebehaviour::{self, StrokeBehaviour};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "vectorimage")]
 pub struct VectorImage {
-    #[serde(rename = "svg_data")]
+    #[serde(rename = "svg_data", with = "crate::sheet::attachments::svg_text")]
     pub svg_data: String,
     #[serde(rename = "intrinsic_size")]
     pub intrinsic_size: na::Vector2<f64>,
@@ -21,6 +23,12 @@ pub struct VectorImage {
     pub rectangle: shapes::Rectangle,
     #[serde(rename = "bounds")]
     pub bounds: AABB,
+    /// The pdf page the image was imported from
+    #[serde(rename = "pdf_origin")]
+    pub pdf_origin: Option<PdfPageOrigin>,
+    /// Crop and flip
+    #[serde(rename = "edits")]
+    pub edits: ImageEdits,
 }
 
 impl Default for VectorImage {
@@ -30,6 +38,8 @@ impl Default for VectorImage {
             intrinsic_size: na::Vector2::zeros(),
             rectangle: shapes::Rectangle::default(),
             bounds: geometry::aabb_new_zero(),
+            pdf_origin: None,
+            edits: ImageEdits::default(),
         }
     }
 }
@@ -53,26 +63,11 @@ impl DrawBehaviour for VectorImage {
             .transform
             .append_translation_mut(offset);
 
-        let transform_string = rectangle.transform.transform_as_svg_transform_attr();
-
-        let svg_root = element::SVG::new()
-            .set("x", -self.rectangle.cuboid.half_extents[0])
-            .set("y", -self.rectangle.cuboid.half_extents[1])
-            .set("width", 2.0 * self.rectangle.cuboid.half_extents[0])
-            .set("height", 2.0 * self.rectangle.cuboid.half_extents[1])
-            .set(
-                "viewBox",
-                format!(
-                    "{:.3} {:.3} {:.3} {:.3}",
-                    0.0, 0.0, self.intrinsic_size[0], self.intrinsic_size[1]
-                ),
-            )
-            .set("preserveAspectRatio", "none")
-            .add(node::Text::new(self.svg_data.clone()));
-
-        let group = element::Group::new()
-            .set("transform", transform_string)
-            .add(svg_root);
+        let group = self.edits.wrap_svg_content(
+            node::Text::new(self.svg_data.clone()),
+            self.intrinsic_size,
+            &rectangle,
+        );
 
         let svg_data = compose::node_to_string(&group)?;
         let svg = render::Svg {
@@ -99,6 +94,11 @@ impl StrokeBehaviour for VectorImage {
         self.rectangle.scale(scale);
         self.update_geometry();
     }
+
+    fn flip(&mut self, horizontal: bool, vertical: bool, center: na::Point2<f64>) {
+        self.rectangle.flip(horizontal, vertical, center);
+        self.update_geometry();
+    }
 }
 
 impl VectorImage {
@@ -150,6 +150,8 @@ impl VectorImage {
             intrinsic_size,
             rectangle,
             bounds: geometry::aabb_new_zero(),
+            pdf_origin: None,
+            edits: ImageEdits::default(),
         };
         vector_image.update_geometry();
 
@@ -158,28 +160,21 @@ impl VectorImage {
 
     pub fn import_from_pdf_bytes(
         to_be_read: &[u8],
-        pos: na::Vector2<f64>,
-        page_width: Option<i32>,
+        prefs: &PdfImportPrefs,
+        document: Option<u32>,
         renderer: &Renderer,
     ) -> Result<Vec<Self>, anyhow::Error> {
         let doc = poppler::Document::from_data(to_be_read, None)?;
+        let n_pages = u32::try_from(doc.n_pages()).unwrap_or(0);
 
         let mut images = Vec::new();
 
-        for i in 0..doc.n_pages() {
-            if let Some(page) = doc.page(i) {
+        for (i, page_i) in prefs.pages(n_pages).enumerate() {
+            if let Some(page) = doc.page(page_i as i32) {
                 let intrinsic_size = page.size();
-
-                let (width, height, _zoom) = if let Some(page_width) = page_width {
-                    let zoom = f64::from(page_width) / intrinsic_size.0;
-
-                    (f64::from(page_width), (intrinsic_size.1 * zoom), zoom)
-                } else {
-                    (intrinsic_size.0, intrinsic_size.1, 1.0)
-                };
-
-                let x = pos[0];
-                let y = pos[1] + f64::from(i) * (height + Self::OFFSET_Y_DEFAULT / 2.0);
+                let bounds = prefs
+                    .layout
+                    .page_bounds(i as u32, na::vector![intrinsic_size.0, intrinsic_size.1]);
 
                 let svg_stream: Vec<u8> = vec![];
 
@@ -236,12 +231,18 @@ impl VectorImage {
                 };
                 let svg_data = String::from_utf8(svg_data)?;
 
-                images.push(Self::import_from_svg_data(
+                let mut image = Self::import_from_svg_data(
                     svg_data.as_str(),
-                    na::vector![x, y],
-                    Some(na::vector![width, height]),
+                    bounds.mins.coords,
+                    Some(bounds.extents()),
                     renderer,
-                )?);
+                )?;
+                image.pdf_origin = document.map(|document| PdfPageOrigin {
+                    document,
+                    page: page_i,
+                });
+
+                images.push(image);
             }
         }
 
@@ -1,3 +1,4 @@
+use p2d::bounding_volume::AABB;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use rayon::slice::ParallelSliceMut;
 use serde::{Deserialize, Serialize};
@@ -37,6 +38,73 @@ impl StrokesState {
         }
     }
 
+    /// Brings the selected strokes to the front, keeping their order among each other.
+    /// Returns the keys of the reordered strokes together with their previous chronology values, to undo the reordering
+    pub fn bring_selection_to_front(&mut self) -> Vec<(StrokeKey, u32)> {
+        let mut previous = self
+            .selection_keys()
+            .into_iter()
+            .filter_map(|key| Some((key, self.chrono_t(key)?)))
+            .collect::<Vec<(StrokeKey, u32)>>();
+        previous.sort_unstable_by_key(|&(_, t)| t);
+
+        for &(key, _) in previous.iter() {
+            self.set_chrono_to_last(key);
+        }
+        previous
+    }
+
+    /// Sends the selected strokes to the back, keeping their order among each other.
+    /// The chronology values of all strokes are reassigned in the new order, so no new values are taken from the counter.
+    /// Returns the keys of the reordered strokes together with their previous chronology values, to undo the reordering
+    pub fn send_selection_to_back(&mut self) -> Vec<(StrokeKey, u32)> {
+        let sorted = self.keys_sorted_chrono();
+        let ts = sorted
+            .iter()
+            .filter_map(|&key| self.chrono_t(key))
+            .collect::<Vec<u32>>();
+        let (selected, unselected): (Vec<StrokeKey>, Vec<StrokeKey>) = sorted
+            .into_iter()
+            .partition(|&key| self.selected(key).unwrap_or(false));
+
+        let mut previous = vec![];
+        for (key, t) in selected.into_iter().chain(unselected).zip(ts) {
+            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                if chrono_comp.t != t {
+                    previous.push((key, chrono_comp.t));
+                    chrono_comp.t = t;
+                }
+            }
+        }
+        previous
+    }
+
+    /// Sets the chronology values of the strokes and replaces them with their current values, used to undo and redo reorderings
+    pub fn swap_chrono(&mut self, chronos: &mut [(StrokeKey, u32)]) {
+        for (key, t) in chronos.iter_mut() {
+            if let Some(chrono_comp) = self.chrono_components.get_mut(*key) {
+                std::mem::swap(&mut chrono_comp.t, t);
+            }
+        }
+    }
+
+    pub fn chrono_counter(&self) -> u32 {
+        self.chrono_counter
+    }
+
+    /// Advances the chrono counter and returns its new value.
+    /// Used to place operations other than strokes in the chronology, e.g. page operations
+    pub fn next_chrono_counter(&mut self) -> u32 {
+        self.chrono_counter += 1;
+        self.chrono_counter
+    }
+
+    pub fn chrono_t(&self, key: StrokeKey) -> Option<u32> {
+        self.chrono_components
+            .get(key)
+            .map(|chrono_comp| chrono_comp.t)
+    }
+
     pub fn last_stroke_key(&self) -> Option<StrokeKey> {
         let chrono_components = &self.chrono_components;
         let trash_components = &self.trash_components;
@@ -122,4 +190,18 @@ impl StrokesState {
         let (keys, _ts): (Vec<StrokeKey>, Vec<u32>) = sorted.into_iter().unzip();
         keys
     }
+
+    /// The keys of the strokes whose bounds intersect the given bounds, sorted by their chronology.
+    /// Looks them up in the spatial index, so only the strokes near the bounds are sorted
+    pub fn keys_sorted_chrono_intersecting_bounds(&self, bounds: AABB) -> Vec<StrokeKey> {
+        let mut sorted = self
+            .keys_intersecting_bounds(bounds)
+            .into_iter()
+            .filter_map(|key| Some((key, self.chrono_components.get(key)?.t)))
+            .collect::<Vec<(StrokeKey, u32)>>();
+        sorted.par_sort_unstable_by(|first, second| first.1.cmp(&second.1));
+
+        let (keys, _ts): (Vec<StrokeKey>, Vec<u32>) = sorted.into_iter().unzip();
+        keys
+    }
 }
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{StrokeKey, StrokesState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "group_component")]
+pub struct GroupComponent {
+    /// The ids of the groups the stroke is a member of, from the outermost to the innermost group. Empty if the stroke is not grouped
+    #[serde(rename = "groups")]
+    pub groups: Vec<u32>,
+}
+
+impl Default for GroupComponent {
+    fn default() -> Self {
+        Self { groups: vec![] }
+    }
+}
+
+impl GroupComponent {
+    pub fn new(groups: Vec<u32>) -> Self {
+        Self { groups }
+    }
+
+    /// The outermost group of the stroke, which is selected as a whole
+    pub fn outermost(&self) -> Option<u32> {
+        self.groups.first().copied()
+    }
+}
+
+/// Systems that are related to the groups of strokes.
+impl StrokesState {
+    /// The outermost group of the stroke, if it is grouped
+    pub fn outermost_group(&self, key: StrokeKey) -> Option<u32> {
+        self.group_components.get(key)?.outermost()
+    }
+
+    /// Returns all keys which are a member of the group, the members of nested groups included
+    pub fn group_keys(&self, group: u32) -> Vec<StrokeKey> {
+        self.keys_sorted_chrono()
+            .into_iter()
+            .filter(|&key| {
+                self.group_components
+                    .get(key)
+                    .map(|group_comp| group_comp.groups.contains(&group))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// A group id which is not used by any stroke
+    fn next_group_id(&self) -> u32 {
+        self.group_components
+            .values()
+            .flat_map(|group_comp| group_comp.groups.iter().copied())
+            .max()
+            .map(|id| id + 1)
+            .unwrap_or(0)
+    }
+
+    /// Returns true if the selection can be grouped, which is when it consists of more than a single group
+    pub fn can_group_selection(&self) -> bool {
+        let selection_keys = self.selection_keys();
+        let first_group = match selection_keys.first() {
+            Some(&key) => self.outermost_group(key),
+            None => return false,
+        };
+
+        selection_keys.len() > 1
+            && (first_group.is_none()
+                || selection_keys
+                    .iter()
+                    .any(|&key| self.outermost_group(key) != first_group))
+    }
+
+    /// Returns true if the selection contains any grouped stroke
+    pub fn can_ungroup_selection(&self) -> bool {
+        self.selection_keys()
+            .iter()
+            .any(|&key| self.outermost_group(key).is_some())
+    }
+
+    /// Groups the selected strokes into a new group. Existing groups in the selection become nested groups of the new group.
+    /// Returns the previous groups of the changed strokes
+    pub fn group_selection(&mut self) -> Vec<(StrokeKey, Vec<u32>)> {
+        if !self.can_group_selection() {
+            return vec![];
+        }
+        let group = self.next_group_id();
+
+        self.selection_keys()
+            .into_iter()
+            .filter_map(|key| {
+                let group_comp = self.group_components.entry(key)?.or_default();
+                let previous = group_comp.groups.clone();
+                group_comp.groups.insert(0, group);
+
+                Some((key, previous))
+            })
+            .collect()
+    }
+
+    /// Dissolves the outermost groups of the selected strokes. Nested groups become the outermost groups.
+    /// Returns the previous groups of the changed strokes
+    pub fn ungroup_selection(&mut self) -> Vec<(StrokeKey, Vec<u32>)> {
+        self.selection_keys()
+            .into_iter()
+            .filter_map(|key| {
+                let group_comp = self.group_components.get_mut(key)?;
+                if group_comp.groups.is_empty() {
+                    return None;
+                }
+                let previous = group_comp.groups.clone();
+                group_comp.groups.remove(0);
+
+                Some((key, previous))
+            })
+            .collect()
+    }
+
+    /// Swaps the groups of the strokes with the given groups. Used to undo and redo group changes
+    pub fn swap_groups(&mut self, groups: &mut [(StrokeKey, Vec<u32>)]) {
+        for (key, groups) in groups.iter_mut() {
+            if let Some(entry) = self.group_components.entry(*key) {
+                std::mem::swap(&mut entry.or_default().groups, groups);
+            }
+        }
+    }
+
+    /// The selected strokes, split into the units which are arranged as a whole: the outermost groups, and the strokes which are not grouped
+    pub fn selection_units(&self) -> Vec<Vec<StrokeKey>> {
+        let mut units = Vec::<Vec<StrokeKey>>::new();
+        let mut group_units = HashMap::<u32, usize>::new();
+
+        for key in self.selection_keys() {
+            match self.outermost_group(key) {
+                Some(group) => match group_units.get(&group) {
+                    Some(&i) => units[i].push(key),
+                    None => {
+                        group_units.insert(group, units.len());
+                        units.push(vec![key]);
+                    }
+                },
+                None => units.push(vec![key]),
+            }
+        }
+
+        units
+    }
+
+    /// Extends the selection so that it contains the groups of the selected strokes as a whole
+    pub fn select_groups_of_selection(&mut self) {
+        let mut groups = self
+            .selection_keys()
+            .into_iter()
+            .filter_map(|key| self.outermost_group(key))
+            .collect::<Vec<u32>>();
+        groups.sort_unstable();
+        groups.dedup();
+
+        for group in groups {
+            for key in self.group_keys(group) {
+                if !self.trashed(key).unwrap_or(true) && !self.selected(key).unwrap_or(true) {
+                    self.set_selected(key, true);
+                }
+            }
+        }
+    }
+
+    /// Copies the groups of the strokes to the new strokes, replacing the group ids with new ones so that the new strokes form groups of their own.
+    /// `keys` are pairs of the original and the new key
+    pub fn copy_groups_to_new_strokes(&mut self, keys: &[(StrokeKey, StrokeKey)]) {
+        let groups = keys
+            .iter()
+            .filter_map(|&(key, new_key)| {
+                Some((new_key, self.group_components.get(key)?.groups.clone()))
+            })
+            .collect::<Vec<(StrokeKey, Vec<u32>)>>();
+
+        self.insert_groups_renumbered(groups);
+    }
+
+    /// Sets the groups of the strokes, replacing the group ids with ones which are not used yet.
+    /// Strokes which share a group id in `groups` keep sharing it
+    pub fn insert_groups_renumbered(&mut self, groups: Vec<(StrokeKey, Vec<u32>)>) {
+        let mut next_group = self.next_group_id();
+        let mut new_groups = HashMap::<u32, u32>::new();
+
+        for (key, groups) in groups {
+            let groups = groups
+                .into_iter()
+                .map(|group| {
+                    *new_groups.entry(group).or_insert_with(|| {
+                        next_group += 1;
+                        next_group - 1
+                    })
+                })
+                .collect::<Vec<u32>>();
+
+            self.group_components
+                .insert(key, GroupComponent::new(groups));
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! A spatial index over the bounds of the strokes, so that hit-testing and rendering only have to look at the strokes near the queried area.
+//! This module only depends on external crates, so that it can be included in the benchmarks.
+
+use p2d::bounding_volume::AABB;
+use slotmap::SecondaryMap;
+
+/// The object stored in the R-tree, the bounds of a stroke together with its key
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct KeyTreeObject<K> {
+    key: K,
+    mins: [f64; 2],
+    maxs: [f64; 2],
+}
+
+impl<K> KeyTreeObject<K> {
+    fn new(key: K, bounds: AABB) -> Self {
+        Self {
+            key,
+            mins: [bounds.mins[0], bounds.mins[1]],
+            maxs: [bounds.maxs[0], bounds.maxs[1]],
+        }
+    }
+}
+
+impl<K> rstar::RTreeObject for KeyTreeObject<K> {
+    type Envelope = rstar::AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        rstar::AABB::from_corners(self.mins, self.maxs)
+    }
+}
+
+/// Converts the bounds to the envelope of the R-tree
+fn aabb_to_envelope(bounds: AABB) -> rstar::AABB<[f64; 2]> {
+    rstar::AABB::from_corners(
+        [bounds.mins[0], bounds.mins[1]],
+        [bounds.maxs[0], bounds.maxs[1]],
+    )
+}
+
+/// The spatial index. Holds the bounds of every key, which have to be updated whenever the geometry of the stroke changes
+pub struct KeyTree<K: slotmap::Key> {
+    tree: rstar::RTree<KeyTreeObject<K>>,
+    bounds: SecondaryMap<K, AABB>,
+}
+
+impl<K: slotmap::Key> Default for KeyTree<K> {
+    fn default() -> Self {
+        Self {
+            tree: rstar::RTree::new(),
+            bounds: SecondaryMap::new(),
+        }
+    }
+}
+
+impl<K: slotmap::Key> std::fmt::Debug for KeyTree<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyTree")
+            .field("len", &self.bounds.len())
+            .finish()
+    }
+}
+
+impl<K: slotmap::Key> KeyTree<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Inserts the bounds for the key. Replaces the bounds if the key is already in the tree
+    pub fn insert(&mut self, key: K, bounds: AABB) {
+        if let Some(old_bounds) = self.bounds.insert(key, bounds) {
+            if old_bounds == bounds {
+                return;
+            }
+            self.tree.remove(&KeyTreeObject::new(key, old_bounds));
+        }
+
+        self.tree.insert(KeyTreeObject::new(key, bounds));
+    }
+
+    pub fn remove(&mut self, key: K) {
+        if let Some(old_bounds) = self.bounds.remove(key) {
+            self.tree.remove(&KeyTreeObject::new(key, old_bounds));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = rstar::RTree::new();
+        self.bounds.clear();
+    }
+
+    /// Clears the tree and bulk loads it with the bounds of the keys, which is much faster than inserting them one by one
+    pub fn rebuild_from(&mut self, keys_bounds: impl IntoIterator<Item = (K, AABB)>) {
+        self.bounds.clear();
+
+        let objects = keys_bounds
+            .into_iter()
+            .map(|(key, bounds)| {
+                self.bounds.insert(key, bounds);
+                KeyTreeObject::new(key, bounds)
+            })
+            .collect::<Vec<KeyTreeObject<K>>>();
+
+        self.tree = rstar::RTree::bulk_load(objects);
+    }
+
+    /// The stored bounds of the key
+    pub fn bounds(&self, key: K) -> Option<AABB> {
+        self.bounds.get(key).copied()
+    }
+
+    /// Returns the keys whose bounds intersect the given bounds, in no particular order
+    pub fn keys_intersecting_bounds(&self, bounds: AABB) -> Vec<K> {
+        self.tree
+            .locate_in_envelope_intersecting(&aabb_to_envelope(bounds))
+            .map(|object| object.key)
+            .collect::<Vec<K>>()
+    }
+}
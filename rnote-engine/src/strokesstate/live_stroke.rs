@@ -0,0 +1,187 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use crate::render::NodeSnapshot;
+
+use super::{StrokeKey, StrokesState};
+use crate::compose::drawpath::DrawPath;
+use crate::render;
+
+/// The latency between an input arriving and the live stroke being drawn with it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiveLatency {
+    pub last: Duration,
+    pub max: Duration,
+    /// Exponential moving average over the samples
+    pub average: Duration,
+    pub n_samples: u64,
+}
+
+impl LiveLatency {
+    /// The weight of a new sample in the moving average
+    const AVERAGE_WEIGHT: f64 = 0.1;
+
+    fn add_sample(&mut self, sample: Duration) {
+        self.last = sample;
+        self.max = self.max.max(sample);
+        self.average = if self.n_samples == 0 {
+            sample
+        } else {
+            self.average.mul_f64(1.0 - Self::AVERAGE_WEIGHT) + sample.mul_f64(Self::AVERAGE_WEIGHT)
+        };
+        self.n_samples += 1;
+    }
+}
+
+/// The stroke which is currently drawn with a pen. It is drawn directly from its geometry instead of its rendering,
+/// until the pen ends it and its full quality rendering is swapped in
+#[derive(Debug)]
+pub struct LiveStroke {
+    key: StrokeKey,
+    /// The number of elements of the stroke which are already turned into paths
+    n_elements: usize,
+    paths: Vec<DrawPath>,
+    /// The paths are recorded into rendernodes in chunks, so that a long stroke is not recorded again for every frame
+    chunk_nodes: Vec<gsk::RenderNode>,
+    /// The zoom the chunk nodes were recorded at
+    chunks_zoom: f64,
+    /// When the oldest input which is not yet drawn arrived
+    input_pending_since: Cell<Option<Instant>>,
+    /// Set when the pen ended the stroke, it is drawn until the rendering is generated
+    ended: bool,
+}
+
+impl LiveStroke {
+    /// The number of paths which are recorded into one rendernode
+    pub const CHUNK_SIZE: usize = 32;
+
+    fn new(key: StrokeKey, zoom: f64) -> Self {
+        Self {
+            key,
+            n_elements: 0,
+            paths: vec![],
+            chunk_nodes: vec![],
+            chunks_zoom: zoom,
+            input_pending_since: Cell::new(None),
+            ended: false,
+        }
+    }
+
+    /// Records the completed chunks of paths into rendernodes. All chunks are recorded again when the zoom has changed
+    fn record_chunks(&mut self, zoom: f64) {
+        if (self.chunks_zoom - zoom).abs() > f64::EPSILON {
+            self.chunk_nodes.clear();
+            self.chunks_zoom = zoom;
+        }
+
+        while (self.chunk_nodes.len() + 1) * Self::CHUNK_SIZE <= self.paths.len() {
+            let start = self.chunk_nodes.len() * Self::CHUNK_SIZE;
+
+            match render::paths_to_rendernode(
+                &self.paths[start..start + Self::CHUNK_SIZE],
+                self.chunks_zoom,
+            ) {
+                Ok(rendernode) => self.chunk_nodes.push(rendernode),
+                Err(e) => {
+                    log::error!(
+                        "paths_to_rendernode() failed in record_chunks() with Err {}",
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl StrokesState {
+    /// Starts drawing the stroke live. Its rendering is not generated for every new element anymore, until the stroke is ended
+    pub fn begin_live_stroke(&mut self, key: StrokeKey) {
+        self.live_stroke = Some(LiveStroke::new(key, self.zoom));
+        self.live_latency.set(LiveLatency::default());
+
+        self.update_live_stroke(key);
+    }
+
+    /// Ends drawing the stroke live. It is still drawn live until its rendering is regenerated
+    pub fn end_live_stroke(&mut self, key: StrokeKey) {
+        if let Some(live_stroke) = self.live_stroke.as_mut() {
+            if live_stroke.key == key {
+                live_stroke.ended = true;
+            }
+        }
+    }
+
+    /// Swaps in the rendering of the stroke, if it is the ended live stroke
+    pub fn finish_live_stroke(&mut self, key: StrokeKey) {
+        if let Some(live_stroke) = self.live_stroke.as_ref() {
+            if live_stroke.key == key && live_stroke.ended {
+                self.live_stroke = None;
+            }
+        }
+    }
+
+    /// Wether the stroke is drawn live instead of from its rendering
+    pub fn is_drawn_live(&self, key: StrokeKey) -> bool {
+        self.live_stroke
+            .as_ref()
+            .map_or(false, |live_stroke| live_stroke.key == key)
+    }
+
+    /// Generates the paths for the new elements of the live stroke. Returns false if the stroke is not the live stroke, or it is ended
+    pub fn update_live_stroke(&mut self, key: StrokeKey) -> bool {
+        let zoom = self.zoom;
+        let (live_stroke, stroke) = match (self.live_stroke.as_mut(), self.strokes.get(key)) {
+            (Some(live_stroke), Some(stroke)) if live_stroke.key == key && !live_stroke.ended => {
+                (live_stroke, stroke)
+            }
+            _ => return false,
+        };
+
+        if let (Some(n_elements), Some(mut new_paths)) = (
+            stroke.n_elements(),
+            stroke.gen_live_paths(live_stroke.n_elements),
+        ) {
+            live_stroke.n_elements = n_elements;
+            live_stroke.paths.append(&mut new_paths);
+        }
+        if live_stroke.input_pending_since.get().is_none() {
+            live_stroke.input_pending_since.set(Some(Instant::now()));
+        }
+
+        live_stroke.record_chunks(zoom);
+        true
+    }
+
+    /// Draws the live stroke from its paths, and samples the latency since the input arrived
+    pub fn draw_live_stroke(&self, snapshot: &NodeSnapshot) {
+        let live_stroke = match self.live_stroke.as_ref() {
+            Some(live_stroke) if self.strokes.contains_key(live_stroke.key) => live_stroke,
+            _ => return,
+        };
+
+        // Chunks recorded at a different zoom are not used until they are recorded again
+        let remaining_paths = if (live_stroke.chunks_zoom - self.zoom).abs() > f64::EPSILON {
+            &live_stroke.paths[..]
+        } else {
+            for rendernode in live_stroke.chunk_nodes.iter() {
+                snapshot.append_node(rendernode);
+            }
+            &live_stroke.paths[live_stroke.chunk_nodes.len() * LiveStroke::CHUNK_SIZE..]
+        };
+
+        match render::paths_to_rendernode(remaining_paths, self.zoom) {
+            Ok(rendernode) => snapshot.append_node(&rendernode),
+            Err(e) => log::error!(
+                "paths_to_rendernode() failed in draw_live_stroke() with Err {}",
+                e
+            ),
+        }
+
+        if let Some(input_pending_since) = live_stroke.input_pending_since.take() {
+            let mut live_latency = self.live_latency.get();
+            live_latency.add_sample(input_pending_since.elapsed());
+            self.live_latency.set(live_latency);
+        }
+    }
+}
@@ -0,0 +1,1423 @@
+pub mod chrono_comp;
+pub mod group_comp;
+pub mod keytree;
+pub mod live_stroke;
+pub mod render_comp;
+pub mod selection_comp;
+pub mod tile_cache;
+pub mod trash_comp;
+
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+use chrono_comp::ChronoComponent;
+use group_comp::GroupComponent;
+use keytree::KeyTree;
+use live_stroke::{LiveLatency, LiveStroke};
+use p2d::query::PointQuery;
+use render_comp::RenderComponent;
+use selection_comp::SelectionComponent;
+use tile_cache::TileCache;
+use trash_comp::TrashComponent;
+
+use crate::compose::{geometry, shapes};
+use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::{self, PdfDocument, PdfImportPrefs, PdfPageOrigin, PdfTextSelection};
+use crate::pens::tools::DragProximityTool;
+use crate::render;
+use crate::strokes::bitmapimage::BitmapImage;
+use crate::strokes::imageedits::{BitmapAdjustments, ImageEdits};
+use crate::strokes::strokebehaviour::StrokeBehaviour;
+use crate::strokes::strokestyle::{Element, StrokeStyle, StyleEdit};
+use crate::strokes::vectorimage::VectorImage;
+use crate::utils::FileType;
+
+use gdk::prelude::*;
+use p2d::bounding_volume::{BoundingVolume, AABB, BoundingSphere};
+use serde::{Deserialize, Serialize};
+use slotmap::{HopSlotMap, SecondaryMap};
+
+#[derive(Debug, Clone)]
+pub enum StateTask {
+    UpdateStrokeWithImages {
+        key: StrokeKey,
+        images: Vec<render::Image>,
+    },
+    AppendImagesToStroke {
+        key: StrokeKey,
+        images: Vec<render::Image>,
+    },
+    InsertStroke {
+        stroke: StrokeStyle,
+    },
+    Quit,
+}
+
+pub fn default_threadpool() -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::default()
+        .build()
+        .unwrap_or_else(|e| {
+            log::error!("default_render_threadpool() failed with Err {}", e);
+            panic!()
+        })
+}
+
+slotmap::new_key_type! {
+    pub struct StrokeKey;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default, rename = "strokes_state")]
+pub struct StrokesState {
+    #[serde(rename = "strokes")]
+    // Components
+    strokes: HopSlotMap<StrokeKey, StrokeStyle>,
+    #[serde(rename = "trash_components")]
+    trash_components: SecondaryMap<StrokeKey, TrashComponent>,
+    #[serde(rename = "selection_components")]
+    selection_components: SecondaryMap<StrokeKey, SelectionComponent>,
+    #[serde(rename = "chrono_components")]
+    chrono_components: SecondaryMap<StrokeKey, ChronoComponent>,
+    #[serde(rename = "render_components")]
+    render_components: SecondaryMap<StrokeKey, RenderComponent>,
+    #[serde(rename = "group_components")]
+    group_components: SecondaryMap<StrokeKey, GroupComponent>,
+
+    /// The imported pdf documents, referenced by the strokes which were imported from them
+    #[serde(rename = "pdf_documents")]
+    pdf_documents: BTreeMap<u32, PdfDocument>,
+    /// The spatial index of the stroke bounds. Not serialized, it is rebuilt when the state is imported
+    #[serde(skip)]
+    key_tree: KeyTree<StrokeKey>,
+    /// The lazily rendered pdf pages, whose renderings are evicted when they are outside of the viewport. Not serialized, it is rebuilt when the state is imported
+    #[serde(skip)]
+    lazy_pdf_pages: HashSet<StrokeKey>,
+    /// The strokes composited into tiles, for drawing when zoomed out
+    #[serde(skip)]
+    tile_cache: TileCache,
+    /// The stroke which is currently drawn with a pen
+    #[serde(skip)]
+    live_stroke: Option<LiveStroke>,
+    /// The latency of drawing the live stroke, shown in the debug overlay
+    #[serde(skip)]
+    pub live_latency: Cell<LiveLatency>,
+
+    // Other state
+    /// value is equal chrono_component of the newest inserted or modified stroke.
+    #[serde(rename = "chrono_counter")]
+    chrono_counter: u32,
+
+    #[serde(skip)]
+    pub zoom: f64, // changes with the canvas zoom
+    #[serde(skip)]
+    pub renderer: Arc<RwLock<render::Renderer>>,
+    #[serde(skip)]
+    pub tasks_tx: Option<glib::Sender<StateTask>>,
+    /// Taken by the ui, which attaches it to its main context and processes the received tasks
+    #[serde(skip)]
+    pub tasks_rx: Option<glib::Receiver<StateTask>>,
+    #[serde(skip)]
+    pub channel_source: Option<glib::Source>,
+    #[serde(skip, default = "default_threadpool")]
+    pub threadpool: rayon::ThreadPool,
+}
+
+impl Default for StrokesState {
+    fn default() -> Self {
+        let threadpool = default_threadpool();
+
+        let (render_tx, render_rx) =
+            glib::MainContext::channel::<StateTask>(glib::PRIORITY_HIGH_IDLE);
+
+        Self {
+            strokes: HopSlotMap::with_key(),
+            trash_components: SecondaryMap::new(),
+            selection_components: SecondaryMap::new(),
+            chrono_components: SecondaryMap::new(),
+            render_components: SecondaryMap::new(),
+            group_components: SecondaryMap::new(),
+
+            pdf_documents: BTreeMap::new(),
+            key_tree: KeyTree::new(),
+            lazy_pdf_pages: HashSet::new(),
+            tile_cache: TileCache::new(),
+            live_stroke: None,
+            live_latency: Cell::new(LiveLatency::default()),
+
+            chrono_counter: 0,
+            zoom: 1.0,
+            renderer: Arc::new(RwLock::new(render::Renderer::default())),
+            tasks_tx: Some(render_tx),
+            tasks_rx: Some(render_rx),
+            channel_source: None,
+            threadpool,
+        }
+    }
+}
+
+impl StrokesState {
+    /// The spacing between images which are imported in sequence
+    pub const IMPORT_SEQUENCE_SPACING: f64 = 20.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_stroke(&mut self, stroke: StrokeStyle) -> StrokeKey {
+        let key = self.strokes.insert(stroke);
+        self.chrono_counter += 1;
+
+        self.trash_components.insert(key, TrashComponent::default());
+        self.selection_components
+            .insert(key, SelectionComponent::default());
+        self.render_components
+            .insert(key, RenderComponent::default());
+        self.chrono_components
+            .insert(key, ChronoComponent::new(self.chrono_counter));
+        self.group_components.insert(key, GroupComponent::default());
+        self.key_tree.insert(key, self.strokes[key].bounds());
+
+        // Lazily rendered pdf pages are rendered when they enter the viewport
+        if self.strokes[key].is_lazy_pdf_page() {
+            self.lazy_pdf_pages.insert(key);
+        } else {
+            self.regenerate_rendering_for_stroke(key);
+        }
+        key
+    }
+
+    pub fn insert_stroke_threaded(&mut self, stroke: StrokeStyle) -> StrokeKey {
+        let key = self.strokes.insert(stroke);
+        self.chrono_counter += 1;
+
+        self.trash_components.insert(key, TrashComponent::default());
+        self.selection_components
+            .insert(key, SelectionComponent::default());
+        self.render_components
+            .insert(key, RenderComponent::default());
+        self.chrono_components
+            .insert(key, ChronoComponent::new(self.chrono_counter));
+        self.group_components.insert(key, GroupComponent::default());
+        self.key_tree.insert(key, self.strokes[key].bounds());
+
+        // Lazily rendered pdf pages are rendered when they enter the viewport
+        if self.strokes[key].is_lazy_pdf_page() {
+            self.lazy_pdf_pages.insert(key);
+        } else {
+            self.regenerate_rendering_for_stroke_threaded(key);
+        }
+        key
+    }
+
+    pub fn remove_stroke(&mut self, key: StrokeKey) -> Option<StrokeStyle> {
+        self.trash_components.remove(key);
+        self.selection_components.remove(key);
+        self.chrono_components.remove(key);
+        self.render_components.remove(key);
+        self.group_components.remove(key);
+        self.key_tree.remove(key);
+        self.lazy_pdf_pages.remove(&key);
+        if self.is_drawn_live(key) {
+            self.live_stroke = None;
+        }
+
+        self.strokes.remove(key)
+    }
+
+    /// returns key to last stroke
+    pub fn add_to_stroke(&mut self, key: StrokeKey, element: Element) -> Option<StrokeKey> {
+        match self.strokes.get_mut(key).unwrap() {
+            StrokeStyle::MarkerStroke(ref mut markerstroke) => {
+                markerstroke.push_elem(element);
+            }
+            StrokeStyle::BrushStroke(ref mut brushstroke) => {
+                brushstroke.push_elem(element);
+            }
+            StrokeStyle::ShapeStroke(ref mut shapestroke) => {
+                shapestroke.update_shape(element);
+            }
+            StrokeStyle::VectorImage(_vectorimage) => {}
+            StrokeStyle::BitmapImage(_bitmapimage) => {}
+        }
+        self.update_key_tree_for_stroke(key);
+
+        // The live stroke is drawn directly from its geometry, without appending to its rendering
+        if !self.update_live_stroke(key) {
+            self.append_rendering_new_elem_threaded_fifo(key);
+        }
+        Some(key)
+    }
+
+    /// Clears every stroke and every component
+    pub fn clear(&mut self) {
+        self.chrono_counter = 0;
+
+        self.strokes.clear();
+        self.trash_components.clear();
+        self.selection_components.clear();
+        self.chrono_components.clear();
+        self.render_components.clear();
+        self.group_components.clear();
+        self.key_tree.clear();
+        self.lazy_pdf_pages.clear();
+        self.tile_cache.clear();
+        self.live_stroke = None;
+
+        self.pdf_documents.clear();
+    }
+
+    pub fn insert_vectorimage_bytes_threaded(&mut self, pos: na::Vector2<f64>, bytes: glib::Bytes) {
+        let renderer = self.renderer.clone();
+
+        if let Some(tasks_tx) = self.tasks_tx.clone() {
+            self.threadpool.spawn(move || {
+                match String::from_utf8(bytes.to_vec()) {
+                    Ok(svg) => {
+                        match VectorImage::import_from_svg_data(svg.as_str(), pos, None, &renderer.read().unwrap()) {
+                            Ok(vectorimage) => {
+                                let vectorimage = StrokeStyle::VectorImage(vectorimage);
+
+                                tasks_tx.send(StateTask::InsertStroke {
+                                    stroke: vectorimage
+                                }).unwrap_or_else(|e| {
+                                    log::error!("tasks_tx.send() failed in insert_vectorimage_bytes_threaded() with Err, {}", e);
+                                });
+                            }
+                            Err(e) => {
+                                log::error!("VectorImage::import_from_svg_data() failed in insert_vectorimage_bytes_threaded() with Err, {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("from_utf8() failed in thread from insert_vectorimages_bytes_threaded() with Err {}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn insert_bitmapimage_bytes_threaded(&mut self, pos: na::Vector2<f64>, bytes: glib::Bytes) {
+        if let Some(tasks_tx) = self.tasks_tx.clone() {
+            self.threadpool.spawn(move || {
+                match BitmapImage::import_from_image_bytes(bytes, pos) {
+                    Ok(bitmapimage) => {
+                        let bitmapimage = StrokeStyle::BitmapImage(bitmapimage);
+
+                        tasks_tx.send(StateTask::InsertStroke {
+                            stroke: bitmapimage
+                        }).unwrap_or_else(|e| {
+                            log::error!("tasks_tx.send() failed in insert_bitmapimage_bytes_threaded() with Err, {}", e);
+                        });
+                    }
+                    Err(e) => {
+                        log::error!("BitmapImage::import_from_svg_data() failed in insert_bitmapimage_bytes_threaded() with Err, {}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Imports the svgs and bitmap images one after another, laid out in a row starting at the position.
+    /// Each image is paired with its file type, other file types are skipped
+    pub fn insert_images_bytes_in_sequence_threaded(
+        &mut self,
+        pos: na::Vector2<f64>,
+        images: Vec<(FileType, glib::Bytes)>,
+    ) {
+        let renderer = self.renderer.clone();
+
+        if let Some(tasks_tx) = self.tasks_tx.clone() {
+            self.threadpool.spawn(move || {
+                let mut pos = pos;
+
+                for (file_type, bytes) in images {
+                    let stroke = match file_type {
+                        FileType::VectorImageFile => String::from_utf8(bytes.to_vec())
+                            .map_err(anyhow::Error::from)
+                            .and_then(|svg| {
+                                VectorImage::import_from_svg_data(svg.as_str(), pos, None, &renderer.read().unwrap())
+                            })
+                            .map(StrokeStyle::VectorImage),
+                        FileType::BitmapImageFile => {
+                            BitmapImage::import_from_image_bytes(bytes, pos).map(StrokeStyle::BitmapImage)
+                        }
+                        _ => continue,
+                    };
+
+                    match stroke {
+                        Ok(stroke) => {
+                            // The next image is placed to the right of this one
+                            pos[0] = stroke.bounds().maxs[0] + Self::IMPORT_SEQUENCE_SPACING;
+
+                            tasks_tx.send(StateTask::InsertStroke {
+                                stroke
+                            }).unwrap_or_else(|e| {
+                                log::error!("tasks_tx.send() failed in insert_images_bytes_in_sequence_threaded() with Err, {}", e);
+                            });
+                        }
+                        Err(e) => {
+                            log::error!("importing image failed in insert_images_bytes_in_sequence_threaded() with Err, {}", e);
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn insert_pdf_bytes_as_vector_threaded(
+        &mut self,
+        prefs: PdfImportPrefs,
+        bytes: glib::Bytes,
+    ) {
+        let renderer = self.renderer.clone();
+        let document = self.insert_pdf_document(&bytes);
+
+        if let Some(tasks_tx) = self.tasks_tx.clone() {
+            self.threadpool.spawn(move || {
+                match VectorImage::import_from_pdf_bytes(
+                    &bytes,
+                    &prefs,
+                    Some(document),
+                    &renderer.read().unwrap(),
+                ) {
+                    Ok(images) => {
+                        for image in images {
+                            let image = StrokeStyle::VectorImage(image);
+
+                            tasks_tx.send(StateTask::InsertStroke {
+                                stroke: image
+                            }).unwrap_or_else(|e| {
+                                log::error!("tasks_tx.send() failed in insert_pdf_bytes_as_vector_threaded() with Err, {}", e);
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("VectorImage::import_from_pdf_bytes() failed in insert_pdf_bytes_as_vector_threaded() with Err, {}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn insert_pdf_bytes_as_bitmap_threaded(
+        &mut self,
+        prefs: PdfImportPrefs,
+        bytes: glib::Bytes,
+    ) {
+        let document = self.insert_pdf_document(&bytes);
+        let pdf_data = Arc::clone(&self.pdf_documents[&document].data);
+
+        if let Some(tasks_tx) = self.tasks_tx.clone() {
+            self.threadpool.spawn(move || {
+                match BitmapImage::import_from_pdf_document(document, pdf_data, &prefs) {
+                    Ok(images) => {
+                        for image in images {
+                            let image = StrokeStyle::BitmapImage(image);
+
+                            tasks_tx.send(StateTask::InsertStroke {
+                                stroke: image
+                            }).unwrap_or_else(|e| {
+                                log::error!("tasks_tx.send() failed in insert_pdf_bytes_as_bitmap_threaded() with Err, {}", e);
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("BitmapImage::import_from_pdf_document() failed in insert_pdf_bytes_as_bitmap_threaded() with Err, {}", e);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Stores the pdf document, if it is not already stored. Returns its key
+    pub fn insert_pdf_document(&mut self, bytes: &[u8]) -> u32 {
+        let pdf_document = PdfDocument::new(bytes);
+
+        if let Some((&key, _)) = self
+            .pdf_documents
+            .iter()
+            .find(|(_, stored)| stored.data == pdf_document.data)
+        {
+            return key;
+        }
+
+        let key = self
+            .pdf_documents
+            .keys()
+            .next_back()
+            .map(|&key| key + 1)
+            .unwrap_or(0);
+        self.pdf_documents.insert(key, pdf_document);
+
+        key
+    }
+
+    /// The data of the stored pdf document the stroke was imported from
+    pub fn pdf_document_data(&self, key: StrokeKey) -> Option<Arc<Vec<u8>>> {
+        let (pdf_origin, _) = self.strokes.get(key)?.pdf_page()?;
+
+        self.pdf_documents
+            .get(&pdf_origin.document)
+            .map(|pdf_document| Arc::clone(&pdf_document.data))
+    }
+
+    /// Attaches the data of the stored pdf documents to the lazily rendered pdf pages, which reference them
+    fn attach_pdf_documents(&mut self) {
+        for (_key, stroke) in self.strokes.iter_mut() {
+            if let StrokeStyle::BitmapImage(bitmapimage) = stroke {
+                if let Some(pdf_origin) = bitmapimage.pdf_origin {
+                    bitmapimage.pdf_data = self
+                        .pdf_documents
+                        .get(&pdf_origin.document)
+                        .map(|pdf_document| Arc::clone(&pdf_document.data));
+                }
+            }
+        }
+    }
+
+    /// The imported pdf pages on the sheet with their origin, the rectangle they are drawn into and their bounds. Ordered by their position
+    fn pdf_pages_sorted(&self) -> Vec<(PdfPageOrigin, shapes::Rectangle, AABB)> {
+        let mut pdf_pages = self
+            .keys_sorted_chrono()
+            .into_iter()
+            .filter(|&key| !self.trashed(key).unwrap_or(true))
+            .filter_map(|key| {
+                let stroke = self.strokes.get(key)?;
+                let (pdf_origin, rectangle) = stroke.pdf_page()?;
+
+                Some((pdf_origin, rectangle.clone(), stroke.bounds()))
+            })
+            .collect::<Vec<(PdfPageOrigin, shapes::Rectangle, AABB)>>();
+
+        pdf_pages.sort_by(|(_, _, first), (_, _, second)| {
+            first.mins[1]
+                .partial_cmp(&second.mins[1])
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(
+                    first.mins[0]
+                        .partial_cmp(&second.mins[0])
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+        });
+
+        pdf_pages
+    }
+
+    /// Loads the page of a stored pdf document. The loaded documents are kept in the documents map, to be reused for other pages
+    fn load_pdf_page(
+        &self,
+        documents: &mut HashMap<u32, poppler::Document>,
+        pdf_origin: PdfPageOrigin,
+    ) -> Result<poppler::Page, anyhow::Error> {
+        if !documents.contains_key(&pdf_origin.document) {
+            let pdf_document = self
+                .pdf_documents
+                .get(&pdf_origin.document)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "pdf document {} of imported page is missing",
+                        pdf_origin.document
+                    )
+                })?;
+
+            documents.insert(
+                pdf_origin.document,
+                pdf::parsed_document(&pdf_document.data)?,
+            );
+        }
+
+        documents
+            .get(&pdf_origin.document)
+            .and_then(|document| document.page(pdf_origin.page as i32))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "page {} of pdf document {} not found",
+                    pdf_origin.page,
+                    pdf_origin.document
+                )
+            })
+    }
+
+    /// Generates a pdf from the imported pdf pages on the sheet, ordered by their position.
+    /// The original pages keep their vector content and text, and the strokes on top of them are drawn as vector graphics.
+    pub fn gen_annotated_pdf(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let pdf_pages = self.pdf_pages_sorted();
+
+        if pdf_pages.is_empty() {
+            return Err(anyhow::anyhow!(
+                "gen_annotated_pdf() failed, the sheet has no imported pdf pages"
+            ));
+        }
+
+        // The annotations, which are all strokes that are not imported pdf pages
+        let annotations = self
+            .keys_sorted_chrono()
+            .into_iter()
+            .filter(|&key| {
+                !self.trashed(key).unwrap_or(true)
+                    && self
+                        .strokes
+                        .get(key)
+                        .map(|stroke| stroke.pdf_page().is_none())
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<StrokeKey>>();
+
+        let mut documents = HashMap::<u32, poppler::Document>::new();
+        let surface = cairo::PdfSurface::for_stream(1.0, 1.0, Vec::<u8>::new()).map_err(|e| {
+            anyhow::anyhow!(
+                "create PdfSurface failed in gen_annotated_pdf() with Err {}",
+                e
+            )
+        })?;
+
+        {
+            let cx = cairo::Context::new(&surface).map_err(|e| {
+                anyhow::anyhow!(
+                    "new cairo::Context failed in gen_annotated_pdf() with Err {}",
+                    e
+                )
+            })?;
+
+            for (pdf_origin, rectangle, page_bounds) in pdf_pages {
+                let page = match self.load_pdf_page(&mut documents, pdf_origin) {
+                    Ok(page) => page,
+                    Err(e) => {
+                        log::error!(
+                            "load_pdf_page() failed in gen_annotated_pdf() with Err {}",
+                            e
+                        );
+                        continue;
+                    }
+                };
+                let page_size = na::vector![page.size().0, page.size().1];
+                surface.set_size(page_size[0], page_size[1])?;
+
+                // The original page, rendered as vectors and text
+                cx.save()?;
+                page.render(&cx);
+                cx.restore()?;
+
+                let svgs = annotations
+                    .iter()
+                    .filter_map(|&key| self.strokes.get(key))
+                    .filter(|stroke| stroke.bounds().intersects(&page_bounds))
+                    .filter_map(|stroke| match stroke.gen_svgs(na::vector![0.0, 0.0]) {
+                        Ok(svgs) => Some(svgs),
+                        Err(e) => {
+                            log::error!(
+                                "stroke.gen_svgs() failed in gen_annotated_pdf() with Err {}",
+                                e
+                            );
+                            None
+                        }
+                    })
+                    .flatten()
+                    .collect::<Vec<render::Svg>>();
+
+                if !svgs.is_empty() {
+                    let sheet_to_page = pdf::page_to_sheet_transform(&rectangle, page_size)
+                        .try_inverse()
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("imported pdf page has a non invertible transform")
+                        })?;
+
+                    cx.save()?;
+                    cx.rectangle(0.0, 0.0, page_size[0], page_size[1]);
+                    cx.clip();
+                    cx.transform(cairo::Matrix::new(
+                        sheet_to_page[(0, 0)],
+                        sheet_to_page[(1, 0)],
+                        sheet_to_page[(0, 1)],
+                        sheet_to_page[(1, 1)],
+                        sheet_to_page[(0, 2)],
+                        sheet_to_page[(1, 2)],
+                    ));
+                    render::draw_svgs_to_cairo_context(1.0, &svgs, page_bounds, &cx)?;
+                    cx.restore()?;
+                }
+
+                cx.show_page()?;
+            }
+        }
+
+        match surface.finish_output_stream() {
+            Ok(file_content) => match file_content.downcast::<Vec<u8>>() {
+                Ok(file_content) => Ok(*file_content),
+                Err(_) => Err(anyhow::anyhow!(
+                    "file_content.downcast() failed in gen_annotated_pdf()"
+                )),
+            },
+            Err(e) => Err(anyhow::anyhow!(
+                "surface.finish_output_stream() failed in gen_annotated_pdf() with Err {}",
+                e
+            )),
+        }
+    }
+
+    /// Searches the text of the imported pdf pages, case insensitive. Returns the bounds of the matches, ordered by the position of the pages
+    pub fn search_pdf_text(&self, text: &str) -> Vec<AABB> {
+        if text.is_empty() {
+            return vec![];
+        }
+        let mut documents = HashMap::<u32, poppler::Document>::new();
+
+        self.pdf_pages_sorted()
+            .into_iter()
+            .filter_map(|(pdf_origin, rectangle, _)| {
+                match self.load_pdf_page(&mut documents, pdf_origin) {
+                    Ok(page) => Some((page, rectangle)),
+                    Err(e) => {
+                        log::error!("load_pdf_page() failed in search_pdf_text() with Err {}", e);
+                        None
+                    }
+                }
+            })
+            .flat_map(|(page, rectangle)| {
+                let page_size = na::vector![page.size().0, page.size().1];
+                let page_to_sheet = pdf::page_to_sheet_transform(&rectangle, page_size);
+
+                // The matches have their origin at the bottom left of the page
+                page.find_text(text)
+                    .into_iter()
+                    .map(|text_match| {
+                        pdf::page_area_to_sheet(
+                            &page_to_sheet,
+                            na::point![text_match.x1(), page_size[1] - text_match.y2()],
+                            na::point![text_match.x2(), page_size[1] - text_match.y1()],
+                        )
+                    })
+                    .collect::<Vec<AABB>>()
+            })
+            .collect()
+    }
+
+    /// Selects the text of the imported pdf pages inside the bounds. Returns None if there is no text
+    pub fn select_pdf_text(&self, bounds: AABB) -> Option<PdfTextSelection> {
+        let mut documents = HashMap::<u32, poppler::Document>::new();
+        let mut text_selection = PdfTextSelection {
+            text: String::new(),
+            bounds: vec![],
+        };
+
+        for (pdf_origin, rectangle, page_bounds) in self.pdf_pages_sorted() {
+            let selected_bounds = match page_bounds.intersection(&bounds) {
+                Some(selected_bounds) => selected_bounds,
+                None => continue,
+            };
+            let page = match self.load_pdf_page(&mut documents, pdf_origin) {
+                Ok(page) => page,
+                Err(e) => {
+                    log::error!("load_pdf_page() failed in select_pdf_text() with Err {}", e);
+                    continue;
+                }
+            };
+            let page_size = na::vector![page.size().0, page.size().1];
+            let sheet_to_page =
+                match pdf::page_to_sheet_transform(&rectangle, page_size).try_inverse() {
+                    Some(sheet_to_page) => sheet_to_page,
+                    None => continue,
+                };
+
+            let area_on_page =
+                pdf::page_area_to_sheet(&sheet_to_page, selected_bounds.mins, selected_bounds.maxs);
+            let mut selection = poppler::Rectangle::new();
+            selection.set_x1(area_on_page.mins[0]);
+            selection.set_y1(area_on_page.mins[1]);
+            selection.set_x2(area_on_page.maxs[0]);
+            selection.set_y2(area_on_page.maxs[1]);
+
+            if let Some(text) = page.selected_text(poppler::SelectionStyle::Glyph, &mut selection) {
+                if text.trim().is_empty() {
+                    continue;
+                }
+                if !text_selection.text.is_empty() {
+                    text_selection.text.push('\n');
+                }
+                text_selection.text.push_str(text.as_str());
+                text_selection.bounds.push(selected_bounds);
+            }
+        }
+
+        if text_selection.text.is_empty() {
+            None
+        } else {
+            Some(text_selection)
+        }
+    }
+
+    pub fn import_state(&mut self, strokes_state: &Self) {
+        self.clear();
+        self.chrono_counter = strokes_state.chrono_counter;
+
+        self.strokes = strokes_state.strokes.clone();
+        self.trash_components = strokes_state.trash_components.clone();
+        self.selection_components = strokes_state.selection_components.clone();
+        self.chrono_components = strokes_state.chrono_components.clone();
+        self.render_components = strokes_state.render_components.clone();
+        self.group_components = strokes_state.group_components.clone();
+
+        self.pdf_documents = strokes_state.pdf_documents.clone();
+        self.rebuild_unserialized();
+
+        self.regenerate_strokes_current_view_threaded(None, true);
+    }
+
+    /// Rebuilds the state which is not serialized. Needed after deserializing, before svgs can be generated from the state
+    pub fn rebuild_unserialized(&mut self) {
+        self.attach_pdf_documents();
+        self.rebuild_key_tree();
+        self.lazy_pdf_pages = self
+            .strokes
+            .iter()
+            .filter(|(_key, stroke)| stroke.is_lazy_pdf_page())
+            .map(|(key, _stroke)| key)
+            .collect();
+    }
+
+    /// Updates the bounds of the stroke in the spatial index. Needs to be called whenever the geometry of the stroke changed
+    fn update_key_tree_for_stroke(&mut self, key: StrokeKey) {
+        if let Some(stroke) = self.strokes.get(key) {
+            self.key_tree.insert(key, stroke.bounds());
+        }
+    }
+
+    /// Rebuilds the spatial index from the bounds of all strokes
+    fn rebuild_key_tree(&mut self) {
+        let strokes = &self.strokes;
+        self.key_tree
+            .rebuild_from(strokes.iter().map(|(key, stroke)| (key, stroke.bounds())));
+    }
+
+    /// Returns the keys of all strokes, trashed ones included, whose bounds intersect the given bounds. In no particular order
+    pub fn keys_intersecting_bounds(&self, bounds: AABB) -> Vec<StrokeKey> {
+        self.key_tree.keys_intersecting_bounds(bounds)
+    }
+
+    pub fn update_geometry_for_stroke(&mut self, key: StrokeKey) {
+        if let Some(stroke) = self.strokes.get_mut(key) {
+            match stroke {
+                StrokeStyle::MarkerStroke(ref mut markerstroke) => {
+                    markerstroke.update_geometry();
+                }
+                StrokeStyle::BrushStroke(ref mut brushstroke) => {
+                    brushstroke.update_geometry();
+                }
+                StrokeStyle::ShapeStroke(shapestroke) => {
+                    shapestroke.update_geometry();
+                }
+                StrokeStyle::VectorImage(ref mut vectorimage) => {
+                    vectorimage.update_geometry();
+                }
+                StrokeStyle::BitmapImage(ref mut bitmapimage) => {
+                    bitmapimage.update_geometry();
+                }
+            }
+            self.update_key_tree_for_stroke(key);
+        } else {
+            log::debug!(
+                "get stroke in update_stroke_geometry() returned None in complete_stroke() for key {:?}",
+                key
+            );
+        }
+    }
+
+    pub fn update_geometry_all_strokes(&mut self) {
+        let keys: Vec<StrokeKey> = self.strokes.keys().collect();
+
+        keys.iter().for_each(|&key| {
+            self.update_geometry_for_stroke(key);
+        });
+    }
+
+    pub fn update_geometry_selection_strokes(&mut self) {
+        let keys: Vec<StrokeKey> = self.selection_keys();
+
+        keys.iter().for_each(|&key| {
+            self.update_geometry_for_stroke(key);
+        });
+    }
+
+    pub fn regenerate_strokes_current_view(
+        &mut self,
+        viewport: Option<AABB>,
+        force_regenerate: bool,
+    ) {
+        self.evict_pdf_page_renders(viewport, Self::PDF_PAGE_RENDERS_BUDGET);
+
+        // Only the strokes in the viewport are looked up
+        let keys = match viewport {
+            Some(viewport) => self.keys_intersecting_bounds(viewport),
+            None => self.render_components.keys().collect::<Vec<StrokeKey>>(),
+        };
+
+        keys.iter().for_each(|&key| {
+            self.update_geometry_for_stroke(key);
+
+            if let (Some(stroke), Some(render_comp)) =
+                (self.strokes.get(key), self.render_components.get_mut(key))
+            {
+                // skip if stroke is not in viewport or does not need regeneration
+                if let Some(viewport) = viewport {
+                    if !viewport.intersects(&stroke.bounds()) {
+                        return;
+                    }
+                } else if stroke.is_lazy_pdf_page() {
+                    // Lazily rendered pdf pages are only rendered when they are in the viewport
+                    render_comp.regenerate_flag = true;
+                    return;
+                }
+                if !force_regenerate && !render_comp.regenerate_flag {
+                    return;
+                }
+
+                match stroke.gen_image(self.zoom, &self.renderer.read().unwrap()) {
+                    Ok(image) => {
+                        let images = vec![image];
+
+                        match render::images_to_rendernode(&images, self.zoom) {
+                            Ok(rendernode) => {
+                                render_comp.rendernode = rendernode;
+                                render_comp.regenerate_flag = false;
+                                render_comp.images = images;
+                                render_comp.images_generation += 1;
+                            }
+                            Err(e) => log::error!("stroke.gen_images() failed in regenerate_stroke_current_view() with Err {}", e),
+                        }
+                    }
+                    Err(e) => {
+                        log::debug!(
+                            "gen_image() failed in regenerate_rendering_current_view() for stroke with key: {:?}, with Err {}",
+                            key,
+                            e
+                        )
+                    }
+                }
+            } else {
+                log::debug!(
+                    "get stroke, render_comp returned None in regenerate_rendering_current_view() for stroke with key {:?}",
+                    key
+                );
+            }
+        })
+    }
+
+    pub fn regenerate_strokes_current_view_threaded(
+        &mut self,
+        viewport: Option<AABB>,
+        force_regenerate: bool,
+    ) {
+        self.evict_pdf_page_renders(viewport, Self::PDF_PAGE_RENDERS_BUDGET);
+
+        // Only the strokes in the viewport are looked up
+        let keys = match viewport {
+            Some(viewport) => self.keys_intersecting_bounds(viewport),
+            None => self.render_components.keys().collect::<Vec<StrokeKey>>(),
+        };
+
+        keys.iter().for_each(|&key| {
+            if let (Some(stroke), Some(render_comp)) =
+                (self.strokes.get(key), self.render_components.get_mut(key))
+            {
+                // skip if stroke is not in viewport or does not need regeneration
+                if let Some(viewport) = viewport {
+                    if !viewport.intersects(&stroke.bounds()) {
+                        return;
+                    }
+                } else if stroke.is_lazy_pdf_page() {
+                    // Lazily rendered pdf pages are only rendered when they are in the viewport
+                    render_comp.regenerate_flag = true;
+                    return;
+                }
+                if !force_regenerate && !render_comp.regenerate_flag {
+                    return;
+                }
+
+                self.update_geometry_for_stroke(key);
+                self.regenerate_rendering_for_stroke_threaded(key);
+            } else {
+                log::debug!(
+                    "get stroke, render_comp returned None in regenerate_rendering_current_view_threaded() for stroke with key {:?}",
+                    key
+                );
+            }
+        })
+    }
+
+    /// Calculates the height needed to fit all strokes
+    pub fn calc_height(&self) -> i32 {
+        let new_height = if let Some(stroke) = self
+            .strokes
+            .iter()
+            .filter_map(|(key, stroke)| {
+                if let Some(trash_comp) = self.trash_components.get(key) {
+                    if !trash_comp.trashed {
+                        return Some(stroke);
+                    }
+                }
+                None
+            })
+            .max_by_key(|&stroke| stroke.bounds().maxs[1].round() as i32)
+        {
+            // max_by_key() returns the element, so we need to extract the height again
+            stroke.bounds().maxs[1].round() as i32
+        } else {
+            0
+        };
+
+        new_height
+    }
+
+    /// Generates the bounds which enclose the strokes
+    pub fn gen_bounds(&self, keys: &[StrokeKey]) -> Option<AABB> {
+        let mut keys_iter = keys.iter();
+        if let Some(&key) = keys_iter.next() {
+            if let Some(first) = self.strokes.get(key) {
+                let mut bounds = first.bounds();
+
+                keys_iter
+                    .filter_map(|&key| self.strokes.get(key))
+                    .for_each(|stroke| {
+                        bounds.merge(&stroke.bounds());
+                    });
+
+                return Some(bounds);
+            }
+        }
+
+        None
+    }
+
+    /// Generates a Svg for all strokes as drawn onto the canvas without xml headers or svg roots. Does not include the selection.
+    pub fn gen_svgs_for_strokes(&self) -> Result<Vec<render::Svg>, anyhow::Error> {
+        self.gen_svgs_for_keys(&self.keys_sorted_chrono())
+    }
+
+    /// Generates a Svg for all strokes which intersect the bounds, as drawn onto the canvas without xml headers or svg roots. Does not include the selection.
+    pub fn gen_svgs_for_bounds(&self, bounds: AABB) -> Result<Vec<render::Svg>, anyhow::Error> {
+        self.gen_svgs_for_keys(&self.keys_sorted_chrono_intersecting_bounds(bounds))
+    }
+
+    fn gen_svgs_for_keys(&self, keys: &[StrokeKey]) -> Result<Vec<render::Svg>, anyhow::Error> {
+        let svgs = keys
+            .iter()
+            .filter(|&&key| {
+                self.does_render(key).unwrap_or(false)
+                    && !(self.trashed(key).unwrap_or(false))
+                    && !(self.selected(key).unwrap_or(false))
+                    && (self.does_render(key).unwrap_or(false))
+            })
+            .filter_map(|&key| {
+                let stroke = self.strokes.get(key)?;
+
+                match stroke.gen_svgs(na::vector![0.0, 0.0]) {
+                    Ok(svgs) => Some(svgs),
+                    Err(e) => {
+                        log::error!(
+                            "stroke.gen_svgs() failed in gen_svg_all_strokes() with Err {}",
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .flatten()
+            .collect::<Vec<render::Svg>>();
+
+        Ok(svgs)
+    }
+
+    /// Translate the strokes with the offset
+    pub fn translate_strokes(&mut self, strokes: &[StrokeKey], offset: na::Vector2<f64>) {
+        strokes.iter().for_each(|&key| {
+            if let Some(stroke) = self.strokes.get_mut(key) {
+                stroke.translate(offset);
+                self.key_tree.insert(key, stroke.bounds());
+
+                if let Some(render_comp) = self.render_components.get_mut(key) {
+                    for image in render_comp.images.iter_mut() {
+                        image.bounds = geometry::aabb_translate(image.bounds, offset);
+                    }
+
+                    match render::images_to_rendernode(&render_comp.images, self.zoom) {
+                        Ok(rendernode) => {
+                            render_comp.rendernode = rendernode;
+                            // NOt touch the regenerate flag consciously
+                        }
+                        Err(e) => log::error!(
+                            "images_to_rendernode() failed in translate_strokes() with Err {}",
+                            e
+                        ),
+                    }
+                }
+            }
+        });
+    }
+
+    /// Rotates the stroke with angle (rad) around the center
+    pub fn rotate_strokes(&mut self, strokes: &[StrokeKey], angle: f64, center: na::Point2<f64>) {
+        strokes.iter().for_each(|&key| {
+            if let Some(stroke) = self.strokes.get_mut(key) {
+                stroke.rotate(angle, center);
+                self.update_key_tree_for_stroke(key);
+                self.regenerate_rendering_for_stroke(key)
+            }
+        });
+    }
+
+    /// Flips the strokes horizontally and / or vertically at the axes through the center
+    pub fn flip_strokes(
+        &mut self,
+        strokes: &[StrokeKey],
+        horizontal: bool,
+        vertical: bool,
+        center: na::Point2<f64>,
+    ) {
+        strokes.iter().for_each(|&key| {
+            if let Some(stroke) = self.strokes.get_mut(key) {
+                stroke.flip(horizontal, vertical, center);
+                self.update_key_tree_for_stroke(key);
+                self.regenerate_rendering_for_stroke(key);
+            }
+        });
+    }
+
+    // Resizes the strokes to new bounds
+    pub fn resize_strokes(&mut self, strokes: &[StrokeKey], old_bounds: AABB, new_bounds: AABB) {
+        strokes.iter().for_each(|&key| {
+            if let Some(stroke) = self.strokes.get_mut(key) {
+                let old_stroke_bounds = stroke.bounds();
+                let new_stroke_bounds = geometry::scale_inner_bounds_to_new_outer_bounds(
+                    stroke.bounds(),
+                    old_bounds,
+                    new_bounds,
+                );
+
+                let offset = new_stroke_bounds.center() - old_stroke_bounds.center();
+                let scale = new_stroke_bounds
+                    .extents()
+                    .component_div(&old_stroke_bounds.extents());
+
+                stroke.translate(offset);
+                stroke.scale(scale);
+                self.key_tree.insert(key, stroke.bounds());
+
+                self.regenerate_rendering_for_stroke(key);
+            }
+        });
+    }
+
+    /// The key of the selected stroke, if the selection is a single bitmap or vector image
+    pub fn selected_image_key(&self) -> Option<StrokeKey> {
+        match self.selection_keys().as_slice() {
+            [key] => match self.strokes.get(*key) {
+                Some(StrokeStyle::VectorImage(_)) | Some(StrokeStyle::BitmapImage(_)) => Some(*key),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn image_edits(&self, key: StrokeKey) -> Option<ImageEdits> {
+        self.strokes.get(key)?.image_edits()
+    }
+
+    pub fn image_crop_bounds(&self, key: StrokeKey) -> Option<AABB> {
+        self.strokes.get(key)?.image_crop_bounds()
+    }
+
+    /// Crops the image to the crop rectangle in the coordinate space of its intrinsic size
+    pub fn crop_image(&mut self, key: StrokeKey, crop: AABB) {
+        if let Some(stroke) = self.strokes.get_mut(key) {
+            stroke.crop_image(crop);
+            self.update_key_tree_for_stroke(key);
+            self.regenerate_rendering_for_stroke(key);
+        }
+    }
+
+    pub fn flip_image(&mut self, key: StrokeKey, horizontal: bool, vertical: bool) {
+        if let Some(stroke) = self.strokes.get_mut(key) {
+            stroke.flip_image(horizontal, vertical);
+            self.update_key_tree_for_stroke(key);
+            self.regenerate_rendering_for_stroke(key);
+        }
+    }
+
+    /// Removes the crop and the flips, and resets the color adjustments of the image
+    pub fn reset_image_edits(&mut self, key: StrokeKey) {
+        if let Some(stroke) = self.strokes.get_mut(key) {
+            stroke.reset_image_edits();
+            stroke.set_bitmap_adjustments(BitmapAdjustments::default());
+            self.update_key_tree_for_stroke(key);
+            self.regenerate_rendering_for_stroke(key);
+        }
+    }
+
+    /// Converts a offset in the coordinate space of the sheet to a offset in the coordinate space of the intrinsic size of the image
+    pub fn image_offset_to_intrinsic(
+        &self,
+        key: StrokeKey,
+        offset: na::Vector2<f64>,
+    ) -> Option<na::Vector2<f64>> {
+        self.strokes.get(key)?.image_offset_to_intrinsic(offset)
+    }
+
+    pub fn bitmap_adjustments(&self, key: StrokeKey) -> Option<BitmapAdjustments> {
+        self.strokes.get(key)?.bitmap_adjustments()
+    }
+
+    pub fn set_bitmap_adjustments(&mut self, key: StrokeKey, adjustments: BitmapAdjustments) {
+        if let Some(stroke) = self.strokes.get_mut(key) {
+            stroke.set_bitmap_adjustments(adjustments);
+            self.regenerate_rendering_for_stroke(key);
+        }
+    }
+
+    /// Applies the style edit to the selected strokes. Returns the keys of the changed strokes together with edits which restore their previous style
+    pub fn restyle_selection(&mut self, edit: &StyleEdit) -> Vec<(StrokeKey, StyleEdit)> {
+        let mut previous = vec![];
+
+        for key in self.selection_keys() {
+            if let Some(stroke) = self.strokes.get_mut(key) {
+                let before = stroke.style_of_edit(edit);
+                if stroke.apply_style_edit(edit) {
+                    previous.push((key, before));
+                    self.update_key_tree_for_stroke(key);
+                }
+            }
+        }
+        self.regenerate_rendering_for_selection();
+
+        previous
+    }
+
+    /// Applies the style edits to the strokes and replaces them with edits which restore the current style, used to undo and redo style changes.
+    /// Only the style fields are restored, other changes to the strokes in the meantime are kept
+    pub fn swap_styles(&mut self, styles: &mut [(StrokeKey, StyleEdit)]) {
+        for (key, style) in styles.iter_mut() {
+            if let Some(stroke) = self.strokes.get_mut(*key) {
+                let current = stroke.style_of_edit(style);
+                stroke.apply_style_edit(style);
+                *style = current;
+                self.update_key_tree_for_stroke(*key);
+                self.regenerate_rendering_for_stroke(*key);
+            }
+        }
+    }
+
+    /// Returns all strokes below the y_pos
+    pub fn strokes_below_y_pos(&self, y_pos: f64) -> Vec<StrokeKey> {
+        let below_bounds = AABB::new(
+            na::point![f64::MIN, y_pos],
+            na::point![f64::MAX, f64::MAX],
+        );
+
+        self.keys_intersecting_bounds(below_bounds)
+            .into_iter()
+            .filter(|&key| {
+                self.strokes
+                    .get(key)
+                    .map(|stroke| stroke.bounds().mins[1] > y_pos)
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// Generates a hash for each page over the strokes which are drawn onto it, so that changes to the pages can be detected without generating their Svgs.
+    /// Includes the generation of the stroke renderings, so it also changes when the strokes are rendered again
+    pub fn gen_page_hashes(&self, n_pages: u32, page_height: f64) -> Vec<u64> {
+        let mut hashers = (0..n_pages)
+            .map(|_| DefaultHasher::new())
+            .collect::<Vec<DefaultHasher>>();
+
+        for key in self.keys_sorted_chrono() {
+            if !self.does_render(key).unwrap_or(false)
+                || self.trashed(key).unwrap_or(true)
+                || self.selected(key).unwrap_or(false)
+            {
+                continue;
+            }
+            if let Some(stroke) = self.strokes.get(key) {
+                let bounds = stroke.bounds();
+                let first_page = (bounds.mins[1] / page_height).floor().max(0.0) as u32;
+                let last_page = (bounds.maxs[1] / page_height).floor().max(0.0) as u32;
+
+                for hasher in hashers
+                    .iter_mut()
+                    .skip(first_page as usize)
+                    .take(last_page.saturating_sub(first_page) as usize + 1)
+                {
+                    key.hash(hasher);
+                    self.chrono_t(key).hash(hasher);
+                    // Changes which keep the bounds, like restyling, image adjustments or flips, render the stroke again
+                    self.render_components
+                        .get(key)
+                        .map(|render_comp| render_comp.images_generation)
+                        .hash(hasher);
+                    bounds
+                        .mins
+                        .iter()
+                        .chain(bounds.maxs.iter())
+                        .for_each(|coord| coord.to_bits().hash(hasher));
+                }
+            }
+        }
+
+        hashers
+            .into_iter()
+            .map(|hasher| hasher.finish())
+            .collect::<Vec<u64>>()
+    }
+
+    /// Returns all strokes, trashed ones included, which lie on the page with the given index
+    pub fn strokes_on_page(&self, page: u32, page_height: f64) -> Vec<StrokeKey> {
+        // Strokes above the first page belong to it
+        let page_top = if page == 0 {
+            f64::MIN
+        } else {
+            f64::from(page) * page_height
+        };
+        let page_bounds = AABB::new(
+            na::point![f64::MIN, page_top],
+            na::point![f64::MAX, f64::from(page + 1) * page_height],
+        );
+
+        self.keys_intersecting_bounds(page_bounds)
+            .into_iter()
+            .filter(|&key| {
+                self.strokes
+                    .get(key)
+                    .map(|stroke| geometry::page_of_aabb(stroke.bounds(), page_height) == page)
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// Returns all strokes, trashed ones included, which lie on the page with the given index or on any page below
+    pub fn strokes_from_page(&self, page: u32, page_height: f64) -> Vec<StrokeKey> {
+        // Strokes above the first page belong to it
+        let page_top = if page == 0 {
+            f64::MIN
+        } else {
+            f64::from(page) * page_height
+        };
+        let below_bounds = AABB::new(
+            na::point![f64::MIN, page_top],
+            na::point![f64::MAX, f64::MAX],
+        );
+
+        self.keys_intersecting_bounds(below_bounds)
+            .into_iter()
+            .filter(|&key| {
+                self.strokes
+                    .get(key)
+                    .map(|stroke| geometry::page_of_aabb(stroke.bounds(), page_height) >= page)
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    /// Inserts copies of the strokes, translated by the offset. Trashed strokes are not copied.
+    /// Returns the keys of the copies
+    pub fn duplicate_strokes(
+        &mut self,
+        strokes: &[StrokeKey],
+        offset: na::Vector2<f64>,
+    ) -> Vec<StrokeKey> {
+        let mut duplicates = strokes
+            .iter()
+            .filter(|&&key| !self.trashed(key).unwrap_or(true))
+            .filter_map(|&key| Some((self.chrono_t(key)?, self.strokes.get(key)?.clone())))
+            .collect::<Vec<(u32, StrokeStyle)>>();
+        // Keep the order of the originals
+        duplicates.sort_unstable_by_key(|(t, _)| *t);
+
+        duplicates
+            .into_iter()
+            .map(|(_, mut stroke)| {
+                stroke.translate(offset);
+                self.insert_stroke_threaded(stroke)
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    pub fn drag_strokes_proximity(&mut self, drag_proximity_tool: &DragProximityTool) {
+        let sphere = BoundingSphere {
+            center: na::Point2::from(drag_proximity_tool.pos),
+            radius: drag_proximity_tool.radius,
+        };
+        let tool_bounds = geometry::aabb_new_positive(
+            na::point![
+                drag_proximity_tool.pos[0] - drag_proximity_tool.radius,
+                drag_proximity_tool.pos[1] - drag_proximity_tool.radius
+            ],
+            na::point![
+                drag_proximity_tool.pos[0] + drag_proximity_tool.radius,
+                drag_proximity_tool.pos[1] + drag_proximity_tool.radius
+            ],
+        );
+
+        self.keys_intersecting_bounds(tool_bounds)
+            .into_iter()
+            .filter_map(|key| match self.strokes.get_mut(key)? {
+                StrokeStyle::MarkerStroke(markerstroke) => {
+                    if markerstroke.bounds().intersects(&tool_bounds) {
+                        markerstroke.elements.iter_mut().for_each(|element| {
+                            if sphere
+                                .contains_local_point(&na::Point2::from(element.inputdata.pos()))
+                            {
+                                // Zero when right at drag_proximity_tool position, One when right at the radius
+                                let distance_ratio = (1.0
+                                    - (element.inputdata.pos() - drag_proximity_tool.pos)
+                                        .magnitude()
+                                        / drag_proximity_tool.radius)
+                                    .clamp(0.0, 1.0);
+
+                                element.inputdata.set_pos(
+                                    element.inputdata.pos()
+                                        + drag_proximity_tool.offset * distance_ratio,
+                                );
+                            }
+                        });
+                        Some(key)
+                    } else {
+                        None
+                    }
+                }
+                StrokeStyle::BrushStroke(brushstroke) => {
+                    if brushstroke.bounds().intersects(&tool_bounds) {
+                        brushstroke.elements.iter_mut().for_each(|element| {
+                            if sphere
+                                .contains_local_point(&na::Point2::from(element.inputdata.pos()))
+                            {
+                                // Zero when right at drag_proximity_tool position, One when right at the radius
+                                let distance_ratio = (1.0
+                                    - (element.inputdata.pos() - drag_proximity_tool.pos)
+                                        .magnitude()
+                                        / drag_proximity_tool.radius)
+                                    .clamp(0.0, 1.0);
+
+                                element.inputdata.set_pos(
+                                    element.inputdata.pos()
+                                        + drag_proximity_tool.offset * distance_ratio,
+                                );
+                            }
+                        });
+                        Some(key)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
+            .collect::<Vec<StrokeKey>>()
+            .iter()
+            .for_each(|&key| {
+                self.update_geometry_for_stroke(key);
+                self.regenerate_rendering_for_stroke_threaded(key);
+            })
+    }
+}
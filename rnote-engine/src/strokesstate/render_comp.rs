@@ -1,10 +1,15 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::tile_cache::TileCache;
 use super::StateTask;
 use super::{StrokeKey, StrokeStyle, StrokesState};
+use crate::compose::geometry;
 use crate::drawbehaviour::DrawBehaviour;
-use crate::ui::canvas;
-use crate::{render, utils};
+use crate::render::NodeSnapshot;
+use crate::{debug, render, utils};
 
-use gtk4::{graphene, gsk, Snapshot};
+use gsk::graphene;
 use p2d::bounding_volume::{BoundingVolume, AABB};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +24,9 @@ pub struct RenderComponent {
     pub images: Vec<render::Image>,
     #[serde(skip, default = "render::default_rendernode")]
     pub rendernode: gsk::RenderNode,
+    /// Increased every time the images change, so that cached tiles which contain the stroke can be detected as stale
+    #[serde(skip)]
+    pub images_generation: u64,
 }
 
 impl Default for RenderComponent {
@@ -28,11 +36,15 @@ impl Default for RenderComponent {
             regenerate_flag: true,
             images: vec![],
             rendernode: render::default_rendernode(),
+            images_generation: 0,
         }
     }
 }
 
 impl StrokesState {
+    /// The size in bytes the cached renderings of lazily rendered pdf pages may occupy, before the ones outside of the viewport are evicted
+    pub const PDF_PAGE_RENDERS_BUDGET: usize = 256 * 1024 * 1024;
+
     /// Returns false if rendering is not supported
     pub fn can_render(&self, key: StrokeKey) -> bool {
         self.render_components.get(key).is_some()
@@ -104,6 +116,7 @@ impl StrokesState {
                             render_comp.rendernode = rendernode;
                             render_comp.regenerate_flag = false;
                             render_comp.images = vec![image];
+                            render_comp.images_generation += 1;
                         }
                         Err(e) => log::error!("image_to_rendernode() failed in regenerate_rendering_for_stroke() with Err {}", e),
                     }
@@ -157,107 +170,42 @@ impl StrokesState {
         if let (Some(stroke), Some(render_comp)) =
             (self.strokes.get(key), self.render_components.get_mut(key))
         {
-            match stroke {
-                StrokeStyle::BrushStroke(brushstroke) => {
-                    let elems_len = brushstroke.elements.len();
-
-                    let elements = if elems_len >= 4 {
-                        Some((
-                            brushstroke.elements.get(elems_len - 4).unwrap(),
-                            brushstroke.elements.get(elems_len - 3).unwrap(),
-                            brushstroke.elements.get(elems_len - 2).unwrap(),
-                            brushstroke.elements.get(elems_len - 1).unwrap(),
-                        ))
-                    } else {
-                        None
-                    };
-
-                    if let Some(elements) = elements {
-                        let offset = na::vector![0.0, 0.0];
-                        if let Ok(Some(last_elems_svg)) =
-                            brushstroke.gen_svg_for_elems(elements, offset, true)
-                        {
-                            let bounds = last_elems_svg.bounds;
-                            match self.renderer.read().unwrap().gen_image(
-                                self.zoom,
-                                &[last_elems_svg],
-                                bounds,
-                            ) {
-                                Ok(last_elems_image) => {
-                                    let mut images = vec![last_elems_image];
-
-                                    match render::append_images_to_rendernode(
-                                        &render_comp.rendernode,
-                                        &images,
-                                        self.zoom,
-                                    ) {
-                                        Ok(rendernode) => {
-                                            render_comp.rendernode = rendernode;
-                                            render_comp.images.append(&mut images);
-                                            render_comp.regenerate_flag = false;
-                                        }
-                                        Err(e) => log::error!("append_images_to_rendernode() failed in append_rendering_new_elem() with Err {}", e),
-                                    }
-                                }
-                                Err(e) => {
-                                    log::warn!("renderer.gen_image() failed in regenerate_image_new_elem() for stroke with key {:?}, with Err {}", key, e);
-                                }
-                            }
+            let last_elems_image = match stroke {
+                StrokeStyle::MarkerStroke(markerstroke) => Some(
+                    markerstroke.gen_image_for_last_elems(self.zoom, &self.renderer.read().unwrap()),
+                ),
+                StrokeStyle::BrushStroke(brushstroke) => Some(
+                    brushstroke.gen_image_for_last_elems(self.zoom, &self.renderer.read().unwrap()),
+                ),
+                StrokeStyle::ShapeStroke(_)
+                | StrokeStyle::VectorImage(_)
+                | StrokeStyle::BitmapImage(_) => None,
+            };
+
+            match last_elems_image {
+                Some(Ok(Some(last_elems_image))) => {
+                    let mut images = vec![last_elems_image];
+
+                    match render::append_images_to_rendernode(
+                        &render_comp.rendernode,
+                        &images,
+                        self.zoom,
+                    ) {
+                        Ok(rendernode) => {
+                            render_comp.rendernode = rendernode;
+                            render_comp.images.append(&mut images);
+                            render_comp.images_generation += 1;
+                            render_comp.regenerate_flag = false;
                         }
+                        Err(e) => log::error!("append_images_to_rendernode() failed in append_rendering_new_elem() with Err {}", e),
                     }
                 }
-                StrokeStyle::MarkerStroke(markerstroke) => {
-                    let elems_len = markerstroke.elements.len();
-
-                    let elements = if elems_len >= 4 {
-                        Some((
-                            markerstroke.elements.get(elems_len - 4).unwrap(),
-                            markerstroke.elements.get(elems_len - 3).unwrap(),
-                            markerstroke.elements.get(elems_len - 2).unwrap(),
-                            markerstroke.elements.get(elems_len - 1).unwrap(),
-                        ))
-                    } else {
-                        None
-                    };
-
-                    if let Some(elements) = elements {
-                        let offset = na::vector![0.0, 0.0];
-                        if let Some(last_elems_svg) =
-                            markerstroke.gen_svg_elem(elements, offset, true)
-                        {
-                            let bounds = last_elems_svg.bounds;
-                            match self.renderer.read().unwrap().gen_image(
-                                self.zoom,
-                                &[last_elems_svg],
-                                bounds,
-                            ) {
-                                Ok(last_elems_image) => {
-                                    let mut images = vec![last_elems_image];
-
-                                    match render::append_images_to_rendernode(
-                                        &render_comp.rendernode,
-                                        &images,
-                                        self.zoom,
-                                    ) {
-                                        Ok(rendernode) => {
-                                            render_comp.rendernode = rendernode;
-                                            render_comp.images.append(&mut images);
-                                            render_comp.regenerate_flag = false;
-                                        }
-                                        Err(e) => log::error!("append_images_to_rendernode() failed in append_rendering_new_elem() with Err {}", e),
-                                    }
-                                }
-                                Err(e) => {
-                                    log::warn!("renderer.gen_image() failed in regenerate_image_new_elem() with Err {}", e);
-                                }
-                            }
-                        }
-                    }
+                Some(Ok(None)) => {}
+                Some(Err(e)) => {
+                    log::warn!("gen_image_for_last_elems() failed in append_rendering_new_elem() for stroke with key {:?}, with Err {}", key, e);
                 }
-                // regenerate everything for strokes that don't support generating svgs for the last added elements
-                StrokeStyle::ShapeStroke(_)
-                | StrokeStyle::VectorImage(_)
-                | StrokeStyle::BitmapImage(_) => {
+                // regenerate everything for strokes that don't support generating images for the last added elements
+                None => {
                     match stroke.gen_image(self.zoom, &self.renderer.read().unwrap()) {
                         Ok(image) => {
                             match render::image_to_rendernode(&image, self.zoom) {
@@ -265,6 +213,7 @@ impl StrokesState {
                                     render_comp.rendernode = rendernode;
                                     render_comp.regenerate_flag = false;
                                     render_comp.images = vec![image];
+                                    render_comp.images_generation += 1;
                                 }
                                 Err(e) => log::error!("image_to_rendernode() failed in regenerate_rendering_for_stroke() with Err {}", e),
                             }
@@ -301,95 +250,35 @@ impl StrokesState {
             render_comp.regenerate_flag = true;
 
             self.threadpool.spawn_fifo(move || {
-                match stroke {
-                    StrokeStyle::MarkerStroke(markerstroke) => {
-                        let elems_len = markerstroke.elements.len();
-
-                        let elements = if elems_len >= 4 {
-                            Some((
-                                markerstroke.elements.get(elems_len - 4).unwrap(),
-                                markerstroke.elements.get(elems_len - 3).unwrap(),
-                                markerstroke.elements.get(elems_len - 2).unwrap(),
-                                markerstroke.elements.get(elems_len - 1).unwrap(),
-                            ))
-                        } else {
-                            None
-                        };
-
-                        if let Some(elements) = elements {
-                            let offset = na::vector![0.0, 0.0];
-                            if let Some(last_elems_svg) =
-                                markerstroke.gen_svg_elem(elements, offset, true)
-                            {
-                            let bounds = last_elems_svg.bounds;
-                                match renderer.read().unwrap().gen_image(
-                                    zoom,
-                                    &[last_elems_svg],
-                                    bounds,
-                                ) {
-                                    Ok(last_elems_image) => {
-                                        let images = vec![last_elems_image];
-
-                                        tasks_tx.send(StateTask::AppendImagesToStroke {
-                                            key,
-                                            images,
-                                        }).unwrap_or_else(|e| {
-                                            log::error!("sending AppendImagesToStroke as task for markerstroke failed in regenerate_rendering_new_elem() for stroke with key {:?}, with Err {}", key, e);
-                                        });
-                                    }
-                                    Err(e) => {
-                                        log::warn!("renderer.gen_image() failed in regenerate_image_new_elem() for stroke with key {:?}, with Err {}",key, e);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    StrokeStyle::BrushStroke(brushstroke) => {
-                        let elems_len = brushstroke.elements.len();
-
-                        let elements = if elems_len >= 4 {
-                            Some((
-                                brushstroke.elements.get(elems_len - 4).unwrap(),
-                                brushstroke.elements.get(elems_len - 3).unwrap(),
-                                brushstroke.elements.get(elems_len - 2).unwrap(),
-                                brushstroke.elements.get(elems_len - 1).unwrap(),
-                            ))
-                        } else {
-                            None
-                        };
-
-                        if let Some(elements) = elements {
-                            let offset = na::vector![0.0, 0.0];
-                            if let Ok(Some(last_elems_svg)) =
-                                brushstroke.gen_svg_for_elems(elements, offset, true)
-                            {
-                                let bounds = last_elems_svg.bounds;
-                                match renderer.read().unwrap().gen_image(
-                                    zoom,
-                                    &[last_elems_svg],
-                                    bounds,
-                                ) {
-                                    Ok(last_elems_image) => {
-                                        let images = vec![last_elems_image];
-
-                                        tasks_tx.send(StateTask::AppendImagesToStroke {
-                                            key,
-                                            images,
-                                        }).unwrap_or_else(|e| {
-                                            log::error!("sending AppendImagesToStroke as task for markerstroke failed in regenerate_rendering_new_elem() for stroke with key {:?}, with Err, {}",key, e);
-                                        });
-                                    }
-                                    Err(e) => {
-                                        log::warn!("renderer.gen_image() failed in regenerate_image_new_elem() for stroke with key {:?} with Err {}", key, e);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    // regenerate everything for strokes that don't support generating svgs for the last added elements
+                let last_elems_image = match &stroke {
+                    StrokeStyle::MarkerStroke(markerstroke) => Some(
+                        markerstroke.gen_image_for_last_elems(zoom, &renderer.read().unwrap()),
+                    ),
+                    StrokeStyle::BrushStroke(brushstroke) => Some(
+                        brushstroke.gen_image_for_last_elems(zoom, &renderer.read().unwrap()),
+                    ),
                     StrokeStyle::ShapeStroke(_)
                     | StrokeStyle::VectorImage(_)
-                    | StrokeStyle::BitmapImage(_) => {
+                    | StrokeStyle::BitmapImage(_) => None,
+                };
+
+                match last_elems_image {
+                    Some(Ok(Some(last_elems_image))) => {
+                        let images = vec![last_elems_image];
+
+                        tasks_tx.send(StateTask::AppendImagesToStroke {
+                            key,
+                            images,
+                        }).unwrap_or_else(|e| {
+                            log::error!("sending AppendImagesToStroke as task failed in append_rendering_new_elem_threaded_fifo() for stroke with key {:?}, with Err {}", key, e);
+                        });
+                    }
+                    Some(Ok(None)) => {}
+                    Some(Err(e)) => {
+                        log::warn!("gen_image_for_last_elems() failed in append_rendering_new_elem_threaded_fifo() for stroke with key {:?}, with Err {}", key, e);
+                    }
+                    // regenerate everything for strokes that don't support generating images for the last added elements
+                    None => {
                         match stroke.gen_image(zoom, &renderer.read().unwrap()) {
                             Ok(image) => {
                                 tasks_tx.send(StateTask::UpdateStrokeWithImages {
@@ -471,6 +360,9 @@ impl StrokesState {
                     render_comp.rendernode = rendernode;
                     render_comp.regenerate_flag = false;
                     render_comp.images = images;
+                    render_comp.images_generation += 1;
+
+                    self.finish_live_stroke(key);
                 }
                 Err(e) => log::error!(
                     "image_to_rendernode() failed in regenerate_rendering_with_images() with Err {}",
@@ -492,6 +384,7 @@ impl StrokesState {
                     render_comp.rendernode = rendernode;
                     render_comp.regenerate_flag = false;
                     render_comp.images.append(&mut images);
+                    render_comp.images_generation += 1;
                 }
                 Err(e) => log::error!(
                     "append_images_to_rendernode() failed in append_images_to_rendering() with Err {}",
@@ -518,35 +411,207 @@ impl StrokesState {
             });
     }
 
+    /// Evicts the cached renderings of lazily rendered pdf pages outside of the viewport, until they fit into the budget in bytes.
+    /// The pages farthest from the viewport are evicted first. They are rendered again when they enter the viewport
+    pub fn evict_pdf_page_renders(&mut self, viewport: Option<AABB>, budget: usize) {
+        let viewport = match viewport {
+            Some(viewport) => viewport,
+            None => return,
+        };
+
+        let mut cached_size = 0;
+        let mut offscreen_pages = vec![];
+        for &key in self.lazy_pdf_pages.iter() {
+            if let (Some(stroke), Some(render_comp)) =
+                (self.strokes.get(key), self.render_components.get(key))
+            {
+                let size = render_comp
+                    .images
+                    .iter()
+                    .map(|image| image.data.len())
+                    .sum::<usize>();
+                if size == 0 {
+                    continue;
+                }
+                cached_size += size;
+
+                if !viewport.intersects(&stroke.bounds()) {
+                    let distance = (stroke.bounds().center() - viewport.center()).norm();
+                    offscreen_pages.push((key, size, distance));
+                }
+            }
+        }
+        if cached_size <= budget {
+            return;
+        }
+
+        // Farthest first
+        offscreen_pages.sort_unstable_by(|(_, _, first), (_, _, second)| second.total_cmp(first));
+
+        for (key, size, _distance) in offscreen_pages {
+            if cached_size <= budget {
+                break;
+            }
+
+            if let Some(render_comp) = self.render_components.get_mut(key) {
+                render_comp.images.clear();
+                render_comp.images_generation += 1;
+                render_comp.rendernode = render::default_rendernode();
+                render_comp.regenerate_flag = true;
+
+                cached_size -= size;
+            }
+        }
+    }
+
+    /// Evicts all cached renderings of lazily rendered pdf pages outside of the viewport. Used when the system is low on memory
+    pub fn evict_offscreen_pdf_page_renders(&mut self, viewport: Option<AABB>) {
+        self.evict_pdf_page_renders(viewport, 0);
+    }
+
     /// Draws the strokes without the selection
-    pub fn draw_strokes(&self, snapshot: &Snapshot, viewport: Option<AABB>) {
-        self.keys_sorted_chrono()
-            .iter()
+    pub fn draw_strokes(&self, snapshot: &NodeSnapshot, viewport: Option<AABB>) {
+        // Only the strokes in the viewport are looked up
+        let keys = match viewport {
+            Some(viewport) => self.keys_sorted_chrono_intersecting_bounds(viewport),
+            None => self.keys_sorted_chrono(),
+        };
+
+        keys.iter()
             .filter(|&&key| {
                 self.does_render(key).unwrap_or(false)
                     && !(self.trashed(key).unwrap_or(false))
                     && !(self.selected(key).unwrap_or(false))
+                    && !self.is_drawn_live(key)
             })
             .for_each(|&key| {
-                if let (Some(stroke), Some(render_comp)) =
-                    (self.strokes.get(key), self.render_components.get(key))
-                {
-                    // skip if stroke is not in viewport
-                    if let Some(viewport) = viewport {
-                        if !viewport.intersects(&stroke.bounds()) {
-                            return;
-                        }
+                if let Some(render_comp) = self.render_components.get(key) {
+                    snapshot.append_node(&render_comp.rendernode);
+                }
+            });
+    }
+
+    /// The strokes which are drawn into the tile in their chronological order, and a hash over everything which changes their rendering
+    fn tile_content(&self, tile_bounds: AABB) -> (Vec<StrokeKey>, u64) {
+        let keys = self
+            .keys_sorted_chrono_intersecting_bounds(tile_bounds)
+            .into_iter()
+            .filter(|&key| {
+                self.does_render(key).unwrap_or(false)
+                    && !(self.trashed(key).unwrap_or(false))
+                    && !(self.selected(key).unwrap_or(false))
+                    && !self.is_drawn_live(key)
+            })
+            .collect::<Vec<StrokeKey>>();
+
+        let mut hasher = DefaultHasher::new();
+        for &key in keys.iter() {
+            key.hash(&mut hasher);
+            if let Some(render_comp) = self.render_components.get(key) {
+                render_comp.images_generation.hash(&mut hasher);
+                for image in render_comp.images.iter() {
+                    image
+                        .bounds
+                        .mins
+                        .iter()
+                        .chain(image.bounds.maxs.iter())
+                        .for_each(|coord| coord.to_bits().hash(&mut hasher));
+                }
+            }
+        }
+
+        (keys, hasher.finish())
+    }
+
+    /// Draws the strokes without the selection from the cached tiles of the level.
+    /// Tiles which are not cached or stale are drawn from the renderings of the strokes instead.
+    /// Returns true if tiles are missing, they then should be composited with composite_tiles()
+    pub fn draw_strokes_tiled(&mut self, snapshot: &NodeSnapshot, viewport: AABB, level: i32) -> bool {
+        let mut tiles_missing = false;
+
+        for tile_key in TileCache::tile_keys_for_bounds(level, viewport) {
+            let tile_bounds = tile_key.bounds();
+            let (keys, hash) = self.tile_content(tile_bounds);
+            if keys.is_empty() {
+                continue;
+            }
+            let tile_rect =
+                geometry::aabb_to_graphene_rect(geometry::aabb_scale(tile_bounds, self.zoom));
+
+            if let Some(texture) = self.tile_cache.valid_texture(tile_key, hash) {
+                snapshot.append_texture(&texture, &tile_rect);
+            } else {
+                tiles_missing = true;
+
+                snapshot.push_clip(&tile_rect);
+                for key in keys {
+                    if let Some(render_comp) = self.render_components.get(key) {
+                        snapshot.append_node(&render_comp.rendernode);
                     }
+                }
+                snapshot.pop();
+            }
+        }
 
+        tiles_missing
+    }
+
+    /// Composites the missing and stale tiles of the level in the viewport from the renderings of the strokes, at most the given number of them.
+    pub fn composite_tiles(
+        &mut self,
+        renderer: &gsk::Renderer,
+        viewport: AABB,
+        level: i32,
+        max_tiles: usize,
+    ) {
+        let level_zoom = TileCache::level_zoom(level);
+        let mut n_composited = 0;
+
+        for tile_key in TileCache::tile_keys_for_bounds(level, viewport) {
+            if n_composited >= max_tiles {
+                break;
+            }
+            let tile_bounds = tile_key.bounds();
+            let (keys, hash) = self.tile_content(tile_bounds);
+            if keys.is_empty() || self.tile_cache.valid_texture(tile_key, hash).is_some() {
+                continue;
+            }
+
+            // The renderings of the strokes are in the coordinate space of the sheet scaled by the current zoom
+            let snapshot = NodeSnapshot::new();
+            snapshot.scale(
+                (level_zoom / self.zoom) as f32,
+                (level_zoom / self.zoom) as f32,
+            );
+            for key in keys {
+                if let Some(render_comp) = self.render_components.get(key) {
                     snapshot.append_node(&render_comp.rendernode);
                 }
-            });
+            }
+
+            match render::rendernode_to_texture(
+                renderer,
+                &snapshot.to_node(),
+                Some(geometry::aabb_scale(tile_bounds, level_zoom)),
+            ) {
+                Ok(texture) => {
+                    self.tile_cache.insert(tile_key, texture, hash);
+                }
+                Err(e) => {
+                    log::error!(
+                        "rendernode_to_texture() failed in composite_tiles() with Err {}",
+                        e
+                    );
+                }
+            }
+            n_composited += 1;
+        }
     }
 
     /// Draws the selection
-    pub fn draw_selection(&self, zoom: f64, snapshot: &Snapshot) {
+    pub fn draw_selection(&self, zoom: f64, snapshot: &NodeSnapshot) {
 
-        fn draw_selected_bounds(bounds: AABB, zoom: f64, snapshot: &Snapshot) {
+        fn draw_selected_bounds(bounds: AABB, zoom: f64, snapshot: &NodeSnapshot) {
             let bounds = graphene::Rect::new(
                 bounds.mins[0] as f32,
                 bounds.mins[1] as f32,
@@ -602,7 +667,7 @@ impl StrokesState {
             });
     }
 
-    pub fn draw_debug(&self, zoom: f64, snapshot: &Snapshot) {
+    pub fn draw_debug(&self, zoom: f64, snapshot: &NodeSnapshot) {
         self.keys_sorted_chrono().iter().for_each(|&key| {
             let stroke = if let Some(stroke) = self.strokes.get(key) {
                 stroke
@@ -619,9 +684,9 @@ impl StrokesState {
                     }
                 }
                 if render_comp.regenerate_flag {
-                    canvas::debug::draw_fill(
+                    debug::draw_fill(
                         stroke.bounds(),
-                        canvas::debug::COLOR_STROKE_REGENERATE_FLAG,
+                        debug::COLOR_STROKE_REGENERATE_FLAG,
                         zoom,
                         snapshot,
                     );
@@ -630,72 +695,72 @@ impl StrokesState {
             match stroke {
                 StrokeStyle::MarkerStroke(markerstroke) => {
                     for element in markerstroke.elements.iter() {
-                        canvas::debug::draw_pos(
+                        debug::draw_pos(
                             element.inputdata.pos(),
-                            canvas::debug::COLOR_POS,
+                            debug::COLOR_POS,
                             zoom,
                             snapshot,
                         )
                     }
                     for &hitbox_elem in markerstroke.hitbox.iter() {
-                        canvas::debug::draw_bounds(
+                        debug::draw_bounds(
                             hitbox_elem,
-                            canvas::debug::COLOR_STROKE_HITBOX,
+                            debug::COLOR_STROKE_HITBOX,
                             zoom,
                             snapshot,
                         );
                     }
-                    canvas::debug::draw_bounds(
+                    debug::draw_bounds(
                         markerstroke.bounds,
-                        canvas::debug::COLOR_STROKE_BOUNDS,
+                        debug::COLOR_STROKE_BOUNDS,
                         zoom,
                         snapshot,
                     );
                 }
                 StrokeStyle::BrushStroke(brushstroke) => {
                     for element in brushstroke.elements.iter() {
-                        canvas::debug::draw_pos(
+                        debug::draw_pos(
                             element.inputdata.pos(),
-                            canvas::debug::COLOR_POS,
+                            debug::COLOR_POS,
                             zoom,
                             snapshot,
                         )
                     }
                     for &hitbox_elem in brushstroke.hitboxes.iter() {
-                        canvas::debug::draw_bounds(
+                        debug::draw_bounds(
                             hitbox_elem,
-                            canvas::debug::COLOR_STROKE_HITBOX,
+                            debug::COLOR_STROKE_HITBOX,
                             zoom,
                             snapshot,
                         );
                     }
-                    canvas::debug::draw_bounds(
+                    debug::draw_bounds(
                         brushstroke.bounds,
-                        canvas::debug::COLOR_STROKE_BOUNDS,
+                        debug::COLOR_STROKE_BOUNDS,
                         zoom,
                         snapshot,
                     );
                 }
                 StrokeStyle::ShapeStroke(shapestroke) => {
-                    canvas::debug::draw_bounds(
+                    debug::draw_bounds(
                         shapestroke.bounds,
-                        canvas::debug::COLOR_STROKE_BOUNDS,
+                        debug::COLOR_STROKE_BOUNDS,
                         zoom,
                         snapshot,
                     );
                 }
                 StrokeStyle::VectorImage(vectorimage) => {
-                    canvas::debug::draw_bounds(
+                    debug::draw_bounds(
                         vectorimage.bounds,
-                        canvas::debug::COLOR_STROKE_BOUNDS,
+                        debug::COLOR_STROKE_BOUNDS,
                         zoom,
                         snapshot,
                     );
                 }
                 StrokeStyle::BitmapImage(bitmapimage) => {
-                    canvas::debug::draw_bounds(
+                    debug::draw_bounds(
                         bitmapimage.bounds,
-                        canvas::debug::COLOR_STROKE_BOUNDS,
+                        debug::COLOR_STROKE_BOUNDS,
                         zoom,
                         snapshot,
                     );
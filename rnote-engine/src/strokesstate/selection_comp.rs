@@ -0,0 +1,713 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::{GroupComponent, StrokeKey, StrokeStyle, StrokesState};
+use crate::compose::geometry;
+use crate::drawbehaviour::DrawBehaviour;
+use crate::pdf::PdfDocument;
+use crate::pens::selector::{self, Selector};
+use crate::strokes::strokebehaviour::StrokeBehaviour;
+use crate::{compose, render};
+
+use geo::line_string;
+use geo::prelude::*;
+use gdk::{gio, prelude::*};
+use p2d::bounding_volume::{BoundingVolume, AABB};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "selection_component")]
+pub struct SelectionComponent {
+    #[serde(default, rename = "selected")]
+    pub selected: bool,
+}
+
+impl Default for SelectionComponent {
+    fn default() -> Self {
+        Self { selected: false }
+    }
+}
+
+impl SelectionComponent {
+    pub const SELECTION_DUPLICATION_OFFSET_X: f64 = 20.0;
+    pub const SELECTION_DUPLICATION_OFFSET_Y: f64 = 20.0;
+
+    pub fn new(selected: bool) -> Self {
+        Self { selected }
+    }
+}
+
+/// The edge or the center of the selection bounds to which the selected strokes are aligned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    HorizontalCenter,
+    Right,
+    Top,
+    VerticalCenter,
+    Bottom,
+}
+
+impl Alignment {
+    /// The offset which aligns the bounds to the reference bounds
+    fn offset(&self, bounds: AABB, reference: AABB) -> na::Vector2<f64> {
+        match self {
+            Self::Left => na::vector![reference.mins[0] - bounds.mins[0], 0.0],
+            Self::HorizontalCenter => na::vector![reference.center()[0] - bounds.center()[0], 0.0],
+            Self::Right => na::vector![reference.maxs[0] - bounds.maxs[0], 0.0],
+            Self::Top => na::vector![0.0, reference.mins[1] - bounds.mins[1]],
+            Self::VerticalCenter => na::vector![0.0, reference.center()[1] - bounds.center()[1]],
+            Self::Bottom => na::vector![0.0, reference.maxs[1] - bounds.maxs[1]],
+        }
+    }
+}
+
+/// The strokes which are copied into the clipboard, in their chronological order and together with their groups.
+/// Pasting them back creates real strokes instead of a flat image
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "strokes_clipboard")]
+pub struct StrokesClipboard {
+    #[serde(rename = "strokes")]
+    pub strokes: Vec<(StrokeStyle, GroupComponent)>,
+    /// The pdf documents the lazily rendered pdf pages in the strokes are rendered from, by the keys they are referenced with
+    #[serde(rename = "pdf_documents")]
+    pub pdf_documents: BTreeMap<u32, PdfDocument>,
+}
+
+impl StrokesClipboard {
+    /// The mime type of the serialized strokes in the clipboard
+    pub const MIME_TYPE: &'static str = "application/x-rnote-strokes";
+
+    pub fn to_json(&self) -> Result<String, anyhow::Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, anyhow::Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The bounds enclosing all strokes. None if the clipboard is empty
+    pub fn bounds(&self) -> Option<AABB> {
+        self.strokes
+            .iter()
+            .map(|(stroke, _)| stroke.bounds())
+            .reduce(|acc, bounds| acc.merged(&bounds))
+    }
+}
+
+impl StrokesState {
+    /// Returns false if selecting is unsupported
+    pub fn can_select(&self, key: StrokeKey) -> bool {
+        self.selection_components.get(key).is_some()
+    }
+
+    pub fn selected(&self, key: StrokeKey) -> Option<bool> {
+        if let Some(selection_comp) = self.selection_components.get(key) {
+            Some(selection_comp.selected)
+        } else {
+            log::debug!(
+                "get selection_comp in selected() returned None for stroke with key {:?}",
+                key
+            );
+            None
+        }
+    }
+
+    /// Sets if the stroke is currently selected
+    pub fn set_selected(&mut self, key: StrokeKey, selected: bool) {
+        if let Some(selection_comp) = self.selection_components.get_mut(key) {
+            selection_comp.selected = selected;
+
+            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                self.chrono_counter += 1;
+                chrono_comp.t = self.chrono_counter;
+            }
+        } else {
+            log::debug!(
+                "get selection_comp in set_selected() returned None for stroke with key {:?}",
+                key
+            );
+        }
+    }
+
+    /// Returns all keys for the selection
+    pub fn selection_keys(&self) -> Vec<StrokeKey> {
+        self.keys_sorted_chrono()
+            .iter()
+            .filter_map(|&key| {
+                let selection_comp = self.selection_components.get(key)?;
+
+                if selection_comp.selected {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<StrokeKey>>()
+    }
+
+    pub fn selection_len(&self) -> usize {
+        self.selection_keys().len()
+    }
+
+    pub fn gen_selection_bounds(&self) -> Option<AABB> {
+        self.gen_bounds(&self.selection_keys())
+    }
+
+    pub fn deselect_all_strokes(&mut self) {
+        self.keys_sorted_chrono().iter().for_each(|&key| {
+            if let Some(selection_comp) = self.selection_components.get_mut(key) {
+                if selection_comp.selected {
+                    if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                        self.chrono_counter += 1;
+                        chrono_comp.t = self.chrono_counter;
+                    }
+                    selection_comp.selected = false;
+                }
+            }
+        });
+    }
+
+    pub fn duplicate_selection(&mut self) {
+        let offset = na::vector![
+            SelectionComponent::SELECTION_DUPLICATION_OFFSET_X,
+            SelectionComponent::SELECTION_DUPLICATION_OFFSET_Y
+        ];
+
+        let old_selected = self.selection_keys();
+        self.deselect_all_strokes();
+
+        let duplicated = old_selected
+            .iter()
+            .map(|&key| {
+                let new_key = self.insert_stroke(self.strokes.get(key).unwrap().clone());
+                self.set_selected(new_key, true);
+                (key, new_key)
+            })
+            .collect::<Vec<(StrokeKey, StrokeKey)>>();
+        // The duplicated groups become groups of their own
+        self.copy_groups_to_new_strokes(&duplicated);
+
+        let new_selected = duplicated
+            .into_iter()
+            .map(|(_, new_key)| new_key)
+            .collect::<Vec<StrokeKey>>();
+
+        // Offsetting the new selected stroke to make the duplication apparent to the user
+        self.translate_strokes(&new_selected, offset);
+    }
+
+    /// The selected strokes and their groups, for copying them into the clipboard
+    pub fn gen_clipboard_selection(&self) -> StrokesClipboard {
+        let strokes = self
+            .keys_sorted_chrono()
+            .into_iter()
+            .filter(|&key| {
+                !(self.trashed(key).unwrap_or(true)) && self.selected(key).unwrap_or(false)
+            })
+            .filter_map(|key| {
+                Some((
+                    self.strokes.get(key)?.clone(),
+                    self.group_components.get(key).cloned().unwrap_or_default(),
+                ))
+            })
+            .collect::<Vec<(StrokeStyle, GroupComponent)>>();
+
+        let pdf_documents = strokes
+            .iter()
+            .filter_map(|(stroke, _)| stroke.pdf_page())
+            .filter_map(|(pdf_origin, _)| {
+                Some((
+                    pdf_origin.document,
+                    self.pdf_documents.get(&pdf_origin.document)?.clone(),
+                ))
+            })
+            .collect::<BTreeMap<u32, PdfDocument>>();
+
+        StrokesClipboard {
+            strokes,
+            pdf_documents,
+        }
+    }
+
+    /// Inserts the strokes of the clipboard centered around the position and selects them. The pasted groups become groups of their own.
+    /// Returns the keys of the inserted strokes
+    pub fn insert_clipboard_strokes(
+        &mut self,
+        clipboard: StrokesClipboard,
+        center: na::Vector2<f64>,
+    ) -> Vec<StrokeKey> {
+        let offset = match clipboard.bounds() {
+            Some(bounds) => center - bounds.center().coords,
+            None => return vec![],
+        };
+
+        self.deselect_all_strokes();
+
+        // The pdf documents are stored again, their keys in this state may differ from the ones in the clipboard
+        let pdf_documents = clipboard
+            .pdf_documents
+            .iter()
+            .map(|(&key, pdf_document)| (key, self.insert_pdf_document(&pdf_document.data)))
+            .collect::<BTreeMap<u32, u32>>();
+
+        let (keys, groups): (Vec<StrokeKey>, Vec<(StrokeKey, Vec<u32>)>) = clipboard
+            .strokes
+            .into_iter()
+            .map(|(mut stroke, group_comp)| {
+                stroke.translate(offset);
+                match &mut stroke {
+                    StrokeStyle::VectorImage(vectorimage) => {
+                        if let Some(pdf_origin) = vectorimage.pdf_origin.as_mut() {
+                            if let Some(&document) = pdf_documents.get(&pdf_origin.document) {
+                                pdf_origin.document = document;
+                            }
+                        }
+                    }
+                    StrokeStyle::BitmapImage(bitmapimage) => {
+                        if let Some(pdf_origin) = bitmapimage.pdf_origin.as_mut() {
+                            if let Some(&document) = pdf_documents.get(&pdf_origin.document) {
+                                pdf_origin.document = document;
+                            }
+                            bitmapimage.pdf_data = self
+                                .pdf_documents
+                                .get(&pdf_origin.document)
+                                .map(|pdf_document| Arc::clone(&pdf_document.data));
+                        }
+                    }
+                    _ => {}
+                }
+
+                let key = self.insert_stroke(stroke);
+                self.set_selected(key, true);
+                (key, (key, group_comp.groups))
+            })
+            .unzip();
+        self.insert_groups_renumbered(groups);
+
+        keys
+    }
+
+    /// Aligns the selected strokes to the edge or the center of the selection bounds. Groups are aligned as a whole
+    pub fn align_selection(&mut self, alignment: Alignment) {
+        let selection_bounds = match self.gen_selection_bounds() {
+            Some(selection_bounds) => selection_bounds,
+            None => return,
+        };
+
+        for unit in self.selection_units() {
+            if let Some(bounds) = self.gen_bounds(&unit) {
+                self.translate_strokes(&unit, alignment.offset(bounds, selection_bounds));
+            }
+        }
+    }
+
+    /// Distributes the selected strokes horizontally or vertically, so that the gaps between them are equal.
+    /// The outermost strokes stay in place, groups are moved as a whole
+    pub fn distribute_selection(&mut self, horizontal: bool) {
+        let axis = if horizontal { 0 } else { 1 };
+
+        let mut units = self
+            .selection_units()
+            .into_iter()
+            .filter_map(|unit| Some((self.gen_bounds(&unit)?, unit)))
+            .filter(|(bounds, _)| bounds.mins[axis].is_finite() && bounds.maxs[axis].is_finite())
+            .collect::<Vec<(AABB, Vec<StrokeKey>)>>();
+        if units.len() < 3 {
+            return;
+        }
+        units.sort_unstable_by(|(first, _), (second, _)| {
+            first.center()[axis].total_cmp(&second.center()[axis])
+        });
+
+        let start = units
+            .iter()
+            .fold(f64::MAX, |acc, (bounds, _)| acc.min(bounds.mins[axis]));
+        let end = units
+            .iter()
+            .fold(f64::MIN, |acc, (bounds, _)| acc.max(bounds.maxs[axis]));
+        let extents_sum = units
+            .iter()
+            .map(|(bounds, _)| bounds.extents()[axis])
+            .sum::<f64>();
+        let gap = (end - start - extents_sum) / (units.len() - 1) as f64;
+
+        let mut pos = start;
+        for (bounds, unit) in units {
+            let mut offset = na::Vector2::zeros();
+            offset[axis] = pos - bounds.mins[axis];
+
+            self.translate_strokes(&unit, offset);
+            pos += bounds.extents()[axis] + gap;
+        }
+    }
+
+    /// Flips the selected strokes horizontally and / or vertically at the center of the selection bounds
+    pub fn flip_selection(&mut self, horizontal: bool, vertical: bool) {
+        let center = match self.gen_selection_bounds() {
+            Some(selection_bounds) => selection_bounds.center(),
+            None => return,
+        };
+
+        let selection_keys = self.selection_keys();
+        self.flip_strokes(&selection_keys, horizontal, vertical, center);
+    }
+
+    /// Moves and resizes the selection to the new bounds. When `scale_widths` is set, the stroke widths are scaled with the selection
+    pub fn resize_selection_to_bounds(&mut self, new_bounds: AABB, scale_widths: bool) {
+        let selection_bounds = match self.gen_selection_bounds() {
+            Some(selection_bounds) => selection_bounds,
+            None => return,
+        };
+        let selection_keys = self.selection_keys();
+
+        if scale_widths {
+            let scale = new_bounds
+                .extents()
+                .component_div(&selection_bounds.extents());
+            // The geometric mean, so that non-uniform scaling changes the widths moderately
+            let factor = (scale[0] * scale[1]).abs().sqrt();
+
+            if factor.is_finite() && factor > 0.0 {
+                for &key in selection_keys.iter() {
+                    if let Some(stroke) = self.strokes.get_mut(key) {
+                        stroke.scale_width(factor);
+                    }
+                }
+            }
+        }
+
+        // The widths change the bounds, so the selection bounds are generated again
+        if let Some(selection_bounds) = self.gen_selection_bounds() {
+            self.resize_strokes(&selection_keys, selection_bounds, new_bounds);
+        }
+    }
+
+    /// Rotates the selection around the center of its bounds by the angle (rad)
+    pub fn rotate_selection(&mut self, angle: f64) {
+        let center = match self.gen_selection_bounds() {
+            Some(selection_bounds) => selection_bounds.center(),
+            None => return,
+        };
+
+        let selection_keys = self.selection_keys();
+        self.rotate_strokes(&selection_keys, angle, center);
+    }
+
+    /// Returns true if selection has changed
+    pub fn update_selection_for_selector(
+        &mut self,
+        selector: &Selector,
+        viewport: Option<AABB>,
+    ) -> bool {
+        let selection_len_prev = self.selection_len();
+
+        let selector_polygon = match selector.style() {
+            selector::SelectorStyle::Polygon => {
+                let selector_path_points = selector
+                    .path
+                    .par_iter()
+                    .map(|inputdata| geo::Coordinate {
+                        x: inputdata.pos()[0],
+                        y: inputdata.pos()[1],
+                    })
+                    .collect::<Vec<geo::Coordinate<f64>>>();
+
+                geo::Polygon::new(selector_path_points.into(), vec![])
+            }
+            selector::SelectorStyle::Rectangle => {
+                if let (Some(first), Some(last)) = (selector.path.first(), selector.path.last()) {
+                    let selector_path_points = line_string![
+                        (x: first.pos()[0], y: first.pos()[1]),
+                        (x: first.pos()[0], y: last.pos()[1]),
+                        (x: last.pos()[0], y: last.pos()[1]),
+                        (x: last.pos()[0], y: first.pos()[1]),
+                        (x: first.pos()[0], y: first.pos()[1]),
+                    ];
+
+                    geo::Polygon::new(selector_path_points, vec![])
+                } else {
+                    return false;
+                }
+            }
+        };
+
+        // Only the strokes in the viewport are looked up
+        let keys = match viewport {
+            Some(viewport) => self.keys_sorted_chrono_intersecting_bounds(viewport),
+            None => self.keys_sorted_chrono(),
+        };
+
+        keys.iter().for_each(|&key| {
+            let stroke = if let Some(stroke) = self.strokes.get(key) {
+                stroke
+            } else {
+                return;
+            };
+            // skip if stroke is trashed
+            if let Some(trash_comp) = self.trash_components.get(key) {
+                if trash_comp.trashed {
+                    return;
+                }
+            }
+            if let Some(selection_comp) = self.selection_components.get_mut(key) {
+                // default to not selected, check for if selected
+                selection_comp.selected = false;
+
+                match stroke {
+                    StrokeStyle::MarkerStroke(markerstroke) => {
+                        if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(markerstroke.bounds))
+                        {
+                            selection_comp.selected = true;
+                        } else if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(markerstroke.bounds))
+                        {
+                            for &hitbox_elem in markerstroke.hitbox.iter() {
+                                if !selector_polygon
+                                    .contains(&geometry::p2d_aabb_to_geo_polygon(hitbox_elem))
+                                {
+                                    return;
+                                }
+                            }
+                            selection_comp.selected = true;
+
+                            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                                self.chrono_counter += 1;
+                                chrono_comp.t = self.chrono_counter;
+                            }
+                        }
+                    }
+                    StrokeStyle::BrushStroke(brushstroke) => {
+                        if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(brushstroke.bounds))
+                        {
+                            selection_comp.selected = true;
+                        } else if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(brushstroke.bounds))
+                        {
+                            for &hitbox_elem in brushstroke.hitboxes.iter() {
+                                if !selector_polygon
+                                    .contains(&geometry::p2d_aabb_to_geo_polygon(hitbox_elem))
+                                {
+                                    return;
+                                }
+                            }
+                            selection_comp.selected = true;
+
+                            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                                self.chrono_counter += 1;
+                                chrono_comp.t = self.chrono_counter;
+                            }
+                        }
+                    }
+                    StrokeStyle::ShapeStroke(shapestroke) => {
+                        if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(shapestroke.bounds))
+                        {
+                            selection_comp.selected = true;
+
+                            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                                self.chrono_counter += 1;
+                                chrono_comp.t = self.chrono_counter;
+                            }
+                        }
+                    }
+                    StrokeStyle::VectorImage(vectorimage) => {
+                        if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(vectorimage.bounds))
+                        {
+                            selection_comp.selected = true;
+
+                            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                                self.chrono_counter += 1;
+                                chrono_comp.t = self.chrono_counter;
+                            }
+                        }
+                    }
+                    StrokeStyle::BitmapImage(bitmapimage) => {
+                        if selector_polygon
+                            .contains(&geometry::p2d_aabb_to_geo_polygon(bitmapimage.bounds))
+                        {
+                            selection_comp.selected = true;
+
+                            if let Some(chrono_comp) = self.chrono_components.get_mut(key) {
+                                self.chrono_counter += 1;
+                                chrono_comp.t = self.chrono_counter;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        // Strokes in a group are only selected together with the whole group
+        self.select_groups_of_selection();
+
+        if self.selection_len() != selection_len_prev {
+            self.regenerate_rendering_for_selection_threaded();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// the svgs of the current selection, without xml header or svg root
+    pub fn gen_svgs_selection(&self) -> Result<Vec<render::Svg>, anyhow::Error> {
+        Ok(self
+            .keys_sorted_chrono()
+            .iter()
+            .filter(|&&key| {
+                self.does_render(key).unwrap_or(false)
+                    && !(self.trashed(key).unwrap_or(false))
+                    && (self.selected(key).unwrap_or(false))
+                    && (self.does_render(key).unwrap_or(false))
+            })
+            .filter_map(|&key| {
+                let stroke = self.strokes.get(key)?;
+
+                stroke.gen_svgs(na::vector![0.0, 0.0]).ok()
+            })
+            .flatten()
+            .collect::<Vec<render::Svg>>())
+    }
+
+    /// The selection as a complete svg, with xml header and svg root. None if nothing is selected
+    pub fn gen_svg_selection(&self) -> Result<Option<String>, anyhow::Error> {
+        let selection_svgs = self.gen_svgs_selection()?;
+        let selection_bounds = if let Some(selection_bounds) = self.gen_selection_bounds() {
+            selection_bounds
+        } else {
+            return Ok(None);
+        };
+
+        let svg_data = selection_svgs
+            .iter()
+            .map(|svg| svg.svg_data.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        Ok(Some(compose::wrap_svg_root(
+            svg_data.as_str(),
+            Some(selection_bounds),
+            Some(selection_bounds),
+            true,
+        )))
+    }
+
+    /// The selection as encoded bitmap image, rendered with the zoom. None if nothing is selected
+    pub fn gen_bitmapimage_selection(
+        &self,
+        format: render::BitmapExportFormat,
+        zoom: f64,
+    ) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        let selection_svgs = self.gen_svgs_selection()?;
+        let selection_bounds = if let Some(selection_bounds) = self.gen_selection_bounds() {
+            selection_bounds
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            self.renderer
+                .read()
+                .unwrap()
+                .gen_image(zoom, &selection_svgs, selection_bounds)?
+                .encode(format)?,
+        ))
+    }
+
+    pub fn export_selection_as_svg(&self, file: gio::File) -> Result<(), anyhow::Error> {
+        let svg_data = if let Some(svg_data) = self.gen_svg_selection()? {
+            svg_data
+        } else {
+            return Ok(());
+        };
+
+        file.replace_async(
+            None,
+            false,
+            gio::FileCreateFlags::REPLACE_DESTINATION,
+            glib::PRIORITY_HIGH_IDLE,
+            None::<&gio::Cancellable>,
+            move |result| {
+                let output_stream = match result {
+                    Ok(output_stream) => output_stream,
+                    Err(e) => {
+                        log::error!(
+                            "replace_async() failed in export_selection_as_svg() with Err {}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                if let Err(e) = output_stream.write(svg_data.as_bytes(), None::<&gio::Cancellable>)
+                {
+                    log::error!(
+                        "output_stream().write() failed in export_selection_as_svg() with Err {}",
+                        e
+                    );
+                };
+                if let Err(e) = output_stream.close(None::<&gio::Cancellable>) {
+                    log::error!(
+                        "output_stream().close() failed in export_selection_as_svg() with Err {}",
+                        e
+                    );
+                };
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Exports the selection as bitmap image, rendered with the zoom
+    pub fn export_selection_as_bitmapimage(
+        &self,
+        file: gio::File,
+        format: render::BitmapExportFormat,
+        zoom: f64,
+    ) -> Result<(), anyhow::Error> {
+        let image_bytes = if let Some(image_bytes) = self.gen_bitmapimage_selection(format, zoom)? {
+            image_bytes
+        } else {
+            return Ok(());
+        };
+
+        file.replace_async(
+            None,
+            false,
+            gio::FileCreateFlags::REPLACE_DESTINATION,
+            glib::PRIORITY_HIGH_IDLE,
+            None::<&gio::Cancellable>,
+            move |result| {
+                let output_stream = match result {
+                    Ok(output_stream) => output_stream,
+                    Err(e) => {
+                        log::error!(
+                            "replace_async() failed in export_selection_as_bitmapimage() with Err {}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                if let Err(e) = output_stream.write(&image_bytes, None::<&gio::Cancellable>) {
+                    log::error!(
+                        "output_stream().write() failed in export_selection_as_bitmapimage() with Err {}",
+                        e
+                    );
+                };
+                if let Err(e) = output_stream.close(None::<&gio::Cancellable>) {
+                    log::error!(
+                        "output_stream().close() failed in export_selection_as_bitmapimage() with Err {}",
+                        e
+                    );
+                };
+            },
+        );
+
+        Ok(())
+    }
+}
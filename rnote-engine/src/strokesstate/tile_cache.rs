@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use gdk::prelude::*;
+use p2d::bounding_volume::AABB;
+
+/// The position of a tile. The tiles of a level have a fixed size in pixels, and cover the sheet in a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    pub level: i32,
+    pub x: i64,
+    pub y: i64,
+}
+
+impl TileKey {
+    /// The bounds of the tile in the coordinate space of the sheet
+    pub fn bounds(&self) -> AABB {
+        let extent = TileCache::tile_extent(self.level);
+
+        AABB::new(
+            na::point![self.x as f64 * extent, self.y as f64 * extent],
+            na::point![(self.x + 1) as f64 * extent, (self.y + 1) as f64 * extent],
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tile {
+    texture: gdk::Texture,
+    /// The hash of the content the tile was composited from
+    hash: u64,
+    last_used: u64,
+}
+
+impl Tile {
+    fn size(&self) -> usize {
+        (self.texture.width() * self.texture.height() * 4) as usize
+    }
+}
+
+/// A cache of textures into which the strokes are composited, in fixed size tiles per zoom level.
+/// When it is zoomed out, drawing a few tiles is much cheaper than drawing the rendering of every stroke.
+/// Tiles are evicted in least recently used order when the cache exceeds its memory budget
+#[derive(Debug, Default)]
+pub struct TileCache {
+    tiles: HashMap<TileKey, Tile>,
+    /// Increased every time a tile is used
+    use_counter: u64,
+    /// The size in bytes of all cached tiles
+    size: usize,
+}
+
+impl TileCache {
+    /// The width and height of the tiles in pixels
+    pub const TILE_SIZE: f64 = 512.0;
+    /// The size in bytes the cached tiles may occupy
+    pub const MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+    /// Tiles are only used when the zoom is below this zoom. Above it there are not enough strokes in the viewport for them to pay off
+    pub const MAX_ZOOM: f64 = 1.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+        self.size = 0;
+    }
+
+    /// Wether tiles are used at the zoom
+    pub fn enabled_for_zoom(zoom: f64) -> bool {
+        zoom < Self::MAX_ZOOM
+    }
+
+    /// The lowest level which has at least the detail of the zoom
+    pub fn level_for_zoom(zoom: f64) -> i32 {
+        zoom.log2().ceil() as i32
+    }
+
+    /// The zoom the tiles of the level are composited at
+    pub fn level_zoom(level: i32) -> f64 {
+        2.0_f64.powi(level)
+    }
+
+    /// The width and height of the tiles of the level in the coordinate space of the sheet
+    pub fn tile_extent(level: i32) -> f64 {
+        Self::TILE_SIZE / Self::level_zoom(level)
+    }
+
+    /// The keys of the tiles of the level which intersect the bounds
+    pub fn tile_keys_for_bounds(level: i32, bounds: AABB) -> Vec<TileKey> {
+        let extent = Self::tile_extent(level);
+        let (x_start, x_end) = (
+            (bounds.mins[0] / extent).floor() as i64,
+            (bounds.maxs[0] / extent).floor() as i64,
+        );
+        let (y_start, y_end) = (
+            (bounds.mins[1] / extent).floor() as i64,
+            (bounds.maxs[1] / extent).floor() as i64,
+        );
+
+        (y_start..=y_end)
+            .flat_map(|y| (x_start..=x_end).map(move |x| TileKey { level, x, y }))
+            .collect::<Vec<TileKey>>()
+    }
+
+    /// Returns the texture of the tile, if it is cached and was composited from the content with the hash. Marks it as used
+    pub fn valid_texture(&mut self, tile_key: TileKey, hash: u64) -> Option<gdk::Texture> {
+        let tile = self.tiles.get_mut(&tile_key)?;
+        if tile.hash != hash {
+            return None;
+        }
+
+        self.use_counter += 1;
+        tile.last_used = self.use_counter;
+
+        Some(tile.texture.clone())
+    }
+
+    /// Inserts the composited tile, replacing the old one. Evicts the least recently used tiles when the cache exceeds the budget
+    pub fn insert(&mut self, tile_key: TileKey, texture: gdk::Texture, hash: u64) {
+        self.use_counter += 1;
+        let tile = Tile {
+            texture,
+            hash,
+            last_used: self.use_counter,
+        };
+
+        self.size += tile.size();
+        if let Some(old_tile) = self.tiles.insert(tile_key, tile) {
+            self.size -= old_tile.size();
+        }
+
+        while self.size > Self::MEMORY_BUDGET && self.tiles.len() > 1 {
+            let least_recently_used = self
+                .tiles
+                .iter()
+                .min_by_key(|(_tile_key, tile)| tile.last_used)
+                .map(|(&tile_key, _tile)| tile_key);
+
+            if let Some(tile) =
+                least_recently_used.and_then(|tile_key| self.tiles.remove(&tile_key))
+            {
+                self.size -= tile.size();
+            } else {
+                break;
+            }
+        }
+    }
+}
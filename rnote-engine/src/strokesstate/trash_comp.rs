@@ -54,6 +54,16 @@ impl StrokesState {
         }
     }
 
+    /// Sets the trash state without touching the chronology of the strokes,
+    /// for operations which are undone as a whole, like page operations.
+    pub fn set_trashed_keep_chrono(&mut self, keys: &[StrokeKey], trash: bool) {
+        keys.iter().for_each(|&key| {
+            if let Some(trash_comp) = self.trash_components.get_mut(key) {
+                trash_comp.trashed = trash;
+            }
+        });
+    }
+
     /// Resize needed after calling this
     pub fn undo_last_stroke(&mut self) -> Option<StrokeKey> {
         let last_stroke_key = self.last_stroke_key();
@@ -113,7 +123,15 @@ impl StrokesState {
                 ),
             );
 
-            self.strokes.iter_mut().for_each(|(key, stroke)| {
+            // Only the strokes near the eraser are looked up
+            let near_eraser = self.keys_intersecting_bounds(eraser_bounds);
+
+            near_eraser.into_iter().for_each(|key| {
+                let stroke = if let Some(stroke) = self.strokes.get(key) {
+                    stroke
+                } else {
+                    return;
+                };
                 if let Some(viewport) = viewport {
                     if !viewport.intersects(&stroke.bounds()) {
                         return;
@@ -0,0 +1,279 @@
+//! Tests of the conversion of svg path commands to tiny-skia paths, compared with how usvg parses the same path data.
+//! Run with `cargo test -p rnote-engine`
+
+extern crate nalgebra as na;
+extern crate parry2d_f64 as p2d;
+
+use rnote_engine::compose::drawpath;
+use rnote_engine::compose::{shapes, solid};
+use rnote_engine::pens::shaper::Shaper;
+use rnote_engine::strokes::strokebehaviour::StrokeTransform;
+use svg::node::element::{self, path};
+use usvg::NodeExt;
+
+/// The number of points each segment is sampled with
+const N_SAMPLES: usize = 256;
+/// The maximum distance of the sampled points to the other path
+const TOLERANCE: f64 = 0.5;
+
+/// Samples the bezier curve with the control points (including the start and end point) with de Casteljau's algorithm
+fn sample_bezier(samples: &mut Vec<(f64, f64)>, control_points: &[(f64, f64)]) {
+    for i in 0..=N_SAMPLES {
+        let t = i as f64 / N_SAMPLES as f64;
+        let mut points = control_points.to_vec();
+
+        while points.len() > 1 {
+            points = points
+                .iter()
+                .zip(points.iter().skip(1))
+                .map(|(p0, p1)| (p0.0 + (p1.0 - p0.0) * t, p0.1 + (p1.1 - p0.1) * t))
+                .collect();
+        }
+        samples.push(points[0]);
+    }
+}
+
+/// Returns the sampled points and the number of subpaths
+fn sample_skia_path(path: &tiny_skia::Path) -> (Vec<(f64, f64)>, usize) {
+    let point = |p: tiny_skia::Point| (f64::from(p.x), f64::from(p.y));
+    let mut samples = Vec::new();
+    let mut n_subpaths = 0;
+    let mut current = (0.0, 0.0);
+    let mut subpath_start = (0.0, 0.0);
+
+    for segment in path.segments() {
+        match segment {
+            tiny_skia::PathSegment::MoveTo(p) => {
+                current = point(p);
+                subpath_start = current;
+                n_subpaths += 1;
+                samples.push(current);
+            }
+            tiny_skia::PathSegment::LineTo(p) => {
+                sample_bezier(&mut samples, &[current, point(p)]);
+                current = point(p);
+            }
+            tiny_skia::PathSegment::QuadTo(p1, p) => {
+                sample_bezier(&mut samples, &[current, point(p1), point(p)]);
+                current = point(p);
+            }
+            tiny_skia::PathSegment::CubicTo(p1, p2, p) => {
+                sample_bezier(&mut samples, &[current, point(p1), point(p2), point(p)]);
+                current = point(p);
+            }
+            tiny_skia::PathSegment::Close => {
+                sample_bezier(&mut samples, &[current, subpath_start]);
+                current = subpath_start;
+            }
+        }
+    }
+
+    (samples, n_subpaths)
+}
+
+/// Parses the svg with usvg and samples all its paths in the coordinate space of the document.
+/// Returns the sampled points and the number of subpaths
+fn sample_usvg_paths(svg_data: &str) -> (Vec<(f64, f64)>, usize) {
+    let rtree = usvg::Tree::from_str(svg_data, &usvg::Options::default().to_ref()).unwrap();
+    let mut samples = Vec::new();
+    let mut n_subpaths = 0;
+
+    for node in rtree.root().descendants() {
+        let transform = node.abs_transform();
+
+        if let usvg::NodeKind::Path(ref usvg_path) = *node.borrow() {
+            let mut path_samples = Vec::new();
+            let mut current = (0.0, 0.0);
+            let mut subpath_start = (0.0, 0.0);
+
+            for segment in usvg_path.data.iter() {
+                match *segment {
+                    usvg::PathSegment::MoveTo { x, y } => {
+                        current = (x, y);
+                        subpath_start = current;
+                        n_subpaths += 1;
+                        path_samples.push(current);
+                    }
+                    usvg::PathSegment::LineTo { x, y } => {
+                        sample_bezier(&mut path_samples, &[current, (x, y)]);
+                        current = (x, y);
+                    }
+                    usvg::PathSegment::CurveTo {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    } => {
+                        sample_bezier(&mut path_samples, &[current, (x1, y1), (x2, y2), (x, y)]);
+                        current = (x, y);
+                    }
+                    usvg::PathSegment::ClosePath => {
+                        sample_bezier(&mut path_samples, &[current, subpath_start]);
+                        current = subpath_start;
+                    }
+                }
+            }
+
+            samples.extend(path_samples.into_iter().map(|(x, y)| transform.apply(x, y)));
+        }
+    }
+
+    (samples, n_subpaths)
+}
+
+/// The maximum distance of the points in `from` to their nearest point in `to`
+fn max_distance(from: &[(f64, f64)], to: &[(f64, f64)]) -> f64 {
+    from.iter()
+        .map(|p| {
+            to.iter()
+                .map(|q| ((p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)).sqrt())
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+}
+
+fn assert_paths_match(
+    skia_samples: (Vec<(f64, f64)>, usize),
+    usvg_samples: (Vec<(f64, f64)>, usize),
+) {
+    let (skia_samples, skia_n_subpaths) = skia_samples;
+    let (usvg_samples, usvg_n_subpaths) = usvg_samples;
+
+    assert!(!skia_samples.is_empty());
+    assert_eq!(skia_n_subpaths, usvg_n_subpaths);
+
+    let distance =
+        max_distance(&skia_samples, &usvg_samples).max(max_distance(&usvg_samples, &skia_samples));
+    assert!(
+        distance < TOLERANCE,
+        "the paths deviate by {} from each other",
+        distance
+    );
+}
+
+fn svg_document(content: &str) -> String {
+    format!(
+        r#"<svg width="200" height="200" viewBox="0 0 200 200" xmlns="http://www.w3.org/2000/svg">{}</svg>"#,
+        content
+    )
+}
+
+/// Converts the path data with `to_skia_path()` and compares it with the path usvg parses from the same data
+fn assert_matches_usvg(path_data: &str) {
+    let data = path::Data::parse(path_data).unwrap();
+    let skia_path = drawpath::to_skia_path(&data).unwrap();
+
+    let svg_data = svg_document(&format!(r#"<path d="{}" fill="black"/>"#, path_data));
+
+    assert_paths_match(sample_skia_path(&skia_path), sample_usvg_paths(&svg_data));
+}
+
+#[test]
+fn move_and_line() {
+    assert_matches_usvg("M 10 10 L 90 10 150 60 Z");
+    assert_matches_usvg("m 10 10 l 80 0 60 50 z");
+    // following pairs of a move are implicit lines
+    assert_matches_usvg("M 10 10 90 10 150 60 Z");
+    assert_matches_usvg("m 10 10 80 0 60 50 z");
+}
+
+#[test]
+fn horizontal_and_vertical_line() {
+    assert_matches_usvg("M 10 10 H 90 V 50 H 150 120 V 180 Z");
+    assert_matches_usvg("M 10 10 h 80 v 40 h 60 -30 v 130 z");
+}
+
+#[test]
+fn quadratic_curve() {
+    assert_matches_usvg("M 10 50 Q 30 10 50 50 70 90 90 50");
+    assert_matches_usvg("M 10 50 q 20 -40 40 0 20 40 40 0");
+}
+
+#[test]
+fn smooth_quadratic_curve() {
+    assert_matches_usvg("M 10 50 Q 30 10 50 50 T 90 50 130 50");
+    assert_matches_usvg("M 10 50 q 20 -40 40 0 t 40 0 40 0");
+    // without a previous quadratic curve the control point is the current point
+    assert_matches_usvg("M 10 50 L 50 50 T 90 10");
+    assert_matches_usvg("M 10 50 C 20 10 40 10 50 50 T 90 10");
+}
+
+#[test]
+fn cubic_curve() {
+    assert_matches_usvg("M 10 90 C 10 10 50 10 50 50 50 90 90 90 90 10");
+    assert_matches_usvg("M 10 90 c 0 -80 40 -80 40 -40 0 40 40 40 40 -40");
+}
+
+#[test]
+fn smooth_cubic_curve() {
+    assert_matches_usvg("M 10 90 C 10 10 50 10 50 50 S 90 90 90 10 130 90 170 50");
+    assert_matches_usvg("M 10 90 c 0 -80 40 -80 40 -40 s 40 40 40 -40 s 40 80 80 40");
+    // without a previous cubic curve the first control point is the current point
+    assert_matches_usvg("M 10 90 L 50 90 S 90 10 130 90");
+    assert_matches_usvg("M 10 90 Q 30 10 50 90 S 90 10 130 90");
+}
+
+#[test]
+fn elliptical_arc() {
+    assert_matches_usvg("M 40 100 A 60 40 0 0 1 160 100");
+    assert_matches_usvg("M 40 100 A 60 40 0 1 0 160 100");
+    assert_matches_usvg("M 40 100 A 60 40 30 1 1 160 120");
+    assert_matches_usvg("M 40 100 a 60 40 30 0 0 120 20 a 30 30 0 1 1 -40 40");
+    // radii which are too small to reach the end point are scaled up
+    assert_matches_usvg("M 40 100 A 10 5 45 0 1 160 100");
+    // zero radii draw a line
+    assert_matches_usvg("M 40 100 A 0 40 0 0 1 160 100");
+}
+
+#[test]
+fn close_resets_current_point() {
+    // relative commands after closing the subpath start from the start of the subpath
+    assert_matches_usvg("M 10 10 l 40 0 l 0 40 z l 60 20 l -20 40 z m 100 100 l 40 0 l 0 40 z");
+}
+
+#[test]
+fn rectangle_commands_match_rectangle_element() {
+    let mut rectangle = shapes::Rectangle {
+        cuboid: p2d::shape::Cuboid::new(na::vector![60.0, 30.0]),
+        transform: StrokeTransform::default(),
+    };
+    rectangle
+        .transform
+        .append_rotation_wrt_point_mut(std::f64::consts::FRAC_PI_6, na::point![0.0, 0.0]);
+    rectangle
+        .transform
+        .append_translation_mut(na::vector![100.0, 100.0]);
+
+    let skia_path = drawpath::to_skia_path(&solid::compose_rectangle_commands(&rectangle)).unwrap();
+    let element: element::Element = solid::compose_rectangle(rectangle, &Shaper::default());
+
+    assert_paths_match(
+        sample_skia_path(&skia_path),
+        sample_usvg_paths(&svg_document(&element.to_string())),
+    );
+}
+
+#[test]
+fn ellipse_commands_match_ellipse_element() {
+    let mut ellipse = shapes::Ellipse {
+        radii: na::vector![60.0, 30.0],
+        transform: StrokeTransform::default(),
+    };
+    ellipse
+        .transform
+        .append_rotation_wrt_point_mut(std::f64::consts::FRAC_PI_6, na::point![0.0, 0.0]);
+    ellipse.transform.append_scale_mut(na::vector![1.0, 1.5]);
+    ellipse
+        .transform
+        .append_translation_mut(na::vector![100.0, 100.0]);
+
+    let skia_path = drawpath::to_skia_path(&solid::compose_ellipse_commands(&ellipse)).unwrap();
+    let element: element::Element = solid::compose_ellipse(ellipse, &Shaper::default());
+
+    assert_paths_match(
+        sample_skia_path(&skia_path),
+        sample_usvg_paths(&svg_document(&element.to_string())),
+    );
+}
@@ -0,0 +1,198 @@
+//! Tests of the stroke model and the sheet serialization, which run without a display.
+//! Run with `cargo test -p rnote-engine`
+
+extern crate nalgebra as na;
+extern crate parry2d_f64 as p2d;
+
+use rnote_engine::drawbehaviour::DrawBehaviour;
+use rnote_engine::pdf::PdfPageOrigin;
+use rnote_engine::pens::brush::Brush;
+use rnote_engine::render::Renderer;
+use rnote_engine::sheet::background::{Background, BackgroundTemplate, PatternStyle};
+use rnote_engine::sheet::{attachments, Sheet};
+use rnote_engine::strokes::brushstroke::BrushStroke;
+use rnote_engine::strokes::strokebehaviour::StrokeBehaviour;
+use rnote_engine::strokes::strokestyle::{Element, InputData, StrokeStyle};
+use rnote_engine::strokes::vectorimage::VectorImage;
+use rnote_engine::strokesstate::selection_comp::StrokesClipboard;
+use rnote_engine::strokesstate::StrokesState;
+
+fn brushstroke(positions: &[na::Vector2<f64>]) -> StrokeStyle {
+    let mut elements = positions
+        .iter()
+        .map(|&pos| Element::new(InputData::new(pos, 0.5)));
+    let mut brushstroke = BrushStroke::new(elements.next().unwrap(), Brush::default());
+    for element in elements {
+        brushstroke.push_elem(element);
+    }
+    brushstroke.update_geometry();
+
+    StrokeStyle::BrushStroke(brushstroke)
+}
+
+#[test]
+fn brushstroke_bounds_contain_elements() {
+    let stroke = brushstroke(&[
+        na::vector![10.0, 20.0],
+        na::vector![50.0, 30.0],
+        na::vector![80.0, 90.0],
+    ]);
+    let bounds = stroke.bounds();
+
+    assert!(bounds.mins[0] <= 10.0 && bounds.mins[1] <= 20.0);
+    assert!(bounds.maxs[0] >= 80.0 && bounds.maxs[1] >= 90.0);
+}
+
+#[test]
+fn brushstroke_translate_moves_bounds() {
+    let mut stroke = brushstroke(&[na::vector![10.0, 20.0], na::vector![50.0, 30.0]]);
+    let bounds = stroke.bounds();
+
+    stroke.translate(na::vector![100.0, -5.0]);
+    let translated = stroke.bounds();
+
+    assert!((translated.mins - bounds.mins - na::vector![100.0, -5.0]).norm() < 1e-9);
+    assert!((translated.maxs - bounds.maxs - na::vector![100.0, -5.0]).norm() < 1e-9);
+}
+
+#[test]
+fn sheet_roundtrips_through_container() {
+    let sheet = Sheet::new();
+    let key = sheet
+        .strokes_state()
+        .borrow_mut()
+        .insert_stroke(brushstroke(&[
+            na::vector![10.0, 20.0],
+            na::vector![50.0, 30.0],
+            na::vector![80.0, 90.0],
+        ]));
+    let bounds = sheet.strokes_state().borrow().gen_bounds(&[key]).unwrap();
+
+    let (sheet_json, sheet_attachments) =
+        attachments::with_attachments_writer(|| serde_json::to_string(&sheet));
+    let bytes = attachments::write_container(&sheet_json.unwrap(), &sheet_attachments).unwrap();
+    assert!(attachments::is_container(&bytes));
+
+    let loaded = Sheet::load_sheet_from_bytes(&bytes).unwrap();
+    loaded.strokes_state().borrow_mut().rebuild_unserialized();
+
+    let loaded_keys = loaded.strokes_state().borrow().keys_sorted_chrono();
+    assert_eq!(loaded_keys.len(), 1);
+    assert_eq!(
+        loaded.strokes_state().borrow().gen_bounds(&loaded_keys),
+        Some(bounds)
+    );
+    assert_eq!(loaded.format().width(), sheet.format().width());
+}
+
+#[test]
+fn inserting_stroke_discards_undone_operations() {
+    let sheet = Sheet::new();
+    sheet.insert_page(0);
+    assert!(sheet.undo());
+
+    sheet
+        .strokes_state()
+        .borrow_mut()
+        .insert_stroke(brushstroke(&[
+            na::vector![10.0, 20.0],
+            na::vector![50.0, 30.0],
+        ]));
+
+    // The undone page insertion is not redone after the new stroke
+    assert!(!sheet.redo());
+    assert!(!sheet.redo());
+}
+
+#[test]
+fn inserted_pages_are_undone_at_once() {
+    let sheet = Sheet::new();
+    let n_pages = sheet.calc_n_pages().max(sheet.min_n_pages());
+
+    sheet.insert_pages(0, 3);
+    assert_eq!(sheet.min_n_pages(), n_pages + 3);
+
+    assert!(sheet.undo());
+    assert_eq!(sheet.min_n_pages(), n_pages);
+    assert!(!sheet.undo());
+}
+
+#[test]
+fn pasted_vector_pdf_page_keeps_its_document() {
+    let mut source = StrokesState::new();
+    source.insert_pdf_document(b"%PDF-1.4 first document");
+    let document = source.insert_pdf_document(b"%PDF-1.4 second document");
+
+    let mut vectorimage = VectorImage::import_from_svg_data(
+        r#"<svg width="100" height="140" xmlns="http://www.w3.org/2000/svg"><rect width="100" height="140" fill="white"/></svg>"#,
+        na::vector![10.0, 20.0],
+        Some(na::vector![100.0, 140.0]),
+        &Renderer::default(),
+    )
+    .unwrap();
+    vectorimage.pdf_origin = Some(PdfPageOrigin { document, page: 0 });
+    let key = source.insert_stroke(StrokeStyle::VectorImage(vectorimage));
+    source.set_selected(key, true);
+
+    let clipboard =
+        StrokesClipboard::from_json(&source.gen_clipboard_selection().to_json().unwrap()).unwrap();
+
+    // The target has other documents, so the pasted document is stored with a different key
+    let mut target = StrokesState::new();
+    target.insert_pdf_document(b"%PDF-1.4 other document");
+    target.insert_pdf_document(b"%PDF-1.4 another document");
+    target.insert_pdf_document(b"%PDF-1.4 first document");
+    let pasted = target.insert_clipboard_strokes(clipboard, na::vector![200.0, 200.0]);
+
+    assert_eq!(pasted.len(), 1);
+    assert_eq!(
+        target.pdf_document_data(pasted[0]).unwrap().as_slice(),
+        b"%PDF-1.4 second document"
+    );
+    assert_eq!(
+        target.pdf_document_data(pasted[0]),
+        source.pdf_document_data(key)
+    );
+}
+
+#[test]
+fn pages_draw_their_own_templates() {
+    let template = |id: &str| BackgroundTemplate {
+        svg_data: format!(r#"<rect id="{}" width="10" height="10"/>"#, id),
+        intrinsic_size: na::vector![10.0, 10.0],
+    };
+    let page_size = na::vector![100.0, 100.0];
+    let page_bounds = |page: f64| {
+        p2d::bounding_volume::AABB::new(
+            na::point![0.0, page * 100.0],
+            na::point![100.0, (page + 1.0) * 100.0],
+        )
+    };
+
+    let mut background = Background::default();
+    background.set_template(Some(template("sheet-template")));
+    background.set_page_templates(
+        1,
+        vec![template("first-template"), template("second-template")],
+    );
+
+    assert_eq!(
+        background.page_background(1).pattern,
+        PatternStyle::Template
+    );
+    assert_ne!(background.page_template(1), background.page_template(2));
+
+    let page_svgs = (0..3)
+        .map(|page| {
+            background
+                .gen_svg(page_bounds(f64::from(page)), page_size)
+                .unwrap()
+                .svg_data
+        })
+        .collect::<Vec<String>>();
+
+    // The first page has no override and the sheet wide pattern, so no template is drawn on it
+    assert!(!page_svgs[0].contains("template"));
+    assert!(page_svgs[1].contains("first-template") && !page_svgs[1].contains("second-template"));
+    assert!(page_svgs[2].contains("second-template") && !page_svgs[2].contains("first-template"));
+}
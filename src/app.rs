@@ -10,15 +10,12 @@ mod imp {
     use once_cell::sync::Lazy;
 
     use crate::{
-        compose::textured::TexturedDotsDistribution,
         config,
-        sheet::format::MeasureUnit,
-        sheet::Sheet,
-        sheet::{background::PatternStyle, format::PredefinedFormat},
         ui::{
             appmenu::AppMenu, appwindow::RnoteAppWindow, canvas::Canvas, canvasmenu::CanvasMenu,
             colorpicker::colorsetter::ColorSetter, colorpicker::ColorPicker,
-            develactions::DevelActions, mainheader::MainHeader, penssidebar::brushpage::BrushPage,
+            develactions::DevelActions, mainheader::MainHeader, pagenavigator::PageNavigator,
+            pdfsearchbar::PdfSearchBar, penssidebar::brushpage::BrushPage,
             penssidebar::eraserpage::EraserPage, penssidebar::markerpage::MarkerPage,
             penssidebar::selectorpage::SelectorPage, penssidebar::shaperpage::ShaperPage,
             penssidebar::toolspage::ToolsPage, penssidebar::PensSideBar,
@@ -26,6 +23,11 @@ mod imp {
             settingspanel::SettingsPanel, unitentry::UnitEntry, workspacebrowser::filerow::FileRow,
             workspacebrowser::WorkspaceBrowser,
         },
+    };
+    use rnote_engine::{
+        compose::textured::TexturedDotsDistribution,
+        sheet::format::MeasureUnit,
+        sheet::{background::PatternStyle, format::PredefinedFormat},
         utils,
     };
 
@@ -89,7 +91,6 @@ mod imp {
             // Custom buildable Widgets need to register
             RnoteAppWindow::static_type();
             DevelActions::static_type();
-            Sheet::static_type();
             Canvas::static_type();
             ColorPicker::static_type();
             ColorSetter::static_type();
@@ -107,6 +108,8 @@ mod imp {
             SelectorPage::static_type();
             ToolsPage::static_type();
             WorkspaceBrowser::static_type();
+            PageNavigator::static_type();
+            PdfSearchBar::static_type();
             FileRow::static_type();
             PredefinedFormat::static_type();
             MeasureUnit::static_type();
@@ -2,11 +2,11 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::time;
 
-use crate::pens::PenStyle;
 use crate::ui::appwindow::RnoteAppWindow;
 use gst::prelude::*;
 use gtk4::{glib, glib::clone};
 use rand::Rng;
+use rnote_engine::pens::PenStyle;
 
 #[derive(Debug)]
 pub struct RnoteAudioPlayer {
@@ -3,16 +3,9 @@
 
 pub mod app;
 pub mod audioplayer;
-pub mod compose;
 pub mod config;
-pub mod drawbehaviour;
 pub mod globals;
 pub mod input;
-pub mod pens;
-pub mod render;
-pub mod sheet;
-pub mod strokes;
-pub mod strokesstate;
 pub mod ui;
 pub mod utils;
 
@@ -2,12 +2,16 @@ use std::{cell::Cell, rc::Rc};
 
 use crate::{
     app::RnoteApp,
-    compose,
-    pens::{brush, selector, shaper, tools, PenStyle},
-    render,
     ui::appwindow::RnoteAppWindow,
+    ui::selectionmodifier::SelectionModifierMode,
     ui::{canvas::Canvas, dialogs},
 };
+use rnote_engine::{
+    pens::{brush, selector, shaper, tools, PenStyle},
+    render,
+    sheet::Sheet,
+    strokesstate::selection_comp::{Alignment, StrokesClipboard},
+};
 use gtk4::{
     gdk, gio, glib, glib::clone, prelude::*, ArrowType, CornerType, PackType, PositionType,
     PrintOperation, PrintOperationAction, Unit,
@@ -20,6 +24,32 @@ with boolean state: They have a boolean parameter, and a boolean state. activati
     A state change can also be directly requested with change_action_state( somebool ).
 for other stateful actions: They have the same values as their state as their parameters. Activating the action with a parameter is equivalent to changing its state directly
 */
+/// Applies a page operation on the page given by the action parameter, or on the current page if it is negative
+fn apply_page_operation<F>(appwindow: &RnoteAppWindow, parameter: Option<&glib::Variant>, operation: F)
+where
+    F: FnOnce(&Sheet, u32),
+{
+    let page = match parameter.and_then(|parameter| parameter.get::<i32>()) {
+        Some(page) if page >= 0 => page as u32,
+        _ => appwindow.canvas().current_page(),
+    };
+
+    appwindow
+        .canvas()
+        .sheet()
+        .strokes_state()
+        .borrow_mut()
+        .deselect_all_strokes();
+    appwindow.canvas().selection_modifier().set_visible(false);
+
+    operation(&appwindow.canvas().sheet(), page);
+
+    appwindow.canvas().sheet().resize_to_format();
+    appwindow.canvas().set_unsaved_changes(true);
+    appwindow.canvas().regenerate_background(true);
+    appwindow.page_navigator().update_pages(appwindow);
+}
+
 pub fn setup_actions(appwindow: &RnoteAppWindow) {
     let app = appwindow
         .application()
@@ -64,6 +94,34 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
     appwindow.add_action(&action_delete_selection);
     let action_duplicate_selection = gio::SimpleAction::new("duplicate-selection", None);
     appwindow.add_action(&action_duplicate_selection);
+    // Enabled by the selection modifier when the selection can be grouped or ungrouped
+    let action_group_selection = gio::SimpleAction::new("group-selection", None);
+    action_group_selection.set_enabled(false);
+    appwindow.add_action(&action_group_selection);
+    let action_ungroup_selection = gio::SimpleAction::new("ungroup-selection", None);
+    action_ungroup_selection.set_enabled(false);
+    appwindow.add_action(&action_ungroup_selection);
+    let action_align_selection = gio::SimpleAction::new(
+        "align-selection",
+        Some(&glib::VariantType::new("s").unwrap()),
+    );
+    appwindow.add_action(&action_align_selection);
+    let action_distribute_selection = gio::SimpleAction::new(
+        "distribute-selection",
+        Some(&glib::VariantType::new("s").unwrap()),
+    );
+    appwindow.add_action(&action_distribute_selection);
+    let action_flip_selection = gio::SimpleAction::new(
+        "flip-selection",
+        Some(&glib::VariantType::new("s").unwrap()),
+    );
+    appwindow.add_action(&action_flip_selection);
+    let action_selection_to_front = gio::SimpleAction::new("selection-to-front", None);
+    appwindow.add_action(&action_selection_to_front);
+    let action_selection_to_back = gio::SimpleAction::new("selection-to-back", None);
+    appwindow.add_action(&action_selection_to_back);
+    let action_edit_selected_image = gio::SimpleAction::new("edit-selected-image", None);
+    appwindow.add_action(&action_edit_selected_image);
     let action_new_sheet = gio::SimpleAction::new("new-sheet", None);
     appwindow.add_action(&action_new_sheet);
     let action_save_sheet = gio::SimpleAction::new("save-sheet", None);
@@ -82,8 +140,34 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
     appwindow.add_action(&action_export_selection_as_svg);
     let action_export_sheet_as_svg = gio::SimpleAction::new("export-sheet-as-svg", None);
     appwindow.add_action(&action_export_sheet_as_svg);
+    let action_export_annotated_pdf = gio::SimpleAction::new("export-annotated-pdf", None);
+    appwindow.add_action(&action_export_annotated_pdf);
+    let action_pdf_search = gio::SimpleAction::new("pdf-search", None);
+    appwindow.add_action(&action_pdf_search);
+    let action_hide_tool_guides = gio::SimpleAction::new("hide-tool-guides", None);
+    appwindow.add_action(&action_hide_tool_guides);
+    let action_insert_page_before =
+        gio::SimpleAction::new("insert-page-before", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_insert_page_before);
+    let action_insert_page_after =
+        gio::SimpleAction::new("insert-page-after", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_insert_page_after);
+    let action_delete_page =
+        gio::SimpleAction::new("delete-page", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_delete_page);
+    let action_duplicate_page =
+        gio::SimpleAction::new("duplicate-page", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_duplicate_page);
+    let action_move_page_up =
+        gio::SimpleAction::new("move-page-up", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_move_page_up);
+    let action_move_page_down =
+        gio::SimpleAction::new("move-page-down", Some(&glib::VariantType::new("i").unwrap()));
+    appwindow.add_action(&action_move_page_down);
     let action_clipboard_copy_selection = gio::SimpleAction::new("clipboard-copy-selection", None);
     appwindow.add_action(&action_clipboard_copy_selection);
+    let action_clipboard_cut_selection = gio::SimpleAction::new("clipboard-cut-selection", None);
+    appwindow.add_action(&action_clipboard_cut_selection);
     let action_clipboard_paste_selection =
         gio::SimpleAction::new("clipboard-paste-selection", None);
     appwindow.add_action(&action_clipboard_paste_selection);
@@ -409,11 +493,82 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
                     appwindow.penssidebar().tools_page().toolstyle_dragproximity_toggle().set_active(true);
                     appwindow.canvas().pens().borrow_mut().tools.set_style(tools::ToolStyle::DragProximity);
                 },
+                "ruler" => {
+                    appwindow.penssidebar().tools_page().toolstyle_ruler_toggle().set_active(true);
+                    appwindow.canvas().pens().borrow_mut().tools.set_style(tools::ToolStyle::Ruler);
+
+                    let viewport_center = appwindow.canvas().viewport_in_sheet_coords().center().coords;
+                    let pens = appwindow.canvas().pens();
+                    let mut pens = pens.borrow_mut();
+                    if !pens.tools.ruler_tool.visible {
+                        pens.tools.ruler_tool.pos = viewport_center;
+                        pens.tools.ruler_tool.visible = true;
+                    }
+                    appwindow.canvas().queue_draw();
+                },
+                "protractor" => {
+                    appwindow.penssidebar().tools_page().toolstyle_protractor_toggle().set_active(true);
+                    appwindow.canvas().pens().borrow_mut().tools.set_style(tools::ToolStyle::Protractor);
+
+                    let viewport_center = appwindow.canvas().viewport_in_sheet_coords().center().coords;
+                    let pens = appwindow.canvas().pens();
+                    let mut pens = pens.borrow_mut();
+                    if !pens.tools.protractor_tool.visible {
+                        pens.tools.protractor_tool.pos = viewport_center;
+                        pens.tools.protractor_tool.visible = true;
+                    }
+                    appwindow.canvas().queue_draw();
+                },
                 _ => { log::error!("set invalid state of action `tool-style`")}
             }
         }),
     );
 
+    // Hide ruler and protractor
+    action_hide_tool_guides.connect_activate(
+        clone!(@weak appwindow => move |_action_hide_tool_guides, _| {
+            appwindow.canvas().pens().borrow_mut().tools.hide_guides();
+            appwindow.canvas().queue_draw();
+        }),
+    );
+
+    // Page operations. The parameter is the page index, or negative for the current page
+    action_insert_page_before.connect_activate(
+        clone!(@weak appwindow => move |_action_insert_page_before, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.insert_page(page));
+        }),
+    );
+
+    action_insert_page_after.connect_activate(
+        clone!(@weak appwindow => move |_action_insert_page_after, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.insert_page(page + 1));
+        }),
+    );
+
+    action_delete_page.connect_activate(
+        clone!(@weak appwindow => move |_action_delete_page, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.delete_page(page));
+        }),
+    );
+
+    action_duplicate_page.connect_activate(
+        clone!(@weak appwindow => move |_action_duplicate_page, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.duplicate_page(page));
+        }),
+    );
+
+    action_move_page_up.connect_activate(
+        clone!(@weak appwindow => move |_action_move_page_up, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.move_page_up(page));
+        }),
+    );
+
+    action_move_page_down.connect_activate(
+        clone!(@weak appwindow => move |_action_move_page_down, parameter| {
+            apply_page_operation(&appwindow, parameter, |sheet, page| sheet.move_page_down(page));
+        }),
+    );
+
     // Trash Selection
     action_delete_selection.connect_activate(
         clone!(@weak appwindow => move |_action_delete_selection, _| {
@@ -434,6 +589,98 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
         }),
     );
 
+    // Group Selection
+    action_group_selection.connect_activate(
+        clone!(@weak appwindow => move |_action_group_selection, _| {
+            appwindow.canvas().sheet().group_selection();
+
+            appwindow.canvas().selection_modifier().update_state(&appwindow.canvas());
+            appwindow.canvas().set_unsaved_changes(true);
+            appwindow.canvas().queue_draw();
+        }),
+    );
+
+    // Ungroup Selection
+    action_ungroup_selection.connect_activate(
+        clone!(@weak appwindow => move |_action_ungroup_selection, _| {
+            appwindow.canvas().sheet().ungroup_selection();
+
+            appwindow.canvas().selection_modifier().update_state(&appwindow.canvas());
+            appwindow.canvas().set_unsaved_changes(true);
+            appwindow.canvas().queue_draw();
+        }),
+    );
+
+    // Align Selection
+    action_align_selection.connect_activate(
+        clone!(@weak appwindow => move |_action_align_selection, parameter| {
+            let alignment = match parameter.unwrap().str().unwrap() {
+                "left" => Alignment::Left,
+                "horizontal-center" => Alignment::HorizontalCenter,
+                "right" => Alignment::Right,
+                "top" => Alignment::Top,
+                "vertical-center" => Alignment::VerticalCenter,
+                "bottom" => Alignment::Bottom,
+                _ => {
+                    log::error!("invalid parameter for action `align-selection`");
+                    return;
+                }
+            };
+            appwindow.canvas().selection_modifier().align_selection(alignment, &appwindow.canvas());
+        }),
+    );
+
+    // Distribute Selection
+    action_distribute_selection.connect_activate(
+        clone!(@weak appwindow => move |_action_distribute_selection, parameter| {
+            let horizontal = match parameter.unwrap().str().unwrap() {
+                "horizontal" => true,
+                "vertical" => false,
+                _ => {
+                    log::error!("invalid parameter for action `distribute-selection`");
+                    return;
+                }
+            };
+            appwindow.canvas().selection_modifier().distribute_selection(horizontal, &appwindow.canvas());
+        }),
+    );
+
+    // Flip Selection
+    action_flip_selection.connect_activate(
+        clone!(@weak appwindow => move |_action_flip_selection, parameter| {
+            let (horizontal, vertical) = match parameter.unwrap().str().unwrap() {
+                "horizontal" => (true, false),
+                "vertical" => (false, true),
+                _ => {
+                    log::error!("invalid parameter for action `flip-selection`");
+                    return;
+                }
+            };
+            appwindow.canvas().selection_modifier().flip_selection(horizontal, vertical, &appwindow.canvas());
+        }),
+    );
+
+    // Bring Selection to the front
+    action_selection_to_front.connect_activate(
+        clone!(@weak appwindow => move |_action_selection_to_front, _| {
+            appwindow.canvas().selection_modifier().bring_selection_to_front(&appwindow.canvas());
+        }),
+    );
+
+    // Send Selection to the back
+    action_selection_to_back.connect_activate(
+        clone!(@weak appwindow => move |_action_selection_to_back, _| {
+            appwindow.canvas().selection_modifier().send_selection_to_back(&appwindow.canvas());
+        }),
+    );
+
+    // Edit selected image
+    action_edit_selected_image.connect_activate(
+        clone!(@weak appwindow => move |_action_edit_selected_image, _| {
+            appwindow.canvas().selection_modifier().set_mode(SelectionModifierMode::EditImage, &appwindow.canvas());
+        }),
+    );
+
     // Format borders
     action_sheet_format_borders.connect_state_notify(
         clone!(@weak appwindow => move |action_sheet_format_borders| {
@@ -542,18 +789,32 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
         dialogs::dialog_clear_sheet(&appwindow);
     }));
 
-    // Undo stroke
+    // Undo stroke or sheet operation
     action_undo_stroke.connect_activate(clone!(@weak appwindow => move |_,_| {
-        appwindow.canvas().sheet().strokes_state().borrow_mut().undo_last_stroke();
+        let page_operation_undone = appwindow.canvas().sheet().undo();
         appwindow.canvas().sheet().resize_to_format();
-        appwindow.canvas().update_background_rendernode(true);
+        appwindow.canvas().selection_modifier().update_state(&appwindow.canvas());
+
+        if page_operation_undone {
+            appwindow.canvas().regenerate_background(true);
+            appwindow.page_navigator().update_pages(&appwindow);
+        } else {
+            appwindow.canvas().update_background_rendernode(true);
+        }
     }));
 
-    // Redo stroke
+    // Redo stroke or sheet operation
     action_redo_stroke.connect_activate(clone!(@weak appwindow => move |_,_| {
-        appwindow.canvas().sheet().strokes_state().borrow_mut().redo_last_stroke();
+        let page_operation_redone = appwindow.canvas().sheet().redo();
         appwindow.canvas().sheet().resize_to_format();
-        appwindow.canvas().update_background_rendernode(true);
+        appwindow.canvas().selection_modifier().update_state(&appwindow.canvas());
+
+        if page_operation_redone {
+            appwindow.canvas().regenerate_background(true);
+            appwindow.page_navigator().update_pages(&appwindow);
+        } else {
+            appwindow.canvas().update_background_rendernode(true);
+        }
     }));
 
     // Zoom reset
@@ -715,95 +976,190 @@ pub fn setup_actions(appwindow: &RnoteAppWindow) {
         dialogs::dialog_export_sheet(&appwindow);
     }));
 
+    // Export annotated PDF
+    action_export_annotated_pdf.connect_activate(clone!(@weak appwindow => move |_,_| {
+        dialogs::dialog_export_annotated_pdf(&appwindow);
+    }));
+
+    // Search text in pdf pages
+    action_pdf_search.connect_activate(clone!(@weak appwindow => move |_,_| {
+        appwindow.pdf_search_bar().search_bar().set_search_mode(true);
+    }));
+
     // Clipboard copy selection
     action_clipboard_copy_selection.connect_activate(clone!(@weak appwindow => move |_, _| {
-        match appwindow.canvas().sheet().strokes_state().borrow().gen_svgs_selection() {
-            Ok(selection_svgs) => {
-                let mut svg_data = selection_svgs
-                    .iter()
-                    .map(|svg| svg.svg_data.as_str())
-                    .collect::<Vec<&str>>()
-                    .join("\n");
-
-                if let Some(selection_bounds) = appwindow.canvas().sheet().strokes_state().borrow().gen_selection_bounds() {
-                    svg_data = compose::wrap_svg_root(svg_data.as_str(), Some(selection_bounds), Some(selection_bounds), true);
-
-                    let svg_content_provider = gdk::ContentProvider::for_bytes("image/svg+xml", &glib::Bytes::from(svg_data.as_bytes()));
-                    match appwindow.clipboard().set_content(Some(&svg_content_provider)) {
-                        Ok(_) => {
-                        }
-                        Err(e) => {
-                            log::error!("copy selection into clipboard failed in clipboard().set_content(), {}", e);
-                        }
-                    }
-                }
+        // Copy the selected pdf text, if there is any
+        if let Some(text_selection) = appwindow.canvas().pdf_text_selection() {
+            appwindow.clipboard().set_text(&text_selection.text);
+            return;
+        }
+
+        let strokes_state = appwindow.canvas().sheet().strokes_state();
+
+        let clipboard_selection = strokes_state.borrow().gen_clipboard_selection();
+        if clipboard_selection.strokes.is_empty() {
+            return;
+        }
+
+        let mut content_providers = Vec::<gdk::ContentProvider>::new();
+
+        // The strokes themselves, to paste them back without losing their data
+        match clipboard_selection.to_json() {
+            Ok(json) => {
+                content_providers.push(gdk::ContentProvider::for_bytes(StrokesClipboard::MIME_TYPE, &glib::Bytes::from(json.as_bytes())));
+            }
+            Err(e) => {
+                log::error!("to_json() failed in clipboard-copy-selection action with Err {}", e);
+            }
+        }
+
+        // Fallbacks for other applications
+        match strokes_state.borrow().gen_svg_selection() {
+            Ok(Some(svg_data)) => {
+                content_providers.push(gdk::ContentProvider::for_bytes("image/svg+xml", &glib::Bytes::from(svg_data.as_bytes())));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("gen_svg_selection() failed in clipboard-copy-selection action with Err {}", e);
             }
+        }
+        match strokes_state.borrow().gen_bitmapimage_selection(render::BitmapExportFormat::Png, 1.0) {
+            Ok(Some(png_data)) => {
+                content_providers.push(gdk::ContentProvider::for_bytes("image/png", &glib::Bytes::from_owned(png_data)));
+            }
+            Ok(None) => {}
             Err(e) => {
-                log::error!("copy selection into clipboard failed in gen_svg_selection(), {}", e);
+                log::error!("gen_bitmapimage_selection() failed in clipboard-copy-selection action with Err {}", e);
             }
         }
+
+        if let Err(e) = appwindow.clipboard().set_content(Some(&gdk::ContentProvider::new_union(&content_providers))) {
+            log::error!("clipboard().set_content() failed in clipboard-copy-selection action with Err {}", e);
+        }
+    }));
+
+    // Clipboard cut selection
+    action_clipboard_cut_selection.connect_activate(clone!(@weak appwindow => move |_, _| {
+        adw::prelude::ActionGroupExt::activate_action(&appwindow, "clipboard-copy-selection", None);
+
+        // Selected pdf text is only copied, it can't be removed
+        if appwindow.canvas().pdf_text_selection().is_none() {
+            adw::prelude::ActionGroupExt::activate_action(&appwindow, "delete-selection", None);
+        }
     }));
 
     // Clipboard paste as selection
     action_clipboard_paste_selection.connect_activate(clone!(@weak appwindow => move |_, _| {
         let clipboard = appwindow.clipboard();
-            for mime_type in clipboard.formats().mime_types() {
-                    match mime_type.as_str() {
-                        "image/svg+xml" => {
-                            appwindow.clipboard().read_text_async(None::<&gio::Cancellable>, clone!(@weak appwindow => move |text_res| {
-                                match text_res {
-                                    Ok(Some(text)) => {
-                                        appwindow.load_in_vectorimage_bytes(glib::Bytes::from(text.as_bytes()), None).unwrap_or_else(|e| {
-                                            log::error!("failed to paste clipboard as VectorImage, load_in_vectorimage_bytes() returned Err, {}", e);
-                                        });
-                                    }
-                                    Ok(None) => {
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to paste clipboard as VectorImage, text in callback is Err, {}", e);
-
-                                    }
-                                }
-                            }));
-                            break;
-                        }
-/*                         "image/png" | "image/jpeg" => {
-                            appwindow.clipboard().read_texture_async(gio::NONE_CANCELLABLE, clone!(@weak appwindow => move |texture_res| {
-                                match texture_res {
-                                    Ok(Some(texture)) => {
-                                        let mut texture_bytes: Vec<u8> = Vec::new();
-                                        texture.download(&mut texture_bytes, texture.width() as usize * 4);
-
-                                        if let Some(image) = image::ImageBuffer::<image::Bgra<u8>, Vec<u8>>::from_vec(texture.width() as u32, texture.height() as u32, texture_bytes) {
-                                            let mut image_bytes = Vec::<u8>::new();
-                                            image::DynamicImage::ImageBgra8(image).write_to(&mut image_bytes, image::ImageOutputFormat::Png).unwrap_or_else(|e| {
-                                                log::error!("failed to paste clipboard as BitmapImage, DynamicImage.write_to() returned Err, {}", e);
-                                            });
-
-                                            appwindow.load_in_bitmapimage_bytes(&image_bytes).unwrap_or_else(|e| {
-                                                log::error!("failed to paste clipboard as BitmapImage, load_in_vectorimage_bytes() returned Err, {}", e);
-                                            });
-                                        };
-
-
-                                    }
-                                    Ok(None) => {
-                                    }
-                                    Err(e) => {
-                                        log::error!("failed to paste clipboard as BitmapImage, texture in callback is Err, {}", e);
-                                    }
-                                }
-                            }));
-                            break;
-                        }
-                        */
-                        // Pdfs are not supported in the clipboard
-                        _ => {}
-                    }
+        let formats = clipboard.formats();
+
+        // Pasted content lands at the pointer, or in the center of the viewport if the pointer is outside of the canvas
+        let target_pos = appwindow.canvas().pointer_pos_in_sheet_coords().unwrap_or_else(|| {
+            appwindow.canvas().viewport_in_sheet_coords().center().coords
+        });
+
+        // Strokes copied from rnote are preferred over their fallback formats
+        if formats.contain_mime_type(StrokesClipboard::MIME_TYPE) {
+            read_clipboard_bytes(&clipboard, StrokesClipboard::MIME_TYPE, clone!(@weak appwindow => move |bytes| {
+                appwindow.load_in_clipboard_strokes_bytes(bytes, Some(target_pos)).unwrap_or_else(|e| {
+                    log::error!("failed to paste clipboard as strokes, load_in_clipboard_strokes_bytes() returned Err, {}", e);
+                });
+            }));
+            return;
+        }
+
+        // Files copied in the file manager
+        if formats.contain_mime_type("text/uri-list") {
+            read_clipboard_bytes(&clipboard, "text/uri-list", clone!(@weak appwindow => move |bytes| {
+                appwindow.load_in_uri_list_bytes(bytes, Some(target_pos)).unwrap_or_else(|e| {
+                    log::error!("failed to paste clipboard as files, load_in_uri_list_bytes() returned Err, {}", e);
+                });
+            }));
+            return;
+        }
+
+        for mime_type in formats.mime_types() {
+            match mime_type.as_str() {
+                "image/svg+xml" => {
+                    read_clipboard_bytes(&clipboard, "image/svg+xml", clone!(@weak appwindow => move |bytes| {
+                        appwindow.load_in_vectorimage_bytes(bytes, Some(target_pos)).unwrap_or_else(|e| {
+                            log::error!("failed to paste clipboard as VectorImage, load_in_vectorimage_bytes() returned Err, {}", e);
+                        });
+                    }));
+                    return;
+                }
+                "image/png" | "image/jpeg" | "image/webp" | "image/gif" | "image/bmp" | "image/tiff" => {
+                    // Reading the encoded bytes, the format is normalized when the image is imported
+                    read_clipboard_bytes(&clipboard, mime_type.as_str(), clone!(@weak appwindow => move |bytes| {
+                        appwindow.load_in_bitmapimage_bytes(bytes, Some(target_pos)).unwrap_or_else(|e| {
+                            log::error!("failed to paste clipboard as BitmapImage, load_in_bitmapimage_bytes() returned Err, {}", e);
+                        });
+                    }));
+                    return;
+                }
+                // Pdfs are not supported in the clipboard
+                _ => {}
             }
+        }
+
+        // Textures, e.g. screenshots, which are not offered in an encoded format
+        if formats.contain_gtype(gdk::Texture::static_type()) {
+            clipboard.read_texture_async(None::<&gio::Cancellable>, clone!(@weak appwindow => move |texture_res| {
+                match texture_res {
+                    Ok(Some(texture)) => {
+                        appwindow.load_in_texture(&texture, Some(target_pos)).unwrap_or_else(|e| {
+                            log::error!("failed to paste clipboard as BitmapImage, load_in_texture() returned Err, {}", e);
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::error!("failed to paste clipboard as BitmapImage, read_texture_async() returned Err, {}", e);
+                    }
+                }
+            }));
+        }
     }));
 }
 
+/// Reads the content of the clipboard in the mime type, and hands over the bytes to the callback when all are read
+fn read_clipboard_bytes<F>(clipboard: &gdk::Clipboard, mime_type: &str, callback: F)
+where
+    F: FnOnce(glib::Bytes) + 'static,
+{
+    clipboard.read_async(
+        &[mime_type],
+        glib::PRIORITY_DEFAULT,
+        None::<&gio::Cancellable>,
+        move |read_res| match read_res {
+            Ok((input_stream, _)) => {
+                let output_stream = gio::MemoryOutputStream::new_resizable();
+                output_stream.clone().splice_async(
+                    &input_stream,
+                    gio::OutputStreamSpliceFlags::CLOSE_SOURCE
+                        | gio::OutputStreamSpliceFlags::CLOSE_TARGET,
+                    glib::PRIORITY_DEFAULT,
+                    None::<&gio::Cancellable>,
+                    move |splice_res| match splice_res {
+                        Ok(_) => callback(output_stream.steal_as_bytes()),
+                        Err(e) => {
+                            log::error!(
+                                "splice_async() failed in read_clipboard_bytes() with Err {}",
+                                e
+                            );
+                        }
+                    },
+                );
+            }
+            Err(e) => {
+                log::error!(
+                    "read_async() failed in read_clipboard_bytes() with Err {}",
+                    e
+                );
+            }
+        },
+    );
+}
+
 // ### Accelerators / Keyboard Shortcuts
 pub fn setup_accels(appwindow: &RnoteAppWindow) {
     let app = appwindow
@@ -830,7 +1186,32 @@ pub fn setup_accels(appwindow: &RnoteAppWindow) {
     app.set_accels_for_action("win.zoomout", &["minus"]);
     app.set_accels_for_action("win.delete-selection", &["Delete"]);
     app.set_accels_for_action("win.duplicate-selection", &["<Ctrl>d"]);
+    app.set_accels_for_action("win.group-selection", &["<Ctrl>g"]);
+    app.set_accels_for_action("win.ungroup-selection", &["<Ctrl><Shift>g"]);
+    app.set_accels_for_action("win.align-selection('left')", &["<Ctrl><Alt>Left"]);
+    app.set_accels_for_action(
+        "win.align-selection('horizontal-center')",
+        &["<Ctrl><Alt>h"],
+    );
+    app.set_accels_for_action("win.align-selection('right')", &["<Ctrl><Alt>Right"]);
+    app.set_accels_for_action("win.align-selection('top')", &["<Ctrl><Alt>Up"]);
+    app.set_accels_for_action("win.align-selection('vertical-center')", &["<Ctrl><Alt>v"]);
+    app.set_accels_for_action("win.align-selection('bottom')", &["<Ctrl><Alt>Down"]);
+    app.set_accels_for_action(
+        "win.distribute-selection('horizontal')",
+        &["<Ctrl><Alt><Shift>h"],
+    );
+    app.set_accels_for_action(
+        "win.distribute-selection('vertical')",
+        &["<Ctrl><Alt><Shift>v"],
+    );
+    app.set_accels_for_action("win.flip-selection('horizontal')", &["<Shift>h"]);
+    app.set_accels_for_action("win.flip-selection('vertical')", &["<Shift>v"]);
+    app.set_accels_for_action("win.selection-to-front", &["<Ctrl><Shift>Page_Up"]);
+    app.set_accels_for_action("win.selection-to-back", &["<Ctrl><Shift>Page_Down"]);
     app.set_accels_for_action("win.tmperaser(true)", &["d"]);
     app.set_accels_for_action("win.clipboard-copy-selection", &["<Ctrl>c"]);
+    app.set_accels_for_action("win.clipboard-cut-selection", &["<Ctrl>x"]);
+    app.set_accels_for_action("win.pdf-search", &["<Ctrl>f"]);
     app.set_accels_for_action("win.clipboard-paste-selection", &["<Ctrl>v"]);
 }
@@ -187,6 +187,12 @@ pub fn save_state_to_settings(appwindow: &RnoteAppWindow) -> Result<(), glib::Bo
                 PatternStyle::Lines => "lines",
                 PatternStyle::Grid => "grid",
                 PatternStyle::Dots => "dots",
+                PatternStyle::IsometricGrid => "isometric-grid",
+                PatternStyle::HexGrid => "hex-grid",
+                PatternStyle::MusicStaff => "music-staff",
+                PatternStyle::Cornell => "cornell",
+                PatternStyle::LeftMargin => "left-margin",
+                PatternStyle::Template => "template",
             },
         )
         .unwrap();
@@ -399,29 +405,21 @@ pub fn load_settings(appwindow: &RnoteAppWindow) {
         .eraser
         .set_width(eraser_width);
 
+    let mut format = appwindow.canvas().sheet().format();
+
     // Format Size
     let format_size = appwindow
         .app_settings()
         .value("format-size")
         .get::<(u32, u32)>()
         .unwrap();
-    appwindow
-        .canvas()
-        .sheet()
-        .format()
-        .set_width(format_size.0 as i32);
-    appwindow
-        .canvas()
-        .sheet()
-        .format()
-        .set_height(format_size.1 as i32);
+    format.set_width(format_size.0 as i32);
+    format.set_height(format_size.1 as i32);
 
     // Format DPI
-    appwindow
-        .canvas()
-        .sheet()
-        .format()
-        .set_dpi(appwindow.app_settings().double("format-dpi"));
+    format.set_dpi(appwindow.app_settings().double("format-dpi"));
+
+    appwindow.canvas().sheet().import_format(format);
 
     // Background color
     let background_color = utils::Color::from(appwindow.app_settings().uint("background-color"));
@@ -464,6 +462,42 @@ pub fn load_settings(appwindow: &RnoteAppWindow) {
             .background()
             .borrow_mut()
             .set_pattern(PatternStyle::Dots),
+        "isometric-grid" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::IsometricGrid),
+        "hex-grid" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::HexGrid),
+        "music-staff" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::MusicStaff),
+        "cornell" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::Cornell),
+        "left-margin" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::LeftMargin),
+        "template" => appwindow
+            .canvas()
+            .sheet()
+            .background()
+            .borrow_mut()
+            .set_pattern(PatternStyle::Template),
         _ => {
             log::error!("failed to load setting color-scheme, unsupported string as key")
         }
@@ -12,8 +12,9 @@ mod imp {
     use crate::audioplayer::RnoteAudioPlayer;
     use crate::{
         app::RnoteApp, config, ui::canvas::Canvas, ui::develactions::DevelActions, ui::dialogs,
-        ui::mainheader::MainHeader, ui::penssidebar::PensSideBar, ui::settingspanel::SettingsPanel,
-        ui::workspacebrowser::WorkspaceBrowser,
+        ui::mainheader::MainHeader, ui::pagenavigator::PageNavigator,
+        ui::pdfsearchbar::PdfSearchBar, ui::penssidebar::PensSideBar,
+        ui::settingspanel::SettingsPanel, ui::workspacebrowser::WorkspaceBrowser,
     };
 
     #[derive(Debug, CompositeTemplate)]
@@ -53,6 +54,10 @@ mod imp {
         #[template_child]
         pub workspacebrowser: TemplateChild<WorkspaceBrowser>,
         #[template_child]
+        pub page_navigator: TemplateChild<PageNavigator>,
+        #[template_child]
+        pub pdf_search_bar: TemplateChild<PdfSearchBar>,
+        #[template_child]
         pub flapreveal_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub flap_menus_box: TemplateChild<Box>,
@@ -83,6 +88,8 @@ mod imp {
                 flap_resizer: TemplateChild::<gtk4::Box>::default(),
                 flap_resizer_box: TemplateChild::<gtk4::Box>::default(),
                 workspacebrowser: TemplateChild::<WorkspaceBrowser>::default(),
+                page_navigator: TemplateChild::<PageNavigator>::default(),
+                pdf_search_bar: TemplateChild::<PdfSearchBar>::default(),
                 flapreveal_toggle: TemplateChild::<ToggleButton>::default(),
                 flap_menus_box: TemplateChild::<Box>::default(),
                 mainheader: TemplateChild::<MainHeader>::default(),
@@ -309,14 +316,20 @@ use gtk4::{
 use crate::{
     app::RnoteApp,
     audioplayer::RnoteAudioPlayer,
-    strokes::{bitmapimage::BitmapImage, vectorimage::VectorImage},
-    strokesstate::StateTask,
     ui::canvas::Canvas,
     ui::develactions::DevelActions,
+    ui::pdfsearchbar::PdfSearchBar,
     ui::settingspanel::SettingsPanel,
-    ui::{actions, workspacebrowser::WorkspaceBrowser},
+    ui::{actions, pagenavigator::PageNavigator, workspacebrowser::WorkspaceBrowser},
     ui::{appsettings, penssidebar::PensSideBar},
     ui::{dialogs, mainheader::MainHeader},
+};
+use rnote_engine::{
+    pdf::PdfImportPrefs,
+    render,
+    sheet::background::{BackgroundTemplate, PatternStyle},
+    strokes::{bitmapimage::BitmapImage, strokestyle::StrokeStyle, vectorimage::VectorImage},
+    strokesstate::{selection_comp::StrokesClipboard, StateTask},
     utils,
 };
 
@@ -439,6 +452,18 @@ impl RnoteAppWindow {
             .get()
     }
 
+    pub fn page_navigator(&self) -> PageNavigator {
+        imp::RnoteAppWindow::from_instance(self)
+            .page_navigator
+            .get()
+    }
+
+    pub fn pdf_search_bar(&self) -> PdfSearchBar {
+        imp::RnoteAppWindow::from_instance(self)
+            .pdf_search_bar
+            .get()
+    }
+
     pub fn flap(&self) -> adw::Flap {
         imp::RnoteAppWindow::from_instance(self).flap.get()
     }
@@ -548,6 +573,8 @@ impl RnoteAppWindow {
             log::error!("failed to init audio_player with Err {}", e);
         }
         priv_.workspacebrowser.get().init(self);
+        priv_.page_navigator.get().init(self);
+        priv_.pdf_search_bar.get().init(self);
         priv_.settings_panel.get().init(self);
         priv_.devel_actions.get().init(self);
         priv_.mainheader.get().init(self);
@@ -561,14 +588,8 @@ impl RnoteAppWindow {
         priv_.penssidebar.get().selector_page().init(self);
         priv_.penssidebar.get().tools_page().init(self);
         priv_.canvas.get().init(self);
-        priv_.canvas.get().sheet().format().init(self);
-        priv_
-            .canvas
-            .get()
-            .sheet()
-            .strokes_state()
-            .borrow_mut()
-            .init(self);
+        self.init_state_tasks();
+        self.init_memory_monitor();
         priv_.canvas.get().selection_modifier().init(self);
 
         // zoom scrolling with <ctrl> + scroll
@@ -769,6 +790,147 @@ impl RnoteAppWindow {
         }
     }
 
+    /// Attaches the receiver of the tasks which the strokes state sends from its worker threads to the main context
+    fn init_state_tasks(&self) {
+        let main_cx = glib::MainContext::default();
+        let tasks_rx = match self
+            .canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .tasks_rx
+            .take()
+        {
+            Some(tasks_rx) => tasks_rx,
+            None => {
+                log::error!("init_state_tasks() failed, the tasks receiver was already taken");
+                return;
+            }
+        };
+
+        let source_id = tasks_rx.attach(
+            Some(&main_cx),
+            clone!(@weak self as appwindow => @default-return glib::Continue(false), move |render_task| {
+                match render_task {
+                    StateTask::UpdateStrokeWithImages { key, images } => {
+                        appwindow
+                            .canvas()
+                            .sheet()
+                            .strokes_state()
+                            .borrow_mut()
+                            .regenerate_rendering_with_images(key, images);
+
+                        appwindow.canvas().queue_draw();
+                    }
+                    StateTask::AppendImagesToStroke { key, images } => {
+                        appwindow
+                            .canvas()
+                            .sheet()
+                            .strokes_state()
+                            .borrow_mut()
+                            .append_images_to_rendering(key, images);
+
+                        appwindow.canvas().queue_draw();
+                    }
+                    StateTask::InsertStroke { stroke } => {
+                        match stroke {
+                            StrokeStyle::MarkerStroke(markerstroke) => {
+                                appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .insert_stroke_threaded(StrokeStyle::MarkerStroke(markerstroke));
+                            }
+                            StrokeStyle::BrushStroke(brushstroke) => {
+                                appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .insert_stroke_threaded(StrokeStyle::BrushStroke(brushstroke));
+                            }
+                            StrokeStyle::ShapeStroke(shapestroke) => {
+                                appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .insert_stroke_threaded(StrokeStyle::ShapeStroke(shapestroke));
+                            }
+                            StrokeStyle::VectorImage(vectorimage) => {
+                                let inserted = appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .insert_stroke_threaded(StrokeStyle::VectorImage(vectorimage));
+                                appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .set_selected(inserted, true);
+
+                                appwindow.canvas().selection_modifier().set_visible(true);
+                                appwindow.mainheader().selector_toggle().set_active(true);
+
+                                appwindow.canvas().sheet().resize_to_format();
+                                appwindow.canvas().update_background_rendernode(true);
+                            }
+                            StrokeStyle::BitmapImage(bitmapimage) => {
+                                let inserted = appwindow
+                                    .canvas()
+                                    .sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .insert_stroke_threaded(StrokeStyle::BitmapImage(bitmapimage));
+
+                                appwindow.canvas().sheet()
+                                    .strokes_state()
+                                    .borrow_mut()
+                                    .set_selected(inserted, true);
+
+                                appwindow.canvas().selection_modifier().set_visible(true);
+                                appwindow.mainheader().selector_toggle().set_active(true);
+
+                                appwindow.canvas().sheet().resize_to_format();
+                                appwindow.canvas().update_background_rendernode(false);
+                                // Renders lazily rendered pdf pages which are in the viewport
+                                appwindow.canvas().regenerate_content(false, true);
+                            }
+                        }
+
+                    }
+                    StateTask::Quit => {
+                        return glib::Continue(false);
+                    }
+                }
+
+                glib::Continue(true)
+            }),
+        );
+
+        let source = main_cx.find_source_by_id(&source_id).unwrap_or_else(|| {
+            log::error!("find_source_by_id() in init_state_tasks() failed.");
+            panic!();
+        });
+        self.canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .channel_source
+            .replace(source);
+    }
+
+    /// Evicts the cached renderings of pdf pages outside of the viewport when the system is low on memory
+    fn init_memory_monitor(&self) {
+        // The default memory monitor is kept alive by gio
+        gio::MemoryMonitor::dup_default().connect_low_memory_warning(
+            clone!(@weak self as appwindow => move |_memory_monitor, level| {
+                log::debug!("received low memory warning with level {:?}", level);
+
+                let viewport = appwindow.canvas().viewport_in_sheet_coords();
+                appwindow
+                    .canvas()
+                    .sheet()
+                    .strokes_state()
+                    .borrow_mut()
+                    .evict_offscreen_pdf_page_renders(Some(viewport));
+            }),
+        );
+    }
+
     pub fn open_file_w_dialogs(&self, file: &gio::File, target_pos: Option<na::Vector2<f64>>) {
         let app = self.application().unwrap().downcast::<RnoteApp>().unwrap();
         match utils::FileType::lookup_file_type(file) {
@@ -800,6 +962,61 @@ impl RnoteAppWindow {
         }
     }
 
+    /// Opens a single file like `open_file_w_dialogs()`. Multiple files are imported like `import_files()`
+    pub fn open_files_w_dialogs(
+        &self,
+        files: Vec<gio::File>,
+        target_pos: Option<na::Vector2<f64>>,
+    ) {
+        if let [file] = files.as_slice() {
+            self.open_file_w_dialogs(file, target_pos);
+        } else {
+            self.import_files(files, target_pos);
+        }
+    }
+
+    /// Imports the files into the current sheet the way the import dialog does. The images are laid out in sequence starting at the target position,
+    /// pdfs are imported through their import dialog. Sheets, folders and unsupported files are skipped
+    pub fn import_files(&self, files: Vec<gio::File>, target_pos: Option<na::Vector2<f64>>) {
+        let main_cx = glib::MainContext::default();
+
+        main_cx.spawn_local(clone!(@weak self as appwindow => async move {
+            let mut images = Vec::<(utils::FileType, glib::Bytes)>::new();
+            // The pdf dialogs are shown one after another. Each import is placed below the previous one, the images below all of them
+            let mut target_pos = target_pos;
+
+            for file in files {
+                let file_type = utils::FileType::lookup_file_type(&file);
+
+                match file_type {
+                    utils::FileType::VectorImageFile
+                    | utils::FileType::BitmapImageFile
+                    | utils::FileType::Pdf => match file.load_bytes_future().await {
+                        Ok((file_bytes, _)) => {
+                            if file_type == utils::FileType::Pdf {
+                                if let Some(next_pos) = dialogs::dialog_import_pdf_w_prefs(&appwindow, file_bytes, target_pos).await {
+                                    target_pos = Some(next_pos);
+                                }
+                            } else {
+                                images.push((file_type, file_bytes));
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("load_bytes_future() failed in import_files() with Err {}", e);
+                        }
+                    },
+                    _ => {
+                        log::warn!("skipped importing unsupported file `{}`", file.uri());
+                    }
+                }
+            }
+
+            if let Err(e) = appwindow.load_in_images_bytes_in_sequence(images, target_pos) {
+                log::error!("load_in_images_bytes_in_sequence() failed in import_files() with Err {}", e);
+            }
+        }));
+    }
+
     /// Loads in a file of any supported type into the current sheet.
     pub fn load_in_file(
         &self,
@@ -854,12 +1071,8 @@ impl RnoteAppWindow {
                 main_cx.spawn_local(clone!(@weak self as appwindow => async move {
                     let result = file.load_bytes_future().await;
                     if let Ok((file_bytes, _)) = result {
-                        if let Err(e) = appwindow.load_in_pdf_bytes(file_bytes, target_pos) {
-                            log::error!(
-                                "load_in_rnote_bytes() failed in load_in_file() with Err {}",
-                                e
-                            );
-                        }
+                        // The pages and their placement are chosen in the import dialog
+                        dialogs::dialog_import_pdf_w_prefs(&appwindow, file_bytes, target_pos).await;
                     }
                 }));
             }
@@ -885,6 +1098,8 @@ impl RnoteAppWindow {
     {
         let app = self.application().unwrap().downcast::<RnoteApp>().unwrap();
         self.canvas().sheet().open_sheet_from_bytes(bytes)?;
+        self.canvas().set_pdf_text_selection(None);
+        self.pdf_search_bar().search_bar().set_search_mode(false);
 
         // Loading the sheet properties into the format settings panel
         self.settings_panel().load_all(self);
@@ -973,13 +1188,83 @@ impl RnoteAppWindow {
         Ok(())
     }
 
-    /// Target position is in the coordinate space of the sheet
-    pub fn load_in_pdf_bytes(
+    /// Pastes the strokes copied into the clipboard, centered around the target position.
+    /// Target position is in the coordinate space of the sheet, it defaults to the center of the viewport
+    pub fn load_in_clipboard_strokes_bytes(
         &self,
         bytes: glib::Bytes,
         target_pos: Option<na::Vector2<f64>>,
     ) -> Result<(), anyhow::Error> {
-        let app = self.application().unwrap().downcast::<RnoteApp>().unwrap();
+        let clipboard = StrokesClipboard::from_json(std::str::from_utf8(&bytes)?)?;
+
+        let pos =
+            target_pos.unwrap_or_else(|| self.canvas().viewport_in_sheet_coords().center().coords);
+
+        let inserted = self
+            .canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .insert_clipboard_strokes(clipboard, pos);
+        if inserted.is_empty() {
+            return Ok(());
+        }
+
+        self.canvas().selection_modifier().set_visible(true);
+        self.mainheader().selector_toggle().set_active(true);
+        self.canvas()
+            .selection_modifier()
+            .update_state(&self.canvas());
+
+        self.canvas().sheet().resize_to_format();
+        self.canvas().update_background_rendernode(false);
+        self.canvas().regenerate_content(false, true);
+
+        self.canvas().set_unsaved_changes(true);
+        self.canvas().set_empty(false);
+
+        Ok(())
+    }
+
+    /// Imports the texture as bitmap image. Target position is in the coordinate space of the sheet
+    pub fn load_in_texture(
+        &self,
+        texture: &gdk::Texture,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> Result<(), anyhow::Error> {
+        let png_bytes =
+            render::texture_to_image(texture)?.encode(render::BitmapExportFormat::Png)?;
+
+        self.load_in_bitmapimage_bytes(glib::Bytes::from_owned(png_bytes), target_pos)
+    }
+
+    /// Imports the files of an uri list (mime type `text/uri-list`) like `import_files()`. Target position is in the coordinate space of the sheet
+    pub fn load_in_uri_list_bytes(
+        &self,
+        bytes: glib::Bytes,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> Result<(), anyhow::Error> {
+        let files = std::str::from_utf8(&bytes)?
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(gio::File::for_uri)
+            .collect::<Vec<gio::File>>();
+
+        self.import_files(files, target_pos);
+        Ok(())
+    }
+
+    /// Imports the images one after another, laid out in a row starting at the target position.
+    /// Each image is paired with its file type. Target position is in the coordinate space of the sheet
+    pub fn load_in_images_bytes_in_sequence(
+        &self,
+        images: Vec<(utils::FileType, glib::Bytes)>,
+        target_pos: Option<na::Vector2<f64>>,
+    ) -> Result<(), anyhow::Error> {
+        if images.is_empty() {
+            return Ok(());
+        }
 
         let pos = target_pos.unwrap_or_else(|| {
             self.canvas()
@@ -988,9 +1273,31 @@ impl RnoteAppWindow {
                     self.canvas().sheet_margin() + BitmapImage::OFFSET_Y_DEFAULT
                 ])
         });
-        let page_width = (f64::from(self.canvas().sheet().width())
-            * (self.canvas().pdf_import_width() / 100.0))
-            .round() as i32;
+        self.canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .deselect_all_strokes();
+
+        self.canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .insert_images_bytes_in_sequence_threaded(pos, images);
+
+        self.canvas().set_unsaved_changes(true);
+        self.canvas().set_empty(false);
+
+        Ok(())
+    }
+
+    /// Imports the pdf pages with the given preferences
+    pub fn load_in_pdf_bytes(
+        &self,
+        bytes: glib::Bytes,
+        prefs: PdfImportPrefs,
+    ) -> Result<(), anyhow::Error> {
+        let app = self.application().unwrap().downcast::<RnoteApp>().unwrap();
 
         self.canvas()
             .sheet()
@@ -1003,13 +1310,13 @@ impl RnoteAppWindow {
                 .sheet()
                 .strokes_state()
                 .borrow_mut()
-                .insert_pdf_bytes_as_vector_threaded(pos, Some(page_width), bytes);
+                .insert_pdf_bytes_as_vector_threaded(prefs, bytes);
         } else {
             self.canvas()
                 .sheet()
                 .strokes_state()
                 .borrow_mut()
-                .insert_pdf_bytes_as_bitmap_threaded(pos, Some(page_width), bytes);
+                .insert_pdf_bytes_as_bitmap_threaded(prefs, bytes);
         }
 
         app.set_input_file(None);
@@ -1018,4 +1325,62 @@ impl RnoteAppWindow {
         self.canvas().set_empty(false);
         Ok(())
     }
+
+    /// Loads a svg, pdf or bitmap file as the template of the edited background and selects the template pattern.
+    /// The pages of a pdf with multiple pages become the templates of the pages starting at the current one
+    pub fn load_in_background_template(&self, file: &gio::File) {
+        let main_cx = glib::MainContext::default();
+        let file = file.clone();
+
+        main_cx.spawn_local(clone!(@weak self as appwindow => async move {
+            let file_bytes = match file.load_bytes_future().await {
+                Ok((file_bytes, _)) => file_bytes,
+                Err(e) => {
+                    log::error!("load_bytes_future() failed in load_in_background_template() with Err {}", e);
+                    return;
+                }
+            };
+
+            let templates = {
+                let renderer = appwindow.canvas().sheet().strokes_state().borrow().renderer.clone();
+                let renderer = renderer.read().unwrap();
+
+                match utils::FileType::lookup_file_type(&file) {
+                    utils::FileType::VectorImageFile => String::from_utf8(file_bytes.to_vec())
+                        .map_err(anyhow::Error::from)
+                        .and_then(|svg_data| BackgroundTemplate::import_from_svg_data(&svg_data, &renderer))
+                        .map(|template| vec![template]),
+                    utils::FileType::Pdf => BackgroundTemplate::import_from_pdf_bytes(&file_bytes, &renderer),
+                    utils::FileType::BitmapImageFile => BackgroundTemplate::import_from_image_bytes(&file_bytes)
+                        .map(|template| vec![template]),
+                    _ => Err(anyhow::anyhow!("unsupported file type for background template")),
+                }
+            };
+
+            match templates {
+                Ok(mut templates) if templates.len() == 1 => {
+                    appwindow.settings_panel().update_edited_page_background(&appwindow, |page_background| {
+                        page_background.pattern = PatternStyle::Template;
+                        page_background.template = templates.pop();
+                    });
+                    appwindow.settings_panel().load_background(&appwindow.canvas());
+
+                    appwindow.canvas().set_unsaved_changes(true);
+                    appwindow.canvas().regenerate_background(true);
+                }
+                Ok(templates) => {
+                    // The pages of a pdf become the templates of the pages starting at the current one
+                    let current_page = appwindow.canvas().current_page();
+                    appwindow.canvas().sheet().background().borrow_mut().set_page_templates(current_page, templates);
+                    appwindow.settings_panel().load_background(&appwindow.canvas());
+
+                    appwindow.canvas().set_unsaved_changes(true);
+                    appwindow.canvas().regenerate_background(true);
+                }
+                Err(e) => {
+                    log::error!("importing background template failed in load_in_background_template() with Err {}", e);
+                }
+            }
+        }));
+    }
 }
@@ -8,10 +8,10 @@ mod imp {
     use gtk4::SizeRequestMode;
     use gtk4::Widget;
 
-    use crate::compose::geometry;
     use crate::ui::canvas::Canvas;
     use crate::ui::selectionmodifier;
     use crate::ui::selectionmodifier::SelectionModifier;
+    use rnote_engine::compose::geometry;
 
     #[derive(Debug, Default)]
     pub struct CanvasLayout {}
@@ -5,17 +5,19 @@ mod imp {
     use std::rc::Rc;
 
     use super::canvaslayout::CanvasLayout;
-    use super::debug;
-    use crate::compose::geometry;
-    use crate::pens::penbehaviour::PenBehaviour;
-    use crate::pens::{PenStyle, Pens};
-    use crate::sheet::Sheet;
+    use crate::config;
     use crate::ui::selectionmodifier::SelectionModifier;
-    use crate::{config, utils};
+    use rnote_engine::compose::geometry;
+    use rnote_engine::pdf::PdfTextSelection;
+    use rnote_engine::pens::penbehaviour::PenBehaviour;
+    use rnote_engine::pens::{PenStyle, Pens};
+    use rnote_engine::render::NodeSnapshot;
+    use rnote_engine::sheet::Sheet;
+    use rnote_engine::{debug, utils};
 
     use gtk4::{
-        gdk, glib, graphene, gsk, prelude::*, subclass::prelude::*, GestureDrag, GestureStylus,
-        PropagationPhase, Snapshot, Widget,
+        gdk, glib, graphene, gsk, prelude::*, subclass::prelude::*, EventControllerMotion,
+        GestureDrag, GestureStylus, PropagationPhase, Snapshot, Widget,
     };
     use gtk4::{AccessibleRole, Adjustment, Scrollable, ScrollablePolicy};
 
@@ -25,7 +27,7 @@ mod imp {
     #[derive(Debug)]
     pub struct Canvas {
         pub pens: Rc<RefCell<Pens>>,
-        pub sheet: Sheet,
+        pub sheet: Rc<Sheet>,
         pub sheet_margin: Cell<f64>,
         pub zoom: Cell<f64>,
         pub temporary_zoom: Cell<f64>,
@@ -40,7 +42,16 @@ mod imp {
         pub stylus_drawing_gesture: GestureStylus,
         pub mouse_drawing_gesture: GestureDrag,
         pub touch_drawing_gesture: GestureDrag,
+        pub pointer_motion_controller: EventControllerMotion,
+        /// The last position of the pointer in canvas coordinates, None if the pointer is outside of the canvas
+        pub pointer_pos: Cell<Option<na::Vector2<f64>>>,
         pub zoom_timeout_id: RefCell<Option<glib::SourceId>>,
+        /// Wether compositing the missing tiles is already scheduled
+        pub tiles_composite_pending: Cell<bool>,
+        /// The matches of the pdf text search
+        pub pdf_text_highlights: RefCell<Vec<AABB>>,
+        pub pdf_text_current_highlight: Cell<Option<usize>>,
+        pub pdf_text_selection: RefCell<Option<PdfTextSelection>>,
 
         pub hadjustment: RefCell<Option<Adjustment>>,
         pub hadjustment_signal: RefCell<Option<glib::SignalHandlerId>>,
@@ -73,13 +84,18 @@ mod imp {
                 .propagation_phase(PropagationPhase::Bubble)
                 .build();
 
+            let pointer_motion_controller = EventControllerMotion::builder()
+                .name("pointer_motion_controller")
+                .propagation_phase(PropagationPhase::Capture)
+                .build();
+
             // Gesture grouping
             mouse_drawing_gesture.group_with(&stylus_drawing_gesture);
             touch_drawing_gesture.group_with(&stylus_drawing_gesture);
 
             Self {
                 pens: Rc::new(RefCell::new(Pens::default())),
-                sheet: Sheet::default(),
+                sheet: Rc::new(Sheet::default()),
                 sheet_margin: Cell::new(super::Canvas::SHEET_MARGIN_DEFAULT),
                 zoom: Cell::new(super::Canvas::ZOOM_DEFAULT),
                 temporary_zoom: Cell::new(1.0),
@@ -112,7 +128,13 @@ mod imp {
                 stylus_drawing_gesture,
                 mouse_drawing_gesture,
                 touch_drawing_gesture,
+                pointer_motion_controller,
+                pointer_pos: Cell::new(None),
                 zoom_timeout_id: RefCell::new(None),
+                tiles_composite_pending: Cell::new(false),
+                pdf_text_highlights: RefCell::new(vec![]),
+                pdf_text_current_highlight: Cell::new(None),
+                pdf_text_selection: RefCell::new(None),
 
                 hadjustment: RefCell::new(None),
                 hadjustment_signal: RefCell::new(None),
@@ -158,6 +180,7 @@ mod imp {
             obj.add_controller(&self.stylus_drawing_gesture);
             obj.add_controller(&self.mouse_drawing_gesture);
             obj.add_controller(&self.touch_drawing_gesture);
+            obj.add_controller(&self.pointer_motion_controller);
         }
 
         fn dispose(&self, obj: &Self::Type) {
@@ -431,33 +454,59 @@ mod imp {
                 zoom,
             )));
 
-            self.sheet.draw(zoom, snapshot);
+            // The engine records its drawing into node snapshots, which are then appended to the widget snapshot
+            let sheet_snapshot = NodeSnapshot::new();
+            self.sheet.draw(zoom, &sheet_snapshot);
+
+            if let Some(level) = widget.tiles_level() {
+                let tiles_missing = self.sheet.strokes_state().borrow_mut().draw_strokes_tiled(
+                    &sheet_snapshot,
+                    widget.viewport_in_sheet_coords(),
+                    level,
+                );
+
+                if tiles_missing {
+                    widget.composite_tiles_when_idle();
+                }
+            } else {
+                self.sheet
+                    .strokes_state()
+                    .borrow()
+                    .draw_strokes(&sheet_snapshot, Some(widget.viewport_in_sheet_coords()));
+            }
 
             self.sheet
                 .strokes_state()
                 .borrow()
-                .draw_strokes(snapshot, Some(widget.viewport_in_sheet_coords()));
+                .draw_live_stroke(&sheet_snapshot);
+
+            snapshot.append_node(&sheet_snapshot.to_node());
+
+            self.draw_pdf_text_highlights(zoom, snapshot);
 
             snapshot.pop();
 
+            let overlay_snapshot = NodeSnapshot::new();
             self.sheet
                 .strokes_state()
                 .borrow()
-                .draw_selection(zoom, snapshot);
+                .draw_selection(zoom, &overlay_snapshot);
 
             if let Err(e) = self.pens.borrow().draw(
                 self.sheet.bounds(),
                 &self.sheet.strokes_state().borrow().renderer.read().unwrap(),
                 zoom,
-                snapshot,
+                &overlay_snapshot,
             ) {
                 log::debug!("pens draw() failed in canvas snapshot() with Err {}", e);
             };
 
             if self.visual_debug.get() {
-                self.draw_debug(widget, snapshot, zoom);
+                self.draw_debug(widget, &overlay_snapshot, zoom);
             }
 
+            snapshot.append_node(&overlay_snapshot.to_node());
+
             // Draw the children
             snapshot.restore();
 
@@ -478,6 +527,52 @@ mod imp {
             a: 0.3,
         };
 
+        pub const PDF_TEXT_HIGHLIGHT_COLOR: utils::Color = utils::Color {
+            r: 1.0,
+            g: 0.85,
+            b: 0.0,
+            a: 0.35,
+        };
+        pub const PDF_TEXT_CURRENT_HIGHLIGHT_COLOR: utils::Color = utils::Color {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+            a: 0.5,
+        };
+        pub const PDF_TEXT_SELECTION_COLOR: utils::Color = utils::Color {
+            r: 0.2,
+            g: 0.5,
+            b: 0.9,
+            a: 0.3,
+        };
+
+        /// Draws the matches of the pdf text search and the selected pdf text
+        fn draw_pdf_text_highlights(&self, zoom: f64, snapshot: &Snapshot) {
+            let current_highlight = self.pdf_text_current_highlight.get();
+
+            for (i, &bounds) in self.pdf_text_highlights.borrow().iter().enumerate() {
+                let color = if Some(i) == current_highlight {
+                    Self::PDF_TEXT_CURRENT_HIGHLIGHT_COLOR
+                } else {
+                    Self::PDF_TEXT_HIGHLIGHT_COLOR
+                };
+
+                snapshot.append_color(
+                    &color.to_gdk(),
+                    &geometry::aabb_to_graphene_rect(geometry::aabb_scale(bounds, zoom)),
+                );
+            }
+
+            if let Some(text_selection) = self.pdf_text_selection.borrow().as_ref() {
+                for &bounds in text_selection.bounds.iter() {
+                    snapshot.append_color(
+                        &Self::PDF_TEXT_SELECTION_COLOR.to_gdk(),
+                        &geometry::aabb_to_graphene_rect(geometry::aabb_scale(bounds, zoom)),
+                    );
+                }
+            }
+        }
+
         pub fn draw_shadow(&self, bounds: AABB, width: f64, snapshot: &Snapshot) {
             let corner_radius = graphene::Size::new(width as f32 / 4.0, width as f32 / 4.0);
 
@@ -500,7 +595,7 @@ mod imp {
         }
 
         // Draw bounds, positions, .. for visual debugging purposes
-        fn draw_debug(&self, widget: &super::Canvas, snapshot: &Snapshot, zoom: f64) {
+        fn draw_debug(&self, widget: &super::Canvas, snapshot: &NodeSnapshot, zoom: f64) {
             if self.pens.borrow().shown() {
                 match self.pens.borrow().current_pen() {
                     PenStyle::Eraser => {
@@ -545,28 +640,47 @@ mod imp {
                 .strokes_state()
                 .borrow()
                 .draw_debug(zoom, snapshot);
+
+            let live_latency = self.sheet.strokes_state().borrow().live_latency.get();
+            if live_latency.n_samples > 0 {
+                debug::draw_text(
+                    &format!(
+                        "live stroke latency: last {:.1} ms, average {:.1} ms, max {:.1} ms",
+                        live_latency.last.as_secs_f64() * 1000.0,
+                        live_latency.average.as_secs_f64() * 1000.0,
+                        live_latency.max.as_secs_f64() * 1000.0,
+                    ),
+                    viewport.mins.coords,
+                    debug::COLOR_POS,
+                    zoom,
+                    snapshot,
+                );
+            }
         }
     }
 }
 
 use crate::audioplayer::RnoteAudioPlayer;
-use crate::compose::geometry;
 use crate::input;
-use crate::pens::penbehaviour::PenBehaviour;
-use crate::strokes::strokestyle::InputData;
 use crate::ui::selectionmodifier::SelectionModifier;
-use crate::{
-    app::RnoteApp, pens::PenStyle, pens::Pens, render, sheet::Sheet, ui::appwindow::RnoteAppWindow,
-};
+use crate::{app::RnoteApp, ui::appwindow::RnoteAppWindow};
+use rnote_engine::compose::geometry;
+use rnote_engine::pdf::PdfTextSelection;
+use rnote_engine::pens::penbehaviour::{PenBehaviour, SurfaceFlags};
+use rnote_engine::pens::{PenStyle, Pens};
+use rnote_engine::sheet::Sheet;
+use rnote_engine::strokes::strokestyle::InputData;
+use rnote_engine::strokesstate::tile_cache::TileCache;
+use rnote_engine::{input as engine_input, render};
 
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 use std::time;
 
-use gtk4::{gdk, glib, glib::clone, prelude::*, subclass::prelude::*};
+use gtk4::{gdk, glib, glib::clone, gsk, prelude::*, subclass::prelude::*};
 use gtk4::{
-    gio, Adjustment, DropTarget, EventSequenceState, PropagationPhase, Scrollable, Snapshot, Widget,
+    gio, Adjustment, DropTarget, EventSequenceState, PropagationPhase, Scrollable, Snapshot,
 };
 use p2d::bounding_volume::{BoundingVolume, AABB};
 
@@ -589,6 +703,8 @@ impl Canvas {
     /// The zoom amount when activating the zoom-in / zoom-out action
     pub const ZOOM_ACTION_DELTA: f64 = 0.1;
     pub const ZOOM_TIMEOUT_TIME: time::Duration = time::Duration::from_millis(300);
+    /// The maximum number of tiles which are composited in one idle callback, so that the canvas stays responsive
+    pub const TILES_COMPOSITED_PER_IDLE: usize = 4;
     pub const SHEET_MARGIN_DEFAULT: f64 = 30.0;
     // The default width of imported PDF's in percentage to the sheet width
     pub const PDF_IMPORT_WIDTH_DEFAULT: f64 = 50.0;
@@ -614,8 +730,14 @@ impl Canvas {
         priv_.motion_cursor.clone()
     }
 
-    pub fn sheet(&self) -> Sheet {
-        imp::Canvas::from_instance(self).sheet.clone()
+    pub fn sheet(&self) -> Rc<Sheet> {
+        Rc::clone(&imp::Canvas::from_instance(self).sheet)
+    }
+
+    /// The renderer of the window the canvas is in. None when the canvas is not yet realized in a window
+    pub fn renderer(&self) -> Option<gsk::Renderer> {
+        self.root()
+            .map(|root| root.upcast::<gtk4::Native>().renderer())
     }
 
     pub fn sheet_margin(&self) -> f64 {
@@ -762,7 +884,7 @@ impl Canvas {
                 // Disable backlog, only allowed in motion signal handler
                 let mut data_entries = input::retreive_stylus_inputdata(stylus_drawing_gesture, false, x, y);
 
-                input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
+                engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
 
                 match device_tool.tool_type() {
                     gdk::DeviceToolType::Pen => { },
@@ -780,7 +902,7 @@ impl Canvas {
         priv_.stylus_drawing_gesture.connect_motion(clone!(@weak self as canvas, @weak appwindow => move |stylus_drawing_gesture, x, y| {
             // backlog doesn't provide time equidistant inputdata and makes line look worse, so its disabled for now
             let mut data_entries: VecDeque<InputData> = input::retreive_stylus_inputdata(stylus_drawing_gesture, false, x, y);
-            input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
+            engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
             canvas.process_peninput_motion(&appwindow, data_entries);
         }));
 
@@ -788,7 +910,7 @@ impl Canvas {
             clone!(@weak self as canvas, @weak appwindow => move |gesture_stylus,x,y| {
                 let mut data_entries = input::retreive_stylus_inputdata(gesture_stylus, false, x, y);
 
-                input::map_inputdata(canvas.zoom(), &mut data_entries, na::vector![0.0, 0.0]);
+                engine_input::map_inputdata(canvas.zoom(), &mut data_entries, na::vector![0.0, 0.0]);
                 canvas.process_peninput_end(&appwindow, data_entries);
             }),
         );
@@ -805,7 +927,7 @@ impl Canvas {
                     mouse_drawing_gesture.set_state(EventSequenceState::Claimed);
 
                     let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                    input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
+                    engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
                     canvas.process_peninput_start(&appwindow, data_entries);
                 }
             }),
@@ -820,7 +942,7 @@ impl Canvas {
 
                 if let Some(start_point) = mouse_drawing_gesture.start_point() {
                     let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                    input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
+                    engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
                     canvas.process_peninput_motion(&appwindow, data_entries);
                 }
             }
@@ -837,7 +959,7 @@ impl Canvas {
 
                     if let Some(start_point) = mouse_drawing_gesture.start_point() {
                         let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                        input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
+                        engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
                         canvas.process_peninput_end(&appwindow, data_entries);
                     }
                 }
@@ -850,7 +972,7 @@ impl Canvas {
                 touch_drawing_gesture.set_state(EventSequenceState::Claimed);
 
                 let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
+                engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![0.0, 0.0]));
 
                 canvas.process_peninput_start(&appwindow, data_entries);
             }),
@@ -859,7 +981,7 @@ impl Canvas {
         priv_.touch_drawing_gesture.connect_drag_update(clone!(@weak self as canvas, @weak appwindow => move |touch_drawing_gesture, x, y| {
             if let Some(start_point) = touch_drawing_gesture.start_point() {
                 let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
+                engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
                 canvas.process_peninput_motion(&appwindow, data_entries);
             }
         }));
@@ -868,28 +990,74 @@ impl Canvas {
             clone!(@weak self as canvas @weak appwindow => move |touch_drawing_gesture, x, y| {
                 if let Some(start_point) = touch_drawing_gesture.start_point() {
                     let mut data_entries = input::retreive_pointer_inputdata(x, y);
-                    input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
+                    engine_input::map_inputdata(canvas.zoom(), &mut data_entries, canvas.transform_canvas_coords_to_sheet_coords(na::vector![start_point.0, start_point.1]));
                     canvas.process_peninput_end(&appwindow, data_entries);
                 }
             }),
         );
 
+        // Pointer position
+        priv_.pointer_motion_controller.connect_motion(
+            clone!(@weak self as canvas => move |_pointer_motion_controller, x, y| {
+                imp::Canvas::from_instance(&canvas).pointer_pos.set(Some(na::vector![x, y]));
+            }),
+        );
+
+        priv_.pointer_motion_controller.connect_leave(
+            clone!(@weak self as canvas => move |_pointer_motion_controller| {
+                imp::Canvas::from_instance(&canvas).pointer_pos.set(None);
+            }),
+        );
+
         // Drop Target
         let drop_target = DropTarget::builder()
             .name("canvas_drop_target")
             .propagation_phase(PropagationPhase::Capture)
             .actions(gdk::DragAction::COPY)
             .build();
-        drop_target.set_types(&[gio::File::static_type()]);
+        drop_target.set_types(&[
+            gdk::FileList::static_type(),
+            gio::File::static_type(),
+            gdk::Texture::static_type(),
+            String::static_type(),
+        ]);
         self.add_controller(&drop_target);
 
         drop_target.connect_drop(clone!(@weak appwindow => @default-return false, move |_drop_target, value, x, y| {
             let pos = appwindow.canvas().transform_canvas_coords_to_sheet_coords(na::vector![x, y]);
 
+            if let Ok(file_list) = value.get::<gdk::FileList>() {
+                appwindow.open_files_w_dialogs(file_list.files(), Some(pos));
+                return true;
+            }
             if let Ok(file) = value.get::<gio::File>() {
-                appwindow.open_file_w_dialogs(&file, Some(pos))
+                appwindow.open_file_w_dialogs(&file, Some(pos));
+                return true;
+            }
+            // Raw image data, e.g. dragged from a browser
+            if let Ok(texture) = value.get::<gdk::Texture>() {
+                if let Err(e) = appwindow.load_in_texture(&texture, Some(pos)) {
+                    log::error!("load_in_texture() failed in drop target with Err {}", e);
+                }
+                return true;
+            }
+            if let Ok(text) = value.get::<String>() {
+                let text_trimmed = text.trim_start();
+
+                if text_trimmed.starts_with("<svg") || (text_trimmed.starts_with("<?xml") && text_trimmed.contains("<svg")) {
+                    if let Err(e) = appwindow.load_in_vectorimage_bytes(glib::Bytes::from(text.as_bytes()), Some(pos)) {
+                        log::error!("load_in_vectorimage_bytes() failed in drop target with Err {}", e);
+                    }
+                    return true;
+                } else if text_trimmed.starts_with("file://") {
+                    if let Err(e) = appwindow.load_in_uri_list_bytes(glib::Bytes::from(text.as_bytes()), Some(pos)) {
+                        log::error!("load_in_uri_list_bytes() failed in drop target with Err {}", e);
+                    }
+                    return true;
+                }
             }
-            true
+
+            false
         }));
     }
 
@@ -971,6 +1139,14 @@ impl Canvas {
             ]
     }
 
+    /// The position of the pointer in the coordinate space of the sheet. None if the pointer is outside of the canvas
+    pub fn pointer_pos_in_sheet_coords(&self) -> Option<na::Vector2<f64>> {
+        imp::Canvas::from_instance(self)
+            .pointer_pos
+            .get()
+            .map(|pointer_pos| self.transform_canvas_coords_to_sheet_coords(pointer_pos))
+    }
+
     /// The view of the parent scroller onto the Canvas
     pub fn viewport(&self) -> AABB {
         let parent = self.parent().unwrap();
@@ -1004,6 +1180,62 @@ impl Canvas {
         viewport
     }
 
+    /// The page in the center of the viewport, starting at 0
+    pub fn current_page(&self) -> u32 {
+        let page_height = f64::from(self.sheet().format().height());
+        if page_height <= 0.0 {
+            return 0;
+        }
+
+        let center_y = self.viewport_in_sheet_coords().center()[1];
+        let n_pages = (f64::from(self.sheet().height()) / page_height).ceil().max(1.0);
+
+        (center_y / page_height).floor().clamp(0.0, n_pages - 1.0) as u32
+    }
+
+    /// Sets the matches of the pdf text search, which are highlighted. The current match is emphasized
+    pub fn set_pdf_text_highlights(&self, highlights: Vec<AABB>, current: Option<usize>) {
+        let priv_ = imp::Canvas::from_instance(self);
+
+        *priv_.pdf_text_highlights.borrow_mut() = highlights;
+        priv_.pdf_text_current_highlight.set(current);
+        self.queue_draw();
+    }
+
+    pub fn pdf_text_highlights(&self) -> Vec<AABB> {
+        imp::Canvas::from_instance(self)
+            .pdf_text_highlights
+            .borrow()
+            .clone()
+    }
+
+    pub fn pdf_text_current_highlight(&self) -> Option<usize> {
+        imp::Canvas::from_instance(self)
+            .pdf_text_current_highlight
+            .get()
+    }
+
+    pub fn set_pdf_text_current_highlight(&self, current: Option<usize>) {
+        imp::Canvas::from_instance(self)
+            .pdf_text_current_highlight
+            .set(current);
+        self.queue_draw();
+    }
+
+    pub fn pdf_text_selection(&self) -> Option<PdfTextSelection> {
+        imp::Canvas::from_instance(self)
+            .pdf_text_selection
+            .borrow()
+            .clone()
+    }
+
+    pub fn set_pdf_text_selection(&self, text_selection: Option<PdfTextSelection>) {
+        *imp::Canvas::from_instance(self)
+            .pdf_text_selection
+            .borrow_mut() = text_selection;
+        self.queue_draw();
+    }
+
     /// The point parameter has the coordinate space of the sheet!
     pub fn center_around_coord_on_sheet(&self, coord: na::Vector2<f64>) {
         let (parent_width, parent_height) = (
@@ -1087,12 +1319,54 @@ impl Canvas {
             ));
     }
 
+    /// The level of the tiles the strokes are drawn from, None if the strokes are drawn directly at the current zoom.
+    /// While zooming, tiles with lower detail are used. They are refined when the zoom is applied
+    pub fn tiles_level(&self) -> Option<i32> {
+        let total_zoom = self.total_zoom();
+        if !TileCache::enabled_for_zoom(total_zoom) {
+            return None;
+        }
+
+        let level = TileCache::level_for_zoom(total_zoom);
+        if self.temporary_zoom() != 1.0 {
+            Some(level - 1)
+        } else {
+            Some(level)
+        }
+    }
+
+    /// Composites the missing tiles in the viewport when the main loop is idle, and redraws.
+    /// The redraw schedules the next batch if there are still tiles missing
+    pub fn composite_tiles_when_idle(&self) {
+        let priv_ = imp::Canvas::from_instance(self);
+
+        if priv_.tiles_composite_pending.replace(true) {
+            return;
+        }
+
+        glib::idle_add_local_once(clone!(@weak self as canvas => move || {
+            let priv_ = imp::Canvas::from_instance(&canvas);
+            priv_.tiles_composite_pending.set(false);
+
+            if let (Some(level), Some(renderer)) = (canvas.tiles_level(), canvas.renderer()) {
+                canvas.sheet().strokes_state().borrow_mut().composite_tiles(
+                    &renderer,
+                    canvas.viewport_in_sheet_coords(),
+                    level,
+                    Self::TILES_COMPOSITED_PER_IDLE,
+                );
+
+                canvas.queue_draw();
+            }
+        }));
+    }
+
     /// Update rendernodes of the background. Used when sheet size, but not zoom changed
     pub fn update_background_rendernode(&self, redraw: bool) {
         self.sheet()
         .background()
         .borrow_mut()
-        .update_rendernode(self.zoom(), self.sheet().bounds()).unwrap_or_else(|e| {
+        .update_rendernode(self.zoom(), self.sheet().bounds(), self.sheet().page_size()).unwrap_or_else(|e| {
             log::error!("failed to update rendernode for background in update_background_rendernode() with Err {}", e);
         });
 
@@ -1132,6 +1406,7 @@ impl Canvas {
                     .unwrap(),
                 self.zoom(),
                 self.sheet().bounds(),
+                self.sheet().page_size(),
             )
         {
             log::error!("failed to regenerate background, {}", e)
@@ -1168,19 +1443,20 @@ impl Canvas {
 
         priv_.snapshot(self, &snapshot);
 
-        let texture =
-            render::rendernode_to_texture(self.upcast_ref::<Widget>(), &snapshot.to_node(), None)
-                .unwrap_or_else(|e| {
-                log::error!(
-                    "rendernode_to_texture() in current_content_as_texture() failed with Err {}",
-                    e
-                );
-                None
-            })?;
+        let texture = self.renderer().and_then(|renderer| {
+            render::rendernode_to_texture(&renderer, &snapshot.to_node(), None)
+                .map_err(|e| {
+                    log::error!(
+                        "rendernode_to_texture() in current_view_as_texture() failed with Err {}",
+                        e
+                    );
+                })
+                .ok()
+        });
 
         self.selection_modifier().set_visible(true);
 
-        Some(texture)
+        texture
     }
 
     /// Process pen input start
@@ -1202,7 +1478,26 @@ impl Canvas {
             .deselect_all_strokes();
         self.selection_modifier().set_visible(false);
 
-        self.pens().borrow_mut().begin(data_entries, appwindow);
+        let current_pen = self.pens().borrow().current_pen();
+        match current_pen {
+            PenStyle::Marker | PenStyle::Brush | PenStyle::Shaper | PenStyle::Tools => {
+                self.set_cursor(Some(&self.motion_cursor()));
+            }
+            PenStyle::Eraser => {
+                self.set_cursor(gdk::Cursor::from_name("none", None).as_ref());
+            }
+            PenStyle::Selector => {
+                self.set_cursor(gdk::Cursor::from_name("cell", None).as_ref());
+            }
+            PenStyle::Unknown => {}
+        }
+
+        let surface_flags = self.pens().borrow_mut().begin(
+            data_entries,
+            &self.sheet(),
+            self.viewport_in_sheet_coords(),
+        );
+        self.handle_surface_flags(surface_flags);
 
         self.queue_resize();
         self.queue_draw();
@@ -1219,7 +1514,12 @@ impl Canvas {
             self.pens().borrow().current_pen(),
         );
 
-        self.pens().borrow_mut().motion(data_entries, appwindow);
+        let surface_flags = self.pens().borrow_mut().motion(
+            data_entries,
+            &self.sheet(),
+            self.viewport_in_sheet_coords(),
+        );
+        self.handle_surface_flags(surface_flags);
 
         self.queue_resize();
         self.queue_draw();
@@ -1227,7 +1527,16 @@ impl Canvas {
 
     /// Process pen input end
     fn process_peninput_end(&self, appwindow: &RnoteAppWindow, data_entries: VecDeque<InputData>) {
-        self.pens().borrow_mut().end(data_entries, appwindow);
+        let surface_flags = self.pens().borrow_mut().end(
+            data_entries,
+            &self.sheet(),
+            self.viewport_in_sheet_coords(),
+        );
+        self.handle_surface_flags(surface_flags);
+
+        if self.pens().borrow().current_pen() != PenStyle::Unknown {
+            self.set_cursor(Some(&self.cursor()));
+        }
 
         appwindow
             .downcast_ref::<RnoteAppWindow>()
@@ -1244,104 +1553,15 @@ impl Canvas {
         self.queue_resize();
         self.queue_draw();
     }
-}
-
-/// module for visual debugging
-pub mod debug {
-    use gtk4::{graphene, gsk, Snapshot};
-    use p2d::bounding_volume::AABB;
-
-    use crate::compose::geometry;
-    use crate::utils;
-
-    pub const COLOR_POS: utils::Color = utils::Color {
-        r: 1.0,
-        g: 0.0,
-        b: 0.0,
-        a: 1.0,
-    };
-    pub const COLOR_POS_ALT: utils::Color = utils::Color {
-        r: 1.0,
-        g: 1.0,
-        b: 0.0,
-        a: 1.0,
-    };
-    pub const COLOR_STROKE_HITBOX: utils::Color = utils::Color {
-        r: 0.0,
-        g: 0.8,
-        b: 0.2,
-        a: 0.5,
-    };
-    pub const COLOR_STROKE_BOUNDS: utils::Color = utils::Color {
-        r: 0.0,
-        g: 0.8,
-        b: 0.8,
-        a: 1.0,
-    };
-    pub const COLOR_STROKE_REGENERATE_FLAG: utils::Color = utils::Color {
-        r: 0.9,
-        g: 0.0,
-        b: 0.8,
-        a: 0.3,
-    };
-    pub const COLOR_SELECTOR_BOUNDS: utils::Color = utils::Color {
-        r: 1.0,
-        g: 0.0,
-        b: 0.8,
-        a: 1.0,
-    };
-    pub const COLOR_SHEET_BOUNDS: utils::Color = utils::Color {
-        r: 0.8,
-        g: 0.0,
-        b: 0.8,
-        a: 1.0,
-    };
-
-    pub fn draw_bounds(bounds: AABB, color: utils::Color, zoom: f64, snapshot: &Snapshot) {
-        let bounds = graphene::Rect::new(
-            bounds.mins[0] as f32,
-            bounds.mins[1] as f32,
-            (bounds.extents()[0]) as f32,
-            (bounds.extents()[1]) as f32,
-        );
-
-        let border_width = 1.5;
-        let rounded_rect = gsk::RoundedRect::new(
-            bounds.scale(zoom as f32, zoom as f32),
-            graphene::Size::zero(),
-            graphene::Size::zero(),
-            graphene::Size::zero(),
-            graphene::Size::zero(),
-        );
-
-        snapshot.append_border(
-            &rounded_rect,
-            &[border_width, border_width, border_width, border_width],
-            &[
-                color.to_gdk(),
-                color.to_gdk(),
-                color.to_gdk(),
-                color.to_gdk(),
-            ],
-        )
-    }
-
-    pub fn draw_pos(pos: na::Vector2<f64>, color: utils::Color, zoom: f64, snapshot: &Snapshot) {
-        snapshot.append_color(
-            &color.to_gdk(),
-            &graphene::Rect::new(
-                (zoom * pos[0] - 1.0) as f32,
-                (zoom * pos[1] - 1.0) as f32,
-                2.0,
-                2.0,
-            ),
-        );
-    }
 
-    pub fn draw_fill(rect: AABB, color: utils::Color, zoom: f64, snapshot: &Snapshot) {
-        snapshot.append_color(
-            &color.to_gdk(),
-            &geometry::aabb_to_graphene_rect(geometry::aabb_scale(rect, zoom)),
-        );
+    /// Updates what the pens changed on the sheet
+    fn handle_surface_flags(&self, surface_flags: SurfaceFlags) {
+        if surface_flags.sheet_resized {
+            self.update_background_rendernode(false);
+        }
+        if surface_flags.selection_changed {
+            let text_selection = self.pens().borrow_mut().selector.text_selection.take();
+            self.set_pdf_text_selection(text_selection);
+        }
     }
 }
@@ -210,7 +210,7 @@ mod imp {
 
 use gtk4::{gdk, glib, prelude::*, Button, PositionType, ToggleButton, Widget};
 
-use crate::utils;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct ColorSetter(ObjectSubclass<imp::ColorSetter>)
@@ -326,7 +326,7 @@ mod imp {
 
 use gtk4::{gdk, glib, prelude::*, subclass::prelude::*, Orientable, PositionType, Widget};
 
-use crate::utils;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct ColorPicker(ObjectSubclass<imp::ColorPicker>) @extends Widget, @implements Orientable;
@@ -1,13 +1,16 @@
 use gettextrs::gettext;
-use gtk4::{glib, glib::clone, prelude::*, Builder};
+use gtk4::{gio, glib, glib::clone, prelude::*, Builder};
 use gtk4::{
-    AboutDialog, FileChooserAction, FileChooserNative, FileFilter, MessageDialog, ResponseType,
-    ShortcutsWindow,
+    AboutDialog, Adjustment, Button, Dialog, Entry, FileChooserAction, FileChooserNative,
+    FileFilter, Label, MessageDialog, ResponseType, ShortcutsWindow, Switch, ToggleButton,
 };
 
 use crate::ui::appwindow::RnoteAppWindow;
-use crate::utils;
 use crate::{app::RnoteApp, config};
+use rnote_engine::pdf::{self, PdfImportLayout, PdfImportPrefs};
+use rnote_engine::render;
+use rnote_engine::strokes::bitmapimage::{self, BitmapImage};
+use rnote_engine::utils;
 
 // About Dialog
 pub fn dialog_about(appwindow: &RnoteAppWindow) {
@@ -56,6 +59,11 @@ pub fn dialog_clear_sheet(appwindow: &RnoteAppWindow) {
             match responsetype {
                 ResponseType::Ok => {
                     appwindow.canvas().sheet().strokes_state().borrow_mut().clear();
+                    appwindow.canvas().sheet().reset_pages();
+                appwindow.canvas().set_pdf_text_selection(None);
+                appwindow.pdf_search_bar().search_bar().set_search_mode(false);
+                    appwindow.canvas().set_pdf_text_selection(None);
+                    appwindow.pdf_search_bar().search_bar().set_search_mode(false);
                     appwindow.canvas().selection_modifier().set_visible(false);
                     appwindow.canvas().set_unsaved_changes(false);
                     appwindow.canvas().set_empty(true);
@@ -87,6 +95,9 @@ pub fn dialog_new_sheet(appwindow: &RnoteAppWindow) {
                 appwindow.application().unwrap().downcast::<RnoteApp>().unwrap().set_output_file(None, &appwindow);
 
                 appwindow.canvas().sheet().strokes_state().borrow_mut().clear();
+                appwindow.canvas().sheet().reset_pages();
+                appwindow.canvas().set_pdf_text_selection(None);
+                appwindow.pdf_search_bar().search_bar().set_search_mode(false);
                 appwindow.canvas().selection_modifier().set_visible(false);
                 appwindow.canvas().set_unsaved_changes(false);
                 appwindow.canvas().set_empty(true);
@@ -136,6 +147,107 @@ pub fn dialog_quit_save(appwindow: &RnoteAppWindow) {
     dialog_quit_save.show();
 }
 
+/// Asks for the name of a file in the workspace. The name is handed over to `on_apply` when the dialog is applied
+fn dialog_workspace_file_name<F>(
+    appwindow: &RnoteAppWindow,
+    title: &str,
+    apply_label: &str,
+    initial_name: &str,
+    on_apply: F,
+) where
+    F: Fn(&str) + 'static,
+{
+    let builder =
+        Builder::from_resource((String::from(config::APP_IDPATH) + "ui/dialogs.ui").as_str());
+    let dialog_workspace_file_name: MessageDialog =
+        builder.object("dialog_workspace_file_name").unwrap();
+    let workspace_file_name_entry: Entry = builder.object("workspace_file_name_entry").unwrap();
+    let workspace_file_name_button_apply: Button =
+        builder.object("workspace_file_name_button_apply").unwrap();
+
+    dialog_workspace_file_name.set_transient_for(Some(appwindow));
+    dialog_workspace_file_name.set_property("text", title.to_value());
+    workspace_file_name_button_apply.set_label(apply_label);
+
+    // Selecting the name without the extension, so that it can be replaced right away
+    workspace_file_name_entry.set_text(initial_name);
+    let name_len = initial_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(initial_name)
+        .chars()
+        .count();
+    workspace_file_name_entry.select_region(0, name_len as i32);
+
+    dialog_workspace_file_name.connect_response(
+        clone!(@weak workspace_file_name_entry => move |dialog_workspace_file_name, responsetype| {
+            match responsetype {
+                ResponseType::Apply => {
+                    let name = workspace_file_name_entry.text();
+
+                    if !name.trim().is_empty() {
+                        on_apply(name.trim());
+                    }
+                    dialog_workspace_file_name.close();
+                }
+                _ => {
+                    dialog_workspace_file_name.close();
+                }
+            }
+        }),
+    );
+
+    dialog_workspace_file_name.show();
+}
+
+pub fn dialog_rename_workspace_file(appwindow: &RnoteAppWindow, file: &gio::File) {
+    let name = file
+        .basename()
+        .map(|basename| basename.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    dialog_workspace_file_name(
+        appwindow,
+        &gettext("Rename"),
+        &gettext("Rename"),
+        &name,
+        clone!(@weak appwindow, @strong file => move |new_name| {
+            if let Err(e) = appwindow.workspacebrowser().rename_file(&appwindow, &file, new_name) {
+                log::error!("rename_file() failed in dialog_rename_workspace_file() with Err {}", e);
+            }
+        }),
+    );
+}
+
+pub fn dialog_new_workspace_note(appwindow: &RnoteAppWindow) {
+    dialog_workspace_file_name(
+        appwindow,
+        &gettext("New Note"),
+        &gettext("Create"),
+        &(gettext("New Note") + ".rnote"),
+        clone!(@weak appwindow => move |name| {
+            if let Err(e) = appwindow.workspacebrowser().create_note(name) {
+                log::error!("create_note() failed in dialog_new_workspace_note() with Err {}", e);
+            }
+        }),
+    );
+}
+
+pub fn dialog_new_workspace_folder(appwindow: &RnoteAppWindow) {
+    dialog_workspace_file_name(
+        appwindow,
+        &gettext("New Folder"),
+        &gettext("Create"),
+        &gettext("New Folder"),
+        clone!(@weak appwindow => move |name| {
+            if let Err(e) = appwindow.workspacebrowser().create_folder(name) {
+                log::error!("create_folder() failed in dialog_new_workspace_folder() with Err {}", e);
+            }
+        }),
+    );
+}
+
 pub fn dialog_open_overwrite(appwindow: &RnoteAppWindow) {
     let builder =
         Builder::from_resource((String::from(config::APP_IDPATH) + "ui/dialogs.ui").as_str());
@@ -168,6 +280,172 @@ pub fn dialog_open_overwrite(appwindow: &RnoteAppWindow) {
     dialog_open_input_file.show();
 }
 
+/// Shows the dialog for the pdf import preferences and imports the pdf when accepted.
+/// Returns the position below the imported pages, where the next import can be placed
+pub async fn dialog_import_pdf_w_prefs(
+    appwindow: &RnoteAppWindow,
+    bytes: glib::Bytes,
+    target_pos: Option<na::Vector2<f64>>,
+) -> Option<na::Vector2<f64>> {
+    let n_pages = match pdf::pdf_n_pages(&bytes) {
+        Ok(n_pages) => n_pages,
+        Err(e) => {
+            log::error!(
+                "pdf_n_pages() failed in dialog_import_pdf_w_prefs() with Err {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    let builder =
+        Builder::from_resource((String::from(config::APP_IDPATH) + "ui/dialogs.ui").as_str());
+    let dialog_import_pdf: Dialog = builder.object("dialog_import_pdf_w_prefs").unwrap();
+    let pdf_import_info_label: Label = builder.object("pdf_import_info_label").unwrap();
+    let pdf_import_first_page_adj: Adjustment =
+        builder.object("pdf_import_first_page_adj").unwrap();
+    let pdf_import_last_page_adj: Adjustment = builder.object("pdf_import_last_page_adj").unwrap();
+    let pdf_import_placement_end_toggle: ToggleButton =
+        builder.object("pdf_import_placement_end_toggle").unwrap();
+    let pdf_import_on_sheet_pages_switch: Switch =
+        builder.object("pdf_import_on_sheet_pages_switch").unwrap();
+    let pdf_import_as_vector_toggle: ToggleButton =
+        builder.object("pdf_import_as_vector_toggle").unwrap();
+    let pdf_import_as_bitmap_toggle: ToggleButton =
+        builder.object("pdf_import_as_bitmap_toggle").unwrap();
+    let pdf_import_bitmap_dpi_row: adw::ActionRow =
+        builder.object("pdf_import_bitmap_dpi_row").unwrap();
+    let pdf_import_bitmap_dpi_adj: Adjustment =
+        builder.object("pdf_import_bitmap_dpi_adj").unwrap();
+
+    dialog_import_pdf.set_transient_for(Some(appwindow));
+
+    pdf_import_info_label.set_label(&format!(
+        "{} {}",
+        gettext("Number of pages in the document:"),
+        n_pages
+    ));
+
+    pdf_import_first_page_adj.set_upper(f64::from(n_pages.max(1)));
+    pdf_import_first_page_adj.set_value(1.0);
+    pdf_import_last_page_adj.set_upper(f64::from(n_pages.max(1)));
+    pdf_import_last_page_adj.set_value(f64::from(n_pages.max(1)));
+
+    pdf_import_as_vector_toggle.set_active(appwindow.canvas().pdf_import_as_vector());
+    pdf_import_as_bitmap_toggle.set_active(!appwindow.canvas().pdf_import_as_vector());
+    pdf_import_bitmap_dpi_row.set_sensitive(!appwindow.canvas().pdf_import_as_vector());
+    pdf_import_bitmap_dpi_adj.set_value(PdfImportPrefs::BITMAP_DPI_DEFAULT);
+
+    pdf_import_as_bitmap_toggle.connect_toggled(
+        clone!(@weak pdf_import_bitmap_dpi_row => move |pdf_import_as_bitmap_toggle| {
+            pdf_import_bitmap_dpi_row.set_sensitive(pdf_import_as_bitmap_toggle.is_active());
+        }),
+    );
+
+    let responsetype = dialog_import_pdf.run_future().await;
+    dialog_import_pdf.close();
+
+    if responsetype != ResponseType::Ok {
+        return None;
+    }
+
+    let sheet = appwindow.canvas().sheet();
+    let page_size = sheet.page_size();
+
+    // The spinbuttons count the pages starting at 1
+    let first_page = pdf_import_first_page_adj.value().round() as u32;
+    let last_page = pdf_import_last_page_adj.value().round() as u32;
+    let page_range = (first_page.min(last_page).max(1) - 1)..first_page.max(last_page);
+
+    let at_end = pdf_import_placement_end_toggle.is_active();
+    let target_page = if at_end {
+        let content_height = f64::from(sheet.strokes_state().borrow().calc_height());
+        // The strokes of previous imports are inserted in the background, so they might not be part of the content yet
+        let target_pos_page =
+            target_pos.map_or(0, |pos| (pos[1] / page_size[1]).floor().max(0.0) as u32);
+
+        ((content_height / page_size[1]).ceil() as u32).max(target_pos_page)
+    } else {
+        target_pos.map_or_else(
+            || appwindow.canvas().current_page(),
+            |pos| (pos[1] / page_size[1]).floor().max(0.0) as u32,
+        )
+    };
+
+    let layout = if pdf_import_on_sheet_pages_switch.is_active() {
+        if !at_end {
+            // Make room for the imported pages
+            sheet.insert_pages(target_page, page_range.len() as u32);
+            sheet.resize_to_format();
+            appwindow.canvas().regenerate_background(false);
+            appwindow.page_navigator().update_pages(appwindow);
+        }
+
+        PdfImportLayout::SheetPages {
+            first_page: target_page,
+            page_size,
+        }
+    } else {
+        let pos = target_pos.filter(|_| !at_end).unwrap_or_else(|| {
+            na::vector![
+                BitmapImage::OFFSET_X_DEFAULT,
+                f64::from(target_page) * page_size[1] + BitmapImage::OFFSET_Y_DEFAULT
+            ]
+        });
+        let page_width = f64::from(sheet.width()) * (appwindow.canvas().pdf_import_width() / 100.0);
+
+        PdfImportLayout::Continuous {
+            pos,
+            page_width: Some(page_width.round()),
+        }
+    };
+
+    let prefs = PdfImportPrefs {
+        page_range: Some(page_range.clone()),
+        layout,
+        bitmap_dpi: pdf_import_bitmap_dpi_adj.value(),
+    };
+
+    let next_pos = match layout {
+        PdfImportLayout::SheetPages { first_page, .. } => Some(na::vector![
+            BitmapImage::OFFSET_X_DEFAULT,
+            f64::from(first_page + page_range.len() as u32) * page_size[1]
+                + BitmapImage::OFFSET_Y_DEFAULT
+        ]),
+        PdfImportLayout::Continuous { pos, .. } => match pdf::pdf_import_bounds(&bytes, &prefs) {
+            Ok(bounds) => bounds.map(|bounds| {
+                na::vector![
+                    pos[0],
+                    bounds.maxs[1] + PdfImportLayout::CONTINUOUS_PAGE_SPACING
+                ]
+            }),
+            Err(e) => {
+                log::error!(
+                    "pdf_import_bounds() failed in dialog_import_pdf_w_prefs() with Err {}",
+                    e
+                );
+                None
+            }
+        },
+    };
+
+    // Remember the image type for the next import
+    appwindow.application().unwrap().change_action_state(
+        "pdf-import-as-vector",
+        &pdf_import_as_vector_toggle.is_active().to_variant(),
+    );
+
+    if let Err(e) = appwindow.load_in_pdf_bytes(bytes, prefs) {
+        log::error!(
+            "load_in_pdf_bytes() failed in dialog_import_pdf_w_prefs() with Err {}",
+            e
+        );
+        return None;
+    }
+
+    next_pos
+}
+
 // FileChooserNative Dialogs
 
 pub fn dialog_open_sheet(appwindow: &RnoteAppWindow) {
@@ -297,14 +575,21 @@ pub fn dialog_save_sheet_as(appwindow: &RnoteAppWindow) {
 pub fn dialog_import_file(appwindow: &RnoteAppWindow) {
     let filter = FileFilter::new();
     filter.add_mime_type("image/svg+xml");
-    filter.add_mime_type("image/png");
-    filter.add_mime_type("image/jpeg");
+    for mime_type in bitmapimage::IMPORT_MIME_TYPES {
+        filter.add_mime_type(mime_type);
+    }
     filter.add_mime_type("application/pdf");
     filter.add_pattern("*.svg");
     filter.add_pattern("*.png");
     filter.add_pattern("*.jpg");
+    filter.add_pattern("*.jpeg");
+    filter.add_pattern("*.webp");
+    filter.add_pattern("*.gif");
+    filter.add_pattern("*.bmp");
+    filter.add_pattern("*.tif");
+    filter.add_pattern("*.tiff");
     filter.add_pattern("*.pdf");
-    filter.set_name(Some(&gettext("PNG / SVG / JPG / PDF File")));
+    filter.set_name(Some(&gettext("SVG / Image / PDF File")));
 
     let dialog_import_file: FileChooserNative = FileChooserNative::builder()
         .title(&gettext("Import File"))
@@ -342,11 +627,71 @@ pub fn dialog_import_file(appwindow: &RnoteAppWindow) {
     *appwindow.filechoosernative().borrow_mut() = Some(dialog_import_file);
 }
 
+pub fn dialog_import_background_template(appwindow: &RnoteAppWindow) {
+    let filter = FileFilter::new();
+    filter.add_mime_type("image/svg+xml");
+    for mime_type in bitmapimage::IMPORT_MIME_TYPES {
+        filter.add_mime_type(mime_type);
+    }
+    filter.add_mime_type("application/pdf");
+    filter.add_pattern("*.svg");
+    filter.add_pattern("*.png");
+    filter.add_pattern("*.jpg");
+    filter.add_pattern("*.jpeg");
+    filter.add_pattern("*.webp");
+    filter.add_pattern("*.gif");
+    filter.add_pattern("*.bmp");
+    filter.add_pattern("*.tif");
+    filter.add_pattern("*.tiff");
+    filter.add_pattern("*.pdf");
+    filter.set_name(Some(&gettext("SVG / Image / PDF File")));
+
+    let dialog_import_background_template: FileChooserNative = FileChooserNative::builder()
+        .title(&gettext("Import Background Template"))
+        .modal(true)
+        .transient_for(appwindow)
+        .accept_label(&gettext("Import"))
+        .cancel_label(&gettext("Cancel"))
+        .action(FileChooserAction::Open)
+        .select_multiple(false)
+        .build();
+
+    dialog_import_background_template.add_filter(&filter);
+
+    dialog_import_background_template.connect_response(
+        clone!(@weak appwindow => move |dialog_import_background_template, responsetype| {
+            match responsetype {
+                ResponseType::Accept => {
+                    match dialog_import_background_template.file() {
+                        Some(file) => {
+                            appwindow.load_in_background_template(&file);
+                        },
+                        None => { log::error!("unable to import background template. No file selected.")},
+                    }
+                }
+                _ => {
+                }
+            }
+        }),
+    );
+
+    dialog_import_background_template.show();
+    // keeping the filechooser around because otherwise GTK won't keep it alive
+    *appwindow.filechoosernative().borrow_mut() = Some(dialog_import_background_template);
+}
+
 pub fn dialog_export_selection(appwindow: &RnoteAppWindow) {
     let filter = FileFilter::new();
     filter.add_mime_type("image/svg+xml");
+    filter.add_mime_type("image/png");
+    filter.add_mime_type("image/jpeg");
+    filter.add_mime_type("image/webp");
     filter.add_pattern("*.svg");
-    filter.set_name(Some(&gettext("SVG File")));
+    filter.add_pattern("*.png");
+    filter.add_pattern("*.jpg");
+    filter.add_pattern("*.jpeg");
+    filter.add_pattern("*.webp");
+    filter.set_name(Some(&gettext("SVG / PNG / JPG / WebP File")));
 
     let dialog_export_selection: FileChooserNative = FileChooserNative::builder()
         .title(&gettext("Export Selection"))
@@ -367,7 +712,19 @@ pub fn dialog_export_selection(appwindow: &RnoteAppWindow) {
                 ResponseType::Accept => {
                     match dialog_export_selection.file() {
                         Some(file) => {
-                            if let Err(e) = appwindow.canvas().sheet().strokes_state().borrow().export_selection_as_svg(file) {
+                            // The format is chosen by the file extension, defaulting to svg
+                            let bitmap_format = file
+                                .path()
+                                .and_then(|path| path.extension().map(|extension| extension.to_string_lossy().to_string()))
+                                .and_then(|extension| render::BitmapExportFormat::from_extension(&extension));
+
+                            let result = if let Some(bitmap_format) = bitmap_format {
+                                appwindow.canvas().sheet().strokes_state().borrow().export_selection_as_bitmapimage(file, bitmap_format, appwindow.canvas().zoom())
+                            } else {
+                                appwindow.canvas().sheet().strokes_state().borrow().export_selection_as_svg(file)
+                            };
+
+                            if let Err(e) = result {
                                 log::error!("exporting selection failed with error `{}`", e);
                             }
                         },
@@ -427,3 +784,47 @@ pub fn dialog_export_sheet(appwindow: &RnoteAppWindow) {
     // keeping the filechooser around because otherwise GTK won't keep it alive
     *appwindow.filechoosernative().borrow_mut() = Some(dialog_export_sheet);
 }
+
+pub fn dialog_export_annotated_pdf(appwindow: &RnoteAppWindow) {
+    let filter = FileFilter::new();
+    filter.add_mime_type("application/pdf");
+    filter.add_pattern("*.pdf");
+    filter.set_name(Some(&gettext("PDF file")));
+
+    let dialog_export_annotated_pdf: FileChooserNative = FileChooserNative::builder()
+        .title(&gettext("Export Annotated PDF"))
+        .modal(true)
+        .transient_for(appwindow)
+        .accept_label(&gettext("Export"))
+        .cancel_label(&gettext("Cancel"))
+        .action(FileChooserAction::Save)
+        .select_multiple(false)
+        .build();
+    dialog_export_annotated_pdf.add_filter(&filter);
+
+    dialog_export_annotated_pdf
+        .set_current_name(format!("{}_annotated.pdf", utils::now()).as_str());
+
+    dialog_export_annotated_pdf.connect_response(
+        clone!(@weak appwindow => move |dialog_export_annotated_pdf, responsetype| {
+            match responsetype {
+                ResponseType::Accept => {
+                    match dialog_export_annotated_pdf.file() {
+                        Some(file) => {
+                            if let Err(e) = appwindow.canvas().sheet().export_sheet_as_annotated_pdf(file) {
+                                log::error!("exporting annotated pdf failed with error `{}`", e);
+                            }
+                        },
+                        None => { log::error!("Can't export annotated pdf. No file selected.")},
+                    }
+                }
+                _ => {
+                }
+            }
+        }),
+    );
+
+    dialog_export_annotated_pdf.show();
+    // keeping the filechooser around because otherwise GTK won't keep it alive
+    *appwindow.filechoosernative().borrow_mut() = Some(dialog_export_annotated_pdf);
+}
@@ -8,6 +8,8 @@ pub mod colorpicker;
 pub mod develactions;
 pub mod dialogs;
 pub mod mainheader;
+pub mod pagenavigator;
+pub mod pdfsearchbar;
 pub mod penssidebar;
 pub mod selectionmodifier;
 pub mod settingspanel;
@@ -0,0 +1,394 @@
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk4::{
+        glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate, ListBox, Picture, Widget,
+    };
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/pagenavigator.ui")]
+    pub struct PageNavigator {
+        pub n_pages: Cell<u32>,
+        /// The thumbnail pictures, indexed by the page
+        pub thumbnails: RefCell<Vec<Picture>>,
+        /// The hashes of the pages the thumbnails were last requested for. None if not yet requested
+        pub page_hashes: RefCell<Vec<Option<u64>>>,
+        pub thumbnail_tx: RefCell<Option<glib::Sender<super::RenderedThumbnail>>>,
+        pub update_timeout_id: RefCell<Option<glib::SourceId>>,
+        #[template_child]
+        pub pages_listbox: TemplateChild<ListBox>,
+        #[template_child]
+        pub insert_page_before_button: TemplateChild<Button>,
+        #[template_child]
+        pub insert_page_after_button: TemplateChild<Button>,
+        #[template_child]
+        pub duplicate_page_button: TemplateChild<Button>,
+        #[template_child]
+        pub move_page_up_button: TemplateChild<Button>,
+        #[template_child]
+        pub move_page_down_button: TemplateChild<Button>,
+        #[template_child]
+        pub delete_page_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PageNavigator {
+        const NAME: &'static str = "PageNavigator";
+        type Type = super::PageNavigator;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PageNavigator {
+        fn constructed(&self, obj: &Self::Type) {
+            self.parent_constructed(obj);
+        }
+
+        fn dispose(&self, obj: &Self::Type) {
+            if let Some(update_timeout_id) = self.update_timeout_id.take() {
+                update_timeout_id.remove();
+            }
+
+            while let Some(child) = obj.first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for PageNavigator {}
+}
+
+use std::time;
+
+use crate::ui::appwindow::RnoteAppWindow;
+use gettextrs::gettext;
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Align, Frame, Label, ListBox, ListBoxRow,
+    Orientation, Picture, Widget,
+};
+use rnote_engine::render;
+
+/// A page thumbnail which was rendered in the background
+#[derive(Debug, Clone)]
+pub struct RenderedThumbnail {
+    pub page: u32,
+    /// The hash of the page the thumbnail was rendered from
+    pub hash: u64,
+    pub image: render::Image,
+}
+
+glib::wrapper! {
+    pub struct PageNavigator(ObjectSubclass<imp::PageNavigator>)
+        @extends Widget;
+}
+
+impl Default for PageNavigator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PageNavigator {
+    /// The width of the thumbnails in pixels
+    pub const THUMBNAIL_WIDTH: f64 = 128.0;
+    /// The interval in which the pages are checked for changes while the navigator is shown
+    pub const UPDATE_INTERVAL: time::Duration = time::Duration::from_millis(500);
+    /// The maximum number of thumbnails requested in a single update, so that long sheets don't block the UI
+    pub const MAX_THUMBNAILS_PER_UPDATE: usize = 8;
+
+    pub fn new() -> Self {
+        glib::Object::new(&[]).expect("Failed to create PageNavigator")
+    }
+
+    pub fn pages_listbox(&self) -> ListBox {
+        imp::PageNavigator::from_instance(self).pages_listbox.get()
+    }
+
+    /// The page of the selected row
+    pub fn selected_page(&self) -> Option<u32> {
+        self.pages_listbox()
+            .selected_row()
+            .and_then(|row| u32::try_from(row.index()).ok())
+    }
+
+    /// Selects the row of the page, without navigating to it
+    pub fn select_page(&self, page: u32) {
+        if self.selected_page() == Some(page) {
+            return;
+        }
+
+        if let Some(row) = self.pages_listbox().row_at_index(page as i32) {
+            self.pages_listbox().select_row(Some(&row));
+        }
+    }
+
+    pub fn init(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::PageNavigator::from_instance(self);
+
+        let (thumbnail_tx, thumbnail_rx) =
+            glib::MainContext::channel::<RenderedThumbnail>(glib::PRIORITY_DEFAULT_IDLE);
+        thumbnail_rx.attach(
+            None,
+            clone!(@weak self as page_navigator => @default-return glib::Continue(false), move |thumbnail| {
+                page_navigator.set_thumbnail(thumbnail);
+
+                glib::Continue(true)
+            }),
+        );
+        priv_.thumbnail_tx.borrow_mut().replace(thumbnail_tx);
+
+        priv_.pages_listbox.get().connect_row_activated(
+            clone!(@weak appwindow => move |_pages_listbox, row| {
+                if let Ok(page) = u32::try_from(row.index()) {
+                    let page_size = appwindow.canvas().sheet().page_size();
+
+                    appwindow.canvas().center_around_coord_on_sheet(na::vector![
+                        page_size[0] * 0.5,
+                        (f64::from(page) + 0.5) * page_size[1]
+                    ]);
+                }
+            }),
+        );
+
+        for (button, action_name) in [
+            (priv_.insert_page_before_button.get(), "insert-page-before"),
+            (priv_.insert_page_after_button.get(), "insert-page-after"),
+            (priv_.duplicate_page_button.get(), "duplicate-page"),
+            (priv_.move_page_up_button.get(), "move-page-up"),
+            (priv_.move_page_down_button.get(), "move-page-down"),
+            (priv_.delete_page_button.get(), "delete-page"),
+        ] {
+            button.connect_clicked(
+                clone!(@weak self as page_navigator, @weak appwindow => move |_button| {
+                    // Operate on the selected page, or the current page if none is selected
+                    let page = page_navigator
+                        .selected_page()
+                        .map(|page| page as i32)
+                        .unwrap_or(-1);
+
+                    adw::prelude::ActionGroupExt::activate_action(
+                        &appwindow,
+                        action_name,
+                        Some(&page.to_variant()),
+                    );
+                }),
+            );
+        }
+
+        // Keep the pages up to date with the sheet size and the scroll position
+        if let Some(vadjustment) = appwindow.canvas_scroller().vadjustment() {
+            vadjustment.connect_changed(
+                clone!(@weak self as page_navigator, @weak appwindow => move |_vadjustment| {
+                    page_navigator.update_pages(&appwindow);
+                }),
+            );
+            vadjustment.connect_value_changed(
+                clone!(@weak self as page_navigator, @weak appwindow => move |_vadjustment| {
+                    page_navigator.select_page(appwindow.canvas().current_page());
+                }),
+            );
+        }
+
+        // Pages are only checked for changes while the navigator is shown
+        self.connect_map(clone!(@weak appwindow => move |page_navigator| {
+            let priv_ = imp::PageNavigator::from_instance(page_navigator);
+
+            page_navigator.update_pages(&appwindow);
+
+            if let Some(update_timeout_id) = priv_.update_timeout_id.take() {
+                update_timeout_id.remove();
+            }
+            priv_.update_timeout_id.borrow_mut().replace(glib::source::timeout_add_local(
+                Self::UPDATE_INTERVAL,
+                clone!(@weak page_navigator, @weak appwindow => @default-return glib::Continue(false), move || {
+                    page_navigator.update_thumbnails(&appwindow);
+
+                    glib::Continue(true)
+                }),
+            ));
+        }));
+        self.connect_unmap(|page_navigator| {
+            let priv_ = imp::PageNavigator::from_instance(page_navigator);
+
+            if let Some(update_timeout_id) = priv_.update_timeout_id.take() {
+                update_timeout_id.remove();
+            }
+        });
+    }
+
+    /// Updates the page rows to match the pages of the sheet.
+    /// Rows are only appended or removed at the end, so the thumbnails of the remaining pages are kept
+    pub fn update_pages(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::PageNavigator::from_instance(self);
+        let n_pages = appwindow.canvas().sheet().calc_n_pages().max(1) as u32;
+        let pages_listbox = self.pages_listbox();
+
+        while priv_.n_pages.get() < n_pages {
+            let (row, thumbnail) = Self::new_page_row(priv_.n_pages.get());
+            pages_listbox.append(&row);
+
+            priv_.thumbnails.borrow_mut().push(thumbnail);
+            priv_.page_hashes.borrow_mut().push(None);
+            priv_.n_pages.set(priv_.n_pages.get() + 1);
+        }
+        while priv_.n_pages.get() > n_pages {
+            if let Some(row) = pages_listbox.row_at_index(priv_.n_pages.get() as i32 - 1) {
+                pages_listbox.remove(&row);
+            }
+
+            priv_.thumbnails.borrow_mut().pop();
+            priv_.page_hashes.borrow_mut().pop();
+            priv_.n_pages.set(priv_.n_pages.get() - 1);
+        }
+
+        self.select_page(appwindow.canvas().current_page());
+        self.update_thumbnails(appwindow);
+    }
+
+    /// Renders the thumbnails of the pages which changed since they were last requested.
+    /// The Svgs are generated on the main thread, the images are rendered in the background
+    pub fn update_thumbnails(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::PageNavigator::from_instance(self);
+
+        if !self.is_mapped() {
+            return;
+        }
+        let thumbnail_tx = if let Some(thumbnail_tx) = priv_.thumbnail_tx.borrow().clone() {
+            thumbnail_tx
+        } else {
+            return;
+        };
+
+        let sheet = appwindow.canvas().sheet();
+        let page_size = sheet.page_size();
+        if page_size[0] <= 0.0 || page_size[1] <= 0.0 {
+            return;
+        }
+        let zoom = Self::THUMBNAIL_WIDTH / page_size[0];
+
+        for thumbnail in priv_.thumbnails.borrow().iter() {
+            thumbnail.set_size_request(
+                Self::THUMBNAIL_WIDTH as i32,
+                (page_size[1] * zoom).round() as i32,
+            );
+        }
+
+        let changed_pages = sheet
+            .gen_page_hashes(priv_.n_pages.get())
+            .into_iter()
+            .enumerate()
+            .filter(|(page, hash)| {
+                priv_.page_hashes.borrow().get(*page).copied().flatten() != Some(*hash)
+            })
+            .take(Self::MAX_THUMBNAILS_PER_UPDATE)
+            .collect::<Vec<(usize, u64)>>();
+
+        for (page, hash) in changed_pages {
+            let page = page as u32;
+            let page_bounds = sheet.page_bounds(page);
+
+            let svgs = match sheet.gen_page_svgs(page) {
+                Ok(svgs) => svgs,
+                Err(e) => {
+                    log::error!(
+                        "gen_page_svgs() failed in update_thumbnails() with Err {}",
+                        e
+                    );
+                    continue;
+                }
+            };
+            priv_.page_hashes.borrow_mut()[page as usize] = Some(hash);
+
+            let strokes_state = sheet.strokes_state();
+            let strokes_state = strokes_state.borrow();
+            let renderer = strokes_state.renderer.clone();
+            let thumbnail_tx = thumbnail_tx.clone();
+
+            strokes_state.threadpool.spawn(move || {
+                match renderer.read().unwrap().gen_image(zoom, &svgs, page_bounds) {
+                    Ok(image) => {
+                        thumbnail_tx
+                            .send(RenderedThumbnail { page, hash, image })
+                            .unwrap_or_else(|e| {
+                                log::error!(
+                                    "thumbnail_tx.send() failed in update_thumbnails() for page {}, with Err {}",
+                                    page,
+                                    e
+                                );
+                            });
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "gen_image() failed in update_thumbnails() for page {}, with Err {}",
+                            page,
+                            e
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    /// Sets the rendered thumbnail, if the page did not change in the meantime
+    fn set_thumbnail(&self, thumbnail: RenderedThumbnail) {
+        let priv_ = imp::PageNavigator::from_instance(self);
+        let page = thumbnail.page as usize;
+
+        if priv_.page_hashes.borrow().get(page).copied().flatten() != Some(thumbnail.hash) {
+            return;
+        }
+
+        match render::image_to_memtexture(&thumbnail.image) {
+            Ok(memtexture) => {
+                if let Some(picture) = priv_.thumbnails.borrow().get(page) {
+                    picture.set_paintable(Some(&memtexture));
+                }
+            }
+            Err(e) => {
+                log::error!(
+                    "image_to_memtexture() failed in set_thumbnail() with Err {}",
+                    e
+                );
+            }
+        }
+    }
+
+    fn new_page_row(page: u32) -> (ListBoxRow, Picture) {
+        let page_box = gtk4::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(6)
+            .margin_end(6)
+            .build();
+
+        let thumbnail = Picture::builder()
+            .can_shrink(true)
+            .keep_aspect_ratio(true)
+            .build();
+        let thumbnail_frame = Frame::builder()
+            .child(&thumbnail)
+            .halign(Align::Center)
+            .build();
+        page_box.append(&thumbnail_frame);
+
+        let page_label = Label::builder()
+            .label(&format!("{} {}", gettext("Page"), page + 1))
+            .halign(Align::Center)
+            .build();
+        page_box.append(&page_label);
+
+        let row = ListBoxRow::new();
+        row.set_child(Some(&page_box));
+
+        (row, thumbnail)
+    }
+}
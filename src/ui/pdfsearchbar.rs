@@ -0,0 +1,228 @@
+mod imp {
+    use gtk4::{
+        glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate, Label, SearchBar,
+        SearchEntry, Widget,
+    };
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/pdfsearchbar.ui")]
+    pub struct PdfSearchBar {
+        #[template_child]
+        pub search_bar: TemplateChild<SearchBar>,
+        #[template_child]
+        pub search_entry: TemplateChild<SearchEntry>,
+        #[template_child]
+        pub prev_button: TemplateChild<Button>,
+        #[template_child]
+        pub next_button: TemplateChild<Button>,
+        #[template_child]
+        pub matches_label: TemplateChild<Label>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PdfSearchBar {
+        const NAME: &'static str = "PdfSearchBar";
+        type Type = super::PdfSearchBar;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            Self::bind_template(klass);
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PdfSearchBar {
+        fn constructed(&self, obj: &Self::Type) {
+            self.parent_constructed(obj);
+        }
+
+        fn dispose(&self, obj: &Self::Type) {
+            while let Some(child) = obj.first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for PdfSearchBar {}
+}
+
+use crate::ui::appwindow::RnoteAppWindow;
+use gettextrs::gettext;
+use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, SearchBar, SearchEntry, Widget};
+
+glib::wrapper! {
+    pub struct PdfSearchBar(ObjectSubclass<imp::PdfSearchBar>)
+        @extends Widget;
+}
+
+impl Default for PdfSearchBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfSearchBar {
+    pub fn new() -> Self {
+        glib::Object::new(&[]).expect("Failed to create PdfSearchBar")
+    }
+
+    pub fn search_bar(&self) -> SearchBar {
+        imp::PdfSearchBar::from_instance(self).search_bar.get()
+    }
+
+    pub fn search_entry(&self) -> SearchEntry {
+        imp::PdfSearchBar::from_instance(self).search_entry.get()
+    }
+
+    pub fn init(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::PdfSearchBar::from_instance(self);
+
+        priv_.search_entry.get().connect_search_changed(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |search_entry| {
+                pdf_search_bar.search(&appwindow, search_entry.text().as_str());
+            }),
+        );
+
+        priv_.search_entry.get().connect_activate(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |_search_entry| {
+                pdf_search_bar.next_match(&appwindow);
+            }),
+        );
+
+        priv_.search_entry.get().connect_next_match(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |_search_entry| {
+                pdf_search_bar.next_match(&appwindow);
+            }),
+        );
+
+        priv_.search_entry.get().connect_previous_match(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |_search_entry| {
+                pdf_search_bar.prev_match(&appwindow);
+            }),
+        );
+
+        priv_.next_button.get().connect_clicked(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |_next_button| {
+                pdf_search_bar.next_match(&appwindow);
+            }),
+        );
+
+        priv_.prev_button.get().connect_clicked(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |_prev_button| {
+                pdf_search_bar.prev_match(&appwindow);
+            }),
+        );
+
+        priv_.search_bar.get().connect_search_mode_enabled_notify(
+            clone!(@weak self as pdf_search_bar, @weak appwindow => move |search_bar| {
+                if search_bar.is_search_mode() {
+                    pdf_search_bar.search_entry().grab_focus();
+                    pdf_search_bar.search(&appwindow, pdf_search_bar.search_entry().text().as_str());
+                } else {
+                    // Remove the highlights when the search bar is closed
+                    appwindow.canvas().set_pdf_text_highlights(vec![], None);
+                    pdf_search_bar.update_matches_label(&appwindow);
+                }
+            }),
+        );
+    }
+
+    /// Searches the text in the imported pdf pages, highlights the matches and jumps to the first match below the top of the viewport
+    pub fn search(&self, appwindow: &RnoteAppWindow, text: &str) {
+        let matches = appwindow
+            .canvas()
+            .sheet()
+            .strokes_state()
+            .borrow()
+            .search_pdf_text(text);
+
+        let viewport_top = appwindow.canvas().viewport_in_sheet_coords().mins[1];
+        let current = if matches.is_empty() {
+            None
+        } else {
+            Some(
+                matches
+                    .iter()
+                    .position(|text_match| text_match.mins[1] >= viewport_top)
+                    .unwrap_or(0),
+            )
+        };
+
+        appwindow.canvas().set_pdf_text_highlights(matches, current);
+        self.jump_to_current_match(appwindow);
+    }
+
+    pub fn next_match(&self, appwindow: &RnoteAppWindow) {
+        let n_matches = appwindow.canvas().pdf_text_highlights().len();
+        if n_matches == 0 {
+            return;
+        }
+
+        let current = appwindow
+            .canvas()
+            .pdf_text_current_highlight()
+            .map(|current| (current + 1) % n_matches)
+            .unwrap_or(0);
+
+        appwindow
+            .canvas()
+            .set_pdf_text_current_highlight(Some(current));
+        self.jump_to_current_match(appwindow);
+    }
+
+    pub fn prev_match(&self, appwindow: &RnoteAppWindow) {
+        let n_matches = appwindow.canvas().pdf_text_highlights().len();
+        if n_matches == 0 {
+            return;
+        }
+
+        let current = appwindow
+            .canvas()
+            .pdf_text_current_highlight()
+            .map(|current| (current + n_matches - 1) % n_matches)
+            .unwrap_or(n_matches - 1);
+
+        appwindow
+            .canvas()
+            .set_pdf_text_current_highlight(Some(current));
+        self.jump_to_current_match(appwindow);
+    }
+
+    fn jump_to_current_match(&self, appwindow: &RnoteAppWindow) {
+        if let Some(current) = appwindow.canvas().pdf_text_current_highlight() {
+            if let Some(text_match) = appwindow.canvas().pdf_text_highlights().get(current) {
+                appwindow
+                    .canvas()
+                    .center_around_coord_on_sheet(text_match.center().coords);
+            }
+        }
+
+        self.update_matches_label(appwindow);
+    }
+
+    fn update_matches_label(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::PdfSearchBar::from_instance(self);
+        let n_matches = appwindow.canvas().pdf_text_highlights().len();
+
+        let label = if self.search_entry().text().is_empty() {
+            String::new()
+        } else if n_matches == 0 {
+            gettext("No matches")
+        } else {
+            format!(
+                "{} / {}",
+                appwindow
+                    .canvas()
+                    .pdf_text_current_highlight()
+                    .map(|current| current + 1)
+                    .unwrap_or(0),
+                n_matches
+            )
+        };
+
+        priv_.matches_label.get().set_label(&label);
+    }
+}
@@ -72,10 +72,7 @@ mod imp {
     impl WidgetImpl for BrushPage {}
 }
 
-use crate::compose::textured::{TexturedConfig, TexturedDotsDistribution};
-use crate::pens::brush::Brush;
 use crate::ui::{appwindow::RnoteAppWindow, colorpicker::ColorPicker};
-use crate::utils;
 use adw::prelude::*;
 use gtk4::{
     gdk, Accessible, Actionable, Buildable, ConstraintTarget, Image, ListBox, MenuButton, Popover,
@@ -83,6 +80,9 @@ use gtk4::{
 use gtk4::{
     glib, glib::clone, subclass::prelude::*, Adjustment, Button, Orientable, SpinButton, Widget,
 };
+use rnote_engine::compose::textured::{TexturedConfig, TexturedDotsDistribution};
+use rnote_engine::pens::brush::Brush;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct BrushPage(ObjectSubclass<imp::BrushPage>)
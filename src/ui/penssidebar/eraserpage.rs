@@ -44,12 +44,12 @@ mod imp {
     impl WidgetImpl for EraserPage {}
 }
 
-use crate::pens::eraser::Eraser;
 use crate::ui::appwindow::RnoteAppWindow;
 use gtk4::{
     glib, glib::clone, prelude::*, subclass::prelude::*, Adjustment, Button, Orientable,
     SpinButton, Widget,
 };
+use rnote_engine::pens::eraser::Eraser;
 
 glib::wrapper! {
     pub struct EraserPage(ObjectSubclass<imp::EraserPage>)
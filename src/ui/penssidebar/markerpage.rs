@@ -47,13 +47,13 @@ mod imp {
     impl WidgetImpl for MarkerPage {}
 }
 
-use crate::pens::marker::Marker;
 use crate::ui::{appwindow::RnoteAppWindow, colorpicker::ColorPicker};
-use crate::utils;
 use gtk4::{
     gdk, glib, glib::clone, prelude::*, subclass::prelude::*, Adjustment, Button, Orientable,
     SpinButton, Widget,
 };
+use rnote_engine::pens::marker::Marker;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct MarkerPage(ObjectSubclass<imp::MarkerPage>)
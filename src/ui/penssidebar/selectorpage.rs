@@ -1,6 +1,11 @@
 mod imp {
-    use gtk4::ToggleButton;
-    use gtk4::{glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate};
+    use std::cell::Cell;
+
+    use gtk4::{
+        glib, prelude::*, subclass::prelude::*, Adjustment, Button, ColorButton, CompositeTemplate,
+        DropDown, MenuButton, Popover, SpinButton, Switch, ToggleButton,
+    };
+    use rnote_engine::sheet::format::MeasureUnit;
 
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/penssidebar/selectorpage.ui")]
@@ -13,6 +18,62 @@ mod imp {
         pub delete_button: TemplateChild<Button>,
         #[template_child]
         pub duplicate_button: TemplateChild<Button>,
+        #[template_child]
+        pub group_button: TemplateChild<Button>,
+        #[template_child]
+        pub ungroup_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub style_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub style_color_button: TemplateChild<ColorButton>,
+        #[template_child]
+        pub style_width_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub style_width_spinbutton: TemplateChild<SpinButton>,
+        #[template_child]
+        pub style_brush_solid_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_brush_textured_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_brush_experimental_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_fill_button: TemplateChild<ColorButton>,
+        #[template_child]
+        pub style_remove_fill_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_drawstyle_smooth_button: TemplateChild<Button>,
+        #[template_child]
+        pub style_drawstyle_rough_button: TemplateChild<Button>,
+        #[template_child]
+        pub transform_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub transform_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub transform_unit_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub transform_x_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub transform_y_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub transform_width_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub transform_height_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub transform_rotation_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub transform_aspect_lock_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub transform_scale_widths_switch: TemplateChild<Switch>,
+        #[template_child]
+        pub edit_image_button: TemplateChild<Button>,
+
+        // The unit of the position and size in the transform panel
+        pub(super) transform_unit: Cell<MeasureUnit>,
+        // The rotation in degrees which was applied since the transform panel was opened
+        pub(super) transform_rotation: Cell<f64>,
+        pub(super) transform_syncing: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -46,8 +107,18 @@ mod imp {
 }
 
 use crate::ui::appwindow::RnoteAppWindow;
-use gtk4::ToggleButton;
-use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, Button, Orientable, Widget};
+use crate::ui::selectionmodifier::SelectionModifier;
+use rnote_engine::pens::brush::{Brush, BrushStyle};
+use rnote_engine::pens::shaper::DrawStyle;
+use rnote_engine::sheet::format::MeasureUnit;
+use rnote_engine::strokes::strokestyle::StyleEdit;
+use rnote_engine::utils;
+
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Adjustment, Button, ColorButton,
+    MenuButton, Orientable, ToggleButton, Widget,
+};
+use p2d::bounding_volume::AABB;
 
 glib::wrapper! {
     pub struct SelectorPage(ObjectSubclass<imp::SelectorPage>)
@@ -87,7 +158,51 @@ impl SelectorPage {
             .get()
     }
 
+    pub fn group_button(&self) -> Button {
+        imp::SelectorPage::from_instance(self).group_button.get()
+    }
+
+    pub fn ungroup_button(&self) -> Button {
+        imp::SelectorPage::from_instance(self).ungroup_button.get()
+    }
+
+    pub fn style_menubutton(&self) -> MenuButton {
+        imp::SelectorPage::from_instance(self)
+            .style_menubutton
+            .get()
+    }
+
+    pub fn style_color_button(&self) -> ColorButton {
+        imp::SelectorPage::from_instance(self)
+            .style_color_button
+            .get()
+    }
+
+    pub fn style_width_adj(&self) -> Adjustment {
+        imp::SelectorPage::from_instance(self).style_width_adj.get()
+    }
+
+    pub fn style_fill_button(&self) -> ColorButton {
+        imp::SelectorPage::from_instance(self)
+            .style_fill_button
+            .get()
+    }
+
+    pub fn transform_menubutton(&self) -> MenuButton {
+        imp::SelectorPage::from_instance(self)
+            .transform_menubutton
+            .get()
+    }
+
+    pub fn edit_image_button(&self) -> Button {
+        imp::SelectorPage::from_instance(self)
+            .edit_image_button
+            .get()
+    }
+
     pub fn init(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::SelectorPage::from_instance(self);
+
         // selecting with Polygon / Rect toggles
         self.selectorstyle_polygon_toggle().connect_active_notify(clone!(@weak appwindow => move |selectorstyle_polygon_toggle| {
             if selectorstyle_polygon_toggle.is_active() {
@@ -108,5 +223,284 @@ impl SelectorPage {
         self.duplicate_button().connect_clicked(clone!(@weak appwindow => move |_| {
             adw::prelude::ActionGroupExt::activate_action(&appwindow, "duplicate-selection", None);
         }));
+
+        self.group_button()
+            .connect_clicked(clone!(@weak appwindow => move |_| {
+                adw::prelude::ActionGroupExt::activate_action(&appwindow, "group-selection", None);
+            }));
+
+        self.ungroup_button().connect_clicked(clone!(@weak appwindow => move |_| {
+            adw::prelude::ActionGroupExt::activate_action(&appwindow, "ungroup-selection", None);
+        }));
+
+        // Selection style
+        self.style_color_button().connect_color_set(
+            clone!(@weak appwindow => move |style_color_button| {
+                restyle_selection(&appwindow, StyleEdit {
+                    color: Some(Some(utils::Color::from(style_color_button.rgba()))),
+                    ..StyleEdit::default()
+                });
+            }),
+        );
+
+        self.style_width_adj().set_lower(Brush::WIDTH_MIN);
+        self.style_width_adj().set_upper(Brush::WIDTH_MAX);
+        self.style_width_adj().set_value(Brush::WIDTH_DEFAULT);
+        self.style_width_adj().connect_value_changed(
+            clone!(@weak appwindow => move |style_width_adj| {
+                restyle_selection(&appwindow, StyleEdit {
+                    width: Some(style_width_adj.value()),
+                    ..StyleEdit::default()
+                });
+            }),
+        );
+
+        for (button, brush_style) in [
+            (&priv_.style_brush_solid_button, BrushStyle::Solid),
+            (&priv_.style_brush_textured_button, BrushStyle::Textured),
+            (
+                &priv_.style_brush_experimental_button,
+                BrushStyle::Experimental,
+            ),
+        ] {
+            button.connect_clicked(clone!(@weak appwindow => move |_| {
+                restyle_selection(&appwindow, StyleEdit {
+                    brush_style: Some(brush_style),
+                    ..StyleEdit::default()
+                });
+            }));
+        }
+
+        self.style_fill_button().connect_color_set(
+            clone!(@weak appwindow => move |style_fill_button| {
+                restyle_selection(&appwindow, StyleEdit {
+                    fill: Some(Some(utils::Color::from(style_fill_button.rgba()))),
+                    ..StyleEdit::default()
+                });
+            }),
+        );
+
+        priv_
+            .style_remove_fill_button
+            .connect_clicked(clone!(@weak appwindow => move |_| {
+                restyle_selection(&appwindow, StyleEdit {
+                    fill: Some(None),
+                    ..StyleEdit::default()
+                });
+            }));
+
+        for (button, drawstyle) in [
+            (&priv_.style_drawstyle_smooth_button, DrawStyle::Smooth),
+            (&priv_.style_drawstyle_rough_button, DrawStyle::Rough),
+        ] {
+            button.connect_clicked(clone!(@weak appwindow => move |_| {
+                restyle_selection(&appwindow, StyleEdit {
+                    drawstyle: Some(drawstyle),
+                    ..StyleEdit::default()
+                });
+            }));
+        }
+
+        // Transform panel
+        priv_.transform_popover.connect_show(
+            clone!(@weak self as selectorpage, @weak appwindow => move |_transform_popover| {
+                let priv_ = imp::SelectorPage::from_instance(&selectorpage);
+
+                priv_.transform_syncing.set(true);
+                priv_.transform_rotation_adj.set_value(0.0);
+                priv_.transform_syncing.set(false);
+                priv_.transform_rotation.set(0.0);
+
+                selectorpage.sync_transform_panel(&appwindow);
+            }),
+        );
+
+        priv_.transform_unit_dropdown.connect_selected_notify(
+            clone!(@weak self as selectorpage, @weak appwindow => move |transform_unit_dropdown| {
+                let item = transform_unit_dropdown
+                    .model()
+                    .and_then(|model| model.item(transform_unit_dropdown.selected()));
+                if let Some(item) = item {
+                    let unit = match item.downcast::<adw::EnumListItem>().unwrap().nick().as_str() {
+                        "px" => MeasureUnit::Px,
+                        "mm" => MeasureUnit::Mm,
+                        "cm" => MeasureUnit::Cm,
+                        "in" => MeasureUnit::In,
+                        _ => return,
+                    };
+                    imp::SelectorPage::from_instance(&selectorpage).transform_unit.set(unit);
+                    selectorpage.sync_transform_panel(&appwindow);
+                }
+            }),
+        );
+
+        for adj in [priv_.transform_x_adj.get(), priv_.transform_y_adj.get()] {
+            adj.connect_value_changed(
+                clone!(@weak self as selectorpage, @weak appwindow => move |_adj| {
+                    selectorpage.apply_transform_bounds(&appwindow);
+                }),
+            );
+        }
+
+        priv_.transform_width_adj.connect_value_changed(
+            clone!(@weak self as selectorpage, @weak appwindow => move |_transform_width_adj| {
+                selectorpage.transform_size_changed(&appwindow, true);
+            }),
+        );
+
+        priv_.transform_height_adj.connect_value_changed(
+            clone!(@weak self as selectorpage, @weak appwindow => move |_transform_height_adj| {
+                selectorpage.transform_size_changed(&appwindow, false);
+            }),
+        );
+
+        priv_.transform_rotation_adj.connect_value_changed(
+            clone!(@weak self as selectorpage, @weak appwindow => move |transform_rotation_adj| {
+                let priv_ = imp::SelectorPage::from_instance(&selectorpage);
+                if priv_.transform_syncing.get() {
+                    return;
+                }
+
+                let angle = transform_rotation_adj.value() - priv_.transform_rotation.get();
+                priv_.transform_rotation.set(transform_rotation_adj.value());
+
+                appwindow.canvas().sheet().strokes_state().borrow_mut().rotate_selection(angle.to_radians());
+                selectorpage.update_after_transform(&appwindow);
+            }),
+        );
+
+        self.edit_image_button().connect_clicked(clone!(@weak appwindow => move |_| {
+            adw::prelude::ActionGroupExt::activate_action(&appwindow, "edit-selected-image", None);
+        }));
+    }
+
+    /// Updates the position and size in the transform panel to the bounds of the selection
+    pub fn sync_transform_panel(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::SelectorPage::from_instance(self);
+
+        let selection_bounds = appwindow
+            .canvas()
+            .sheet()
+            .strokes_state()
+            .borrow()
+            .gen_selection_bounds();
+        let selection_bounds = match selection_bounds {
+            Some(selection_bounds) => selection_bounds,
+            None => return,
+        };
+
+        let unit = priv_.transform_unit.get();
+        let dpi = appwindow.canvas().sheet().format().dpi();
+        let from_px =
+            |value: f64| MeasureUnit::convert_measurement(value, MeasureUnit::Px, dpi, unit, dpi);
+
+        priv_.transform_syncing.set(true);
+        priv_
+            .transform_x_adj
+            .set_value(from_px(selection_bounds.mins[0]));
+        priv_
+            .transform_y_adj
+            .set_value(from_px(selection_bounds.mins[1]));
+        priv_
+            .transform_width_adj
+            .set_value(from_px(selection_bounds.extents()[0]));
+        priv_
+            .transform_height_adj
+            .set_value(from_px(selection_bounds.extents()[1]));
+        priv_.transform_syncing.set(false);
+    }
+
+    /// Keeps the aspect ratio of the selection if it is locked, then applies the new size
+    fn transform_size_changed(&self, appwindow: &RnoteAppWindow, width_changed: bool) {
+        let priv_ = imp::SelectorPage::from_instance(self);
+        if priv_.transform_syncing.get() {
+            return;
+        }
+
+        if priv_.transform_aspect_lock_switch.is_active() {
+            let selection_bounds = appwindow
+                .canvas()
+                .sheet()
+                .strokes_state()
+                .borrow()
+                .gen_selection_bounds();
+
+            if let Some(selection_bounds) = selection_bounds {
+                let aspect_ratio = selection_bounds.extents()[0] / selection_bounds.extents()[1];
+
+                if aspect_ratio.is_finite() && aspect_ratio > 0.0 {
+                    priv_.transform_syncing.set(true);
+                    if width_changed {
+                        priv_
+                            .transform_height_adj
+                            .set_value(priv_.transform_width_adj.value() / aspect_ratio);
+                    } else {
+                        priv_
+                            .transform_width_adj
+                            .set_value(priv_.transform_height_adj.value() * aspect_ratio);
+                    }
+                    priv_.transform_syncing.set(false);
+                }
+            }
+        }
+
+        self.apply_transform_bounds(appwindow);
+    }
+
+    /// Moves and resizes the selection to the position and size in the transform panel
+    fn apply_transform_bounds(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::SelectorPage::from_instance(self);
+        if priv_.transform_syncing.get() {
+            return;
+        }
+
+        let unit = priv_.transform_unit.get();
+        let dpi = appwindow.canvas().sheet().format().dpi();
+        let to_px =
+            |value: f64| MeasureUnit::convert_measurement(value, unit, dpi, MeasureUnit::Px, dpi);
+
+        let mins = na::point![
+            to_px(priv_.transform_x_adj.value()),
+            to_px(priv_.transform_y_adj.value())
+        ];
+        let extents = na::vector![
+            to_px(priv_.transform_width_adj.value()).max(SelectionModifier::SELECTION_MIN),
+            to_px(priv_.transform_height_adj.value()).max(SelectionModifier::SELECTION_MIN)
+        ];
+
+        appwindow
+            .canvas()
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .resize_selection_to_bounds(
+                AABB::new(mins, mins + extents),
+                priv_.transform_scale_widths_switch.is_active(),
+            );
+
+        self.update_after_transform(appwindow);
+    }
+
+    fn update_after_transform(&self, appwindow: &RnoteAppWindow) {
+        appwindow
+            .canvas()
+            .selection_modifier()
+            .update_state(&appwindow.canvas());
+        appwindow.canvas().set_unsaved_changes(true);
+        appwindow.canvas().queue_draw();
+
+        self.sync_transform_panel(appwindow);
+    }
+}
+
+/// Applies the style edit to the selected strokes as one undoable operation
+fn restyle_selection(appwindow: &RnoteAppWindow, edit: StyleEdit) {
+    if appwindow.canvas().sheet().restyle_selection(&edit) {
+        appwindow
+            .canvas()
+            .selection_modifier()
+            .update_state(&appwindow.canvas());
+        appwindow.canvas().set_unsaved_changes(true);
+        appwindow.canvas().queue_draw();
     }
 }
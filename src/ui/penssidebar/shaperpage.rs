@@ -80,12 +80,12 @@ mod imp {
     impl WidgetImpl for ShaperPage {}
 }
 
-use crate::compose::rough::roughoptions;
-use crate::pens::shaper::Shaper;
 use crate::ui::{appwindow::RnoteAppWindow, colorpicker::ColorPicker};
-use crate::utils;
 use gtk4::{gdk, Adjustment, Button, MenuButton, Popover, Revealer, ToggleButton};
 use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, Orientable, Widget};
+use rnote_engine::compose::rough::roughoptions;
+use rnote_engine::pens::shaper::Shaper;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct ShaperPage(ObjectSubclass<imp::ShaperPage>)
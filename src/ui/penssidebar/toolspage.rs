@@ -1,5 +1,7 @@
 mod imp {
-    use gtk4::{glib, prelude::*, subclass::prelude::*, CompositeTemplate, ToggleButton};
+    use gtk4::{
+        glib, prelude::*, subclass::prelude::*, Button, CompositeTemplate, ToggleButton,
+    };
 
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/penssidebar/toolspage.ui")]
@@ -8,6 +10,12 @@ mod imp {
         pub toolstyle_expandsheet_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub toolstyle_dragproximity_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub toolstyle_ruler_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub toolstyle_protractor_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub hide_guides_button: TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
@@ -41,7 +49,9 @@ mod imp {
 }
 
 use crate::ui::appwindow::RnoteAppWindow;
-use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, Orientable, ToggleButton, Widget};
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Button, Orientable, ToggleButton, Widget,
+};
 
 glib::wrapper! {
     pub struct ToolsPage(ObjectSubclass<imp::ToolsPage>)
@@ -71,6 +81,24 @@ impl ToolsPage {
             .get()
     }
 
+    pub fn toolstyle_ruler_toggle(&self) -> ToggleButton {
+        imp::ToolsPage::from_instance(self)
+            .toolstyle_ruler_toggle
+            .get()
+    }
+
+    pub fn toolstyle_protractor_toggle(&self) -> ToggleButton {
+        imp::ToolsPage::from_instance(self)
+            .toolstyle_protractor_toggle
+            .get()
+    }
+
+    pub fn hide_guides_button(&self) -> Button {
+        imp::ToolsPage::from_instance(self)
+            .hide_guides_button
+            .get()
+    }
+
     pub fn init(&self, appwindow: &RnoteAppWindow) {
         self.toolstyle_expandsheet_toggle().connect_active_notify(clone!(@weak appwindow => move |toolstyle_expandsheet_toggle| {
             if toolstyle_expandsheet_toggle.is_active() {
@@ -83,5 +111,21 @@ impl ToolsPage {
                 adw::prelude::ActionGroupExt::activate_action(&appwindow, "tool-style", Some(&"dragproximity".to_variant()));
             }
         }));
+
+        self.toolstyle_ruler_toggle().connect_active_notify(clone!(@weak appwindow => move |toolstyle_ruler_toggle| {
+            if toolstyle_ruler_toggle.is_active() {
+                adw::prelude::ActionGroupExt::activate_action(&appwindow, "tool-style", Some(&"ruler".to_variant()));
+            }
+        }));
+
+        self.toolstyle_protractor_toggle().connect_active_notify(clone!(@weak appwindow => move |toolstyle_protractor_toggle| {
+            if toolstyle_protractor_toggle.is_active() {
+                adw::prelude::ActionGroupExt::activate_action(&appwindow, "tool-style", Some(&"protractor".to_variant()));
+            }
+        }));
+
+        self.hide_guides_button().connect_clicked(clone!(@weak appwindow => move |_hide_guides_button| {
+            adw::prelude::ActionGroupExt::activate_action(&appwindow, "hide-tool-guides", None);
+        }));
     }
 }
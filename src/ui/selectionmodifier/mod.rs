@@ -3,14 +3,19 @@ pub mod modifiernode;
 pub mod imp {
     use std::cell::Cell;
 
-    use crate::compose::geometry;
     use crate::ui::canvas::Canvas;
-    use crate::{compose, render, utils};
+    use crate::utils;
+    use rnote_engine::compose::geometry;
+    use rnote_engine::strokesstate::StrokeKey;
+    use rnote_engine::Color;
+    use rnote_engine::{compose, render};
 
     use super::modifiernode::ModifierNode;
+    use super::SelectionModifierMode;
 
     use anyhow::Context;
     use gtk4::{gdk, graphene, Orientation, SizeRequestMode, Snapshot};
+    use gtk4::{Button, Grid, Popover, Scale, Switch};
     use gtk4::{glib, prelude::*, subclass::prelude::*, CompositeTemplate};
     use p2d::bounding_volume::AABB;
     use svg::node::element;
@@ -30,11 +35,38 @@ pub mod imp {
         pub translate_node: TemplateChild<gtk4::Box>,
         #[template_child]
         pub rotate_node: TemplateChild<ModifierNode>,
+        #[template_child]
+        pub image_edit_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub flip_horizontal_button: TemplateChild<Button>,
+        #[template_child]
+        pub flip_vertical_button: TemplateChild<Button>,
+        #[template_child]
+        pub reset_image_edits_button: TemplateChild<Button>,
+        #[template_child]
+        pub finish_image_edit_button: TemplateChild<Button>,
+        #[template_child]
+        pub bitmap_adjustments_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub brightness_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub contrast_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub opacity_scale: TemplateChild<Scale>,
+        #[template_child]
+        pub greyscale_switch: TemplateChild<Switch>,
 
         // Internal state for allocation, drawing
         pub(super) selection_bounds: Cell<Option<AABB>>,
         pub(super) current_rotation_center: Cell<Option<na::Point2<f64>>>,
         pub(super) current_rotation_angle: Cell<f64>,
+
+        pub(super) mode: Cell<SelectionModifierMode>,
+        // The image which is edited in the EditImage mode
+        pub(super) image_edit_key: Cell<Option<StrokeKey>>,
+        // The crop rectangle when dragging a node started
+        pub(super) image_crop_start: Cell<Option<AABB>>,
+        pub(super) image_edit_widgets_syncing: Cell<bool>,
     }
 
     impl Default for SelectionModifier {
@@ -46,10 +78,25 @@ pub mod imp {
                 resize_br_node: TemplateChild::default(),
                 translate_node: TemplateChild::default(),
                 rotate_node: TemplateChild::default(),
+                image_edit_popover: TemplateChild::default(),
+                flip_horizontal_button: TemplateChild::default(),
+                flip_vertical_button: TemplateChild::default(),
+                reset_image_edits_button: TemplateChild::default(),
+                finish_image_edit_button: TemplateChild::default(),
+                bitmap_adjustments_grid: TemplateChild::default(),
+                brightness_scale: TemplateChild::default(),
+                contrast_scale: TemplateChild::default(),
+                opacity_scale: TemplateChild::default(),
+                greyscale_switch: TemplateChild::default(),
 
                 selection_bounds: Cell::new(None),
                 current_rotation_center: Cell::new(None),
                 current_rotation_angle: Cell::new(0.0),
+
+                mode: Cell::new(SelectionModifierMode::Transform),
+                image_edit_key: Cell::new(None),
+                image_crop_start: Cell::new(None),
+                image_edit_widgets_syncing: Cell::new(false),
             }
         }
     }
@@ -160,6 +207,13 @@ pub mod imp {
             }
         }
 
+        fn size_allocate(&self, widget: &Self::Type, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(widget, width, height, baseline);
+
+            // The popover is not allocated by the layout manager
+            self.image_edit_popover.present();
+        }
+
         fn snapshot(&self, widget: &Self::Type, snapshot: &Snapshot) {
             let bounds = AABB::new(
                 na::point![0.0, 0.0],
@@ -201,13 +255,13 @@ pub mod imp {
             _widget_bounds: AABB,
             canvas: &Canvas,
         ) {
-            const SELECTION_BOUNDS_COLOR: utils::Color = utils::Color {
+            const SELECTION_BOUNDS_COLOR: Color = Color {
                 r: 0.5,
                 g: 0.5,
                 b: 0.5,
                 a: 0.7,
             };
-            const SELECTION_BOUNDS_FILL: utils::Color = utils::Color {
+            const SELECTION_BOUNDS_FILL: Color = Color {
                 r: 0.49,
                 g: 0.56,
                 b: 0.63,
@@ -299,7 +353,7 @@ pub mod imp {
             _widget_bounds: AABB,
             canvas: &Canvas,
         ) {
-            const ROTATION_LINESTART_COLOR: utils::Color = utils::Color {
+            const ROTATION_LINESTART_COLOR: Color = Color {
                 r: 0.7,
                 g: 0.3,
                 b: 0.3,
@@ -385,15 +439,25 @@ pub mod imp {
 use std::cell::Cell;
 use std::rc::Rc;
 
-use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*};
+use gtk4::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
 use gtk4::{EventSequenceState, GestureDrag, PropagationPhase};
 use p2d::bounding_volume::AABB;
 
-use crate::compose::geometry;
 use crate::{ui::appwindow::RnoteAppWindow, ui::selectionmodifier::modifiernode::ModifierNode};
+use rnote_engine::compose::geometry;
+use rnote_engine::strokes::imageedits::BitmapAdjustments;
+use rnote_engine::strokesstate::selection_comp::Alignment;
 
 use super::canvas::Canvas;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionModifierMode {
+    /// Translating, rotating and resizing the selection
+    Transform,
+    /// Cropping, flipping and adjusting the selected image. The nodes crop the image instead of resizing it
+    EditImage,
+}
+
 glib::wrapper! {
     pub struct SelectionModifier(ObjectSubclass<imp::SelectionModifier>)
         @extends gtk4::Widget;
@@ -455,9 +519,173 @@ impl SelectionModifier {
         self.imp().selection_bounds.get()
     }
 
+    pub fn mode(&self) -> SelectionModifierMode {
+        self.imp().mode.get()
+    }
+
+    /// Sets the mode. The EditImage mode is only entered when the selection is a single image
+    pub fn set_mode(&self, mode: SelectionModifierMode, canvas: &Canvas) {
+        let priv_ = self.imp();
+
+        let image_edit_key = match mode {
+            SelectionModifierMode::Transform => None,
+            SelectionModifierMode::EditImage => {
+                canvas.sheet().strokes_state().borrow().selected_image_key()
+            }
+        };
+        let mode = if image_edit_key.is_some() {
+            mode
+        } else {
+            SelectionModifierMode::Transform
+        };
+
+        priv_.mode.set(mode);
+        priv_.image_edit_key.set(image_edit_key);
+        priv_.image_crop_start.set(None);
+
+        // Images are rotated in the Transform mode
+        priv_
+            .rotate_node
+            .set_visible(mode == SelectionModifierMode::Transform);
+
+        if mode == SelectionModifierMode::EditImage {
+            self.sync_image_edit_widgets(canvas);
+            priv_.image_edit_popover.popup();
+        } else {
+            priv_.image_edit_popover.popdown();
+        }
+
+        self.queue_resize();
+        self.queue_draw();
+    }
+
+    /// Updates the widgets in the image edit popover to the values of the edited image
+    fn sync_image_edit_widgets(&self, canvas: &Canvas) {
+        let priv_ = self.imp();
+
+        let adjustments = priv_.image_edit_key.get().and_then(|key| {
+            canvas
+                .sheet()
+                .strokes_state()
+                .borrow()
+                .bitmap_adjustments(key)
+        });
+
+        priv_
+            .bitmap_adjustments_grid
+            .set_visible(adjustments.is_some());
+
+        let adjustments = adjustments.unwrap_or_default();
+        priv_.image_edit_widgets_syncing.set(true);
+        priv_.brightness_scale.set_value(adjustments.brightness);
+        priv_.contrast_scale.set_value(adjustments.contrast);
+        priv_.opacity_scale.set_value(adjustments.opacity);
+        priv_.greyscale_switch.set_active(adjustments.greyscale);
+        priv_.image_edit_widgets_syncing.set(false);
+    }
+
+    /// Applies the values of the widgets in the image edit popover to the edited image
+    fn apply_bitmap_adjustments(&self, canvas: &Canvas) {
+        let priv_ = self.imp();
+        if priv_.image_edit_widgets_syncing.get() {
+            return;
+        }
+
+        if let Some(key) = priv_.image_edit_key.get() {
+            let adjustments = BitmapAdjustments {
+                brightness: priv_.brightness_scale.value(),
+                contrast: priv_.contrast_scale.value(),
+                greyscale: priv_.greyscale_switch.is_active(),
+                opacity: priv_.opacity_scale.value(),
+            };
+
+            let strokes_state = canvas.sheet().strokes_state();
+            if strokes_state.borrow().bitmap_adjustments(key) != Some(adjustments) {
+                strokes_state
+                    .borrow_mut()
+                    .set_bitmap_adjustments(key, adjustments);
+
+                canvas.set_unsaved_changes(true);
+                canvas.queue_draw();
+            }
+        }
+    }
+
+    /// Stores the current crop rectangle of the edited image, when a node starts to be dragged
+    fn begin_image_crop(&self, canvas: &Canvas) {
+        let priv_ = self.imp();
+
+        let image_crop_start = priv_.image_edit_key.get().and_then(|key| {
+            canvas
+                .sheet()
+                .strokes_state()
+                .borrow()
+                .image_crop_bounds(key)
+        });
+
+        priv_.image_crop_start.set(image_crop_start);
+    }
+
+    /// Crops the edited image by moving the edges next to the dragged corner by the offset, relative to the crop rectangle when the drag started
+    fn update_image_crop(&self, canvas: &Canvas, offset: na::Vector2<f64>, left: bool, top: bool) {
+        let priv_ = self.imp();
+
+        if let (Some(key), Some(crop_start)) =
+            (priv_.image_edit_key.get(), priv_.image_crop_start.get())
+        {
+            let strokes_state = canvas.sheet().strokes_state();
+
+            let (edits, intrinsic_offset) = {
+                let strokes_state = strokes_state.borrow();
+                match (
+                    strokes_state.image_edits(key),
+                    strokes_state.image_offset_to_intrinsic(key, offset),
+                ) {
+                    (Some(edits), Some(intrinsic_offset)) => (edits, intrinsic_offset),
+                    _ => return,
+                }
+            };
+
+            let mut mins = crop_start.mins;
+            let mut maxs = crop_start.maxs;
+
+            // The displayed edges are swapped when the image is flipped
+            if left != edits.flip_horizontal {
+                mins[0] += intrinsic_offset[0];
+            } else {
+                maxs[0] += intrinsic_offset[0];
+            }
+            if top != edits.flip_vertical {
+                mins[1] += intrinsic_offset[1];
+            } else {
+                maxs[1] += intrinsic_offset[1];
+            }
+
+            strokes_state
+                .borrow_mut()
+                .crop_image(key, AABB::new(mins, maxs));
+
+            canvas.set_unsaved_changes(true);
+            self.queue_resize();
+            canvas.queue_draw();
+        }
+    }
+
     pub fn update_state(&self, canvas: &Canvas) {
         let priv_ = self.imp();
 
+        // Leaving the EditImage mode when the edited image is not the selection anymore
+        if priv_.mode.get() == SelectionModifierMode::EditImage
+            && canvas
+                .sheet()
+                .strokes_state()
+                .borrow()
+                .selected_image_key()
+                != priv_.image_edit_key.get()
+        {
+            self.set_mode(SelectionModifierMode::Transform, canvas);
+        }
+
         priv_
             .selection_bounds
             .set(canvas.sheet().strokes_state().borrow().gen_selection_bounds());
@@ -476,10 +704,88 @@ impl SelectionModifier {
                 .set_height_request((selection_bounds.extents()[1] * total_zoom).round() as i32);
         };
 
+        self.update_group_actions(canvas);
+
         self.queue_resize();
         self.queue_draw();
     }
 
+    /// Enables the group and ungroup actions only when they would change the selection
+    fn update_group_actions(&self, canvas: &Canvas) {
+        let appwindow = match canvas
+            .root()
+            .and_then(|root| root.downcast::<RnoteAppWindow>().ok())
+        {
+            Some(appwindow) => appwindow,
+            None => return,
+        };
+        let (can_group, can_ungroup) = {
+            let strokes_state = canvas.sheet().strokes_state().borrow();
+            (
+                strokes_state.can_group_selection(),
+                strokes_state.can_ungroup_selection(),
+            )
+        };
+
+        for (action_name, enabled) in [
+            ("group-selection", can_group),
+            ("ungroup-selection", can_ungroup),
+        ] {
+            if let Some(action) = appwindow
+                .lookup_action(action_name)
+                .and_then(|action| action.downcast::<gio::SimpleAction>().ok())
+            {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    /// Aligns the selected strokes to the edge or the center of the selection bounds
+    pub fn align_selection(&self, alignment: Alignment, canvas: &Canvas) {
+        canvas
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .align_selection(alignment);
+        self.update_after_arranging(canvas);
+    }
+
+    /// Distributes the selected strokes horizontally or vertically with equal gaps between them
+    pub fn distribute_selection(&self, horizontal: bool, canvas: &Canvas) {
+        canvas
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .distribute_selection(horizontal);
+        self.update_after_arranging(canvas);
+    }
+
+    /// Flips the selected strokes horizontally and / or vertically in place
+    pub fn flip_selection(&self, horizontal: bool, vertical: bool, canvas: &Canvas) {
+        canvas
+            .sheet()
+            .strokes_state()
+            .borrow_mut()
+            .flip_selection(horizontal, vertical);
+        self.update_after_arranging(canvas);
+    }
+
+    pub fn bring_selection_to_front(&self, canvas: &Canvas) {
+        canvas.sheet().bring_selection_to_front();
+        self.update_after_arranging(canvas);
+    }
+
+    pub fn send_selection_to_back(&self, canvas: &Canvas) {
+        canvas.sheet().send_selection_to_back();
+        self.update_after_arranging(canvas);
+    }
+
+    fn update_after_arranging(&self, canvas: &Canvas) {
+        self.update_state(canvas);
+        canvas.set_unsaved_changes(true);
+        canvas.queue_draw();
+    }
+
     pub fn init(&self, appwindow: &RnoteAppWindow) {
         self.init_resize_tl_node(appwindow);
         self.init_resize_tr_node(appwindow);
@@ -487,6 +793,83 @@ impl SelectionModifier {
         self.init_resize_br_node(appwindow);
         self.init_rotate_node(appwindow);
         self.init_translate_node(appwindow);
+        self.init_image_edit_popover(appwindow);
+    }
+
+    pub fn init_image_edit_popover(&self, appwindow: &RnoteAppWindow) {
+        let priv_ = imp::SelectionModifier::from_instance(self);
+
+        priv_.brightness_scale.set_range(
+            BitmapAdjustments::BRIGHTNESS_MIN,
+            BitmapAdjustments::BRIGHTNESS_MAX,
+        );
+        priv_.contrast_scale.set_range(
+            BitmapAdjustments::CONTRAST_MIN,
+            BitmapAdjustments::CONTRAST_MAX,
+        );
+        priv_.opacity_scale.set_range(
+            BitmapAdjustments::OPACITY_MIN,
+            BitmapAdjustments::OPACITY_MAX,
+        );
+        for scale in [
+            priv_.brightness_scale.get(),
+            priv_.contrast_scale.get(),
+            priv_.opacity_scale.get(),
+        ] {
+            scale.set_increments(0.05, 0.1);
+            scale.set_digits(2);
+            scale.set_draw_value(true);
+
+            scale.connect_value_changed(
+                clone!(@weak self as selection_modifier, @weak appwindow => move |_scale| {
+                    selection_modifier.apply_bitmap_adjustments(&appwindow.canvas());
+                }),
+            );
+        }
+
+        priv_.greyscale_switch.connect_active_notify(
+            clone!(@weak self as selection_modifier, @weak appwindow => move |_greyscale_switch| {
+                selection_modifier.apply_bitmap_adjustments(&appwindow.canvas());
+            }),
+        );
+
+        priv_.flip_horizontal_button.connect_clicked(
+            clone!(@weak self as selection_modifier, @weak appwindow => move |_flip_horizontal_button| {
+                if let Some(key) = selection_modifier.imp().image_edit_key.get() {
+                    appwindow.canvas().sheet().strokes_state().borrow_mut().flip_image(key, true, false);
+                    appwindow.canvas().set_unsaved_changes(true);
+                    appwindow.canvas().queue_draw();
+                }
+            }),
+        );
+
+        priv_.flip_vertical_button.connect_clicked(
+            clone!(@weak self as selection_modifier, @weak appwindow => move |_flip_vertical_button| {
+                if let Some(key) = selection_modifier.imp().image_edit_key.get() {
+                    appwindow.canvas().sheet().strokes_state().borrow_mut().flip_image(key, false, true);
+                    appwindow.canvas().set_unsaved_changes(true);
+                    appwindow.canvas().queue_draw();
+                }
+            }),
+        );
+
+        priv_.reset_image_edits_button.connect_clicked(
+            clone!(@weak self as selection_modifier, @weak appwindow => move |_reset_image_edits_button| {
+                if let Some(key) = selection_modifier.imp().image_edit_key.get() {
+                    appwindow.canvas().sheet().strokes_state().borrow_mut().reset_image_edits(key);
+                    selection_modifier.sync_image_edit_widgets(&appwindow.canvas());
+                    appwindow.canvas().set_unsaved_changes(true);
+                    appwindow.canvas().regenerate_content(false, true);
+                    selection_modifier.update_state(&appwindow.canvas());
+                }
+            }),
+        );
+
+        priv_.finish_image_edit_button.connect_clicked(
+            clone!(@weak self as selection_modifier, @weak appwindow => move |_finish_image_edit_button| {
+                selection_modifier.set_mode(SelectionModifierMode::Transform, &appwindow.canvas());
+            }),
+        );
     }
 
     pub fn init_resize_tl_node(&self, appwindow: &RnoteAppWindow) {
@@ -503,10 +886,19 @@ impl SelectionModifier {
                 resize_tl_drag_gesture.set_state(EventSequenceState::Claimed);
                 
                 selection_modifier.update_state(&appwindow.canvas());
+                selection_modifier.begin_image_crop(&appwindow.canvas());
             }),
         );
         resize_tl_drag_gesture.connect_drag_update(
             clone!(@weak self as selection_modifier, @weak appwindow => move |_resize_tl_drag_gesture, x, y| {
+                if selection_modifier.mode() == SelectionModifierMode::EditImage {
+                    let zoom = appwindow.canvas().zoom();
+                    let offset = na::vector![x.round() / zoom, y.round() / zoom];
+
+                    selection_modifier.update_image_crop(&appwindow.canvas(), offset, true, true);
+                    return;
+                }
+
                 if let Some(selection_bounds) = selection_modifier.selection_bounds() {
                     let zoom = appwindow.canvas().zoom();
                     let offset = na::vector![x.round() / zoom, y.round() / zoom];
@@ -559,10 +951,19 @@ impl SelectionModifier {
                 resize_tr_drag_gesture.set_state(EventSequenceState::Claimed);
                 
                 selection_modifier.update_state(&appwindow.canvas());
+                selection_modifier.begin_image_crop(&appwindow.canvas());
             }),
         );
         resize_tr_drag_gesture.connect_drag_update(
             clone!(@weak self as selection_modifier, @weak appwindow => move |_resize_tr_drag_gesture, x, y| {
+                if selection_modifier.mode() == SelectionModifierMode::EditImage {
+                    let zoom = appwindow.canvas().zoom();
+                    let offset = na::vector![x.round() / zoom, y.round() / zoom];
+
+                    selection_modifier.update_image_crop(&appwindow.canvas(), offset, false, true);
+                    return;
+                }
+
                 if let Some(selection_bounds) = selection_modifier.selection_bounds() {
                     let zoom = appwindow.canvas().zoom();
                     let offset = na::vector![x.round() / zoom, y.round() / zoom];
@@ -614,10 +1015,19 @@ impl SelectionModifier {
             clone!(@weak self as selection_modifier, @weak appwindow => move |resize_bl_drag_gesture, _x, _y| {
                 resize_bl_drag_gesture.set_state(EventSequenceState::Claimed);
                 selection_modifier.update_state(&appwindow.canvas());
+                selection_modifier.begin_image_crop(&appwindow.canvas());
             }),
         );
         resize_bl_drag_gesture.connect_drag_update(
             clone!(@weak self as selection_modifier, @weak appwindow => move |_resize_bl_drag_gesture, x, y| {
+                if selection_modifier.mode() == SelectionModifierMode::EditImage {
+                    let zoom = appwindow.canvas().zoom();
+                    let offset = na::vector![x.round() / zoom, y.round() / zoom];
+
+                    selection_modifier.update_image_crop(&appwindow.canvas(), offset, true, false);
+                    return;
+                }
+
                 if let Some(selection_bounds) = selection_modifier.selection_bounds() {
                     let zoom = appwindow.canvas().zoom();
                     let offset = na::vector![x.round() / zoom, y.round() / zoom];
@@ -670,10 +1080,19 @@ impl SelectionModifier {
                 resize_br_drag_gesture.set_state(EventSequenceState::Claimed);
 
                 selection_modifier.update_state(&appwindow.canvas());
+                selection_modifier.begin_image_crop(&appwindow.canvas());
             }),
         );
         resize_br_drag_gesture.connect_drag_update(
             clone!(@weak self as selection_modifier, @weak appwindow => move |_resize_br_drag_gesture, x, y| {
+                if selection_modifier.mode() == SelectionModifierMode::EditImage {
+                    let zoom = appwindow.canvas().zoom();
+                    let offset = na::vector![x.round() / zoom, y.round() / zoom];
+
+                    selection_modifier.update_image_crop(&appwindow.canvas(), offset, false, false);
+                    return;
+                }
+
                 if let Some(selection_bounds) = selection_modifier.selection_bounds() {
                     let zoom = appwindow.canvas().zoom();
                     let offset = na::vector![x.round() / zoom, y.round() / zoom];
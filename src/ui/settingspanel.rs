@@ -1,15 +1,17 @@
 mod imp {
+    use std::cell::Cell;
+
     use adw::prelude::*;
     use gtk4::{glib, glib::clone, subclass::prelude::*, CompositeTemplate};
-    use gtk4::{Adjustment, Button, ColorButton, ToggleButton};
+    use gtk4::{Adjustment, Button, ColorButton, Switch, ToggleButton};
 
-    use crate::sheet::format::{self, Format};
     use crate::ui::unitentry::UnitEntry;
+    use rnote_engine::sheet::format::{self, Format};
 
     #[derive(Debug, Default, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/settingspanel.ui")]
     pub struct SettingsPanel {
-        pub temporary_format: Format,
+        pub temporary_format: Cell<Format>,
         #[template_child]
         pub general_sheet_margin_unitentry: TemplateChild<UnitEntry>,
         #[template_child]
@@ -43,6 +45,8 @@ mod imp {
         #[template_child]
         pub format_apply_button: TemplateChild<Button>,
         #[template_child]
+        pub background_page_override_switch: TemplateChild<Switch>,
+        #[template_child]
         pub background_color_choosebutton: TemplateChild<ColorButton>,
         #[template_child]
         pub background_patterns_row: TemplateChild<adw::ComboRow>,
@@ -52,6 +56,10 @@ mod imp {
         pub background_pattern_width_unitentry: TemplateChild<UnitEntry>,
         #[template_child]
         pub background_pattern_height_unitentry: TemplateChild<UnitEntry>,
+        #[template_child]
+        pub background_template_import_button: TemplateChild<Button>,
+        #[template_child]
+        pub background_template_clear_button: TemplateChild<Button>,
     }
 
     #[glib::object_subclass]
@@ -157,14 +165,6 @@ mod imp {
                 .value_spinner()
                 .set_digits(1);
 
-            self.temporary_format.connect_notify_local(
-                Some("dpi"),
-                clone!(@weak obj as settings_panel => move |format, _pspec| {
-                    settings_panel.format_width_unitentry().set_dpi(format.dpi());
-                    settings_panel.format_height_unitentry().set_dpi(format.dpi());
-                }),
-            );
-
             self.format_width_unitentry.get().connect_local(
                 "measurement-changed",
                 false,
@@ -280,9 +280,9 @@ mod imp {
                         priv_.format_width_row.set_sensitive(true);
                         priv_.format_height_row.set_sensitive(true);
                         priv_.format_orientation_portrait_toggle.set_active(true);
-                        priv_
-                            .temporary_format
-                            .set_orientation(format::Orientation::Portrait);
+                        let mut temporary_format = priv_.temporary_format.get();
+                        temporary_format.set_orientation(format::Orientation::Portrait);
+                        priv_.temporary_format.set(temporary_format);
                         None
                     }
                     _ => {
@@ -294,7 +294,8 @@ mod imp {
                 };
 
                 if let Some(ref mut preconfigured_dimensions) = preconfigured_dimensions {
-                    if priv_.temporary_format.orientation() == format::Orientation::Landscape {
+                    if priv_.temporary_format.get().orientation() == format::Orientation::Landscape
+                    {
                         std::mem::swap(
                             &mut preconfigured_dimensions.0,
                             &mut preconfigured_dimensions.1,
@@ -304,16 +305,16 @@ mod imp {
                     let converted_width_mm = format::MeasureUnit::convert_measurement(
                         preconfigured_dimensions.0,
                         format::MeasureUnit::Mm,
-                        priv_.temporary_format.dpi(),
+                        priv_.temporary_format.get().dpi(),
                         format::MeasureUnit::Mm,
-                        priv_.temporary_format.dpi(),
+                        priv_.temporary_format.get().dpi(),
                     );
                     let converted_height_mm = format::MeasureUnit::convert_measurement(
                         preconfigured_dimensions.1,
                         format::MeasureUnit::Mm,
-                        priv_.temporary_format.dpi(),
+                        priv_.temporary_format.get().dpi(),
                         format::MeasureUnit::Mm,
-                        priv_.temporary_format.dpi(),
+                        priv_.temporary_format.get().dpi(),
                     );
 
                     // Setting the unit dropdowns to Mm
@@ -343,15 +344,16 @@ mod imp {
 
 use adw::prelude::*;
 use gtk4::{glib, glib::clone, subclass::prelude::*, Widget};
-use gtk4::{Adjustment, ColorButton, ToggleButton};
+use gtk4::{Adjustment, ColorButton, Switch, ToggleButton};
 
 use super::appwindow::RnoteAppWindow;
 use super::canvas::Canvas;
-use crate::sheet::background::PatternStyle;
-use crate::sheet::format::{self, Format};
-use crate::sheet::Sheet;
+use super::dialogs;
 use crate::ui::unitentry::UnitEntry;
-use crate::utils;
+use rnote_engine::sheet::background::{PageBackground, PatternStyle};
+use rnote_engine::sheet::format::{self, Format};
+use rnote_engine::sheet::Sheet;
+use rnote_engine::utils;
 
 glib::wrapper! {
     pub struct SettingsPanel(ObjectSubclass<imp::SettingsPanel>)
@@ -372,7 +374,7 @@ impl SettingsPanel {
     pub fn temporary_format(&self) -> Format {
         imp::SettingsPanel::from_instance(self)
             .temporary_format
-            .clone()
+            .get()
     }
 
     pub fn set_predefined_format_variant(&self, predefined_format: format::PredefinedFormat) {
@@ -456,6 +458,12 @@ impl SettingsPanel {
             .clone()
     }
 
+    pub fn background_page_override_switch(&self) -> Switch {
+        imp::SettingsPanel::from_instance(self)
+            .background_page_override_switch
+            .clone()
+    }
+
     pub fn background_color_choosebutton(&self) -> ColorButton {
         imp::SettingsPanel::from_instance(self)
             .background_color_choosebutton
@@ -489,7 +497,7 @@ impl SettingsPanel {
     pub fn load_all(&self, appwindow: &RnoteAppWindow) {
         self.load_general(&appwindow.canvas());
         self.load_format(&appwindow.canvas().sheet());
-        self.load_background(&appwindow.canvas().sheet());
+        self.load_background(&appwindow.canvas());
     }
 
     pub fn load_general(&self, canvas: &Canvas) {
@@ -520,17 +528,25 @@ impl SettingsPanel {
             .set_value(f64::from(sheet.format().height()));
     }
 
-    pub fn load_background(&self, sheet: &Sheet) {
+    pub fn load_background(&self, canvas: &Canvas) {
+        let sheet = canvas.sheet();
+        let current_page = canvas.current_page();
         // Avoid already borrowed errors
-        let background = sheet.background().borrow().clone();
+        let page_override = sheet.background().borrow().page_override(current_page);
+        let has_page_override = page_override.is_some();
+        let page_background =
+            page_override.unwrap_or_else(|| sheet.background().borrow().default_page_background());
+
+        self.background_page_override_switch()
+            .set_active(has_page_override);
 
         self.background_color_choosebutton()
-            .set_rgba(&background.color().to_gdk());
+            .set_rgba(&page_background.color.to_gdk());
 
-        self.set_background_pattern_variant(background.pattern());
+        self.set_background_pattern_variant(page_background.pattern);
 
         self.background_pattern_color_choosebutton()
-            .set_rgba(&background.pattern_color().to_gdk());
+            .set_rgba(&page_background.pattern_color.to_gdk());
 
         // Background pattern Unit Entries
         self.background_pattern_width_unitentry()
@@ -538,19 +554,39 @@ impl SettingsPanel {
         self.background_pattern_width_unitentry()
             .set_unit(format::MeasureUnit::Px);
         self.background_pattern_width_unitentry()
-            .set_value(background.pattern_size()[0]);
+            .set_value(page_background.pattern_size[0]);
 
         self.background_pattern_height_unitentry()
             .set_dpi(sheet.format().dpi());
         self.background_pattern_height_unitentry()
             .set_unit(format::MeasureUnit::Px);
         self.background_pattern_height_unitentry()
-            .set_value(background.pattern_size()[1]);
+            .set_value(page_background.pattern_size[1]);
+    }
+
+    /// Updates the background which is currently edited in the panel.
+    /// This is the override of the current page when the page override switch is active, else the sheet wide background
+    pub fn update_edited_page_background<F>(&self, appwindow: &RnoteAppWindow, update: F)
+    where
+        F: FnOnce(&mut PageBackground),
+    {
+        let current_page = appwindow.canvas().current_page();
+        let sheet = appwindow.canvas().sheet();
+        let mut background = sheet.background().borrow_mut();
+
+        if self.background_page_override_switch().is_active() {
+            let mut page_background = background.page_background(current_page);
+            update(&mut page_background);
+            background.set_page_override(current_page, Some(page_background));
+        } else {
+            let mut page_background = background.default_page_background();
+            update(&mut page_background);
+            background.set_default_page_background(page_background);
+        }
     }
 
     pub fn init(&self, appwindow: &RnoteAppWindow) {
         let priv_ = imp::SettingsPanel::from_instance(self);
-        let temporary_format = priv_.temporary_format.clone();
 
         // General
         priv_.general_sheet_margin_unitentry.get().connect_local(
@@ -598,7 +634,7 @@ impl SettingsPanel {
         priv_.format_revert_button.get().connect_clicked(
             clone!(@weak self as settings_panel, @weak appwindow => move |_format_revert_button| {
                 let priv_ = imp::SettingsPanel::from_instance(&settings_panel);
-                priv_.temporary_format.import_format(appwindow.canvas().sheet().format());
+                priv_.temporary_format.set(appwindow.canvas().sheet().format());
                 let revert_format = appwindow.canvas().sheet().format();
 
                 settings_panel.set_predefined_format_variant(format::PredefinedFormat::Custom);
@@ -618,64 +654,92 @@ impl SettingsPanel {
         );
 
         priv_.format_apply_button.get().connect_clicked(
-            clone!(@weak temporary_format, @weak appwindow => move |_format_apply_button| {
-                appwindow.canvas().sheet().format().import_format(temporary_format);
-                appwindow.canvas().sheet().set_padding_bottom(appwindow.canvas().sheet().format().height());
+            clone!(@weak self as settings_panel, @weak appwindow => move |_format_apply_button| {
+                let temporary_format = settings_panel.temporary_format();
+                appwindow.canvas().sheet().set_padding_bottom(temporary_format.height());
+                appwindow.canvas().sheet().import_format(temporary_format);
+
+                settings_panel.general_sheet_margin_unitentry().set_dpi(temporary_format.dpi());
+                settings_panel.background_pattern_width_unitentry().set_dpi(temporary_format.dpi());
+                settings_panel.background_pattern_height_unitentry().set_dpi(temporary_format.dpi());
 
-                appwindow.canvas().sheet().resize_to_format();
                 appwindow.canvas().regenerate_background(false);
                 appwindow.canvas().regenerate_content(true, true);
             }),
         );
 
         // Background
-        priv_.background_color_choosebutton.connect_color_set(clone!(@weak appwindow => move |background_color_choosebutton| {
-            appwindow.canvas().sheet().background().borrow_mut().set_color(utils::Color::from(background_color_choosebutton.rgba()));
+        self.connect_map(clone!(@weak appwindow => move |settings_panel| {
+            // The current page might have changed since the panel was last shown
+            settings_panel.load_background(&appwindow.canvas());
+        }));
+
+        priv_.background_page_override_switch.connect_active_notify(clone!(@weak self as settings_panel, @weak appwindow => move |background_page_override_switch| {
+            let current_page = appwindow.canvas().current_page();
+            let has_override = appwindow.canvas().sheet().background().borrow().page_override(current_page).is_some();
+
+            if background_page_override_switch.is_active() == has_override {
+                return;
+            }
+
+            if background_page_override_switch.is_active() {
+                let page_background = appwindow.canvas().sheet().background().borrow().page_background(current_page);
+                appwindow.canvas().sheet().background().borrow_mut().set_page_override(current_page, Some(page_background));
+            } else {
+                appwindow.canvas().sheet().background().borrow_mut().set_page_override(current_page, None);
+            }
+
+            settings_panel.load_background(&appwindow.canvas());
+            appwindow.canvas().regenerate_background(true);
+        }));
+
+        priv_.background_color_choosebutton.connect_color_set(clone!(@weak self as settings_panel, @weak appwindow => move |background_color_choosebutton| {
+            settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                page_background.color = utils::Color::from(background_color_choosebutton.rgba());
+            });
             appwindow.canvas().regenerate_background(true);
         }));
 
         priv_.background_patterns_row.get().connect_selected_item_notify(clone!(@weak self as settings_panel, @weak appwindow => move |background_patterns_row| {
             if let Some(selected_item) = background_patterns_row.selected_item() {
-                match selected_item
+                let (pattern, width_sensitive, height_sensitive) = match selected_item
                     .downcast::<adw::EnumListItem>()
                     .unwrap()
                     .nick()
                     .as_str()
                 {
-                    "none" => {
-                        appwindow.canvas().sheet().background().borrow_mut().set_pattern(PatternStyle::None);
-                        settings_panel.background_pattern_width_unitentry().set_sensitive(false);
-                        settings_panel.background_pattern_height_unitentry().set_sensitive(false);
-
-                    },
-                    "lines" => {
-                        appwindow.canvas().sheet().background().borrow_mut().set_pattern(PatternStyle::Lines);
-                        settings_panel.background_pattern_width_unitentry().set_sensitive(false);
-                        settings_panel.background_pattern_height_unitentry().set_sensitive(true);
-                    },
-                    "grid" => {
-                        appwindow.canvas().sheet().background().borrow_mut().set_pattern(PatternStyle::Grid);
-                        settings_panel.background_pattern_width_unitentry().set_sensitive(true);
-                        settings_panel.background_pattern_height_unitentry().set_sensitive(true);
-                    },
-                    "dots" => {
-                        appwindow.canvas().sheet().background().borrow_mut().set_pattern(PatternStyle::Dots);
-                        settings_panel.background_pattern_width_unitentry().set_sensitive(true);
-                        settings_panel.background_pattern_height_unitentry().set_sensitive(true);
-                    },
+                    "none" => (PatternStyle::None, false, false),
+                    "lines" => (PatternStyle::Lines, false, true),
+                    "grid" => (PatternStyle::Grid, true, true),
+                    "dots" => (PatternStyle::Dots, true, true),
+                    "isometric-grid" => (PatternStyle::IsometricGrid, true, false),
+                    "hex-grid" => (PatternStyle::HexGrid, true, false),
+                    "music-staff" => (PatternStyle::MusicStaff, false, true),
+                    "cornell" => (PatternStyle::Cornell, false, true),
+                    "left-margin" => (PatternStyle::LeftMargin, false, true),
+                    "template" => (PatternStyle::Template, false, false),
                     _ => {
                         log::error!(
                             "invalid nick string when selecting a pattern in background_patterns_row"
                         );
+                        return;
                     }
                 };
 
+                settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                    page_background.pattern = pattern;
+                });
+                settings_panel.background_pattern_width_unitentry().set_sensitive(width_sensitive);
+                settings_panel.background_pattern_height_unitentry().set_sensitive(height_sensitive);
+
                 appwindow.canvas().regenerate_background(true);
             }
         }));
 
-        priv_.background_pattern_color_choosebutton.connect_color_set(clone!(@weak appwindow => move |background_pattern_color_choosebutton| {
-            appwindow.canvas().sheet().background().borrow_mut().set_pattern_color(utils::Color::from(background_pattern_color_choosebutton.rgba()));
+        priv_.background_pattern_color_choosebutton.connect_color_set(clone!(@weak self as settings_panel, @weak appwindow => move |background_pattern_color_choosebutton| {
+            settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                page_background.pattern_color = utils::Color::from(background_pattern_color_choosebutton.rgba());
+            });
             appwindow.canvas().regenerate_background(true);
         }));
 
@@ -683,10 +747,11 @@ impl SettingsPanel {
             "measurement-changed",
             false,
             clone!(@weak self as settings_panel, @weak appwindow => @default-return None, move |_args| {
-                    let mut pattern_size = appwindow.canvas().sheet().background().borrow().pattern_size();
-                    pattern_size[0] = f64::from(settings_panel.background_pattern_width_unitentry().value_in_px());
+                    let pattern_width = f64::from(settings_panel.background_pattern_width_unitentry().value_in_px());
 
-                    appwindow.canvas().sheet().background().borrow_mut().set_pattern_size(pattern_size);
+                    settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                        page_background.pattern_size[0] = pattern_width;
+                    });
 
                     appwindow.canvas().regenerate_background(true);
 
@@ -698,52 +763,78 @@ impl SettingsPanel {
             "measurement-changed",
             false,
             clone!(@weak self as settings_panel, @weak appwindow => @default-return None, move |_args| {
-                    let mut pattern_size = appwindow.canvas().sheet().background().borrow().pattern_size();
-                    pattern_size[1] = f64::from(settings_panel.background_pattern_height_unitentry().value_in_px());
-                    appwindow.canvas().sheet().background().borrow_mut().set_pattern_size(pattern_size);
+                    let pattern_height = f64::from(settings_panel.background_pattern_height_unitentry().value_in_px());
+
+                    settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                        page_background.pattern_size[1] = pattern_height;
+                    });
 
                     appwindow.canvas().regenerate_background(true);
 
                     None
             }),
         );
+
+        priv_
+            .background_template_import_button
+            .get()
+            .connect_clicked(
+                clone!(@weak appwindow => move |_background_template_import_button| {
+                    dialogs::dialog_import_background_template(&appwindow);
+                }),
+            );
+
+        priv_
+            .background_template_clear_button
+            .get()
+            .connect_clicked(
+                clone!(@weak self as settings_panel, @weak appwindow => move |_background_template_clear_button| {
+                    settings_panel.update_edited_page_background(&appwindow, |page_background| {
+                        page_background.template = None;
+                    });
+                    appwindow.canvas().regenerate_background(true);
+                }),
+            );
     }
 
     pub fn update_temporary_format_from_rows(&self) {
         let priv_ = imp::SettingsPanel::from_instance(self);
+        let mut temporary_format = priv_.temporary_format.get();
 
         // Format
         if priv_.format_orientation_portrait_toggle.is_active() {
-            priv_
-                .temporary_format
-                .set_orientation(format::Orientation::Portrait);
+            temporary_format.set_orientation(format::Orientation::Portrait);
         } else {
-            priv_
-                .temporary_format
-                .set_orientation(format::Orientation::Landscape);
+            temporary_format.set_orientation(format::Orientation::Landscape);
         }
 
         // DPI (before width, height)
-        priv_.temporary_format.set_dpi(
+        temporary_format.set_dpi(
             priv_
                 .format_dpi_adj
                 .value()
                 .clamp(Format::DPI_MIN, Format::DPI_MAX),
         );
+        priv_.format_width_unitentry.set_dpi(temporary_format.dpi());
+        priv_
+            .format_height_unitentry
+            .set_dpi(temporary_format.dpi());
 
         // Width
-        priv_.temporary_format.set_width(
+        temporary_format.set_width(
             priv_
                 .format_width_unitentry
                 .value_in_px()
                 .clamp(Format::WIDTH_MIN, Format::WIDTH_MAX),
         );
         // Height
-        priv_.temporary_format.set_height(
+        temporary_format.set_height(
             priv_
                 .format_height_unitentry
                 .value_in_px()
                 .clamp(Format::HEIGHT_MIN, Format::HEIGHT_MAX),
         );
+
+        priv_.temporary_format.set(temporary_format);
     }
 }
@@ -7,7 +7,7 @@ mod imp {
     use gtk4::{Adjustment, DropDown};
     use once_cell::sync::Lazy;
 
-    use crate::sheet::format;
+    use rnote_engine::sheet::format;
 
     #[derive(Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/unitentry.ui")]
@@ -97,6 +97,7 @@ mod imp {
                             "px" => Some(format::MeasureUnit::Px),
                             "mm" => Some(format::MeasureUnit::Mm),
                             "cm" => Some(format::MeasureUnit::Cm),
+                            "in" => Some(format::MeasureUnit::In),
                             _ => None,
                         };
 
@@ -215,7 +216,7 @@ mod imp {
 use gtk4::Adjustment;
 use gtk4::{glib, prelude::*, subclass::prelude::*, DropDown, SpinButton, Widget};
 
-use crate::sheet::format;
+use rnote_engine::sheet::format;
 
 glib::wrapper! {
     pub struct UnitEntry(ObjectSubclass<imp::UnitEntry>)
@@ -1,15 +1,26 @@
 mod imp {
-    use gtk4::{gdk, DragSource, Image, Label};
+    use std::cell::RefCell;
+
+    use gtk4::{gdk, gio, DragSource, GestureClick, Image, Label, MenuButton, Picture};
     use gtk4::{glib, prelude::*, subclass::prelude::*, CompositeTemplate, Widget};
 
     #[derive(Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/filerow.ui")]
     pub struct FileRow {
         pub drag_source: DragSource,
+        pub context_click_gesture: GestureClick,
+        /// The file info of the item the row is bound to
+        pub fileinfo: RefCell<Option<gio::FileInfo>>,
         #[template_child]
         pub file_image: TemplateChild<Image>,
         #[template_child]
+        pub file_thumbnail: TemplateChild<Picture>,
+        #[template_child]
         pub file_label: TemplateChild<Label>,
+        #[template_child]
+        pub file_details_label: TemplateChild<Label>,
+        #[template_child]
+        pub menubutton: TemplateChild<MenuButton>,
     }
 
     impl Default for FileRow {
@@ -19,10 +30,20 @@ mod imp {
                 .actions(gdk::DragAction::COPY)
                 .build();
 
+            let context_click_gesture = GestureClick::builder()
+                .name("workspacebrowser-file-context-click-gesture")
+                .button(gdk::BUTTON_SECONDARY)
+                .build();
+
             Self {
                 drag_source,
+                context_click_gesture,
+                fileinfo: RefCell::new(None),
                 file_image: TemplateChild::<Image>::default(),
+                file_thumbnail: TemplateChild::<Picture>::default(),
                 file_label: TemplateChild::<Label>::default(),
+                file_details_label: TemplateChild::<Label>::default(),
+                menubutton: TemplateChild::<MenuButton>::default(),
             }
         }
     }
@@ -47,6 +68,7 @@ mod imp {
             self.parent_constructed(obj);
 
             obj.add_controller(&self.drag_source);
+            obj.add_controller(&self.context_click_gesture);
         }
 
         fn dispose(&self, obj: &Self::Type) {
@@ -59,8 +81,10 @@ mod imp {
     impl WidgetImpl for FileRow {}
 }
 
-use gtk4::{glib, subclass::prelude::*, Widget};
-use gtk4::{DragSource, Image, Label};
+use gtk4::{
+    gdk, gio, glib, prelude::*, subclass::prelude::*, Align, BoxLayout, Orientation, Widget,
+};
+use gtk4::{DragSource, GestureClick, Image, Label, MenuButton, Picture};
 
 glib::wrapper! {
     pub struct FileRow(ObjectSubclass<imp::FileRow>)
@@ -74,6 +98,11 @@ impl Default for FileRow {
 }
 
 impl FileRow {
+    /// The size of the thumbnails when the rows are shown in a list
+    pub const LIST_THUMBNAIL_SIZE: i32 = 48;
+    /// The size of the thumbnails and icons when the rows are shown in a grid
+    pub const GRID_THUMBNAIL_SIZE: i32 = 128;
+
     pub fn new() -> Self {
         let filerow: Self = glib::Object::new(&[]).expect("Failed to create `FileRow`");
         filerow
@@ -83,11 +112,78 @@ impl FileRow {
         imp::FileRow::from_instance(self).file_image.clone()
     }
 
+    pub fn file_thumbnail(&self) -> Picture {
+        imp::FileRow::from_instance(self).file_thumbnail.get()
+    }
+
     pub fn file_label(&self) -> Label {
         imp::FileRow::from_instance(self).file_label.clone()
     }
 
+    pub fn file_details_label(&self) -> Label {
+        imp::FileRow::from_instance(self).file_details_label.get()
+    }
+
+    pub fn fileinfo(&self) -> Option<gio::FileInfo> {
+        imp::FileRow::from_instance(self).fileinfo.borrow().clone()
+    }
+
+    pub fn set_fileinfo(&self, fileinfo: Option<gio::FileInfo>) {
+        *imp::FileRow::from_instance(self).fileinfo.borrow_mut() = fileinfo;
+    }
+
+    /// Shows the thumbnail in place of the file icon. The icon is shown again when set to None
+    pub fn set_thumbnail(&self, thumbnail: Option<&gdk::Texture>) {
+        self.file_thumbnail().set_paintable(thumbnail);
+        self.file_thumbnail().set_visible(thumbnail.is_some());
+        self.file_image().set_visible(thumbnail.is_none());
+    }
+
+    /// Lays out the row as a tile with a large thumbnail above the labels, for showing it in a grid
+    pub fn set_grid_layout(&self, grid_layout: bool) {
+        if let Some(box_layout) = self
+            .layout_manager()
+            .and_then(|layout_manager| layout_manager.downcast::<BoxLayout>().ok())
+        {
+            box_layout.set_orientation(if grid_layout {
+                Orientation::Vertical
+            } else {
+                Orientation::Horizontal
+            });
+        }
+
+        let (thumbnail_size, margin_end, label_align) = if grid_layout {
+            (Self::GRID_THUMBNAIL_SIZE, 0, Align::Center)
+        } else {
+            (Self::LIST_THUMBNAIL_SIZE, 12, Align::Start)
+        };
+
+        self.file_thumbnail()
+            .set_size_request(thumbnail_size, thumbnail_size);
+        self.file_thumbnail().set_margin_end(margin_end);
+        self.file_image()
+            .set_pixel_size(if grid_layout { thumbnail_size } else { -1 });
+        self.file_image().set_margin_end(margin_end);
+        self.file_label().set_halign(label_align);
+        self.file_details_label().set_halign(label_align);
+        self.menubutton().set_halign(if grid_layout {
+            Align::Center
+        } else {
+            Align::End
+        });
+    }
+
     pub fn drag_source(&self) -> DragSource {
         imp::FileRow::from_instance(self).drag_source.clone()
     }
+
+    pub fn context_click_gesture(&self) -> GestureClick {
+        imp::FileRow::from_instance(self)
+            .context_click_gesture
+            .clone()
+    }
+
+    pub fn menubutton(&self) -> MenuButton {
+        imp::FileRow::from_instance(self).menubutton.get()
+    }
 }
@@ -1,12 +1,20 @@
 pub mod filerow;
+pub mod thumbnailer;
 
 mod imp {
+    use std::cell::{Cell, RefCell};
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+
     use gtk4::{
         gio, glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, DirectoryList,
-        ListView, Widget,
+        GridView, ListView, ScrolledWindow, Widget,
     };
     use gtk4::{Button, Entry};
 
+    use super::filerow::FileRow;
+    use super::thumbnailer::Thumbnailer;
+
     #[derive(Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/workspacebrowser.ui")]
     pub struct WorkspaceBrowser {
@@ -19,14 +27,29 @@ mod imp {
         #[template_child]
         pub workspace_controlbox: TemplateChild<gtk4::Box>,
         #[template_child]
+        pub files_scroller: TemplateChild<ScrolledWindow>,
+        #[template_child]
         pub primary_listview: TemplateChild<ListView>,
+        /// Shown in place of the list view when the files are shown as a grid
+        #[template_child]
+        pub primary_gridview: TemplateChild<GridView>,
         pub primary_dirlist: DirectoryList,
+        pub file_sort: Cell<super::FileSort>,
+        pub thumbnailer: RefCell<Option<Thumbnailer>>,
+        /// The received previews of the sheet files, by their path
+        pub previews: RefCell<HashMap<PathBuf, super::CachedPreview>>,
+        /// The paths of the files for which a preview was requested, but not yet received
+        pub pending_previews: RefCell<HashSet<PathBuf>>,
+        /// The rows created by the factories, for updating them when a preview is received
+        pub filerows: RefCell<Vec<glib::WeakRef<FileRow>>>,
     }
 
     impl Default for WorkspaceBrowser {
         fn default() -> Self {
-            let primary_dirlist =
-                DirectoryList::new(Some("standard::*"), None as Option<&gio::File>);
+            let primary_dirlist = DirectoryList::new(
+                Some("standard::*,time::modified"),
+                None as Option<&gio::File>,
+            );
             primary_dirlist.set_monitored(true);
 
             Self {
@@ -34,8 +57,15 @@ mod imp {
                 workspace_pathup_button: TemplateChild::<Button>::default(),
                 workspace_pathentry: TemplateChild::<Entry>::default(),
                 workspace_controlbox: TemplateChild::<gtk4::Box>::default(),
+                files_scroller: TemplateChild::<ScrolledWindow>::default(),
                 primary_listview: TemplateChild::<ListView>::default(),
+                primary_gridview: TemplateChild::<GridView>::default(),
                 primary_dirlist,
+                file_sort: Cell::new(super::FileSort::default()),
+                thumbnailer: RefCell::new(None),
+                previews: RefCell::new(HashMap::new()),
+                pending_previews: RefCell::new(HashSet::new()),
+                filerows: RefCell::new(vec![]),
             }
         }
     }
@@ -79,18 +109,79 @@ mod imp {
 
     impl WidgetImpl for WorkspaceBrowser {}
 }
+use std::cmp;
 use std::path::{Path, PathBuf};
 
+use crate::app::RnoteApp;
 use crate::ui::appwindow::RnoteAppWindow;
+use crate::ui::dialogs;
+use gettextrs::ngettext;
+use rnote_engine::sheet::Sheet;
+use rnote_engine::strokes::bitmapimage;
 use gtk4::{
     gdk, gio, glib, glib::clone, glib::closure, prelude::*, subclass::prelude::*,
     ConstantExpression, CustomSorter, FileFilter, FilterChange, FilterListModel, ListItem,
     PropertyExpression, SignalListItemFactory, SingleSelection, SortListModel, SorterChange,
     Widget,
 };
-use gtk4::{DirectoryList, Entry, ListView, MultiSorter};
+use gtk4::{
+    DirectoryList, Entry, ListView, MultiSorter, NamedAction, Shortcut, ShortcutController,
+    ShortcutTrigger,
+};
 
 use self::filerow::FileRow;
+use self::thumbnailer::{FilePreview, PreviewRequest, Thumbnailer};
+
+/// How the files in the workspace browser are sorted. Folders are always listed before the files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSort {
+    Name,
+    /// The most recently modified files first
+    Date,
+    /// The largest files first
+    Size,
+}
+
+impl Default for FileSort {
+    fn default() -> Self {
+        Self::Name
+    }
+}
+
+impl FileSort {
+    /// The sort for its nick, as stored in the settings
+    pub fn from_nick(nick: &str) -> Option<Self> {
+        match nick {
+            "name" => Some(Self::Name),
+            "date" => Some(Self::Date),
+            "size" => Some(Self::Size),
+            _ => None,
+        }
+    }
+
+    /// Compares the files. Files which are equal in the sorted property are compared by their name
+    pub fn compare(&self, first: &gio::FileInfo, second: &gio::FileInfo) -> cmp::Ordering {
+        let by_name = || first.name().cmp(&second.name());
+
+        match self {
+            Self::Name => by_name(),
+            Self::Date => second
+                .attribute_uint64("time::modified")
+                .cmp(&first.attribute_uint64("time::modified"))
+                .then_with(by_name),
+            Self::Size => second.size().cmp(&first.size()).then_with(by_name),
+        }
+    }
+}
+
+/// The preview of a sheet file as shown in the rows
+#[derive(Debug, Clone)]
+pub struct CachedPreview {
+    /// The modification time of the file the preview was generated from, in seconds since the unix epoch
+    pub modified: u64,
+    pub thumbnail: Option<gdk::Texture>,
+    pub n_pages: Option<u32>,
+}
 
 glib::wrapper! {
     pub struct WorkspaceBrowser(ObjectSubclass<imp::WorkspaceBrowser>)
@@ -164,101 +255,54 @@ impl WorkspaceBrowser {
             .flags(glib::BindingFlags::SYNC_CREATE | glib::BindingFlags::BIDIRECTIONAL)
             .build();
 
-        let primary_list_factory = SignalListItemFactory::new();
+        self.setup_actions(appwindow);
 
-        primary_list_factory.connect_setup(move |_, list_item| {
-            let filerow = FileRow::new();
-
-            list_item.set_child(Some(&filerow));
-
-            let list_item_expr = ConstantExpression::new(list_item);
-            let fileinfo_expr =
-                PropertyExpression::new(ListItem::static_type(), Some(&list_item_expr), "item");
-
-            let content_provider_expr =
-                fileinfo_expr.chain_closure::<gdk::ContentProvider>(closure!(
-                    |_: Option<glib::Object>, fileinfo_obj: Option<glib::Object>| {
-                        if let Some(fileinfo_obj) = fileinfo_obj {
-                            if let Some(file) = fileinfo_obj
-                                .downcast::<gio::FileInfo>()
-                                .unwrap()
-                                .attribute_object("standard::file")
-                            {
-                                let file = file
-                                    .downcast::<gio::File>()
-                                    .expect("failed to downcast::<gio::File>() from file GObject");
-
-                                return gdk::ContentProvider::for_value(&file.to_value());
-                            }
-                        }
+        let (preview_tx, preview_rx) =
+            glib::MainContext::channel::<FilePreview>(glib::PRIORITY_DEFAULT_IDLE);
+        preview_rx.attach(
+            None,
+            clone!(@weak self as workspacebrowser => @default-return glib::Continue(false), move |preview| {
+                workspacebrowser.set_preview(preview);
 
-                        gdk::ContentProvider::for_value(&None::<gio::File>.to_value())
-                    }
-                ));
-
-            let icon_name_expr =
-                fileinfo_expr.chain_closure::<gio::ThemedIcon>(closure!(|_: Option<
-                    glib::Object,
-                >,
-                                                                         fileinfo_obj: Option<
-                    glib::Object,
-                >| {
-                    if let Some(fileinfo_obj) = fileinfo_obj {
-                        if let Some(themed_icon) = fileinfo_obj
-                            .downcast::<gio::FileInfo>()
-                            .unwrap()
-                            .attribute_object("standard::symbolic-icon")
-                        {
-                            return themed_icon.downcast::<gio::ThemedIcon>().unwrap();
-                        }
-                    }
+                glib::Continue(true)
+            }),
+        );
+        priv_
+            .thumbnailer
+            .borrow_mut()
+            .replace(Thumbnailer::new(preview_tx));
 
-                    gio::ThemedIcon::from_names(&[
-                        "workspace-folder-symbolic",
-                        "folder-documents-symbolic",
-                    ])
-                }));
-
-            let basename_expr =
-                fileinfo_expr.chain_closure::<String>(closure!(|_: Option<glib::Object>,
-                                                                fileinfo_obj: Option<
-                    glib::Object,
-                >| {
-                    if let Some(fileinfo_obj) = fileinfo_obj {
-                        if let Some(file) = fileinfo_obj
-                            .downcast::<gio::FileInfo>()
-                            .unwrap()
-                            .attribute_object("standard::file")
-                        {
-                            let file = file
-                                .downcast::<gio::File>()
-                                .expect("failed to downcast::<gio::File>() from file GObject");
-
-                            return String::from(
-                                file.basename()
-                                    .expect("failed to get file.basename()")
-                                    .to_string_lossy(),
-                            );
-                        }
-                    }
+        let primary_list_factory = SignalListItemFactory::new();
+        primary_list_factory.connect_setup(
+            clone!(@weak self as workspacebrowser => move |_, list_item| {
+                workspacebrowser.setup_filerow(list_item, false);
+            }),
+        );
+        primary_list_factory.connect_bind(
+            clone!(@weak self as workspacebrowser => move |_, list_item| {
+                workspacebrowser.bind_filerow(list_item);
+            }),
+        );
 
-                    String::from("")
-                }));
+        let primary_grid_factory = SignalListItemFactory::new();
+        primary_grid_factory.connect_setup(
+            clone!(@weak self as workspacebrowser => move |_, list_item| {
+                workspacebrowser.setup_filerow(list_item, true);
+            }),
+        );
+        primary_grid_factory.connect_bind(
+            clone!(@weak self as workspacebrowser => move |_, list_item| {
+                workspacebrowser.bind_filerow(list_item);
+            }),
+        );
 
-            basename_expr.bind(&filerow.file_label(), "label", Some(&filerow.file_label()));
-            icon_name_expr.bind(&filerow.file_image(), "gicon", Some(&filerow.file_image()));
-            content_provider_expr.bind(
-                &filerow.drag_source(),
-                "content",
-                Some(&filerow.drag_source()),
-            );
-        });
         let filefilter = FileFilter::new();
         filefilter.add_pattern("*.rnote");
         filefilter.add_pattern("*.svg");
         filefilter.add_mime_type("image/svg+xml");
-        filefilter.add_mime_type("image/png");
-        filefilter.add_mime_type("image/jpeg");
+        for mime_type in bitmapimage::IMPORT_MIME_TYPES {
+            filefilter.add_mime_type(mime_type);
+        }
         filefilter.add_mime_type("application/pdf");
         filefilter.add_mime_type("inode/directory");
         let filefilter_model =
@@ -290,35 +334,29 @@ impl WorkspaceBrowser {
             }
         });
 
-        let alphanumeric_sorter = CustomSorter::new(move |obj1, obj2| {
-            let first_fileinfo = obj1
-                .clone()
-                .downcast::<gio::FileInfo>()
-                .expect("failed to downcast obj1");
-            let first_file = first_fileinfo.attribute_object("standard::file").unwrap();
-            let first_file = first_file.downcast::<gio::File>().unwrap();
-            let first_display_name = first_file.basename().unwrap();
-            let first_display_name = first_display_name.to_str().unwrap();
-
-            let second_fileinfo = obj2
-                .clone()
-                .downcast::<gio::FileInfo>()
-                .expect("failed to downcast obj2");
-            let second_file = second_fileinfo.attribute_object("standard::file").unwrap();
-            let second_file = second_file.downcast::<gio::File>().unwrap();
-            let second_display_name = second_file.basename().unwrap();
-            let second_display_name = second_display_name.to_str().unwrap();
-
-            first_display_name.cmp(second_display_name).into()
-        });
+        let file_sorter = CustomSorter::new(
+            clone!(@weak self as workspacebrowser => @default-return gtk4::Ordering::Equal, move |obj1, obj2| {
+                let first_fileinfo = obj1
+                    .clone()
+                    .downcast::<gio::FileInfo>()
+                    .expect("failed to downcast obj1");
+                let second_fileinfo = obj2
+                    .clone()
+                    .downcast::<gio::FileInfo>()
+                    .expect("failed to downcast obj2");
+
+                workspacebrowser.file_sort().compare(&first_fileinfo, &second_fileinfo).into()
+            }),
+        );
 
         let multisorter = MultiSorter::new();
         multisorter.append(&folder_sorter);
-        multisorter.append(&alphanumeric_sorter);
+        multisorter.append(&file_sorter);
         let multi_sort_model = SortListModel::new(Some(&filefilter_model), Some(&multisorter));
 
         let primary_selection_model = SingleSelection::new(Some(&multi_sort_model));
 
+        // The list and the grid view share the selection model, so the selection is kept when switching between them
         priv_
             .primary_listview
             .get()
@@ -327,21 +365,48 @@ impl WorkspaceBrowser {
             .primary_listview
             .get()
             .set_model(Some(&primary_selection_model));
+        priv_
+            .primary_gridview
+            .get()
+            .set_factory(Some(&primary_grid_factory));
+        priv_
+            .primary_gridview
+            .get()
+            .set_model(Some(&primary_selection_model));
 
-        priv_.primary_listview.get().connect_activate(clone!(@weak filefilter, @weak multisorter, @weak appwindow => move |primary_listview, position| {
-            let model = primary_listview.model().expect("model for primary_listview does not exist.");
-            let fileinfo = model.item(position).expect("selected item in primary_listview does not exist.").downcast::<gio::FileInfo>().expect("selected item in primary_list is not of Type `gio::FileInfo`");
-
-            if let Some(file) = fileinfo.attribute_object("standard::file") {
-                let file = file.downcast::<gio::File>().unwrap();
+        priv_.primary_listview.get().connect_activate(clone!(@weak self as workspacebrowser, @weak filefilter, @weak multisorter, @weak appwindow => move |_primary_listview, position| {
+            workspacebrowser.activate_file(&appwindow, position);
 
-                appwindow.open_file_w_dialogs(&file, None);
-            };
+            multisorter.changed(SorterChange::Different);
+            filefilter.changed(FilterChange::Different);
+        }));
+        priv_.primary_gridview.get().connect_activate(clone!(@weak self as workspacebrowser, @weak filefilter, @weak multisorter, @weak appwindow => move |_primary_gridview, position| {
+            workspacebrowser.activate_file(&appwindow, position);
 
             multisorter.changed(SorterChange::Different);
             filefilter.changed(FilterChange::Different);
         }));
 
+        // Sorting and view, stored in the settings
+        self.set_file_sort(
+            FileSort::from_nick(&appwindow.app_settings().string("workspace-sort"))
+                .unwrap_or_default(),
+        );
+        appwindow.app_settings().connect_changed(
+            Some("workspace-sort"),
+            clone!(@weak self as workspacebrowser, @weak multisorter => move |appsettings, _key_str| {
+                workspacebrowser.set_file_sort(FileSort::from_nick(&appsettings.string("workspace-sort")).unwrap_or_default());
+                multisorter.changed(SorterChange::Different);
+            }),
+        );
+        self.set_grid_view(appwindow.app_settings().boolean("workspace-grid-view"));
+        appwindow.app_settings().connect_changed(
+            Some("workspace-grid-view"),
+            clone!(@weak self as workspacebrowser => move |appsettings, _key_str| {
+                workspacebrowser.set_grid_view(appsettings.boolean("workspace-grid-view"));
+            }),
+        );
+
         priv_.primary_dirlist.connect_file_notify(
             clone!(@weak appwindow, @weak filefilter, @weak multisorter => move |primary_dirlist| {
                 if let Some(file) = primary_dirlist.file() {
@@ -361,6 +426,506 @@ impl WorkspaceBrowser {
         }));
     }
 
+    /// Creates the row for the list item. The name, icon and drag content are bound to the file info of the item through expressions
+    fn setup_filerow(&self, list_item: &ListItem, grid_layout: bool) {
+        let priv_ = imp::WorkspaceBrowser::from_instance(self);
+
+        let filerow = FileRow::new();
+        filerow.set_grid_layout(grid_layout);
+
+        list_item.set_child(Some(&filerow));
+
+        let list_item_expr = ConstantExpression::new(list_item);
+        let fileinfo_expr =
+            PropertyExpression::new(ListItem::static_type(), Some(&list_item_expr), "item");
+
+        let content_provider_expr = fileinfo_expr.chain_closure::<gdk::ContentProvider>(closure!(
+            |_: Option<glib::Object>, fileinfo_obj: Option<glib::Object>| {
+                if let Some(fileinfo_obj) = fileinfo_obj {
+                    if let Some(file) = fileinfo_obj
+                        .downcast::<gio::FileInfo>()
+                        .unwrap()
+                        .attribute_object("standard::file")
+                    {
+                        let file = file
+                            .downcast::<gio::File>()
+                            .expect("failed to downcast::<gio::File>() from file GObject");
+
+                        return gdk::ContentProvider::for_value(&file.to_value());
+                    }
+                }
+
+                gdk::ContentProvider::for_value(&None::<gio::File>.to_value())
+            }
+        ));
+
+        let icon_name_expr =
+            fileinfo_expr.chain_closure::<gio::ThemedIcon>(closure!(|_: Option<glib::Object>,
+                                                                     fileinfo_obj: Option<
+                glib::Object,
+            >| {
+                if let Some(fileinfo_obj) = fileinfo_obj {
+                    if let Some(themed_icon) = fileinfo_obj
+                        .downcast::<gio::FileInfo>()
+                        .unwrap()
+                        .attribute_object("standard::symbolic-icon")
+                    {
+                        return themed_icon.downcast::<gio::ThemedIcon>().unwrap();
+                    }
+                }
+
+                gio::ThemedIcon::from_names(&[
+                    "workspace-folder-symbolic",
+                    "folder-documents-symbolic",
+                ])
+            }));
+
+        let basename_expr =
+            fileinfo_expr.chain_closure::<String>(closure!(|_: Option<glib::Object>,
+                                                            fileinfo_obj: Option<
+                glib::Object,
+            >| {
+                if let Some(fileinfo_obj) = fileinfo_obj {
+                    if let Some(file) = fileinfo_obj
+                        .downcast::<gio::FileInfo>()
+                        .unwrap()
+                        .attribute_object("standard::file")
+                    {
+                        let file = file
+                            .downcast::<gio::File>()
+                            .expect("failed to downcast::<gio::File>() from file GObject");
+
+                        return String::from(
+                            file.basename()
+                                .expect("failed to get file.basename()")
+                                .to_string_lossy(),
+                        );
+                    }
+                }
+
+                String::from("")
+            }));
+
+        basename_expr.bind(&filerow.file_label(), "label", Some(&filerow.file_label()));
+        icon_name_expr.bind(&filerow.file_image(), "gicon", Some(&filerow.file_image()));
+        content_provider_expr.bind(
+            &filerow.drag_source(),
+            "content",
+            Some(&filerow.drag_source()),
+        );
+
+        // The file actions act on the selected file, so the row is selected before they are shown
+        filerow.context_click_gesture().connect_pressed(
+            clone!(@weak self as workspacebrowser, @weak filerow, @weak list_item => move |_context_click_gesture, _n_press, _x, _y| {
+                workspacebrowser.select_list_item(&list_item);
+                filerow.menubutton().popup();
+            }),
+        );
+        if let Some(popover) = filerow.menubutton().popover() {
+            popover.connect_show(
+                clone!(@weak self as workspacebrowser, @weak list_item => move |_popover| {
+                    workspacebrowser.select_list_item(&list_item);
+                }),
+            );
+        }
+
+        let mut filerows = priv_.filerows.borrow_mut();
+        filerows.retain(|filerow| filerow.upgrade().is_some());
+        filerows.push(filerow.downgrade());
+    }
+
+    /// Binds the row to the file info of the list item, and shows its thumbnail and details
+    fn bind_filerow(&self, list_item: &ListItem) {
+        let filerow = match list_item
+            .child()
+            .and_then(|child| child.downcast::<FileRow>().ok())
+        {
+            Some(filerow) => filerow,
+            None => return,
+        };
+
+        filerow.set_fileinfo(
+            list_item
+                .item()
+                .and_then(|item| item.downcast::<gio::FileInfo>().ok()),
+        );
+        self.update_filerow(&filerow);
+    }
+
+    /// Updates the thumbnail and the details of the row. The preview of a sheet file is requested when it is missing or outdated
+    fn update_filerow(&self, filerow: &FileRow) {
+        let fileinfo = match filerow.fileinfo() {
+            Some(fileinfo) => fileinfo,
+            None => {
+                filerow.set_thumbnail(None);
+                filerow.file_details_label().set_label("");
+                return;
+            }
+        };
+
+        let preview = fileinfo_path(&fileinfo)
+            .filter(|path| path.extension().map(|ext| ext == "rnote").unwrap_or(false))
+            .and_then(|path| self.file_preview(path, fileinfo.attribute_uint64("time::modified")));
+
+        filerow.set_thumbnail(
+            preview
+                .as_ref()
+                .and_then(|preview| preview.thumbnail.as_ref()),
+        );
+        filerow.file_details_label().set_label(&file_details(
+            &fileinfo,
+            preview.and_then(|preview| preview.n_pages),
+        ));
+    }
+
+    /// The preview of the sheet file. A new preview is requested when it is missing or outdated,
+    /// until it is received the outdated preview is returned
+    fn file_preview(&self, path: PathBuf, modified: u64) -> Option<CachedPreview> {
+        let priv_ = imp::WorkspaceBrowser::from_instance(self);
+        let preview = priv_.previews.borrow().get(&path).cloned();
+
+        if preview.as_ref().map(|preview| preview.modified) != Some(modified)
+            && priv_.pending_previews.borrow_mut().insert(path.clone())
+        {
+            if let Some(thumbnailer) = priv_.thumbnailer.borrow().as_ref() {
+                thumbnailer.request_preview(PreviewRequest { path, modified });
+            }
+        }
+
+        preview
+    }
+
+    /// Stores the received preview and updates the rows which show the file
+    fn set_preview(&self, preview: FilePreview) {
+        let priv_ = imp::WorkspaceBrowser::from_instance(self);
+        priv_.pending_previews.borrow_mut().remove(&preview.path);
+
+        let thumbnail = preview.thumbnail_path.and_then(|thumbnail_path| {
+            match gdk::Texture::from_file(&gio::File::for_path(&thumbnail_path)) {
+                Ok(thumbnail) => Some(thumbnail),
+                Err(e) => {
+                    log::error!(
+                        "gdk::Texture::from_file() failed in set_preview() with Err {}",
+                        e
+                    );
+                    None
+                }
+            }
+        });
+        priv_.previews.borrow_mut().insert(
+            preview.path.clone(),
+            CachedPreview {
+                modified: preview.modified,
+                thumbnail,
+                n_pages: preview.n_pages,
+            },
+        );
+
+        let filerows = priv_
+            .filerows
+            .borrow()
+            .iter()
+            .filter_map(|filerow| filerow.upgrade())
+            .collect::<Vec<FileRow>>();
+        for filerow in filerows {
+            if filerow
+                .fileinfo()
+                .and_then(|fileinfo| fileinfo_path(&fileinfo))
+                .as_ref()
+                == Some(&preview.path)
+            {
+                self.update_filerow(&filerow);
+            }
+        }
+    }
+
+    /// Opens the file at the position in the list
+    fn activate_file(&self, appwindow: &RnoteAppWindow, position: u32) {
+        let model = self
+            .primary_listview()
+            .model()
+            .expect("model for primary_listview does not exist.");
+        let fileinfo = model
+            .item(position)
+            .expect("selected item in primary_listview does not exist.")
+            .downcast::<gio::FileInfo>()
+            .expect("selected item in primary_list is not of Type `gio::FileInfo`");
+
+        if let Some(file) = fileinfo.attribute_object("standard::file") {
+            let file = file.downcast::<gio::File>().unwrap();
+
+            appwindow.open_file_w_dialogs(&file, None);
+        };
+    }
+
+    pub fn file_sort(&self) -> FileSort {
+        imp::WorkspaceBrowser::from_instance(self).file_sort.get()
+    }
+
+    /// Sets how the files are sorted. The sorter needs to be notified about the change
+    fn set_file_sort(&self, file_sort: FileSort) {
+        imp::WorkspaceBrowser::from_instance(self)
+            .file_sort
+            .set(file_sort);
+    }
+
+    /// Shows the files as a grid of tiles, or as a list
+    pub fn set_grid_view(&self, grid_view: bool) {
+        let priv_ = imp::WorkspaceBrowser::from_instance(self);
+        let view = if grid_view {
+            priv_.primary_gridview.get().upcast::<Widget>()
+        } else {
+            priv_.primary_listview.get().upcast::<Widget>()
+        };
+
+        if priv_.files_scroller.child().as_ref() != Some(&view) {
+            priv_.files_scroller.set_child(Some(&view));
+        }
+    }
+
+    fn setup_actions(&self, appwindow: &RnoteAppWindow) {
+        let action_group = gio::SimpleActionGroup::new();
+        self.insert_action_group("workspacebrowser", Some(&action_group));
+
+        let action_rename_file = gio::SimpleAction::new("rename-file", None);
+        action_group.add_action(&action_rename_file);
+        let action_duplicate_file = gio::SimpleAction::new("duplicate-file", None);
+        action_group.add_action(&action_duplicate_file);
+        let action_trash_file = gio::SimpleAction::new("trash-file", None);
+        action_group.add_action(&action_trash_file);
+        let action_show_file_in_filemanager =
+            gio::SimpleAction::new("show-file-in-filemanager", None);
+        action_group.add_action(&action_show_file_in_filemanager);
+        let action_new_note = gio::SimpleAction::new("new-note", None);
+        action_group.add_action(&action_new_note);
+        let action_new_folder = gio::SimpleAction::new("new-folder", None);
+        action_group.add_action(&action_new_folder);
+        let action_workspace_sort = appwindow.app_settings().create_action("workspace-sort");
+        action_group.add_action(&action_workspace_sort);
+        let action_workspace_grid_view = appwindow
+            .app_settings()
+            .create_action("workspace-grid-view");
+        action_group.add_action(&action_workspace_grid_view);
+
+        // Rename file
+        action_rename_file.connect_activate(
+            clone!(@weak self as workspacebrowser, @weak appwindow => move |_action_rename_file, _| {
+                if let Some(file) = workspacebrowser.selected_file() {
+                    dialogs::dialog_rename_workspace_file(&appwindow, &file);
+                }
+            }),
+        );
+
+        // Duplicate file
+        action_duplicate_file.connect_activate(
+            clone!(@weak self as workspacebrowser => move |_action_duplicate_file, _| {
+                if let Some(file) = workspacebrowser.selected_file() {
+                    if let Err(e) = workspacebrowser.duplicate_file(&file) {
+                        log::error!("duplicate_file() failed in duplicate-file action with Err {}", e);
+                    }
+                }
+            }),
+        );
+
+        // Trash file
+        action_trash_file.connect_activate(
+            clone!(@weak self as workspacebrowser, @weak appwindow => move |_action_trash_file, _| {
+                if let Some(file) = workspacebrowser.selected_file() {
+                    if let Err(e) = workspacebrowser.trash_file(&appwindow, &file) {
+                        log::error!("trash_file() failed in trash-file action with Err {}", e);
+                    }
+                }
+            }),
+        );
+
+        // Show file in the file manager
+        action_show_file_in_filemanager.connect_activate(
+            clone!(@weak self as workspacebrowser, @weak appwindow => move |_action_show_file_in_filemanager, _| {
+                if let Some(file) = workspacebrowser.selected_file() {
+                    workspacebrowser.show_file_in_filemanager(&appwindow, &file);
+                }
+            }),
+        );
+
+        // New note
+        action_new_note.connect_activate(clone!(@weak appwindow => move |_action_new_note, _| {
+            dialogs::dialog_new_workspace_note(&appwindow);
+        }));
+
+        // New folder
+        action_new_folder.connect_activate(
+            clone!(@weak appwindow => move |_action_new_folder, _| {
+                dialogs::dialog_new_workspace_folder(&appwindow);
+            }),
+        );
+
+        // Shortcuts, only active when the focus is inside the workspace browser
+        let shortcut_controller = ShortcutController::new();
+        for (trigger, action_name) in [
+            ("F2", "workspacebrowser.rename-file"),
+            ("<Ctrl><Shift>d", "workspacebrowser.duplicate-file"),
+            ("<Ctrl>Delete", "workspacebrowser.trash-file"),
+            ("<Ctrl><Alt>o", "workspacebrowser.show-file-in-filemanager"),
+            ("<Ctrl><Alt>n", "workspacebrowser.new-note"),
+            ("<Ctrl><Shift>n", "workspacebrowser.new-folder"),
+        ] {
+            shortcut_controller.add_shortcut(&Shortcut::new(
+                ShortcutTrigger::parse_string(trigger).as_ref(),
+                Some(&NamedAction::new(action_name)),
+            ));
+        }
+        self.add_controller(&shortcut_controller);
+    }
+
+    /// Selects the row of the list item
+    fn select_list_item(&self, list_item: &ListItem) {
+        if let Some(model) = self.primary_listview().model() {
+            model.select_item(list_item.position(), true);
+        }
+    }
+
+    /// The file of the selected row
+    pub fn selected_file(&self) -> Option<gio::File> {
+        self.primary_listview()
+            .model()?
+            .downcast::<SingleSelection>()
+            .ok()?
+            .selected_item()?
+            .downcast::<gio::FileInfo>()
+            .ok()?
+            .attribute_object("standard::file")?
+            .downcast::<gio::File>()
+            .ok()
+    }
+
+    /// Renames the file. The path of the opened sheet is kept in sync when it is the renamed file, or inside the renamed folder
+    pub fn rename_file(
+        &self,
+        appwindow: &RnoteAppWindow,
+        file: &gio::File,
+        new_name: &str,
+    ) -> Result<gio::File, anyhow::Error> {
+        let new_file = file.set_display_name(new_name, None::<&gio::Cancellable>)?;
+
+        let app = appwindow
+            .application()
+            .unwrap()
+            .downcast::<RnoteApp>()
+            .unwrap();
+        if let Some(output_file) = app.output_file() {
+            if output_file.equal(file) {
+                app.set_output_file(Some(&new_file), appwindow);
+            } else if let Some(relative_path) = file.relative_path(&output_file) {
+                app.set_output_file(
+                    Some(&new_file.resolve_relative_path(relative_path)),
+                    appwindow,
+                );
+            }
+        }
+
+        Ok(new_file)
+    }
+
+    /// Duplicates the file into the same folder, under a name which is not taken yet. Folders can't be duplicated
+    pub fn duplicate_file(&self, file: &gio::File) -> Result<gio::File, anyhow::Error> {
+        if file.query_file_type(gio::FileQueryInfoFlags::NONE, None::<&gio::Cancellable>)
+            == gio::FileType::Directory
+        {
+            return Err(anyhow::anyhow!("duplicating folders is not supported"));
+        }
+        let path = file
+            .path()
+            .ok_or_else(|| anyhow::anyhow!("duplicate_file() failed, file has no path"))?;
+        let parent_path = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("duplicate_file() failed, file has no parent"))?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string());
+
+        let new_file = gio::File::for_path(unique_path(
+            parent_path,
+            &(stem + " - Copy"),
+            extension.as_deref(),
+        ));
+        file.copy(
+            &new_file,
+            gio::FileCopyFlags::NONE,
+            None::<&gio::Cancellable>,
+            None,
+        )?;
+
+        Ok(new_file)
+    }
+
+    /// Moves the file to the trash. When the opened sheet is trashed, it stays open as a sheet with unsaved changes
+    pub fn trash_file(
+        &self,
+        appwindow: &RnoteAppWindow,
+        file: &gio::File,
+    ) -> Result<(), anyhow::Error> {
+        file.trash(None::<&gio::Cancellable>)?;
+
+        let app = appwindow
+            .application()
+            .unwrap()
+            .downcast::<RnoteApp>()
+            .unwrap();
+        if let Some(output_file) = app.output_file() {
+            if output_file.equal(file) || file.relative_path(&output_file).is_some() {
+                app.set_output_file(None, appwindow);
+                appwindow.canvas().set_unsaved_changes(true);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Opens the folder in the file manager. Files are shown in the folder they are in
+    pub fn show_file_in_filemanager(&self, appwindow: &RnoteAppWindow, file: &gio::File) {
+        let folder = if file
+            .query_file_type(gio::FileQueryInfoFlags::NONE, None::<&gio::Cancellable>)
+            == gio::FileType::Directory
+        {
+            Some(file.clone())
+        } else {
+            file.parent()
+        };
+
+        if let Some(folder) = folder {
+            gtk4::show_uri(Some(appwindow), &folder.uri(), gdk::CURRENT_TIME);
+        }
+    }
+
+    /// Creates a new empty sheet file in the workspace folder
+    pub fn create_note(&self, name: &str) -> Result<gio::File, anyhow::Error> {
+        let workspace_path = self
+            .primary_path()
+            .ok_or_else(|| anyhow::anyhow!("create_note() failed, no workspace folder"))?;
+        let name = name.strip_suffix(".rnote").unwrap_or(name);
+
+        let file = gio::File::for_path(unique_path(&workspace_path, name, Some("rnote")));
+        Sheet::default().save_sheet_to_file(&file)?;
+
+        Ok(file)
+    }
+
+    /// Creates a new folder in the workspace folder
+    pub fn create_folder(&self, name: &str) -> Result<gio::File, anyhow::Error> {
+        let workspace_path = self
+            .primary_path()
+            .ok_or_else(|| anyhow::anyhow!("create_folder() failed, no workspace folder"))?;
+
+        let file = gio::File::for_path(unique_path(&workspace_path, name, None));
+        file.make_directory(None::<&gio::Cancellable>)?;
+
+        Ok(file)
+    }
+
     pub fn primary_path(&self) -> Option<PathBuf> {
         let priv_ = imp::WorkspaceBrowser::from_instance(self);
 
@@ -378,3 +943,54 @@ impl WorkspaceBrowser {
         priv_.primary_dirlist.set_file(path.as_ref());
     }
 }
+
+/// A path in the folder which is not taken yet, made up of the name and the extension. A number is appended to the name when it is taken
+fn unique_path(folder: &Path, name: &str, extension: Option<&str>) -> PathBuf {
+    let path_for_name = |name: &str| match extension {
+        Some(extension) => folder.join(format!("{}.{}", name, extension)),
+        None => folder.join(name),
+    };
+
+    let mut path = path_for_name(name);
+    let mut n = 2;
+    while path.exists() {
+        path = path_for_name(&format!("{} {}", name, n));
+        n += 1;
+    }
+
+    path
+}
+
+/// The path of the file the file info is about
+fn fileinfo_path(fileinfo: &gio::FileInfo) -> Option<PathBuf> {
+    fileinfo
+        .attribute_object("standard::file")?
+        .downcast::<gio::File>()
+        .ok()?
+        .path()
+}
+
+/// The details shown below the file name: the modification date, and the page count for sheets or the size for other files
+fn file_details(fileinfo: &gio::FileInfo, n_pages: Option<u32>) -> String {
+    let mut details = Vec::<String>::new();
+
+    let modified = fileinfo.attribute_uint64("time::modified");
+    if modified > 0 {
+        if let Some(date) = glib::DateTime::from_unix_local(modified as i64)
+            .and_then(|datetime| datetime.format("%x"))
+            .ok()
+        {
+            details.push(date.to_string());
+        }
+    }
+
+    if fileinfo.file_type() != gio::FileType::Directory {
+        match n_pages {
+            Some(n_pages) => details
+                .push(ngettext("{} page", "{} pages", n_pages).replace("{}", &n_pages.to_string())),
+            None => details.push(glib::format_size(fileinfo.size().max(0) as u64).to_string()),
+        }
+    }
+
+    details.join(" · ")
+}
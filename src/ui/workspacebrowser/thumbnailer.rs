@@ -0,0 +1,177 @@
+use std::fs;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use gtk4::{gdk_pixbuf, gio, glib, prelude::*};
+use rnote_engine::render;
+use rnote_engine::sheet::Sheet;
+
+/// A request for the preview of a sheet file
+#[derive(Debug, Clone)]
+pub struct PreviewRequest {
+    pub path: PathBuf,
+    /// The modification time of the file, in seconds since the unix epoch
+    pub modified: u64,
+}
+
+/// The preview of a sheet file, loaded from the thumbnail cache or generated from the file
+#[derive(Debug, Clone)]
+pub struct FilePreview {
+    pub path: PathBuf,
+    /// The modification time of the file the preview was generated from, in seconds since the unix epoch
+    pub modified: u64,
+    /// The path of the cached thumbnail. None if the preview could not be generated
+    pub thumbnail_path: Option<PathBuf>,
+    pub n_pages: Option<u32>,
+}
+
+/// Generates the previews of sheet files on a worker thread. The thumbnails are stored in the thumbnail cache
+/// as defined by the freedesktop thumbnail specification, so they are only generated again when the file was modified
+#[derive(Debug)]
+pub struct Thumbnailer {
+    request_tx: mpsc::Sender<PreviewRequest>,
+}
+
+impl Thumbnailer {
+    /// The size of the square the thumbnails fit into, as defined for the "normal" thumbnail size
+    pub const THUMBNAIL_SIZE: f64 = 128.0;
+
+    /// Spawns the worker thread, which sends the previews to the preview_tx as they are ready.
+    /// It exits when the thumbnailer is dropped
+    pub fn new(preview_tx: glib::Sender<FilePreview>) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<PreviewRequest>();
+
+        thread::spawn(move || {
+            // Shared by all previews, loading the system fonts for each of them would be slow
+            let renderer = render::Renderer::default();
+
+            for request in request_rx.iter() {
+                if preview_tx
+                    .send(load_or_gen_preview(request, &renderer))
+                    .is_err()
+                {
+                    // The receiving workspace browser is gone
+                    break;
+                }
+            }
+        });
+
+        Self { request_tx }
+    }
+
+    pub fn request_preview(&self, request: PreviewRequest) {
+        if let Err(e) = self.request_tx.send(request) {
+            log::error!(
+                "request_tx.send() failed in request_preview() with Err {}",
+                e
+            );
+        }
+    }
+}
+
+/// The path of the cached thumbnail of the file with the uri, named by the md5 hash of the uri
+fn thumbnail_path(uri: &str) -> Option<PathBuf> {
+    let hash = glib::compute_checksum_for_string(glib::ChecksumType::Md5, uri)?;
+
+    Some(
+        glib::user_cache_dir()
+            .join("thumbnails")
+            .join("normal")
+            .join(format!("{}.png", hash)),
+    )
+}
+
+fn load_or_gen_preview(request: PreviewRequest, renderer: &render::Renderer) -> FilePreview {
+    let uri = gio::File::for_path(&request.path).uri().to_string();
+
+    let result = thumbnail_path(&uri)
+        .ok_or_else(|| anyhow::anyhow!("failed to compute the thumbnail path"))
+        .and_then(
+            |thumbnail_path| match load_cached_n_pages(&thumbnail_path, request.modified) {
+                Some(n_pages) => Ok((thumbnail_path, n_pages)),
+                None => gen_thumbnail(&request, &uri, &thumbnail_path, renderer)
+                    .map(|n_pages| (thumbnail_path, n_pages)),
+            },
+        );
+
+    match result {
+        Ok((thumbnail_path, n_pages)) => FilePreview {
+            path: request.path,
+            modified: request.modified,
+            thumbnail_path: Some(thumbnail_path),
+            n_pages: Some(n_pages),
+        },
+        Err(e) => {
+            log::error!(
+                "generating the preview failed in load_or_gen_preview() for file {:?}, with Err {}",
+                request.path,
+                e
+            );
+            FilePreview {
+                path: request.path,
+                modified: request.modified,
+                thumbnail_path: None,
+                n_pages: None,
+            }
+        }
+    }
+}
+
+/// The page count stored in the cached thumbnail. None if there is no cached thumbnail, or it is outdated
+fn load_cached_n_pages(thumbnail_path: &Path, modified: u64) -> Option<u32> {
+    let pixbuf = gdk_pixbuf::Pixbuf::from_file(thumbnail_path).ok()?;
+
+    if pixbuf.option("tEXt::Thumb::MTime")?.as_str() != modified.to_string() {
+        return None;
+    }
+    pixbuf
+        .option("tEXt::Thumb::Document::Pages")?
+        .as_str()
+        .parse::<u32>()
+        .ok()
+}
+
+/// Renders the first page of the sheet file and stores it in the thumbnail cache. Returns the page count
+fn gen_thumbnail(
+    request: &PreviewRequest,
+    uri: &str,
+    thumbnail_path: &Path,
+    renderer: &render::Renderer,
+) -> Result<u32, anyhow::Error> {
+    let bytes = fs::read(&request.path)?;
+    let preview = Sheet::gen_preview_from_bytes(&bytes, Thumbnailer::THUMBNAIL_SIZE, renderer)?;
+
+    let png_bytes = preview.thumbnail.encode(render::BitmapExportFormat::Png)?;
+    let pixbuf = gdk_pixbuf::Pixbuf::from_stream(
+        &gio::MemoryInputStream::from_bytes(&glib::Bytes::from_owned(png_bytes)),
+        None::<&gio::Cancellable>,
+    )?;
+
+    if let Some(thumbnail_dir) = thumbnail_path.parent() {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(thumbnail_dir)?;
+    }
+
+    // Saved to a temporary file first, so that other programs never read an incomplete thumbnail
+    let tmp_path = thumbnail_path.with_extension(format!("png.{}.tmp", std::process::id()));
+    let modified = request.modified.to_string();
+    let n_pages = preview.n_pages.to_string();
+    pixbuf.savev(
+        &tmp_path,
+        "png",
+        &[
+            ("tEXt::Thumb::URI", uri),
+            ("tEXt::Thumb::MTime", modified.as_str()),
+            ("tEXt::Thumb::Document::Pages", n_pages.as_str()),
+            ("tEXt::Software", "Rnote"),
+        ],
+    )?;
+    fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
+    fs::rename(&tmp_path, thumbnail_path)?;
+
+    Ok(preview.n_pages)
+}